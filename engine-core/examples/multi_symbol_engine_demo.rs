@@ -145,11 +145,14 @@ async fn main() -> anyhow::Result<()> {
     let results = engine.shutdown_with_results().await;
 
     println!("\n📋 Shutdown Results:");
-    for (runner_id, result) in results {
-        match result {
+    for (runner_id, shutdown) in results {
+        match &shutdown.result {
             Ok(()) => println!("  ✅ {}: OK", runner_id),
             Err(e) => println!("  ❌ {}: {}", runner_id, e),
         }
+        if let Some(position) = shutdown.open_position() {
+            println!("     left open: {} {} @ ${:.2}", position.side(), position.quantity(), position.entry_price());
+        }
     }
 
     println!("\n✨ Demo complete!\n");
@@ -130,6 +130,9 @@ pub struct AlpacaConfig {
 /// [data_source.simulated]
 /// symbol = "BTCUSDT"
 /// starting_price = 50000.0
+/// # Optional: deterministic bar timestamps
+/// start_timestamp = 1700000000000
+/// interval_ms = 60000
 /// ```
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SimulatedConfig {
@@ -137,6 +140,29 @@ pub struct SimulatedConfig {
     pub symbol: String,
     /// Starting price for random walk
     pub starting_price: f64,
+    /// Timestamp of the first bar (ms); the wall clock at startup if unset
+    ///
+    /// With neither this nor `interval_ms` set, every bar is stamped with
+    /// the wall clock.
+    #[serde(default)]
+    pub start_timestamp: Option<i64>,
+    /// Spacing between bar timestamps (ms); the feed's tick interval if unset
+    #[serde(default)]
+    pub interval_ms: Option<i64>,
+}
+
+impl SimulatedConfig {
+    /// Build the feed this configuration describes
+    pub fn feed(&self) -> crate::sources::SimulatedFeed {
+        let feed = crate::sources::SimulatedFeed::new(self.symbol.clone(), self.starting_price);
+        if self.start_timestamp.is_none() && self.interval_ms.is_none() {
+            return feed;
+        }
+
+        let start = self.start_timestamp.unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+        let interval_ms = self.interval_ms.unwrap_or(feed.tick_interval().as_millis() as i64);
+        feed.with_timestamps(start, interval_ms)
+    }
 }
 
 /// Configuration for CSV file data source.
@@ -146,11 +172,42 @@ pub struct SimulatedConfig {
 /// ```toml
 /// [data_source.csv]
 /// path = "/path/to/market_data.csv"
+/// # Optional: symbol for files without a symbol column, and the unit of
+/// # the timestamp column (seconds, milliseconds, microseconds, nanoseconds)
+/// symbol = "BTCUSDT"
+/// timestamp_unit = "seconds"
 /// ```
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CsvConfig {
     /// Path to CSV file containing market data
     pub path: String,
+    /// Symbol for bars when the file has no `symbol` column
+    #[serde(default)]
+    pub symbol: Option<String>,
+    /// Unit of the `timestamp` column; bars are normalized to milliseconds
+    #[serde(default)]
+    pub timestamp_unit: crate::sources::TimestampUnit,
+}
+
+impl CsvConfig {
+    /// Load the file into a replay feed
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IoError` if the file can't be read or isn't valid bar CSV,
+    /// and a `ConfigError` if bars have no symbol because the file has no
+    /// `symbol` column and none is configured.
+    pub fn feed(&self) -> crate::error::Result<crate::sources::ReplayFeed> {
+        let symbol = self.symbol.as_deref().unwrap_or_default();
+        let bars = crate::sources::csv::read_bars(&self.path, symbol, self.timestamp_unit)?;
+        if bars.iter().any(|bar| bar.symbol.is_empty()) {
+            return Err(crate::error::TradingEngineError::ConfigError(format!(
+                "{} has no symbol column; set data_source.csv.symbol",
+                self.path
+            )));
+        }
+        Ok(crate::sources::ReplayFeed::new(bars))
+    }
 }
 
 /// Configuration for market data storage.
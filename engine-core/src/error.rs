@@ -19,6 +19,7 @@
 //! }
 //! ```
 
+use crate::state_machine::State;
 use thiserror::Error;
 
 /// Error type for all trading engine operations.
@@ -134,6 +135,77 @@ pub enum TradingEngineError {
     /// This error occurs when a runner's background task panics.
     #[error("Runner task panicked: {0}")]
     TaskPanic(String),
+
+    /// Action not allowed in the current state.
+    ///
+    /// This error occurs when an action is applied to a state machine in a
+    /// state it doesn't apply to, e.g. an exit while idle.
+    #[error("Invalid transition: {action} not allowed in {from} state")]
+    InvalidTransition {
+        /// State the machine was in
+        from: State,
+        /// The rejected action
+        action: String,
+    },
+
+    /// Configuration values that don't make sense together.
+    ///
+    /// This error occurs when a configuration parses but fails validation,
+    /// such as a negative percentage or an empty symbol list.
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+
+    /// Operation didn't finish in time.
+    ///
+    /// This error occurs when a runner or data source doesn't answer within
+    /// the allowed time.
+    #[error("Timed out: {0}")]
+    Timeout(String),
+}
+
+impl TradingEngineError {
+    /// Name of the error variant, e.g. `"RunnerNotFound"`.
+    ///
+    /// Stable across message changes, so callers such as the web API can
+    /// report it for clients to match on.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            TradingEngineError::WebSocketError(_) => "WebSocketError",
+            TradingEngineError::TungsteniteError(_) => "TungsteniteError",
+            TradingEngineError::NotConnected => "NotConnected",
+            TradingEngineError::InvalidData(_) => "InvalidData",
+            TradingEngineError::ReconnectionFailed(_) => "ReconnectionFailed",
+            TradingEngineError::ParseError(_) => "ParseError",
+            TradingEngineError::IoError(_) => "IoError",
+            TradingEngineError::JsonError(_) => "JsonError",
+            TradingEngineError::ConfigError(_) => "ConfigError",
+            TradingEngineError::StrategyError(_) => "StrategyError",
+            TradingEngineError::LuaError(_) => "LuaError",
+            TradingEngineError::RunnerAlreadyExists(_) => "RunnerAlreadyExists",
+            TradingEngineError::RunnerNotFound(_) => "RunnerNotFound",
+            TradingEngineError::NoRunnersForSymbol(_) => "NoRunnersForSymbol",
+            TradingEngineError::ChannelClosed(_) => "ChannelClosed",
+            TradingEngineError::TaskPanic(_) => "TaskPanic",
+            TradingEngineError::InvalidTransition { .. } => "InvalidTransition",
+            TradingEngineError::InvalidConfig(_) => "InvalidConfig",
+            TradingEngineError::Timeout(_) => "Timeout",
+        }
+    }
+
+    /// Whether retrying the same work might succeed
+    ///
+    /// Lua runtime errors (a failing `error()` call, a callback error, running
+    /// out of memory) are transient. Everything else, including a strategy
+    /// returning a malformed action or invalid data, is fatal: running it
+    /// again on the same input fails the same way.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            TradingEngineError::LuaError(
+                mlua::Error::RuntimeError(_) | mlua::Error::CallbackError { .. } | mlua::Error::MemoryError(_)
+            )
+        )
+    }
 }
 
 /// Convenience type alias for Results using [`TradingEngineError`].
@@ -151,3 +223,32 @@ pub enum TradingEngineError {
 /// }
 /// ```
 pub type Result<T> = std::result::Result<T, TradingEngineError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_transition_display() {
+        let err = TradingEngineError::InvalidTransition {
+            from: State::Idle,
+            action: "ExitPosition".to_string(),
+        };
+        assert_eq!(err.to_string(), "Invalid transition: ExitPosition not allowed in Idle state");
+        assert_eq!(err.variant_name(), "InvalidTransition");
+    }
+
+    #[test]
+    fn test_invalid_config_display() {
+        let err = TradingEngineError::InvalidConfig("max_position_pct must be positive".to_string());
+        assert_eq!(err.to_string(), "Invalid configuration: max_position_pct must be positive");
+        assert_eq!(err.variant_name(), "InvalidConfig");
+    }
+
+    #[test]
+    fn test_timeout_display() {
+        let err = TradingEngineError::Timeout("runner 'btc_ema' snapshot".to_string());
+        assert_eq!(err.to_string(), "Timed out: runner 'btc_ema' snapshot");
+        assert_eq!(err.variant_name(), "Timeout");
+    }
+}
@@ -35,6 +35,7 @@
 //! ```
 
 use crate::market_data::MarketData;
+use crate::runner::RunnerStatus;
 use crate::state_machine::{Action, Position, State};
 use serde::{Deserialize, Serialize};
 
@@ -57,9 +58,12 @@ pub enum RunnerEvent {
     /// Runner was stopped
     ///
     /// Emitted when a runner is removed from the engine or crashes.
+    /// `status` is how it ended: stopped, finished or error.
     RunnerStopped {
         runner_id: String,
         reason: String,
+        #[serde(default = "stopped_status")]
+        status: RunnerStatus,
         timestamp: i64,
     },
 
@@ -96,9 +100,13 @@ pub enum RunnerEvent {
     /// Position opened
     ///
     /// Emitted when entering a long or short position.
+    /// `requested_price` is the strategy's price, `fill_price` the entry
+    /// after slippage (see `RunnerConfig::slippage_bps`).
     PositionOpened {
         runner_id: String,
         position: Position,
+        requested_price: f64,
+        fill_price: f64,
         timestamp: i64,
     },
 
@@ -110,24 +118,79 @@ pub enum RunnerEvent {
         runner_id: String,
         current_price: f64,
         unrealized_pnl: f64,
+        /// `unrealized_pnl` as a percentage of the open quantity's cost
+        #[serde(default)]
+        unrealized_pnl_pct: f64,
         timestamp: i64,
     },
 
     /// Position closed
     ///
     /// Emitted when exiting a position (manual exit or stop loss/take profit hit).
+    /// `requested_price` is the strategy's or trigger price, `exit_price` the
+    /// fill after slippage.
     PositionClosed {
         runner_id: String,
+        requested_price: f64,
         exit_price: f64,
         realized_pnl: f64,
+        /// `realized_pnl` as a percentage of the position's cost basis
+        #[serde(default)]
+        realized_pnl_pct: f64,
         reason: String,
         timestamp: i64,
     },
 
+    /// Indicator warmed up
+    ///
+    /// Emitted once per (kind, period) the strategy uses, on the first tick
+    /// the window holds enough bars for it.
+    IndicatorReady {
+        runner_id: String,
+        kind: String,
+        period: usize,
+        timestamp: i64,
+    },
+
+    /// Strategy passed and said why
+    ///
+    /// Emitted for `Action::NoActionWithReason` when
+    /// `RunnerConfig::emit_no_action_reasons` is set. Can fire every tick.
+    StrategyPassed {
+        runner_id: String,
+        state: State,
+        reason: String,
+        timestamp: i64,
+    },
+
+    /// Stop loss reached with auto-exit disabled
+    ///
+    /// Emitted on every tick the stop is breached while the position is left
+    /// open for the strategy to manage (see `RunnerConfig::auto_exit`).
+    StopLossTriggered {
+        runner_id: String,
+        price: f64,
+        stop_loss: f64,
+        timestamp: i64,
+    },
+
+    /// Take profit reached with auto-exit disabled
+    ///
+    /// Emitted on every tick the target is reached while the position is left
+    /// open for the strategy to manage (see `RunnerConfig::auto_exit`).
+    TakeProfitTriggered {
+        runner_id: String,
+        price: f64,
+        take_profit: f64,
+        timestamp: i64,
+    },
+
     /// Error occurred
     ///
     /// Emitted when a runner encounters an error.
     /// Severity indicates if the runner can continue or must stop.
+    /// `Warning` is also used for notices, such as a suppressed entry or a
+    /// rejected bar, and isn't counted in the runner's error stats.
     Error {
         runner_id: String,
         error: String,
@@ -149,10 +212,15 @@ pub enum RunnerEvent {
     },
 }
 
+/// Status for `RunnerStopped` events recorded before it was carried
+fn stopped_status() -> RunnerStatus {
+    RunnerStatus::Stopped
+}
+
 /// Error severity levels
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ErrorSeverity {
-    /// Warning - runner can continue
+    /// Warning or notice - runner can continue, not counted as an error
     Warning,
 
     /// Error - runner can continue if stop_on_error=false
@@ -174,6 +242,10 @@ impl RunnerEvent {
             RunnerEvent::PositionOpened { runner_id, .. } => runner_id,
             RunnerEvent::PositionUpdated { runner_id, .. } => runner_id,
             RunnerEvent::PositionClosed { runner_id, .. } => runner_id,
+            RunnerEvent::IndicatorReady { runner_id, .. } => runner_id,
+            RunnerEvent::StrategyPassed { runner_id, .. } => runner_id,
+            RunnerEvent::StopLossTriggered { runner_id, .. } => runner_id,
+            RunnerEvent::TakeProfitTriggered { runner_id, .. } => runner_id,
             RunnerEvent::Error { runner_id, .. } => runner_id,
             RunnerEvent::StatsUpdate { runner_id, .. } => runner_id,
         }
@@ -190,6 +262,10 @@ impl RunnerEvent {
             RunnerEvent::PositionOpened { timestamp, .. } => Some(*timestamp),
             RunnerEvent::PositionUpdated { timestamp, .. } => Some(*timestamp),
             RunnerEvent::PositionClosed { timestamp, .. } => Some(*timestamp),
+            RunnerEvent::IndicatorReady { timestamp, .. } => Some(*timestamp),
+            RunnerEvent::StrategyPassed { timestamp, .. } => Some(*timestamp),
+            RunnerEvent::StopLossTriggered { timestamp, .. } => Some(*timestamp),
+            RunnerEvent::TakeProfitTriggered { timestamp, .. } => Some(*timestamp),
             RunnerEvent::Error { timestamp, .. } => Some(*timestamp),
             RunnerEvent::StatsUpdate { timestamp, .. } => Some(*timestamp),
         }
@@ -197,11 +273,13 @@ impl RunnerEvent {
 
     /// Check if this is a high-frequency event
     ///
-    /// High-frequency events (ticks, position updates) may need throttling.
+    /// High-frequency events (ticks, position updates, passes) may need throttling.
     pub fn is_high_frequency(&self) -> bool {
         matches!(
             self,
-            RunnerEvent::TickReceived { .. } | RunnerEvent::PositionUpdated { .. }
+            RunnerEvent::TickReceived { .. }
+                | RunnerEvent::PositionUpdated { .. }
+                | RunnerEvent::StrategyPassed { .. }
         )
     }
 
@@ -219,6 +297,63 @@ impl RunnerEvent {
     }
 }
 
+/// A runner event numbered in the order the engine forwarded it
+///
+/// Sequence numbers start at 1 and increase by one per event across all
+/// runners, so a client can drop duplicates after a reconnect and notice
+/// gaps. Serialized with `seq` alongside the event's `type` and `data`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequencedEvent {
+    /// Position of the event in the engine's event stream
+    pub seq: u64,
+
+    /// The event itself
+    #[serde(flatten)]
+    pub event: RunnerEvent,
+}
+
+/// Consecutive runner events sharing a timestamp, forwarded together
+///
+/// When one tick is broadcast to many runners, their events for it arrive
+/// as a single batch instead of a burst of separate messages. See
+/// [`TradingEngine::subscribe_batched_events`](crate::runner::TradingEngine::subscribe_batched_events).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchEvent {
+    /// Timestamp shared by every event in the batch
+    pub timestamp: Option<i64>,
+
+    /// The events, in forwarding order
+    pub events: Vec<RunnerEvent>,
+}
+
+impl BatchEvent {
+    /// Split `events` into batches of consecutive events with the same timestamp
+    ///
+    /// Order is kept, so events for one timestamp that are separated by
+    /// another timestamp's end up in separate batches.
+    pub fn group(events: impl IntoIterator<Item = RunnerEvent>) -> Vec<BatchEvent> {
+        let mut batches: Vec<BatchEvent> = Vec::new();
+        for event in events {
+            let timestamp = event.timestamp();
+            match batches.last_mut() {
+                Some(batch) if batch.timestamp == timestamp => batch.events.push(event),
+                _ => batches.push(BatchEvent { timestamp, events: vec![event] }),
+            }
+        }
+        batches
+    }
+
+    /// Number of events in the batch
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Check if the batch holds no events
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -242,6 +377,41 @@ mod tests {
         assert_eq!(deserialized.runner_id(), "test_runner");
     }
 
+    #[test]
+    fn test_sequenced_event_serialization() {
+        let event = SequencedEvent {
+            seq: 42,
+            event: RunnerEvent::RunnerStarted {
+                runner_id: "test_runner".to_string(),
+                symbol: "BTCUSDT".to_string(),
+                timestamp: 1234567890,
+            },
+        };
+
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["seq"], 42);
+        assert_eq!(value["type"], "RunnerStarted");
+
+        let deserialized: SequencedEvent = serde_json::from_value(value).unwrap();
+        assert_eq!(deserialized.seq, 42);
+        assert_eq!(deserialized.event.runner_id(), "test_runner");
+    }
+
+    #[test]
+    fn test_batches_split_on_timestamp_change() {
+        let started = |runner_id: &str, timestamp| RunnerEvent::RunnerStarted {
+            runner_id: runner_id.to_string(),
+            symbol: "BTCUSDT".to_string(),
+            timestamp,
+        };
+
+        let batches = BatchEvent::group(vec![started("a", 1), started("b", 1), started("a", 2), started("b", 1)]);
+
+        let shape: Vec<(Option<i64>, usize)> = batches.iter().map(|b| (b.timestamp, b.len())).collect();
+        assert_eq!(shape, vec![(Some(1), 2), (Some(2), 1), (Some(1), 1)]);
+        assert!(BatchEvent::group(Vec::new()).is_empty());
+    }
+
     #[test]
     fn test_state_transition_event() {
         let event = RunnerEvent::StateTransition {
@@ -269,6 +439,9 @@ mod tests {
             volume: 1000,
             bid: 50045.0,
             ask: 50055.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         };
 
         let event = RunnerEvent::TickReceived {
@@ -288,6 +461,8 @@ mod tests {
         let opened = RunnerEvent::PositionOpened {
             runner_id: "btc_ema".to_string(),
             position: position.clone(),
+            requested_price: 50000.0,
+            fill_price: 50000.0,
             timestamp: 1234567890,
         };
 
@@ -295,13 +470,16 @@ mod tests {
             runner_id: "btc_ema".to_string(),
             current_price: 50500.0,
             unrealized_pnl: 50.0,
+            unrealized_pnl_pct: 1.0,
             timestamp: 1234567900,
         };
 
         let closed = RunnerEvent::PositionClosed {
             runner_id: "btc_ema".to_string(),
+            requested_price: 51000.0,
             exit_price: 51000.0,
             realized_pnl: 100.0,
+            realized_pnl_pct: 2.0,
             reason: "Take profit hit".to_string(),
             timestamp: 1234567910,
         };
@@ -311,6 +489,22 @@ mod tests {
         assert!(!closed.is_high_frequency());
     }
 
+    #[test]
+    fn test_position_events_without_pnl_pct_deserialize() {
+        // Events recorded before the percentage fields existed
+        let updated: RunnerEvent = serde_json::from_str(
+            r#"{"type":"PositionUpdated","data":{"runner_id":"btc_ema","current_price":50500.0,"unrealized_pnl":50.0,"timestamp":1234567900}}"#,
+        )
+        .unwrap();
+        assert!(matches!(updated, RunnerEvent::PositionUpdated { unrealized_pnl_pct, .. } if unrealized_pnl_pct == 0.0));
+
+        let closed: RunnerEvent = serde_json::from_str(
+            r#"{"type":"PositionClosed","data":{"runner_id":"btc_ema","requested_price":51000.0,"exit_price":51000.0,"realized_pnl":100.0,"reason":"Take profit hit","timestamp":1234567910}}"#,
+        )
+        .unwrap();
+        assert!(matches!(closed, RunnerEvent::PositionClosed { realized_pnl_pct, .. } if realized_pnl_pct == 0.0));
+    }
+
     #[test]
     fn test_error_severity() {
         let warning = RunnerEvent::Error {
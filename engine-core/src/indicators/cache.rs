@@ -0,0 +1,179 @@
+//! Indicator values shared between runners on the same symbol
+//!
+//! Runners watching a symbol see the same bars, so their strategies tend to
+//! ask for the same indicators over the same closes every tick. The cache
+//! stores each value under a fingerprint of the closes it was computed from:
+//! the first runner to ask computes it and the others read it back. Runners
+//! whose windows hold different closes (e.g. different window sizes) get
+//! different fingerprints and never share values.
+
+use crate::market_data::MarketDataWindow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+/// Distinct windows kept at once (different window sizes, runners a tick behind)
+const MAX_WINDOWS: usize = 8;
+
+/// An indicator and its parameters, e.g. `("bollinger", 20, 2.0)`
+///
+/// The extra parameter is stored as its bit pattern (0 when unused).
+type IndicatorKey = (&'static str, usize, u64);
+
+/// Indicator values shared between the runners on one symbol
+///
+/// Cloning is cheap and every clone sees the same values.
+///
+/// # Examples
+///
+/// ```
+/// use trading_engine::indicators::IndicatorCache;
+/// use trading_engine::market_data::MarketDataWindow;
+///
+/// let cache = IndicatorCache::new();
+/// let window = MarketDataWindow::new(10);
+///
+/// let values = cache.for_window(&window);
+/// assert_eq!(values.get_or_compute("sma", 5, 0.0, || Some(1.0)), Some(1.0));
+/// // Computed once, read back after that
+/// assert_eq!(values.get_or_compute("sma", 5, 0.0, || Some(2.0)), Some(1.0));
+/// assert_eq!((cache.hits(), cache.misses()), (1, 1));
+/// ```
+#[derive(Clone, Default)]
+pub struct IndicatorCache {
+    inner: Arc<Mutex<CacheInner>>,
+}
+
+#[derive(Default)]
+struct CacheInner {
+    /// Values per window fingerprint, most recent last
+    windows: VecDeque<(u64, HashMap<IndicatorKey, Option<f64>>)>,
+    hits: u64,
+    misses: u64,
+}
+
+impl IndicatorCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Values for the closes currently in `window`
+    pub fn for_window(&self, window: &MarketDataWindow) -> WindowIndicators {
+        WindowIndicators {
+            cache: self.clone(),
+            fingerprint: close_fingerprint(window),
+        }
+    }
+
+    /// Lookups answered from the cache
+    pub fn hits(&self) -> u64 {
+        self.inner.lock().unwrap().hits
+    }
+
+    /// Lookups that had to compute the value
+    pub fn misses(&self) -> u64 {
+        self.inner.lock().unwrap().misses
+    }
+}
+
+/// An [`IndicatorCache`] bound to one window's closes
+#[derive(Clone)]
+pub struct WindowIndicators {
+    cache: IndicatorCache,
+    fingerprint: u64,
+}
+
+impl WindowIndicators {
+    /// Get the cached value of an indicator, computing and storing it on a miss
+    ///
+    /// `param` is any extra parameter besides the period (e.g. the Bollinger
+    /// standard deviations), or 0.0.
+    pub fn get_or_compute(
+        &self,
+        kind: &'static str,
+        period: usize,
+        param: f64,
+        compute: impl FnOnce() -> Option<f64>,
+    ) -> Option<f64> {
+        let key = (kind, period, param.to_bits());
+
+        {
+            let mut inner = self.cache.inner.lock().unwrap();
+            let cached = inner
+                .windows
+                .iter()
+                .find(|(fingerprint, _)| *fingerprint == self.fingerprint)
+                .and_then(|(_, values)| values.get(&key).copied());
+            if let Some(value) = cached {
+                inner.hits += 1;
+                return value;
+            }
+            inner.misses += 1;
+        }
+
+        // Computed without holding the lock so other runners aren't blocked
+        let value = compute();
+
+        let mut inner = self.cache.inner.lock().unwrap();
+        let index = match inner.windows.iter().position(|(fingerprint, _)| *fingerprint == self.fingerprint) {
+            Some(index) => index,
+            None => {
+                if inner.windows.len() == MAX_WINDOWS {
+                    inner.windows.pop_front();
+                }
+                inner.windows.push_back((self.fingerprint, HashMap::new()));
+                inner.windows.len() - 1
+            }
+        };
+        inner.windows[index].1.insert(key, value);
+        value
+    }
+}
+
+/// Hash of the window's closes, in order
+fn close_fingerprint(window: &MarketDataWindow) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    window.len().hash(&mut hasher);
+    for bar in window.iter() {
+        bar.close.to_bits().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::MarketData;
+
+    fn window(closes: &[f64], size: usize) -> MarketDataWindow {
+        let mut window = MarketDataWindow::new(size);
+        for (i, &close) in closes.iter().enumerate() {
+            window.push(MarketData::from_trade("BTCUSDT", close, 1.0, i as i64));
+        }
+        window
+    }
+
+    #[test]
+    fn test_different_closes_dont_share_values() {
+        let cache = IndicatorCache::new();
+        let closes = [1.0, 2.0, 3.0, 4.0];
+
+        let full = cache.for_window(&window(&closes, 10));
+        let same = cache.for_window(&window(&closes, 20));
+        let shorter = cache.for_window(&window(&closes, 3));
+
+        assert_eq!(full.get_or_compute("sma", 2, 0.0, || Some(3.5)), Some(3.5));
+        // Same closes in a bigger window: shared
+        assert_eq!(same.get_or_compute("sma", 2, 0.0, || None), Some(3.5));
+        // The smaller window dropped a bar: computed separately
+        assert_eq!(shorter.get_or_compute("sma", 2, 0.0, || Some(3.0)), Some(3.0));
+        // Other parameters are separate entries
+        assert_eq!(full.get_or_compute("bollinger", 2, 2.0, || Some(0.5)), Some(0.5));
+        assert_eq!(full.get_or_compute("bollinger", 2, 1.0, || Some(0.7)), Some(0.7));
+
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 4);
+    }
+}
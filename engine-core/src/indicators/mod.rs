@@ -7,7 +7,14 @@
 //!
 //! - **Moving Averages**: SMA, EMA
 //! - **Momentum**: RSI, MACD
-//! - **Volatility**: Bollinger Bands
+//! - **Volatility**: Bollinger Bands, ATR
+//! - **Trend**: Linear regression slope and endpoint
+//! - **Signals**: Price/oscillator divergence
+//! - **Custom**: User-defined indicators via [`IndicatorRegistry`]
+//!
+//! Each function handles its warmup bars in its own way; [`compute`] runs
+//! any of the moving averages, RSI or Bollinger Bands with one
+//! [`InsufficientDataPolicy`] instead.
 //!
 //! # Examples
 //!
@@ -22,6 +29,15 @@
 /// OCaml indicator bridge (for verification/testing)
 pub mod ocaml;
 
+/// Custom indicators registered at runtime
+pub mod registry;
+
+/// Indicator values shared by the runners on a symbol
+pub mod cache;
+
+pub use cache::{IndicatorCache, WindowIndicators};
+pub use registry::{CustomIndicator, IndicatorRegistry};
+
 /// Calculate Simple Moving Average (SMA)
 ///
 /// Returns a vector of averages for each window of size `period`.
@@ -212,6 +228,71 @@ pub struct BollingerBands {
     pub lower: Vec<f64>,
 }
 
+impl BollingerBands {
+    /// Calculate %B for each bar
+    ///
+    /// %B = (price - lower) / (upper - lower): 0.0 at the lower band, 1.0 at
+    /// the upper band, and outside [0, 1] when price breaks out of the bands.
+    /// Where the bands are flat (zero width, including the warmup period),
+    /// %B is 0.5.
+    ///
+    /// # Arguments
+    ///
+    /// * `prices` - Prices aligned with the bands (usually the same data used to build them)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::indicators::bollinger_bands;
+    ///
+    /// let prices = vec![100.0, 102.0, 101.0, 103.0, 104.0];
+    /// let bb = bollinger_bands(&prices, 3, 2.0);
+    /// let percent_b = bb.percent_b(&prices);
+    /// assert_eq!(percent_b.len(), 5);
+    /// assert!(percent_b[4] > 0.5); // Closing near the upper band
+    /// ```
+    pub fn percent_b(&self, prices: &[f64]) -> Vec<f64> {
+        prices.iter()
+            .zip(self.upper.iter().zip(self.lower.iter()))
+            .map(|(&price, (&upper, &lower))| {
+                let width = upper - lower;
+                if width.abs() < f64::EPSILON {
+                    0.5
+                } else {
+                    (price - lower) / width
+                }
+            })
+            .collect()
+    }
+
+    /// Calculate bandwidth for each bar
+    ///
+    /// Bandwidth = (upper - lower) / middle. Low values indicate a squeeze.
+    /// Returns 0.0 where the middle band is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::indicators::bollinger_bands;
+    ///
+    /// let prices = vec![100.0; 5];
+    /// let bb = bollinger_bands(&prices, 3, 2.0);
+    /// assert_eq!(bb.bandwidth(), vec![0.0; 5]); // Flat prices, no width
+    /// ```
+    pub fn bandwidth(&self) -> Vec<f64> {
+        self.upper.iter()
+            .zip(self.middle.iter().zip(self.lower.iter()))
+            .map(|(&upper, (&middle, &lower))| {
+                if middle == 0.0 {
+                    0.0
+                } else {
+                    (upper - lower) / middle
+                }
+            })
+            .collect()
+    }
+}
+
 /// Calculate Bollinger Bands
 ///
 /// Returns upper band, middle band (SMA), and lower band.
@@ -265,6 +346,466 @@ pub fn bollinger_bands(data: &[f64], period: usize, num_std_dev: f64) -> Bolling
     BollingerBands { upper, middle, lower }
 }
 
+/// Calculate Average True Range (ATR)
+///
+/// The true range of a bar is the largest of `high - low`,
+/// `|high - previous close|` and `|low - previous close|`, so it needs the
+/// bar before it. The first ATR is the mean of the first `period` true
+/// ranges; later values use Wilder's smoothing.
+///
+/// Returns one value per bar from bar `period` onwards
+/// (`len - period` values), or an empty vector if `period` is 0, the slices
+/// differ in length, or there are `period` bars or fewer.
+///
+/// # Arguments
+///
+/// * `high` - Bar highs
+/// * `low` - Bar lows
+/// * `close` - Bar closes
+/// * `period` - Smoothing period (typically 14)
+///
+/// # Examples
+///
+/// ```
+/// use trading_engine::indicators::average_true_range;
+///
+/// let high = vec![11.0, 12.0, 13.0, 14.0];
+/// let low = vec![9.0, 10.0, 11.0, 12.0];
+/// let close = vec![10.0, 11.0, 12.0, 13.0];
+/// let atr = average_true_range(&high, &low, &close, 2);
+/// assert_eq!(atr, vec![2.0, 2.0]);
+/// ```
+pub fn average_true_range(high: &[f64], low: &[f64], close: &[f64], period: usize) -> Vec<f64> {
+    let len = close.len();
+    if period == 0 || high.len() != len || low.len() != len || len <= period {
+        return vec![];
+    }
+
+    let true_ranges: Vec<f64> = (1..len)
+        .map(|i| {
+            let prev_close = close[i - 1];
+            (high[i] - low[i])
+                .max((high[i] - prev_close).abs())
+                .max((low[i] - prev_close).abs())
+        })
+        .collect();
+
+    let mut atr = true_ranges[..period].iter().sum::<f64>() / period as f64;
+    let mut result = Vec::with_capacity(len - period);
+    result.push(atr);
+    for &tr in &true_ranges[period..] {
+        atr = (atr * (period - 1) as f64 + tr) / period as f64;
+        result.push(atr);
+    }
+
+    result
+}
+
+/// How [`compute`] fills bars before an indicator has enough data
+///
+/// The individual indicator functions treat their warmup differently, which
+/// makes their outputs hard to line up:
+///
+/// | Indicator | Warmup output | Too few prices |
+/// |-----------|---------------|----------------|
+/// | [`simple_moving_average`] | omitted (`len - period + 1` values) | empty |
+/// | [`exponential_moving_average`] | first `period` bars hold the SMA seed | empty |
+/// | [`relative_strength_index`] | first `period` bars are 50.0 | empty (needs `period + 1` prices) |
+/// | [`bollinger_bands`] | first `period - 1` bars hold the price | all bands 0.0 |
+///
+/// [`compute`] applies one of these policies to every indicator instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InsufficientDataPolicy {
+    /// Leave the warmup bars out, so the output only holds real values
+    #[default]
+    EmptyVec,
+    /// One value per price, with `f64::NAN` for the warmup bars
+    FillNan,
+    /// One value per price, with a neutral value for the warmup bars:
+    /// 50.0 for RSI and the price itself for the price-following indicators
+    FillNeutral,
+}
+
+/// Bollinger band selected by [`Indicator::Bollinger`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Band {
+    Upper,
+    Middle,
+    Lower,
+}
+
+/// An indicator and its parameters, for [`compute`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Indicator {
+    Sma(usize),
+    Ema(usize),
+    Rsi(usize),
+    Bollinger {
+        period: usize,
+        num_std_dev: f64,
+        band: Band,
+    },
+}
+
+impl Indicator {
+    /// Number of leading bars without a real value
+    pub fn warmup(&self) -> usize {
+        match *self {
+            Indicator::Sma(period) | Indicator::Ema(period) => period.saturating_sub(1),
+            Indicator::Rsi(period) => period,
+            Indicator::Bollinger { period, .. } => period.saturating_sub(1),
+        }
+    }
+
+    /// Name the indicator goes by in [`precompute_indicators`]
+    ///
+    /// `sma_20`, `ema_20`, `rsi_14`, or `bb_upper_20_2` for a Bollinger band.
+    pub fn key(&self) -> String {
+        match *self {
+            Indicator::Sma(period) => format!("sma_{}", period),
+            Indicator::Ema(period) => format!("ema_{}", period),
+            Indicator::Rsi(period) => format!("rsi_{}", period),
+            Indicator::Bollinger { period, num_std_dev, band } => {
+                let band = match band {
+                    Band::Upper => "upper",
+                    Band::Middle => "middle",
+                    Band::Lower => "lower",
+                };
+                format!("bb_{}_{}_{}", band, period, num_std_dev)
+            }
+        }
+    }
+
+    /// Value used for a warmup bar at `price` under [`InsufficientDataPolicy::FillNeutral`]
+    pub fn neutral(&self, price: f64) -> f64 {
+        match self {
+            Indicator::Rsi(_) => 50.0,
+            _ => price,
+        }
+    }
+
+    /// Values after the warmup; empty if there are too few prices
+    fn real_values(&self, data: &[f64]) -> Vec<f64> {
+        let mut values = match *self {
+            // Already starts at the first full window
+            Indicator::Sma(period) => return simple_moving_average(data, period),
+            Indicator::Ema(period) => exponential_moving_average(data, period),
+            Indicator::Rsi(period) => relative_strength_index(data, period),
+            Indicator::Bollinger { period, num_std_dev, band } => {
+                if period == 0 || period > data.len() {
+                    return vec![];
+                }
+                let bands = bollinger_bands(data, period, num_std_dev);
+                match band {
+                    Band::Upper => bands.upper,
+                    Band::Middle => bands.middle,
+                    Band::Lower => bands.lower,
+                }
+            }
+        };
+
+        if values.is_empty() {
+            return values;
+        }
+        values.split_off(self.warmup())
+    }
+}
+
+/// Calculate an indicator with a uniform warmup policy
+///
+/// Under [`InsufficientDataPolicy::EmptyVec`] the output holds only real
+/// values and is empty when there are too few prices. The fill policies
+/// always return one value per price, so outputs of different indicators
+/// line up with each other and with `data`.
+///
+/// # Examples
+///
+/// ```
+/// use trading_engine::indicators::{compute, Indicator, InsufficientDataPolicy};
+///
+/// let prices = vec![1.0, 2.0, 3.0, 4.0];
+///
+/// let sma = compute(Indicator::Sma(3), &prices, InsufficientDataPolicy::EmptyVec);
+/// assert_eq!(sma, vec![2.0, 3.0]);
+///
+/// let sma = compute(Indicator::Sma(3), &prices, InsufficientDataPolicy::FillNeutral);
+/// assert_eq!(sma, vec![1.0, 2.0, 2.0, 3.0]);
+///
+/// let rsi = compute(Indicator::Rsi(3), &prices, InsufficientDataPolicy::FillNan);
+/// assert_eq!(rsi.len(), 4);
+/// assert!(rsi[2].is_nan());
+/// assert!(rsi[3] > 99.0); // Only gains
+/// ```
+pub fn compute(indicator: Indicator, data: &[f64], policy: InsufficientDataPolicy) -> Vec<f64> {
+    let values = indicator.real_values(data);
+    let missing = data.len() - values.len();
+
+    match policy {
+        InsufficientDataPolicy::EmptyVec => values,
+        InsufficientDataPolicy::FillNan => {
+            let mut filled = vec![f64::NAN; missing];
+            filled.extend(values);
+            filled
+        }
+        InsufficientDataPolicy::FillNeutral => {
+            let mut filled: Vec<f64> = data[..missing].iter().map(|&price| indicator.neutral(price)).collect();
+            filled.extend(values);
+            filled
+        }
+    }
+}
+
+/// Calculate whole-series indicators over the closes of `data` in one pass
+///
+/// Each series is keyed by [`Indicator::key`] and holds one value per bar,
+/// `f64::NAN` during warmup, so index `i` lines up with `data[i]`. Backtests
+/// use this to compute indicators once instead of on every tick; the values
+/// match what a strategy would compute from a window holding every bar so far.
+///
+/// # Examples
+///
+/// ```
+/// use trading_engine::indicators::{precompute_indicators, Indicator};
+/// use trading_engine::MarketData;
+///
+/// let bars: Vec<MarketData> = [1.0, 2.0, 3.0, 4.0]
+///     .iter()
+///     .map(|&close| MarketData::from_trade("BTCUSDT", close, 1.0, 0))
+///     .collect();
+///
+/// let series = precompute_indicators(&bars, &[Indicator::Sma(3)]);
+/// assert!(series["sma_3"][1].is_nan());
+/// assert_eq!(series["sma_3"][3], 3.0);
+/// ```
+pub fn precompute_indicators(
+    data: &[crate::MarketData],
+    spec: &[Indicator],
+) -> std::collections::HashMap<String, Vec<f64>> {
+    let closes: Vec<f64> = data.iter().map(|bar| bar.close).collect();
+    spec.iter()
+        .map(|indicator| (indicator.key(), compute(*indicator, &closes, InsufficientDataPolicy::FillNan)))
+        .collect()
+}
+
+/// Fit a least-squares line to a window, returning (slope, endpoint value)
+///
+/// x runs 0..n-1 over the window, so the endpoint is the fitted value at the
+/// most recent bar.
+fn linreg_fit(window: &[f64]) -> (f64, f64) {
+    let n = window.len() as f64;
+    let x_mean = (n - 1.0) / 2.0;
+    let y_mean = window.iter().sum::<f64>() / n;
+
+    let covariance: f64 = window.iter()
+        .enumerate()
+        .map(|(i, &y)| (i as f64 - x_mean) * (y - y_mean))
+        .sum();
+    let variance = n * (n * n - 1.0) / 12.0;
+
+    let slope = covariance / variance;
+    (slope, y_mean + slope * (n - 1.0 - x_mean))
+}
+
+/// Calculate Linear Regression Slope
+///
+/// Fits a least-squares line over each trailing window of `period` prices and
+/// returns its slope, in price units per bar. Positive values indicate an uptrend.
+/// Output length = `data.len() - period + 1`. A period below 2 has no slope
+/// and returns an empty vector.
+///
+/// # Arguments
+///
+/// * `data` - Price data
+/// * `period` - Window size for the fit
+///
+/// # Examples
+///
+/// ```
+/// use trading_engine::indicators::linreg_slope;
+///
+/// let prices = vec![1.0, 3.0, 5.0, 7.0, 9.0];
+/// let slope = linreg_slope(&prices, 3);
+/// assert_eq!(slope, vec![2.0, 2.0, 2.0]);
+/// ```
+pub fn linreg_slope(data: &[f64], period: usize) -> Vec<f64> {
+    if period < 2 || period > data.len() {
+        return vec![];
+    }
+
+    data.windows(period)
+        .map(|window| linreg_fit(window).0)
+        .collect()
+}
+
+/// Calculate Linear Regression Value
+///
+/// Returns the fitted value of the least-squares line at the last bar of each
+/// trailing window of `period` prices, a smoothed price that lags less than an SMA.
+/// Output length = `data.len() - period + 1`. A period below 2 returns an
+/// empty vector.
+///
+/// # Examples
+///
+/// ```
+/// use trading_engine::indicators::linreg_value;
+///
+/// let prices = vec![1.0, 3.0, 5.0, 7.0, 9.0];
+/// let value = linreg_value(&prices, 3);
+/// assert_eq!(value, vec![5.0, 7.0, 9.0]);
+/// ```
+pub fn linreg_value(data: &[f64], period: usize) -> Vec<f64> {
+    if period < 2 || period > data.len() {
+        return vec![];
+    }
+
+    data.windows(period)
+        .map(|window| linreg_fit(window).1)
+        .collect()
+}
+
+/// Disagreement between price swings and an oscillator's swings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Divergence {
+    /// Price makes a lower low while the oscillator makes a higher low
+    RegularBullish,
+    /// Price makes a higher high while the oscillator makes a lower high
+    RegularBearish,
+    /// Price makes a higher low while the oscillator makes a lower low
+    HiddenBullish,
+    /// Price makes a lower high while the oscillator makes a higher high
+    HiddenBearish,
+}
+
+impl Divergence {
+    /// Check if this divergence points to higher prices
+    pub fn is_bullish(&self) -> bool {
+        matches!(self, Divergence::RegularBullish | Divergence::HiddenBullish)
+    }
+
+    /// Name used in Lua (`"regular_bullish"`, `"hidden_bearish"`, ...)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Divergence::RegularBullish => "regular_bullish",
+            Divergence::RegularBearish => "regular_bearish",
+            Divergence::HiddenBullish => "hidden_bullish",
+            Divergence::HiddenBearish => "hidden_bearish",
+        }
+    }
+}
+
+/// Detect divergence between prices and an oscillator
+///
+/// Looks at the last `lookback` bars of both series, which are aligned at
+/// their ends, and compares the two most recent swing highs and the two most
+/// recent swing lows of price against the oscillator at the same bars. A
+/// swing point needs a bar on each side, so the latest bar can't be one.
+/// When both highs and lows diverge, the pair with the later swing wins.
+///
+/// Returns `None` when there is no divergence, fewer than two swings of a
+/// kind, or `lookback` is below 3 or longer than either series.
+///
+/// # Arguments
+///
+/// * `prices` - Price data
+/// * `oscillator` - Oscillator values (e.g., RSI), one per price
+/// * `lookback` - Number of recent bars to search
+///
+/// # Examples
+///
+/// ```
+/// use trading_engine::indicators::{detect_divergence, Divergence};
+///
+/// // Higher high in price, lower high in the oscillator
+/// let prices = vec![1.0, 3.0, 2.0, 4.0, 3.0];
+/// let rsi = vec![40.0, 70.0, 50.0, 60.0, 45.0];
+/// assert_eq!(detect_divergence(&prices, &rsi, 5), Some(Divergence::RegularBearish));
+/// ```
+pub fn detect_divergence(prices: &[f64], oscillator: &[f64], lookback: usize) -> Option<Divergence> {
+    if lookback < 3 || lookback > prices.len() || lookback > oscillator.len() {
+        return None;
+    }
+
+    let prices = &prices[prices.len() - lookback..];
+    let oscillator = &oscillator[oscillator.len() - lookback..];
+
+    let bearish = last_two(&swing_points(prices, |a, b| a > b)).and_then(|(prev, last)| {
+        let divergence = if prices[last] > prices[prev] && oscillator[last] < oscillator[prev] {
+            Divergence::RegularBearish
+        } else if prices[last] < prices[prev] && oscillator[last] > oscillator[prev] {
+            Divergence::HiddenBearish
+        } else {
+            return None;
+        };
+        Some((last, divergence))
+    });
+
+    let bullish = last_two(&swing_points(prices, |a, b| a < b)).and_then(|(prev, last)| {
+        let divergence = if prices[last] < prices[prev] && oscillator[last] > oscillator[prev] {
+            Divergence::RegularBullish
+        } else if prices[last] > prices[prev] && oscillator[last] < oscillator[prev] {
+            Divergence::HiddenBullish
+        } else {
+            return None;
+        };
+        Some((last, divergence))
+    });
+
+    match (bearish, bullish) {
+        (Some(high), Some(low)) => Some(if high.0 > low.0 { high.1 } else { low.1 }),
+        (high, low) => high.or(low).map(|(_, divergence)| divergence),
+    }
+}
+
+/// Combine signals into one weighted score in [-1, 1]
+///
+/// Each pair is `(value, weight)`, with values expected roughly in [-1, 1]
+/// (e.g. -1 fully bearish, 1 fully bullish). Weights are normalized by their
+/// absolute total, so only their ratios matter, and the result is clamped to
+/// [-1, 1]. Pairs with a non-finite value or weight are skipped; with no
+/// usable weight the score is 0.0.
+///
+/// # Examples
+///
+/// ```
+/// use trading_engine::indicators::blend;
+///
+/// // RSI leaning bullish, MACD strongly bullish, trend bearish
+/// let score = blend(&[(0.5, 1.0), (1.0, 2.0), (-1.0, 1.0)]);
+/// assert_eq!(score, 0.375);
+///
+/// // Out-of-range inputs can't push the score past the bounds
+/// assert_eq!(blend(&[(3.0, 1.0)]), 1.0);
+/// assert_eq!(blend(&[]), 0.0);
+/// ```
+pub fn blend(signals: &[(f64, f64)]) -> f64 {
+    let usable = signals
+        .iter()
+        .filter(|(value, weight)| value.is_finite() && weight.is_finite());
+
+    let total_weight: f64 = usable.clone().map(|(_, weight)| weight.abs()).sum();
+    if total_weight == 0.0 {
+        return 0.0;
+    }
+
+    let weighted: f64 = usable.map(|(value, weight)| value * weight).sum();
+    (weighted / total_weight).clamp(-1.0, 1.0)
+}
+
+/// Indices of swing points: bars beyond the previous bar and not passed by the next
+fn swing_points(data: &[f64], beyond: impl Fn(f64, f64) -> bool) -> Vec<usize> {
+    (1..data.len().saturating_sub(1))
+        .filter(|&i| beyond(data[i], data[i - 1]) && !beyond(data[i + 1], data[i]))
+        .collect()
+}
+
+/// The last two entries of a slice, in order
+fn last_two(indices: &[usize]) -> Option<(usize, usize)> {
+    match indices {
+        [.., prev, last] => Some((*prev, *last)),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -283,6 +824,24 @@ mod tests {
         assert_float_eq(result[2], 4.0, 0.001);
     }
 
+    #[test]
+    fn test_atr() {
+        // A gap up makes the third true range |high - previous close|
+        let high = vec![10.0, 11.0, 15.0, 15.5, 16.0];
+        let low = vec![9.0, 10.0, 14.0, 14.5, 15.0];
+        let close = vec![9.5, 10.5, 14.5, 15.0, 15.5];
+        let result = average_true_range(&high, &low, &close, 2);
+
+        // True ranges: 1.5, 4.5, 1.0, 1.0
+        assert_eq!(result.len(), 3);
+        assert_float_eq(result[0], 3.0, 0.001);
+        assert_float_eq(result[1], 2.0, 0.001);
+        assert_float_eq(result[2], 1.5, 0.001);
+
+        assert!(average_true_range(&high, &low, &close, 5).is_empty());
+        assert!(average_true_range(&high, &low[..4], &close, 2).is_empty());
+    }
+
     #[test]
     fn test_ema() {
         let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
@@ -339,4 +898,213 @@ mod tests {
             assert!(result.lower[i] < result.middle[i]);
         }
     }
+
+    #[test]
+    fn test_bollinger_percent_b() {
+        let bands = BollingerBands {
+            upper: vec![110.0, 110.0, 110.0, 110.0],
+            middle: vec![100.0, 100.0, 100.0, 100.0],
+            lower: vec![90.0, 90.0, 90.0, 90.0],
+        };
+        let result = bands.percent_b(&[90.0, 100.0, 110.0, 115.0]);
+        assert_eq!(result, vec![0.0, 0.5, 1.0, 1.25]);
+    }
+
+    #[test]
+    fn test_bollinger_bandwidth() {
+        let bands = BollingerBands {
+            upper: vec![110.0, 105.0],
+            middle: vec![100.0, 100.0],
+            lower: vec![90.0, 95.0],
+        };
+        let result = bands.bandwidth();
+        assert_float_eq(result[0], 0.2, 1e-12);
+        assert_float_eq(result[1], 0.1, 1e-12);
+    }
+
+    #[test]
+    fn test_bollinger_flat_bands() {
+        // Constant prices give zero-width bands everywhere
+        let data = vec![100.0; 10];
+        let result = bollinger_bands(&data, 5, 2.0);
+
+        assert_eq!(result.percent_b(&data), vec![0.5; 10]);
+        assert_eq!(result.bandwidth(), vec![0.0; 10]);
+        assert!(result.percent_b(&data).iter().all(|v| v.is_finite()));
+    }
+
+    #[test]
+    fn test_compute_policies_too_short() {
+        let prices = [1.0, 2.0, 3.0];
+        let indicators = [
+            Indicator::Sma(5),
+            Indicator::Ema(5),
+            Indicator::Rsi(5),
+            Indicator::Bollinger { period: 5, num_std_dev: 2.0, band: Band::Upper },
+        ];
+
+        for indicator in indicators {
+            assert!(compute(indicator, &prices, InsufficientDataPolicy::EmptyVec).is_empty());
+
+            let nan = compute(indicator, &prices, InsufficientDataPolicy::FillNan);
+            assert_eq!(nan.len(), 3);
+            assert!(nan.iter().all(|v| v.is_nan()), "{:?}", indicator);
+
+            let neutral = compute(indicator, &prices, InsufficientDataPolicy::FillNeutral);
+            let expected: Vec<f64> = prices.iter().map(|&p| indicator.neutral(p)).collect();
+            assert_eq!(neutral, expected, "{:?}", indicator);
+        }
+        assert_eq!(compute(Indicator::Rsi(5), &prices, InsufficientDataPolicy::FillNeutral), vec![50.0; 3]);
+    }
+
+    #[test]
+    fn test_compute_policies_align_outputs() {
+        let prices: Vec<f64> = (0..10).map(|i| 100.0 + i as f64).collect();
+        let indicators = [
+            Indicator::Sma(4),
+            Indicator::Ema(4),
+            Indicator::Rsi(4),
+            Indicator::Bollinger { period: 4, num_std_dev: 2.0, band: Band::Middle },
+        ];
+
+        for indicator in indicators {
+            let warmup = indicator.warmup();
+            let values = compute(indicator, &prices, InsufficientDataPolicy::EmptyVec);
+            assert_eq!(values.len(), prices.len() - warmup, "{:?}", indicator);
+
+            let nan = compute(indicator, &prices, InsufficientDataPolicy::FillNan);
+            assert_eq!(nan.len(), prices.len());
+            assert!(nan[..warmup].iter().all(|v| v.is_nan()));
+            assert_eq!(&nan[warmup..], &values[..]);
+
+            let neutral = compute(indicator, &prices, InsufficientDataPolicy::FillNeutral);
+            assert_eq!(&neutral[warmup..], &values[..]);
+        }
+
+        // The Bollinger middle band is the SMA
+        assert_eq!(
+            compute(indicators[0], &prices, InsufficientDataPolicy::FillNan)[3..],
+            compute(indicators[3], &prices, InsufficientDataPolicy::FillNan)[3..]
+        );
+    }
+
+    #[test]
+    fn test_precomputed_ema_matches_streaming() {
+        let closes = [100.0, 102.0, 101.0, 105.0, 107.0, 104.0, 103.0, 108.0, 110.0, 109.0];
+        let bars: Vec<crate::MarketData> = closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| crate::MarketData::from_trade("BTCUSDT", close, 1.0, i as i64 * 1000))
+            .collect();
+
+        let series = precompute_indicators(&bars, &[Indicator::Ema(4), Indicator::Rsi(3)]);
+        assert_eq!(series.len(), 2);
+        let ema = &series["ema_4"];
+        assert_eq!(ema.len(), closes.len());
+
+        // Each index matches the EMA a strategy computes from the bars seen so far
+        for i in 0..closes.len() {
+            let streaming = if i + 1 >= 4 {
+                exponential_moving_average(&closes[..=i], 4).last().copied()
+            } else {
+                None
+            };
+            match streaming {
+                Some(value) => assert_float_eq(ema[i], value, 1e-9),
+                None => assert!(ema[i].is_nan(), "index {}", i),
+            }
+        }
+    }
+
+    #[test]
+    fn test_linreg_slope_linear_series() {
+        // y = 2.5x + 10
+        let data: Vec<f64> = (0..20).map(|i| 10.0 + 2.5 * i as f64).collect();
+        let slope = linreg_slope(&data, 5);
+        assert_eq!(slope.len(), 16);
+        for value in slope {
+            assert_float_eq(value, 2.5, 1e-9);
+        }
+
+        let fitted = linreg_value(&data, 5);
+        assert_float_eq(*fitted.last().unwrap(), *data.last().unwrap(), 1e-9);
+    }
+
+    #[test]
+    fn test_linreg_slope_flat_series() {
+        let data = vec![100.0; 10];
+        for value in linreg_slope(&data, 5) {
+            assert_float_eq(value, 0.0, 1e-9);
+        }
+        assert!(linreg_slope(&data, 1).is_empty());
+        assert!(linreg_slope(&data, 11).is_empty());
+    }
+
+    #[test]
+    fn test_divergence_regular_bearish() {
+        let prices = vec![1.0, 3.0, 2.0, 4.0, 3.0];
+        let oscillator = vec![10.0, 50.0, 30.0, 40.0, 20.0];
+        assert_eq!(detect_divergence(&prices, &oscillator, 5), Some(Divergence::RegularBearish));
+    }
+
+    #[test]
+    fn test_divergence_hidden_bearish() {
+        let prices = vec![1.0, 4.0, 2.0, 3.0, 1.0];
+        let oscillator = vec![10.0, 40.0, 20.0, 50.0, 10.0];
+        assert_eq!(detect_divergence(&prices, &oscillator, 5), Some(Divergence::HiddenBearish));
+    }
+
+    #[test]
+    fn test_divergence_regular_bullish() {
+        let prices = vec![5.0, 2.0, 4.0, 1.0, 3.0];
+        let oscillator = vec![50.0, 20.0, 40.0, 30.0, 45.0];
+        let divergence = detect_divergence(&prices, &oscillator, 5);
+        assert_eq!(divergence, Some(Divergence::RegularBullish));
+        assert!(divergence.unwrap().is_bullish());
+    }
+
+    #[test]
+    fn test_divergence_hidden_bullish() {
+        let prices = vec![5.0, 1.0, 4.0, 2.0, 3.0];
+        let oscillator = vec![50.0, 30.0, 40.0, 20.0, 45.0];
+        assert_eq!(detect_divergence(&prices, &oscillator, 5), Some(Divergence::HiddenBullish));
+    }
+
+    #[test]
+    fn test_divergence_latest_swing_wins() {
+        // Regular bearish on the highs (bars 1, 3), then regular bullish on
+        // the lows (bars 2, 4) completes later
+        let prices = vec![1.0, 3.0, 2.0, 4.0, 1.0, 2.0];
+        let oscillator = vec![10.0, 50.0, 20.0, 40.0, 30.0, 35.0];
+        assert_eq!(detect_divergence(&prices, &oscillator, 6), Some(Divergence::RegularBullish));
+    }
+
+    #[test]
+    fn test_blend_weights_and_clamps() {
+        // (0.8 * 3 + -0.4 * 1) / 4
+        assert_float_eq(blend(&[(0.8, 3.0), (-0.4, 1.0)]), 0.5, 1e-12);
+        // Only ratios of weights matter
+        assert_float_eq(blend(&[(0.8, 30.0), (-0.4, 10.0)]), 0.5, 1e-12);
+        // Over-range signals are clamped
+        assert_eq!(blend(&[(2.5, 1.0), (1.5, 1.0)]), 1.0);
+        assert_eq!(blend(&[(-4.0, 2.0)]), -1.0);
+        // Unusable pairs are ignored
+        assert_eq!(blend(&[(f64::NAN, 5.0), (0.2, 1.0)]), 0.2);
+        assert_eq!(blend(&[(0.5, 0.0)]), 0.0);
+    }
+
+    #[test]
+    fn test_divergence_none() {
+        // Oscillator confirms price
+        let prices = vec![1.0, 3.0, 2.0, 4.0, 3.0];
+        assert_eq!(detect_divergence(&prices, &prices, 5), None);
+
+        // Only the last 3 bars: a single swing high
+        let oscillator = vec![10.0, 50.0, 30.0, 40.0, 20.0];
+        assert_eq!(detect_divergence(&prices, &oscillator, 3), None);
+
+        // Lookback too short or too long
+        assert_eq!(detect_divergence(&prices, &oscillator, 2), None);
+        assert_eq!(detect_divergence(&prices, &oscillator, 6), None);
+    }
 }
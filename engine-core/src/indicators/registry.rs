@@ -0,0 +1,77 @@
+//! Runtime registry of custom indicators
+//!
+//! Lets callers add indicators without changing the crate. Each indicator is a
+//! function from the runner's market data window to a value, available to Lua
+//! strategies as `indicators.custom(name)`.
+
+use crate::market_data::MarketDataWindow;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// A custom indicator: computes a value from the market data window, or
+/// `None` if there isn't enough data yet
+pub type CustomIndicator = Box<dyn Fn(&MarketDataWindow) -> Option<f64> + Send + Sync>;
+
+/// Named custom indicators shared between an engine and its runners
+///
+/// Cloning is cheap and every clone sees the same indicators, so indicators
+/// registered after a runner was created are still visible to it.
+///
+/// # Examples
+///
+/// ```
+/// use trading_engine::indicators::IndicatorRegistry;
+/// use trading_engine::market_data::MarketDataWindow;
+///
+/// let registry = IndicatorRegistry::new();
+/// registry.register("bar_count", Box::new(|window| Some(window.len() as f64)));
+///
+/// let window = MarketDataWindow::new(10);
+/// assert_eq!(registry.evaluate("bar_count", &window), Some(0.0));
+/// assert_eq!(registry.evaluate("missing", &window), None);
+/// ```
+#[derive(Clone, Default)]
+pub struct IndicatorRegistry {
+    indicators: Arc<RwLock<HashMap<String, CustomIndicator>>>,
+}
+
+impl IndicatorRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an indicator under `name`, replacing any existing one
+    pub fn register(&self, name: impl Into<String>, indicator: CustomIndicator) {
+        self.indicators.write().unwrap().insert(name.into(), indicator);
+    }
+
+    /// Check if an indicator is registered under `name`
+    pub fn contains(&self, name: &str) -> bool {
+        self.indicators.read().unwrap().contains_key(name)
+    }
+
+    /// Names of all registered indicators, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.indicators.read().unwrap().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Compute the indicator registered under `name`
+    ///
+    /// Returns `None` if no such indicator is registered or it has no value
+    /// for this window.
+    pub fn evaluate(&self, name: &str, window: &MarketDataWindow) -> Option<f64> {
+        let indicators = self.indicators.read().unwrap();
+        indicators.get(name).and_then(|indicator| indicator(window))
+    }
+}
+
+impl std::fmt::Debug for IndicatorRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndicatorRegistry")
+            .field("indicators", &self.names())
+            .finish()
+    }
+}
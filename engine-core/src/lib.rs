@@ -91,6 +91,8 @@
 //! - [`state_machine`] - Trading state machine and position tracking
 //! - [`strategy`] - Lua-based strategy system
 //! - [`runner`] - Symbol runner orchestration (Phase 5)
+//! - [`events`] - Runner events for real-time monitoring
+//! - [`sinks`] - Durable event sinks (JSONL files)
 
 pub mod error;
 pub mod market_data;
@@ -102,9 +104,10 @@ pub mod state_machine;
 pub mod strategy;
 pub mod runner;
 pub mod events;
+pub mod sinks;
 
 // Re-export commonly used types
 pub use error::{Result, TradingEngineError};
-pub use market_data::{MarketData, MarketDataWindow};
-pub use sources::{MarketDataSource, SimulatedFeed};
+pub use market_data::{BarAggregator, MarketData, MarketDataBus, MarketDataWindow};
+pub use sources::{MarketDataSource, ReplayFeed, SimulatedFeed};
 pub use storage::MarketDataStorage;
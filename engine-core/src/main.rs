@@ -105,11 +105,8 @@ async fn run_binance_demo() -> anyhow::Result<()> {
 
     // Collect 3 completed klines
     for i in 0..3 {
-        match tokio::time::timeout(
-            tokio::time::Duration::from_secs(90),
-            feed.next_tick()
-        ).await {
-            Ok(Ok(data)) => {
+        match feed.next_tick_timeout(tokio::time::Duration::from_secs(90)).await {
+            Ok(Some(data)) => {
                 tracing::info!(
                     "Kline #{}: {} - O:{:.2} H:{:.2} L:{:.2} C:{:.2} V:{} | Bid:{:.2} Ask:{:.2}",
                     i + 1,
@@ -124,11 +121,11 @@ async fn run_binance_demo() -> anyhow::Result<()> {
                 );
                 storage.push(data);
             }
-            Ok(Err(e)) => {
+            Err(e) => {
                 tracing::error!("Error getting market data: {:?}", e);
                 return Err(e.into());
             }
-            Err(_) => {
+            Ok(None) => {
                 tracing::error!("Timeout waiting for market data");
                 return Err(anyhow::anyhow!("Timeout"));
             }
@@ -0,0 +1,122 @@
+//! Aggregation of individual trades into time-based OHLCV bars.
+
+use super::MarketData;
+
+/// Builds fixed-interval OHLCV bars for one symbol from a stream of trades
+///
+/// Bars are aligned to multiples of the interval since the Unix epoch and
+/// stamped with their opening time, like klines. A bar is only known to be
+/// complete once a trade arrives for a later interval, so
+/// [`push_trade`](Self::push_trade) returns the previous bar at that point.
+/// Intervals without trades produce no bar, and a trade from before the bar
+/// being built (an out-of-order trade) is dropped, since its bar has already
+/// been emitted or skipped.
+///
+/// Volume is summed as `f64` and truncated to the integer `volume` field
+/// only when the bar is emitted. Bid and ask are set to the last trade price.
+///
+/// # Examples
+///
+/// ```
+/// use trading_engine::market_data::BarAggregator;
+///
+/// // One-minute bars
+/// let mut aggregator = BarAggregator::new("BTCUSDT", 60_000);
+///
+/// assert!(aggregator.push_trade(100.0, 1.5, 0).is_none());
+/// assert!(aggregator.push_trade(103.0, 1.0, 20_000).is_none());
+/// assert!(aggregator.push_trade(99.0, 0.5, 40_000).is_none());
+///
+/// // The first trade of the next minute completes the bar
+/// let bar = aggregator.push_trade(101.0, 2.0, 60_000).unwrap();
+/// assert_eq!((bar.open, bar.high, bar.low, bar.close), (100.0, 103.0, 99.0, 99.0));
+/// assert_eq!(bar.volume, 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BarAggregator {
+    symbol: String,
+    interval_ms: i64,
+    bar: Option<MarketData>,
+    volume: f64,
+}
+
+impl BarAggregator {
+    /// Create an aggregator producing bars of `interval_ms` milliseconds
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval_ms` is not positive.
+    pub fn new(symbol: impl Into<String>, interval_ms: i64) -> Self {
+        assert!(interval_ms > 0, "Bar interval must be positive");
+        Self {
+            symbol: symbol.into(),
+            interval_ms,
+            bar: None,
+            volume: 0.0,
+        }
+    }
+
+    /// Bar length in milliseconds
+    pub fn interval_ms(&self) -> i64 {
+        self.interval_ms
+    }
+
+    /// Add a trade, returning the previous bar if this trade starts a new one
+    ///
+    /// A trade from before the bar being built is dropped.
+    pub fn push_trade(&mut self, price: f64, quantity: f64, timestamp: i64) -> Option<MarketData> {
+        let bar_start = timestamp - timestamp.rem_euclid(self.interval_ms);
+
+        let completed = match &self.bar {
+            Some(bar) if bar_start > bar.timestamp => self.flush(),
+            Some(bar) if bar_start < bar.timestamp => {
+                tracing::debug!(
+                    "{}: Dropping trade at {} from before the bar at {}",
+                    self.symbol,
+                    timestamp,
+                    bar.timestamp
+                );
+                return None;
+            }
+            _ => None,
+        };
+
+        match &mut self.bar {
+            Some(bar) => {
+                bar.high = bar.high.max(price);
+                bar.low = bar.low.min(price);
+                bar.close = price;
+                bar.bid = price;
+                bar.ask = price;
+                self.volume += quantity;
+            }
+            None => {
+                let mut bar = MarketData::from_trade(self.symbol.clone(), price, quantity, timestamp);
+                bar.timestamp = bar_start;
+                self.bar = Some(bar);
+                self.volume = quantity;
+            }
+        }
+
+        completed
+    }
+
+    /// The bar being built, with the volume so far
+    pub fn current(&self) -> Option<MarketData> {
+        self.bar.clone().map(|mut bar| {
+            bar.volume = self.volume as u64;
+            bar
+        })
+    }
+
+    /// Emit the bar being built, even though its interval may not be over
+    ///
+    /// Use at the end of a stream. Returns `None` if no trade has arrived
+    /// since the last bar was emitted.
+    pub fn flush(&mut self) -> Option<MarketData> {
+        let bar = self.current();
+        self.bar = None;
+        self.volume = 0.0;
+        bar
+    }
+}
@@ -0,0 +1,89 @@
+//! In-process fan-out of a single market data feed.
+
+use super::MarketData;
+use crate::storage::MarketDataStorage;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Distributes each bar from one feed to every consumer
+///
+/// A feed publishes each bar once; attached [`MarketDataStorage`] instances
+/// are updated synchronously during [`publish`](Self::publish), and channel
+/// subscribers (such as a task calling `TradingEngine::feed_data`) receive
+/// a copy in order. Subscribers whose receiver was dropped are removed.
+///
+/// Cloning the bus is cheap and the clones share their consumers.
+///
+/// # Examples
+///
+/// ```
+/// use trading_engine::market_data::MarketDataBus;
+/// use trading_engine::{MarketData, MarketDataStorage};
+///
+/// let bus = MarketDataBus::new();
+/// let storage = MarketDataStorage::new(100);
+/// bus.add_storage(storage.clone());
+/// let mut engine_feed = bus.subscribe();
+///
+/// bus.publish(MarketData::from_trade("BTCUSDT", 50000.0, 0.5, 1000));
+///
+/// assert_eq!(storage.get_window("BTCUSDT").unwrap().len(), 1);
+/// assert_eq!(engine_feed.try_recv().unwrap().close, 50000.0);
+/// ```
+#[derive(Clone, Default)]
+pub struct MarketDataBus {
+    storages: Arc<Mutex<Vec<MarketDataStorage>>>,
+    subscribers: Arc<Mutex<Vec<mpsc::UnboundedSender<MarketData>>>>,
+}
+
+impl MarketDataBus {
+    /// Create a bus with no consumers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Push every published bar into `storage`
+    pub fn add_storage(&self, storage: MarketDataStorage) {
+        self.storages.lock().push(storage);
+    }
+
+    /// Receive every bar published from now on
+    pub fn subscribe(&self) -> mpsc::UnboundedReceiver<MarketData> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.subscribers.lock().push(tx);
+        rx
+    }
+
+    /// Number of attached storages and live channel subscribers
+    pub fn consumer_count(&self) -> usize {
+        self.storages.lock().len() + self.subscribers.lock().len()
+    }
+
+    /// Hand `data` to every consumer
+    pub fn publish(&self, data: MarketData) {
+        for storage in self.storages.lock().iter() {
+            storage.push(data.clone());
+        }
+        self.subscribers
+            .lock()
+            .retain(|tx| tx.send(data.clone()).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropped_subscriber_removed() {
+        let bus = MarketDataBus::new();
+        let kept = bus.subscribe();
+        drop(bus.subscribe());
+        assert_eq!(bus.consumer_count(), 2);
+
+        bus.publish(MarketData::from_trade("BTCUSDT", 100.0, 1.0, 0));
+        assert_eq!(bus.consumer_count(), 1);
+        assert_eq!(kept.len(), 1);
+    }
+}
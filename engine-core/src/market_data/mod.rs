@@ -8,6 +8,7 @@
 //! The main types in this module are:
 //! - [`MarketData`]: Represents a single candlestick/bar
 //! - [`MarketDataWindow`]: A circular buffer for storing recent market data
+//! - [`BarAggregator`]: Builds time-based bars from individual trades
 //!
 //! # Examples
 //!
@@ -24,6 +25,9 @@
 //!     volume: 1000,
 //!     bid: 50499.0,
 //!     ask: 50501.0,
+//!     bid_qty: None,
+//!     ask_qty: None,
+//!     is_final: true,
 //! };
 //!
 //! assert_eq!(data.mid_price(), 50500.0);
@@ -41,7 +45,9 @@ use serde::{Deserialize, Serialize};
 /// # Fields
 ///
 /// * `symbol` - Trading pair symbol (e.g., "BTCUSDT", "AAPL", "ETHUSDT")
-/// * `timestamp` - Unix timestamp in milliseconds when this bar opened
+/// * `timestamp` - Unix timestamp in milliseconds when this bar opened.
+///   Always milliseconds after ingestion: sources convert recorded data in
+///   other units (see [`TimestampUnit`](crate::sources::TimestampUnit))
 /// * `open` - Opening price for the period
 /// * `high` - Highest price during the period
 /// * `low` - Lowest price during the period
@@ -49,6 +55,10 @@ use serde::{Deserialize, Serialize};
 /// * `volume` - Total volume traded during the period
 /// * `bid` - Current bid price (best buy price)
 /// * `ask` - Current ask price (best sell price)
+/// * `bid_qty` - Quantity resting at the best bid, if the source provides it
+/// * `ask_qty` - Quantity resting at the best ask, if the source provides it
+/// * `is_final` - Whether the bar is complete; `false` for a candle that is
+///   still forming. Defaults to `true` when deserializing
 ///
 /// # Thread Safety
 ///
@@ -73,6 +83,9 @@ use serde::{Deserialize, Serialize};
 ///     volume: 1000,
 ///     bid: 50499.0,
 ///     ask: 50501.0,
+///     bid_qty: None,
+///     ask_qty: None,
+///     is_final: true,
 /// };
 ///
 /// // Calculate mid-price
@@ -92,9 +105,53 @@ pub struct MarketData {
     pub volume: u64,
     pub bid: f64,
     pub ask: f64,
+    #[serde(default)]
+    pub bid_qty: Option<f64>,
+    #[serde(default)]
+    pub ask_qty: Option<f64>,
+    #[serde(default = "default_is_final")]
+    pub is_final: bool,
+}
+
+/// Bars without an explicit `is_final` (CSV, older JSON) are complete
+fn default_is_final() -> bool {
+    true
 }
 
 impl MarketData {
+    /// Creates a single-trade bar.
+    ///
+    /// Open, high, low, close, bid and ask are all the trade price, and the
+    /// volume is the trade quantity truncated to a whole number. Use a
+    /// [`BarAggregator`] to combine trades into proper bars.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::MarketData;
+    ///
+    /// let tick = MarketData::from_trade("BTCUSDT", 50000.0, 2.5, 1734278400000);
+    /// assert_eq!(tick.high, tick.low);
+    /// assert_eq!(tick.volume, 2);
+    /// assert!(tick.validate().is_ok());
+    /// ```
+    pub fn from_trade(symbol: impl Into<String>, price: f64, quantity: f64, timestamp: i64) -> Self {
+        Self {
+            symbol: symbol.into(),
+            timestamp,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: quantity as u64,
+            bid: price,
+            ask: price,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
+        }
+    }
+
     /// Calculates the mid-price between bid and ask.
     ///
     /// The mid-price is the average of the best bid and ask prices,
@@ -116,6 +173,9 @@ impl MarketData {
     ///     volume: 0,
     ///     bid: 100.0,
     ///     ask: 102.0,
+    ///     bid_qty: None,
+    ///     ask_qty: None,
+    ///     is_final: true,
     /// };
     ///
     /// assert_eq!(data.mid_price(), 101.0);
@@ -124,6 +184,99 @@ impl MarketData {
         (self.bid + self.ask) / 2.0
     }
 
+    /// Calculates the bid/ask spread (`ask - bid`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::MarketData;
+    ///
+    /// let mut data = MarketData::from_trade("BTCUSDT", 100.0, 1.0, 0);
+    /// data.bid = 99.5;
+    /// data.ask = 100.5;
+    ///
+    /// assert_eq!(data.spread(), 1.0);
+    /// ```
+    pub fn spread(&self) -> f64 {
+        self.ask - self.bid
+    }
+
+    /// Calculates the size-weighted microprice.
+    ///
+    /// Weights each side's price by the quantity on the *opposite* side, so
+    /// the result leans toward the side more likely to be hit next:
+    /// `(bid * ask_qty + ask * bid_qty) / (bid_qty + ask_qty)`.
+    ///
+    /// # Returns
+    ///
+    /// `None` if either quantity is unknown or the total quantity is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::MarketData;
+    ///
+    /// let data = MarketData {
+    ///     symbol: "BTCUSDT".to_string(),
+    ///     timestamp: 0,
+    ///     open: 0.0, high: 0.0, low: 0.0, close: 0.0,
+    ///     volume: 0,
+    ///     bid: 100.0,
+    ///     ask: 102.0,
+    ///     bid_qty: Some(3.0),
+    ///     ask_qty: Some(1.0),
+    ///     is_final: true,
+    /// };
+    ///
+    /// // Heavy bid side pushes the microprice toward the ask
+    /// assert_eq!(data.microprice(), Some(101.5));
+    /// ```
+    pub fn microprice(&self) -> Option<f64> {
+        let (bid_qty, ask_qty) = (self.bid_qty?, self.ask_qty?);
+        let total = bid_qty + ask_qty;
+        if total <= 0.0 {
+            return None;
+        }
+        Some((self.bid * ask_qty + self.ask * bid_qty) / total)
+    }
+
+    /// Calculates the top-of-book order imbalance.
+    ///
+    /// Computed as `(bid_qty - ask_qty) / (bid_qty + ask_qty)`, ranging from
+    /// `-1.0` (all size on the ask) to `1.0` (all size on the bid).
+    ///
+    /// # Returns
+    ///
+    /// `None` if either quantity is unknown or the total quantity is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::MarketData;
+    ///
+    /// let data = MarketData {
+    ///     symbol: "BTCUSDT".to_string(),
+    ///     timestamp: 0,
+    ///     open: 0.0, high: 0.0, low: 0.0, close: 0.0,
+    ///     volume: 0,
+    ///     bid: 100.0,
+    ///     ask: 102.0,
+    ///     bid_qty: Some(3.0),
+    ///     ask_qty: Some(1.0),
+    ///     is_final: true,
+    /// };
+    ///
+    /// assert_eq!(data.imbalance(), Some(0.5));
+    /// ```
+    pub fn imbalance(&self) -> Option<f64> {
+        let (bid_qty, ask_qty) = (self.bid_qty?, self.ask_qty?);
+        let total = bid_qty + ask_qty;
+        if total <= 0.0 {
+            return None;
+        }
+        Some((bid_qty - ask_qty) / total)
+    }
+
     /// Validates the market data for consistency.
     ///
     /// Checks that the data satisfies basic invariants:
@@ -154,6 +307,9 @@ impl MarketData {
     ///     volume: 1000,
     ///     bid: 104.0,
     ///     ask: 106.0,
+    ///     bid_qty: None,
+    ///     ask_qty: None,
+    ///     is_final: true,
     /// };
     /// assert!(valid.validate().is_ok());
     ///
@@ -168,6 +324,9 @@ impl MarketData {
     ///     volume: 1000,
     ///     bid: 104.0,
     ///     ask: 106.0,
+    ///     bid_qty: None,
+    ///     ask_qty: None,
+    ///     is_final: true,
     /// };
     /// assert!(invalid.validate().is_err());
     /// ```
@@ -193,6 +352,12 @@ impl MarketData {
 pub mod window;
 pub use window::MarketDataWindow;
 
+pub mod aggregator;
+pub use aggregator::BarAggregator;
+
+pub mod bus;
+pub use bus::MarketDataBus;
+
 // Unit tests
 #[cfg(test)]
 mod tests;
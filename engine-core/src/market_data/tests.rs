@@ -15,6 +15,9 @@ fn create_test_data(symbol: &str, count: usize) -> Vec<MarketData> {
             volume: 1000 + i as u64,
             bid: 101.0 + i as f64,
             ask: 103.0 + i as f64,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         })
         .collect()
 }
@@ -35,6 +38,9 @@ fn test_market_data_mid_price() {
         volume: 1000,
         bid: 104.0,
         ask: 106.0,
+        bid_qty: None,
+        ask_qty: None,
+        is_final: true,
     };
 
     assert_eq!(data.mid_price(), 105.0);
@@ -52,6 +58,9 @@ fn test_market_data_validate_success() {
         volume: 1000,
         bid: 104.0,
         ask: 106.0,
+        bid_qty: None,
+        ask_qty: None,
+        is_final: true,
     };
 
     assert!(data.validate().is_ok());
@@ -69,6 +78,9 @@ fn test_market_data_validate_high_less_than_low() {
         volume: 1000,
         bid: 104.0,
         ask: 106.0,
+        bid_qty: None,
+        ask_qty: None,
+        is_final: true,
     };
 
     assert!(data.validate().is_err());
@@ -86,11 +98,64 @@ fn test_market_data_validate_negative_price() {
         volume: 1000,
         bid: 104.0,
         ask: 106.0,
+        bid_qty: None,
+        ask_qty: None,
+        is_final: true,
     };
 
     assert!(data.validate().is_err());
 }
 
+/// Helper function to create a quote with top-of-book quantities
+fn create_quote(bid_qty: Option<f64>, ask_qty: Option<f64>) -> MarketData {
+    MarketData {
+        symbol: "BTC".to_string(),
+        timestamp: 0,
+        open: 100.0,
+        high: 110.0,
+        low: 90.0,
+        close: 105.0,
+        volume: 1000,
+        bid: 100.0,
+        ask: 102.0,
+        bid_qty,
+        ask_qty,
+        is_final: true,
+    }
+}
+
+#[test]
+fn test_market_data_microprice() {
+    // (100 * 1 + 102 * 3) / 4 = 101.5
+    let data = create_quote(Some(3.0), Some(1.0));
+    assert_eq!(data.microprice(), Some(101.5));
+
+    // Balanced book collapses to the mid-price
+    let balanced = create_quote(Some(2.0), Some(2.0));
+    assert_eq!(balanced.microprice(), Some(balanced.mid_price()));
+}
+
+#[test]
+fn test_market_data_imbalance() {
+    assert_eq!(create_quote(Some(3.0), Some(1.0)).imbalance(), Some(0.5));
+    assert_eq!(create_quote(Some(1.0), Some(3.0)).imbalance(), Some(-0.5));
+    assert_eq!(create_quote(Some(5.0), Some(0.0)).imbalance(), Some(1.0));
+}
+
+#[test]
+fn test_market_data_book_metrics_zero_quantity() {
+    let data = create_quote(Some(0.0), Some(0.0));
+    assert_eq!(data.microprice(), None);
+    assert_eq!(data.imbalance(), None);
+}
+
+#[test]
+fn test_market_data_book_metrics_missing_quantity() {
+    let data = create_quote(None, Some(1.0));
+    assert_eq!(data.microprice(), None);
+    assert_eq!(data.imbalance(), None);
+}
+
 // ============================================================================
 // MarketDataWindow Tests - Basic Operations
 // ============================================================================
@@ -132,6 +197,9 @@ fn test_window_capacity_limit() {
             volume: 0,
             bid: 0.0,
             ask: 0.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         };
         window.push(data);
     }
@@ -209,6 +277,55 @@ fn test_avg_volume() {
     assert_eq!(avg, 1002.0);
 }
 
+#[test]
+fn test_spread_over_trailing_period() {
+    let mut window = MarketDataWindow::new(10);
+    for (i, (bid, ask)) in [(100.0, 101.0), (100.0, 104.0), (99.0, 100.0), (100.0, 102.0)]
+        .into_iter()
+        .enumerate()
+    {
+        let mut data = create_test_data("BTC", 1).remove(0);
+        data.timestamp = i as i64;
+        data.bid = bid;
+        data.ask = ask;
+        window.push(data);
+    }
+
+    // Last 3 spreads: 4.0, 1.0, 2.0
+    assert!((window.avg_spread(3).unwrap() - 7.0 / 3.0).abs() < 1e-9);
+    assert_eq!(window.max_spread(3), Some(4.0));
+
+    // Last 2 spreads: 1.0, 2.0
+    assert_eq!(window.avg_spread(2), Some(1.5));
+    assert_eq!(window.max_spread(2), Some(2.0));
+
+    // Period longer than the window uses every bar
+    assert_eq!(window.avg_spread(10), Some(2.0));
+    assert_eq!(window.avg_spread(0), None);
+    assert_eq!(window.max_spread(0), None);
+}
+
+#[test]
+fn test_close_streak() {
+    let window_of = |closes: &[f64]| {
+        let mut window = MarketDataWindow::new(10);
+        for (i, &close) in closes.iter().enumerate() {
+            window.push(MarketData::from_trade("BTC", close, 1.0, i as i64));
+        }
+        window
+    };
+
+    // Up, up, down
+    assert_eq!(window_of(&[100.0, 101.0, 102.0, 101.0]).close_streak(), -1);
+    // Up, up, up
+    assert_eq!(window_of(&[100.0, 101.0, 102.0, 103.0]).close_streak(), 3);
+    // A flat close breaks the streak
+    assert_eq!(window_of(&[100.0, 101.0, 101.0, 102.0]).close_streak(), 1);
+    assert_eq!(window_of(&[100.0, 101.0, 101.0]).close_streak(), 0);
+    assert_eq!(window_of(&[100.0]).close_streak(), 0);
+    assert_eq!(window_of(&[]).close_streak(), 0);
+}
+
 #[test]
 fn test_closes_extraction() {
     let mut window = MarketDataWindow::new(100);
@@ -223,6 +340,44 @@ fn test_closes_extraction() {
     assert_eq!(closes, vec![104.0, 105.0, 106.0]);
 }
 
+#[test]
+fn test_momentum_and_roc() {
+    let mut window = MarketDataWindow::new(100);
+    for (i, close) in [100.0, 104.0, 98.0, 101.0, 107.0, 120.0, 90.0].into_iter().enumerate() {
+        let mut data = create_test_data("BTC", 1).remove(0);
+        data.timestamp = i as i64;
+        data.close = close;
+        window.push(data);
+    }
+
+    // 5-bar lookback compares 90.0 with 104.0
+    assert_eq!(window.momentum(5), Some(-14.0));
+    assert!((window.roc(5).unwrap() - (-14.0 / 104.0 * 100.0)).abs() < 1e-9);
+
+    // One bar back: 90.0 vs 120.0
+    assert_eq!(window.momentum(1), Some(-30.0));
+    assert_eq!(window.roc(1), Some(-25.0));
+
+    // Lookback must fit inside the window
+    assert_eq!(window.momentum(6), Some(-10.0));
+    assert_eq!(window.momentum(7), None);
+    assert_eq!(window.roc(7), None);
+    assert_eq!(window.roc(0), None);
+}
+
+#[test]
+fn test_slope_of_closes() {
+    let mut window = MarketDataWindow::new(100);
+    for d in create_test_data("BTC", 5) {
+        window.push(d);
+    }
+
+    // Closes rise by 1.0 per bar
+    assert!((window.slope_of_closes(5).unwrap() - 1.0).abs() < 1e-9);
+    assert_eq!(window.slope_of_closes(6), None);
+    assert_eq!(window.slope_of_closes(1), None);
+}
+
 #[test]
 fn test_range_calculation() {
     let mut window = MarketDataWindow::new(100);
@@ -252,6 +407,47 @@ fn test_oldest_and_latest() {
     assert_eq!(window.latest().unwrap().timestamp, 4);
 }
 
+#[test]
+fn test_at_time() {
+    let mut window = MarketDataWindow::new(3);
+    for (i, ts) in [0, 60_000, 120_000, 180_000, 240_000].into_iter().enumerate() {
+        window.push(MarketData::from_trade("BTC", 100.0 + i as f64, 1.0, ts));
+    }
+
+    // Exact timestamps
+    assert_eq!(window.at_time(120_000).unwrap().close, 102.0);
+    assert_eq!(window.at_time(240_000).unwrap().close, 104.0);
+
+    // Between bars, the earlier one covers it
+    assert_eq!(window.at_time(179_999).unwrap().close, 102.0);
+    assert_eq!(window.at_time(200_000).unwrap().close, 103.0);
+    assert_eq!(window.at_time(i64::MAX).unwrap().close, 104.0);
+
+    // Before the oldest bar still held
+    assert!(window.at_time(60_000).is_none());
+    assert!(MarketDataWindow::new(3).at_time(0).is_none());
+}
+
+#[test]
+fn test_returns_start_at_second_bar() {
+    let mut window = MarketDataWindow::new(10);
+    assert!(window.returns().is_empty());
+
+    for (i, close) in [100.0, 110.0, 99.0].into_iter().enumerate() {
+        window.push(MarketData::from_trade("BTC", close, 1.0, i as i64));
+    }
+
+    let returns = window.returns();
+    assert_eq!(returns.len(), 2);
+    assert!((returns[0] - 0.10).abs() < 1e-12);
+    assert!((returns[1] + 0.10).abs() < 1e-12);
+
+    let log_returns = window.log_returns();
+    assert_eq!(log_returns.len(), 2);
+    assert!((log_returns[0] - (1.1f64).ln()).abs() < 1e-12);
+    assert!((log_returns[1] - (0.9f64).ln()).abs() < 1e-12);
+}
+
 #[test]
 fn test_iterator() {
     let mut window = MarketDataWindow::new(100);
@@ -314,3 +510,75 @@ fn test_clone_window() {
     assert_eq!(cloned.len(), window.len());
     assert_eq!(cloned.latest().unwrap().timestamp, window.latest().unwrap().timestamp);
 }
+
+// ============================================================================
+// BarAggregator Tests
+// ============================================================================
+
+#[test]
+fn test_from_trade() {
+    let tick = MarketData::from_trade("ETHUSDT", 3000.0, 4.0, 1000);
+    assert_eq!(tick.symbol, "ETHUSDT");
+    assert_eq!(tick.timestamp, 1000);
+    assert_eq!((tick.open, tick.high, tick.low, tick.close), (3000.0, 3000.0, 3000.0, 3000.0));
+    assert_eq!((tick.bid, tick.ask), (3000.0, 3000.0));
+    assert_eq!(tick.volume, 4);
+}
+
+#[test]
+fn test_aggregator_builds_ohlcv_bar() {
+    let mut aggregator = BarAggregator::new("BTCUSDT", 1000);
+
+    // Five trades inside [5000, 6000)
+    let trades = [(100.0, 0.4, 5010), (102.5, 1.1, 5200), (98.0, 0.7, 5400), (101.0, 0.3, 5800), (99.5, 0.5, 5999)];
+    for (price, quantity, timestamp) in trades {
+        assert!(aggregator.push_trade(price, quantity, timestamp).is_none());
+    }
+
+    let bar = aggregator.push_trade(103.0, 1.0, 6000).expect("Next interval completes the bar");
+    assert_eq!(bar.symbol, "BTCUSDT");
+    assert_eq!(bar.timestamp, 5000);
+    assert_eq!(bar.open, 100.0);
+    assert_eq!(bar.high, 102.5);
+    assert_eq!(bar.low, 98.0);
+    assert_eq!(bar.close, 99.5);
+    assert_eq!(bar.volume, 3); // 0.4 + 1.1 + 0.7 + 0.3 + 0.5 = 3.0
+    assert_eq!((bar.bid, bar.ask), (99.5, 99.5));
+    bar.validate().unwrap();
+
+    // The new bar holds only the trade that opened it
+    let current = aggregator.current().unwrap();
+    assert_eq!((current.timestamp, current.open, current.volume), (6000, 103.0, 1));
+}
+
+#[test]
+fn test_aggregator_skips_empty_intervals_and_flushes() {
+    let mut aggregator = BarAggregator::new("BTCUSDT", 1000);
+    assert!(aggregator.push_trade(100.0, 1.0, 1500).is_none());
+
+    // Nothing traded in [2000, 4000): the next bar starts at 4000
+    let bar = aggregator.push_trade(105.0, 1.0, 4100).unwrap();
+    assert_eq!(bar.timestamp, 1000);
+
+    let last = aggregator.flush().unwrap();
+    assert_eq!((last.timestamp, last.low, last.close, last.volume), (4000, 105.0, 105.0, 1));
+    assert!(aggregator.flush().is_none());
+}
+
+#[test]
+fn test_aggregator_drops_out_of_order_trades() {
+    let mut aggregator = BarAggregator::new("BTCUSDT", 1000);
+    assert!(aggregator.push_trade(100.0, 1.0, 1500).is_none());
+    assert!(aggregator.push_trade(102.0, 1.0, 2100).is_some());
+
+    // A late trade for the finished [1000, 2000) bar leaves the open bar alone
+    assert!(aggregator.push_trade(90.0, 5.0, 1900).is_none());
+    let current = aggregator.current().unwrap();
+    assert_eq!((current.timestamp, current.low, current.close, current.volume), (2000, 102.0, 102.0, 1));
+    assert_eq!((current.bid, current.ask), (102.0, 102.0));
+
+    // Trades inside the open bar still count, whatever their order
+    assert!(aggregator.push_trade(101.0, 1.0, 2050).is_none());
+    let current = aggregator.current().unwrap();
+    assert_eq!((current.low, current.close, current.volume), (101.0, 101.0, 2));
+}
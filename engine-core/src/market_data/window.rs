@@ -51,6 +51,9 @@ use super::MarketData;
 ///         volume: 1000,
 ///         bid: 50499.0,
 ///         ask: 50501.0,
+///         bid_qty: None,
+///         ask_qty: None,
+///         is_final: true,
 ///     };
 ///     window.push(data);
 /// }
@@ -116,7 +119,7 @@ impl MarketDataWindow {
     ///         symbol: "BTC".to_string(),
     ///         timestamp: i,
     ///         open: 0.0, high: 0.0, low: 0.0, close: 0.0,
-    ///         volume: 0, bid: 0.0, ask: 0.0,
+    ///         volume: 0, bid: 0.0, ask: 0.0, bid_qty: None, ask_qty: None, is_final: true,
     ///     };
     ///     window.push(data);
     /// }
@@ -166,7 +169,7 @@ impl MarketDataWindow {
     ///         high: 100.0 + i as f64,
     ///         low: 0.0,
     ///         close: 0.0,
-    ///         volume: 0, bid: 0.0, ask: 0.0,
+    ///         volume: 0, bid: 0.0, ask: 0.0, bid_qty: None, ask_qty: None, is_final: true,
     ///     };
     ///     window.push(data);
     /// }
@@ -222,6 +225,115 @@ impl MarketDataWindow {
         Some(values.iter().sum::<u64>() as f64 / values.len() as f64)
     }
 
+    /// Returns the latest Average True Range over `period` bars.
+    ///
+    /// See [`average_true_range`](crate::indicators::average_true_range).
+    ///
+    /// # Returns
+    ///
+    /// - `Some(f64)` - The ATR at the latest bar
+    /// - `None` - If `period` is 0 or the window holds `period` bars or fewer
+    pub fn atr(&self, period: usize) -> Option<f64> {
+        let high: Vec<f64> = self.data.iter().map(|d| d.high).collect();
+        let low: Vec<f64> = self.data.iter().map(|d| d.low).collect();
+        let close: Vec<f64> = self.data.iter().map(|d| d.close).collect();
+        crate::indicators::average_true_range(&high, &low, &close, period)
+            .last()
+            .copied()
+    }
+
+    /// Returns the average bid/ask spread over the last `period` bars.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(f64)` - The mean of [`MarketData::spread`] over up to `period` bars
+    /// - `None` - If `period` is 0 or the window is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::{MarketData, MarketDataWindow};
+    ///
+    /// let mut window = MarketDataWindow::new(100);
+    ///
+    /// for spread in [1.0, 2.0, 3.0] {
+    ///     let mut data = MarketData::from_trade("BTC", 100.0, 1.0, 0);
+    ///     data.ask = 100.0 + spread;
+    ///     window.push(data);
+    /// }
+    ///
+    /// assert_eq!(window.avg_spread(2), Some(2.5));
+    /// assert_eq!(window.max_spread(3), Some(3.0));
+    /// ```
+    pub fn avg_spread(&self, period: usize) -> Option<f64> {
+        let spreads: Vec<f64> = self.data
+            .iter()
+            .rev()
+            .take(period)
+            .map(MarketData::spread)
+            .collect();
+
+        if spreads.is_empty() {
+            return None;
+        }
+
+        Some(spreads.iter().sum::<f64>() / spreads.len() as f64)
+    }
+
+    /// Returns the widest bid/ask spread over the last `period` bars.
+    ///
+    /// Returns `None` if `period` is 0 or the window is empty.
+    pub fn max_spread(&self, period: usize) -> Option<f64> {
+        self.data
+            .iter()
+            .rev()
+            .take(period)
+            .map(MarketData::spread)
+            .max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+    }
+
+    /// Returns the signed count of consecutive rising or falling closes at the end of the window.
+    ///
+    /// Positive for a run of higher closes, negative for lower closes. An
+    /// unchanged close breaks the streak, as does an empty or one-bar window (0).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::{MarketData, MarketDataWindow};
+    ///
+    /// let mut window = MarketDataWindow::new(10);
+    /// for (i, close) in [100.0, 99.0, 98.0, 97.0].into_iter().enumerate() {
+    ///     window.push(MarketData::from_trade("BTCUSDT", close, 1.0, i as i64));
+    /// }
+    ///
+    /// // Three red candles
+    /// assert_eq!(window.close_streak(), -3);
+    /// ```
+    pub fn close_streak(&self) -> i32 {
+        let mut closes = self.data.iter().rev().map(|bar| bar.close);
+        let Some(mut later) = closes.next() else {
+            return 0;
+        };
+
+        let mut streak = 0i32;
+        for earlier in closes {
+            let step = if later > earlier {
+                1
+            } else if later < earlier {
+                -1
+            } else {
+                break;
+            };
+            if streak != 0 && step != streak.signum() {
+                break;
+            }
+            streak += step;
+            later = earlier;
+        }
+        streak
+    }
+
     pub fn len(&self) -> usize {
         self.data.len()
     }
@@ -230,6 +342,11 @@ impl MarketDataWindow {
         self.data.is_empty()
     }
 
+    /// Most bars the window holds
+    pub fn capacity(&self) -> usize {
+        self.max_size
+    }
+
     pub fn get(&self, index: usize) -> Option<&MarketData> {
         self.data.get(index)
     }
@@ -257,7 +374,7 @@ impl MarketDataWindow {
     ///         symbol: "BTC".to_string(),
     ///         timestamp: i,
     ///         open: 0.0, high: 0.0, low: 0.0, close: 0.0,
-    ///         volume: 0, bid: 0.0, ask: 0.0,
+    ///         volume: 0, bid: 0.0, ask: 0.0, bid_qty: None, ask_qty: None, is_final: true,
     ///     };
     ///     window.push(data);
     /// }
@@ -269,6 +386,38 @@ impl MarketDataWindow {
         self.data.front()
     }
 
+    /// Returns the bar covering `ts`: the latest bar with a timestamp at or before it.
+    ///
+    /// Relies on bars being pushed in time order.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&MarketData)` - The bar in effect at `ts`
+    /// - `None` - If `ts` is before the oldest bar or the window is empty
+    ///
+    /// # Performance
+    ///
+    /// O(log n) binary search
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::{MarketData, MarketDataWindow};
+    ///
+    /// let mut window = MarketDataWindow::new(10);
+    /// for ts in [1000, 2000, 3000] {
+    ///     window.push(MarketData::from_trade("BTC", 100.0, 1.0, ts));
+    /// }
+    ///
+    /// assert_eq!(window.at_time(2000).unwrap().timestamp, 2000);
+    /// assert_eq!(window.at_time(2999).unwrap().timestamp, 2000);
+    /// assert!(window.at_time(999).is_none());
+    /// ```
+    pub fn at_time(&self, ts: i64) -> Option<&MarketData> {
+        let after = self.data.partition_point(|bar| bar.timestamp <= ts);
+        after.checked_sub(1).and_then(|index| self.data.get(index))
+    }
+
     /// Returns an iterator over the data points from oldest to newest.
     ///
     /// # Examples
@@ -283,7 +432,7 @@ impl MarketDataWindow {
     ///         symbol: "BTC".to_string(),
     ///         timestamp: i,
     ///         open: 0.0, high: 0.0, low: 0.0, close: 0.0,
-    ///         volume: 0, bid: 0.0, ask: 0.0,
+    ///         volume: 0, bid: 0.0, ask: 0.0, bid_qty: None, ask_qty: None, is_final: true,
     ///     };
     ///     window.push(data);
     /// }
@@ -321,7 +470,7 @@ impl MarketDataWindow {
     ///         timestamp: i,
     ///         open: 0.0, high: 0.0, low: 0.0,
     ///         close: 100.0 + i as f64,
-    ///         volume: 0, bid: 0.0, ask: 0.0,
+    ///         volume: 0, bid: 0.0, ask: 0.0, bid_qty: None, ask_qty: None, is_final: true,
     ///     };
     ///     window.push(data);
     /// }
@@ -370,7 +519,7 @@ impl MarketDataWindow {
     ///         high: 110.0 + i as f64,
     ///         low: 90.0 - i as f64,
     ///         close: 0.0,
-    ///         volume: 0, bid: 0.0, ask: 0.0,
+    ///         volume: 0, bid: 0.0, ask: 0.0, bid_qty: None, ask_qty: None, is_final: true,
     ///     };
     ///     window.push(data);
     /// }
@@ -385,6 +534,187 @@ impl MarketDataWindow {
         Some(high - low)
     }
 
+    /// Returns the change in close over the last `period` bars.
+    ///
+    /// Momentum is the latest close minus the close `period` bars before it.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(f64)` - The change in close
+    /// - `None` - If `period` is 0 or the window holds `period` bars or fewer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::{MarketData, MarketDataWindow};
+    ///
+    /// let mut window = MarketDataWindow::new(100);
+    ///
+    /// for i in 0..6 {
+    ///     let data = MarketData {
+    ///         symbol: "BTC".to_string(),
+    ///         timestamp: i,
+    ///         open: 0.0, high: 0.0, low: 0.0,
+    ///         close: 100.0 + 2.0 * i as f64,
+    ///         volume: 0, bid: 0.0, ask: 0.0, bid_qty: None, ask_qty: None, is_final: true,
+    ///     };
+    ///     window.push(data);
+    /// }
+    ///
+    /// // 110 - 100
+    /// assert_eq!(window.momentum(5), Some(10.0));
+    /// assert_eq!(window.momentum(6), None);
+    /// ```
+    pub fn momentum(&self, period: usize) -> Option<f64> {
+        let (then, now) = self.close_and_past_close(period)?;
+        Some(now - then)
+    }
+
+    /// Returns the percentage rate of change of close over the last `period` bars.
+    ///
+    /// ROC is `(close - close[period ago]) / close[period ago] * 100`.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(f64)` - The rate of change in percent
+    /// - `None` - If `period` is 0, the window holds `period` bars or fewer,
+    ///   or the earlier close is zero
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::{MarketData, MarketDataWindow};
+    ///
+    /// let mut window = MarketDataWindow::new(100);
+    ///
+    /// for i in 0..6 {
+    ///     let data = MarketData {
+    ///         symbol: "BTC".to_string(),
+    ///         timestamp: i,
+    ///         open: 0.0, high: 0.0, low: 0.0,
+    ///         close: 100.0 + 2.0 * i as f64,
+    ///         volume: 0, bid: 0.0, ask: 0.0, bid_qty: None, ask_qty: None, is_final: true,
+    ///     };
+    ///     window.push(data);
+    /// }
+    ///
+    /// // (110 - 100) / 100 * 100
+    /// assert_eq!(window.roc(5), Some(10.0));
+    /// ```
+    pub fn roc(&self, period: usize) -> Option<f64> {
+        let (then, now) = self.close_and_past_close(period)?;
+        if then == 0.0 {
+            return None;
+        }
+        Some((now - then) / then * 100.0)
+    }
+
+    /// Returns the simple per-bar returns of close across the window.
+    ///
+    /// Each value is `close / previous close - 1`, so the series starts at the
+    /// second bar and has one entry fewer than the window. A zero previous
+    /// close gives `NaN`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::{MarketData, MarketDataWindow};
+    ///
+    /// let mut window = MarketDataWindow::new(100);
+    ///
+    /// for (i, close) in [100.0, 110.0, 121.0].into_iter().enumerate() {
+    ///     let data = MarketData {
+    ///         symbol: "BTC".to_string(),
+    ///         timestamp: i as i64,
+    ///         open: 0.0, high: 0.0, low: 0.0,
+    ///         close,
+    ///         volume: 0, bid: 0.0, ask: 0.0, bid_qty: None, ask_qty: None, is_final: true,
+    ///     };
+    ///     window.push(data);
+    /// }
+    ///
+    /// let returns = window.returns();
+    /// assert_eq!(returns.len(), 2);
+    /// assert!((returns[0] - 0.10).abs() < 1e-12);
+    /// assert!((returns[1] - 0.10).abs() < 1e-12);
+    /// ```
+    pub fn returns(&self) -> Vec<f64> {
+        self.close_pairs()
+            .map(|(prev, close)| if prev == 0.0 { f64::NAN } else { close / prev - 1.0 })
+            .collect()
+    }
+
+    /// Returns the per-bar log returns of close across the window.
+    ///
+    /// Each value is `ln(close / previous close)`, starting at the second bar
+    /// like [`returns`](Self::returns). Log returns add up across bars, so
+    /// their sum is the log return of the whole window. A non-positive close
+    /// on either side gives `NaN`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::{MarketData, MarketDataWindow};
+    ///
+    /// let mut window = MarketDataWindow::new(100);
+    ///
+    /// for (i, close) in [100.0, 110.0, 99.0].into_iter().enumerate() {
+    ///     let data = MarketData {
+    ///         symbol: "BTC".to_string(),
+    ///         timestamp: i as i64,
+    ///         open: 0.0, high: 0.0, low: 0.0,
+    ///         close,
+    ///         volume: 0, bid: 0.0, ask: 0.0, bid_qty: None, ask_qty: None, is_final: true,
+    ///     };
+    ///     window.push(data);
+    /// }
+    ///
+    /// // ln(110 / 100) + ln(99 / 110) = ln(99 / 100)
+    /// let total: f64 = window.log_returns().iter().sum();
+    /// assert!((total - (0.99f64).ln()).abs() < 1e-12);
+    /// ```
+    pub fn log_returns(&self) -> Vec<f64> {
+        self.close_pairs()
+            .map(|(prev, close)| {
+                if prev <= 0.0 || close <= 0.0 {
+                    f64::NAN
+                } else {
+                    (close / prev).ln()
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the least-squares slope of the last `period` closes.
+    ///
+    /// The slope is in price units per bar; see
+    /// [`linreg_slope`](crate::indicators::linreg_slope).
+    ///
+    /// # Returns
+    ///
+    /// - `Some(f64)` - The slope
+    /// - `None` - If `period` is below 2 or exceeds the window length
+    pub fn slope_of_closes(&self, period: usize) -> Option<f64> {
+        crate::indicators::linreg_slope(&self.closes(period), period)
+            .last()
+            .copied()
+    }
+
+    /// Each close paired with the one before it, oldest first
+    fn close_pairs(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.data.iter().zip(self.data.iter().skip(1)).map(|(prev, bar)| (prev.close, bar.close))
+    }
+
+    /// The close `period` bars ago and the latest close
+    fn close_and_past_close(&self, period: usize) -> Option<(f64, f64)> {
+        if period == 0 || self.data.len() <= period {
+            return None;
+        }
+        let now = self.data.back()?.close;
+        let then = self.data.get(self.data.len() - 1 - period)?.close;
+        Some((then, now))
+    }
+
     /// Clears all data from the window.
     ///
     /// # Examples
@@ -398,7 +728,7 @@ impl MarketDataWindow {
     ///     symbol: "BTC".to_string(),
     ///     timestamp: 0,
     ///     open: 0.0, high: 0.0, low: 0.0, close: 0.0,
-    ///     volume: 0, bid: 0.0, ask: 0.0,
+    ///     volume: 0, bid: 0.0, ask: 0.0, bid_qty: None, ask_qty: None, is_final: true,
     /// };
     /// window.push(data);
     ///
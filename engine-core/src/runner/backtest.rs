@@ -0,0 +1,341 @@
+//! Running a strategy over a fixed series of bars
+
+use super::{RunnerConfig, RunnerStats, SymbolRunner};
+use crate::error::{Result, TradingEngineError};
+use crate::indicators::{precompute_indicators, Indicator};
+use crate::market_data::MarketData;
+use crate::state_machine::Position;
+use crate::strategy::LuaStrategy;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+/// Account equity after one bar
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EquityPoint {
+    /// Bar timestamp (milliseconds)
+    pub timestamp: i64,
+
+    /// Realized P&L so far plus the open position's unrealized P&L
+    pub equity: f64,
+}
+
+/// Outcome of [`run_backtest`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestReport {
+    /// Closed positions, in the order they were closed
+    pub trades: Vec<Position>,
+
+    /// Runner statistics after the last bar
+    pub stats: RunnerStats,
+
+    /// Equity after every bar processed
+    pub equity_curve: Vec<EquityPoint>,
+
+    /// Total realized P&L over all trades
+    pub realized_pnl: f64,
+}
+
+/// Run a strategy over `bars` in a fresh runner and report its trades
+///
+/// Bars are stepped through a new [`SymbolRunner`] one at a time, in order,
+/// so the result only depends on the strategy and the data. The symbol is
+/// taken from the first bar; bars for other symbols are skipped. A position
+/// still open after the last bar is not closed and shows up only in the
+/// equity curve.
+///
+/// # Errors
+///
+/// Returns an error if `bars` is empty, or if a bar fails and the config
+/// has `stop_on_error` set.
+///
+/// # Example
+///
+/// ```no_run
+/// use trading_engine::runner::{run_backtest, RunnerConfig};
+/// use trading_engine::strategy::LuaStrategy;
+///
+/// # async fn example(bars: Vec<trading_engine::MarketData>) -> anyhow::Result<()> {
+/// let strategy = LuaStrategy::new("strategies/ema_crossover.lua")?;
+/// let report = run_backtest(strategy, bars, 200, RunnerConfig::quiet()).await?;
+/// println!("{} trades, P&L {:.2}", report.trades.len(), report.realized_pnl);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn run_backtest(
+    strategy: LuaStrategy,
+    bars: Vec<MarketData>,
+    window_size: usize,
+    config: RunnerConfig,
+) -> Result<BacktestReport> {
+    run_backtest_with_indicators(strategy, bars, window_size, config, &[]).await
+}
+
+/// Run a backtest with `indicators` computed once over the whole series
+///
+/// Works like [`run_backtest`], but first runs
+/// [`precompute_indicators`] over the bars, and on each bar offers the
+/// values at that bar to the strategy as `indicators.precomputed(key)`
+/// (`nil` during warmup), with keys like `"ema_20"` from
+/// [`Indicator::key`]. The whole-series values don't depend on the
+/// window size, and skip recomputing every indicator on every tick.
+///
+/// # Errors
+///
+/// Same as [`run_backtest`].
+///
+/// # Example
+///
+/// ```no_run
+/// use trading_engine::indicators::Indicator;
+/// use trading_engine::runner::{run_backtest_with_indicators, RunnerConfig};
+/// use trading_engine::strategy::LuaStrategy;
+///
+/// # async fn example(bars: Vec<trading_engine::MarketData>) -> anyhow::Result<()> {
+/// // The strategy reads indicators.precomputed("ema_50") and ("rsi_14")
+/// let strategy = LuaStrategy::new("strategies/ema_rsi.lua")?;
+/// let spec = [Indicator::Ema(50), Indicator::Rsi(14)];
+/// let report = run_backtest_with_indicators(strategy, bars, 50, RunnerConfig::quiet(), &spec).await?;
+/// println!("{} trades", report.trades.len());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn run_backtest_with_indicators(
+    strategy: LuaStrategy,
+    bars: Vec<MarketData>,
+    window_size: usize,
+    config: RunnerConfig,
+    indicators: &[Indicator],
+) -> Result<BacktestReport> {
+    let symbol = bars
+        .first()
+        .map(|bar| bar.symbol.clone())
+        .ok_or_else(|| TradingEngineError::InvalidData("Backtest needs at least one bar".to_string()))?;
+    let bars: Vec<MarketData> = bars.into_iter().filter(|bar| bar.symbol == symbol).collect();
+    let series = precompute_indicators(&bars, indicators);
+
+    let (_data_tx, data_rx) = mpsc::unbounded_channel();
+    let mut runner = SymbolRunner::new("backtest".to_string(), symbol.clone(), strategy, data_rx, window_size)
+        .with_config(config);
+
+    let mut trades = Vec::new();
+    let mut equity_curve = Vec::with_capacity(bars.len());
+    let mut realized_pnl = 0.0;
+
+    for (index, bar) in bars.into_iter().enumerate() {
+        runner.precomputed = series
+            .iter()
+            .map(|(key, values)| (key.clone(), values[index]))
+            .collect();
+        let timestamp = bar.timestamp;
+        if !runner.step(bar).await? {
+            break;
+        }
+
+        for position in runner.state_machine.drain_closed_positions() {
+            realized_pnl += position.realized_pnl().unwrap_or(0.0);
+            trades.push(position);
+        }
+
+        let unrealized = runner
+            .position()
+            .and_then(|position| position.unrealized_pnl())
+            .unwrap_or(0.0);
+        equity_curve.push(EquityPoint {
+            timestamp,
+            equity: realized_pnl + unrealized,
+        });
+    }
+
+    Ok(BacktestReport {
+        trades,
+        stats: runner.stats().clone(),
+        equity_curve,
+        realized_pnl,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::LatencyModel;
+    use crate::strategy::test_support::TempScript;
+
+    /// Strategy that enters long on every signal and exits on the next bar
+    fn churn_strategy() -> LuaStrategy {
+        LuaStrategy::new("../lua-strategies/churn_strategy.lua").expect("Failed to load churn strategy")
+    }
+
+    /// One flat bar per close, a second apart
+    fn bars_with_closes(closes: &[f64]) -> Vec<MarketData> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| MarketData {
+                symbol: "BTCUSDT".to_string(),
+                timestamp: i as i64 * 1000,
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 10,
+                bid: close,
+                ask: close,
+                bid_qty: None,
+                ask_qty: None,
+                is_final: true,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_backtest_reports_trades_and_equity() {
+        // Rising closes: each trade buys one bar and sells the next for +10
+        let closes: Vec<f64> = (0..7).map(|i| 100.0 + 10.0 * i as f64).collect();
+
+        let report = run_backtest(churn_strategy(), bars_with_closes(&closes), 50, RunnerConfig::quiet())
+            .await
+            .unwrap();
+        assert_eq!(report.trades.len(), 2);
+        assert_eq!(report.realized_pnl, 20.0);
+        assert_eq!(report.stats.ticks_processed, 7);
+        assert_eq!(report.equity_curve.len(), 7);
+        assert_eq!(report.equity_curve.last().unwrap().equity, 20.0);
+    }
+
+    #[tokio::test]
+    async fn test_precomputed_indicators_reach_strategy() {
+        // Only trades once the precomputed EMA exists and agrees with the window's
+        let script = TempScript::new(
+            "precomputed",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    local precomputed = indicators.precomputed("ema_3")
+    local ema = indicators.ema(3)
+    if precomputed and ema and math.abs(precomputed - ema) < 1e-9 then
+        return { signal = "bullish" }
+    end
+    return nil
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 1.0 }
+end
+function manage_position(market_data, context, indicators)
+    return { action = "exit", price = market_data.close }
+end
+"#,
+        );
+        let strategy = || LuaStrategy::new(script.path()).unwrap();
+        let closes: Vec<f64> = (0..7).map(|i| 100.0 + 10.0 * i as f64).collect();
+
+        let without = run_backtest(strategy(), bars_with_closes(&closes), 50, RunnerConfig::quiet())
+            .await
+            .unwrap();
+        let with = run_backtest_with_indicators(
+            strategy(),
+            bars_with_closes(&closes),
+            50,
+            RunnerConfig::quiet(),
+            &[Indicator::Ema(3)],
+        )
+        .await
+        .unwrap();
+
+        // No signal until the third bar, then the usual enter/exit churn
+        assert!(without.trades.is_empty());
+        assert_eq!(with.trades.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_latency_changes_realized_pnl() {
+        let closes = [100.0, 100.0, 110.0, 120.0, 100.0, 100.0, 100.0, 100.0];
+
+        // Fills at the signal bar: buy 100, sell 110, then buy 100, sell 100
+        let instant = run_backtest(churn_strategy(), bars_with_closes(&closes), 50, RunnerConfig::quiet())
+            .await
+            .unwrap();
+        assert_eq!(instant.trades.len(), 2);
+        assert_eq!(instant.realized_pnl, 10.0);
+
+        // Fills a bar later: the buy signalled at 100 fills at 110, and the
+        // sell signalled at 120 fills at 100
+        let config = RunnerConfig::quiet().with_latency(LatencyModel::fixed(1));
+        let delayed = run_backtest(churn_strategy(), bars_with_closes(&closes), 50, config)
+            .await
+            .unwrap();
+        assert_eq!(delayed.trades.len(), 1);
+        assert_eq!(delayed.realized_pnl, -10.0);
+        assert_eq!(delayed.trades[0].entry_price(), 110.0);
+    }
+
+    #[tokio::test]
+    async fn test_next_bar_open_fills() {
+        // (open, close) per bar
+        let prices = [(100.0, 100.0), (100.0, 100.0), (104.0, 110.0), (112.0, 120.0), (116.0, 100.0), (100.0, 100.0)];
+        let bars = || -> Vec<MarketData> {
+            bars_with_closes(&prices.map(|(_, close)| close))
+                .into_iter()
+                .zip(prices)
+                .map(|(bar, (open, _))| MarketData { open, ..bar })
+                .collect()
+        };
+
+        // Same-bar closes: buy 100, sell 110, then buy 100, sell 100
+        let same_bar = run_backtest(churn_strategy(), bars(), 50, RunnerConfig::quiet())
+            .await
+            .unwrap();
+        assert_eq!(same_bar.trades.len(), 2);
+        assert_eq!(same_bar.trades[0].entry_price(), 100.0);
+        assert_eq!(same_bar.trades[0].exit_price(), Some(110.0));
+        assert_eq!(same_bar.realized_pnl, 10.0);
+
+        // Next-bar opens: the buy signalled on bar 1 fills at bar 2's open,
+        // the sell signalled on bar 3 at bar 4's open
+        let config = RunnerConfig::quiet().with_next_bar_open_fills();
+        let next_open = run_backtest(churn_strategy(), bars(), 50, config).await.unwrap();
+        assert_eq!(next_open.trades.len(), 1);
+        assert_eq!(next_open.trades[0].entry_price(), 104.0);
+        assert_eq!(next_open.trades[0].exit_price(), Some(116.0));
+        assert_eq!(next_open.realized_pnl, 12.0);
+    }
+
+    #[tokio::test]
+    async fn test_exit_ladder_set_on_delayed_entry() {
+        // Declares the whole exit plan at entry, with nothing in manage_position
+        let script = TempScript::new(
+            "ladder",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    return { signal = "bullish" }
+end
+function filter_commitment(market_data, context, indicators)
+    return {
+        action = "enter_long",
+        price = market_data.close,
+        quantity = 1.0,
+        exit_ladder = { { price = 110.0, fraction = 0.5 }, { price = 120.0, fraction = 0.5 } },
+    }
+end
+function manage_position(market_data, context, indicators)
+    return nil
+end
+"#,
+        );
+        let closes = [100.0, 100.0, 100.0, 110.0, 115.0, 120.0];
+        let config = RunnerConfig::quiet().with_next_bar_open_fills();
+        let report = run_backtest(LuaStrategy::new(script.path()).unwrap(), bars_with_closes(&closes), 50, config)
+            .await
+            .unwrap();
+
+        // Entered at bar 2's open, half out at 110 and the rest at 120
+        assert_eq!(report.trades.len(), 1);
+        assert_eq!(report.trades[0].entry_price(), 100.0);
+        assert_eq!(report.trades[0].exit_price(), Some(120.0));
+        assert_eq!(report.realized_pnl, 15.0);
+    }
+
+    #[tokio::test]
+    async fn test_backtest_rejects_empty_series() {
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua").unwrap();
+        assert!(run_backtest(strategy, vec![], 50, RunnerConfig::quiet()).await.is_err());
+    }
+}
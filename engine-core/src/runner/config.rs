@@ -1,30 +1,538 @@
 //! Runner configuration
 
+use super::snapshot::DEFAULT_PRICE_PRECISION;
+use crate::market_data::MarketData;
+use crate::state_machine::{CommissionSchedule, ContractType, Side};
+use chrono::{NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Which position sides a runner may open
+///
+/// Entries on a disallowed side are suppressed by the runner, which emits a
+/// warning event instead of opening the position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SideRestriction {
+    /// Only long positions (e.g., spot-only venues)
+    LongOnly,
+    /// Only short positions
+    ShortOnly,
+    /// Long and short positions
+    #[default]
+    Both,
+}
+
+impl SideRestriction {
+    /// Check if a position on `side` may be opened
+    pub fn allows(&self, side: Side) -> bool {
+        match self {
+            SideRestriction::LongOnly => side.is_long(),
+            SideRestriction::ShortOnly => side.is_short(),
+            SideRestriction::Both => true,
+        }
+    }
+}
+
+/// What happens to an entry larger than `max_position_pct` allows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OversizePolicy {
+    /// Shrink the entry to the largest allowed quantity
+    #[default]
+    Clamp,
+    /// Suppress the entry
+    Reject,
+}
+
+/// What a runner does when processing a tick fails
+///
+/// See [`TradingEngineError::is_transient`](crate::error::TradingEngineError::is_transient)
+/// for which errors count as transient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ErrorPolicy {
+    /// Count the error and move on to the next tick
+    #[default]
+    SkipTick,
+    /// Stop the runner on the first error
+    Stop,
+    /// Call the strategy again once on a transient error, and stop the
+    /// runner if it fails again or the error is fatal
+    RetryOnce,
+}
+
+/// Delay, in bars, between a strategy's entry or exit and its fill
+///
+/// A delayed order fills at the bar it comes due on (at that bar's close,
+/// or its open under [`FillTiming::NextBarOpen`]) rather than at the
+/// strategy's price, so the market can move against it in the
+/// meantime. The delay is drawn uniformly from `min_bars..=max_bars` for
+/// every order. The default fills on the signal bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LatencyModel {
+    /// Shortest delay (bars)
+    pub min_bars: u32,
+    /// Longest delay (bars)
+    pub max_bars: u32,
+}
+
+impl LatencyModel {
+    /// Fill on the signal bar
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Fill exactly `bars` bars after the signal
+    pub fn fixed(bars: u32) -> Self {
+        Self {
+            min_bars: bars,
+            max_bars: bars,
+        }
+    }
+
+    /// Fill a random number of bars in `min_bars..=max_bars` after the signal
+    pub fn random(min_bars: u32, max_bars: u32) -> Self {
+        Self {
+            min_bars: min_bars.min(max_bars),
+            max_bars: max_bars.max(min_bars),
+        }
+    }
+
+    /// Check if orders always fill on the signal bar
+    pub fn is_none(&self) -> bool {
+        self.max_bars == 0
+    }
+
+    /// Draw the delay for one order
+    pub fn sample(&self) -> u32 {
+        if self.min_bars >= self.max_bars {
+            self.min_bars
+        } else {
+            rand::thread_rng().gen_range(self.min_bars..=self.max_bars)
+        }
+    }
+}
+
+/// Which price a strategy's entries and exits fill at
+///
+/// Filling at the signal bar's close assumes the order executes at the very
+/// price the strategy just reacted to, which a backtest can't know until the
+/// bar has completed. `NextBarOpen` defers every fill by one bar and fills
+/// at that bar's open instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FillTiming {
+    /// Fill on the signal bar at the strategy's price
+    #[default]
+    SignalClose,
+    /// Fill at the open of the bar after the signal
+    NextBarOpen,
+}
+
+impl FillTiming {
+    /// Bars a fill waits given the latency model's `latency` draw
+    pub fn delay(&self, latency: u32) -> u32 {
+        match self {
+            FillTiming::SignalClose => latency,
+            FillTiming::NextBarOpen => latency.max(1),
+        }
+    }
+
+    /// Price a delayed order fills at on the bar it comes due
+    pub fn fill_price(&self, bar: &MarketData) -> f64 {
+        match self {
+            FillTiming::SignalClose => bar.close,
+            FillTiming::NextBarOpen => bar.open,
+        }
+    }
+}
+
+/// Stop loss and take profit placed from the Average True Range on entry
+///
+/// A long entry gets its stop at `entry - stop_mult * ATR` and its target at
+/// `entry + target_mult * ATR`; a short entry the mirror image. Levels the
+/// position already has are left alone.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AtrStopConfig {
+    /// ATR period (bars)
+    pub period: usize,
+    /// Stop distance in ATRs
+    pub stop_mult: f64,
+    /// Target distance in ATRs
+    pub target_mult: f64,
+}
+
+impl AtrStopConfig {
+    /// Stop and target for an entry at `entry` on `side`, given the current ATR
+    pub fn levels(&self, side: Side, entry: f64, atr: f64) -> (f64, f64) {
+        let (stop_offset, target_offset) = (self.stop_mult * atr, self.target_mult * atr);
+        if side.is_long() {
+            (entry - stop_offset, entry + target_offset)
+        } else {
+            (entry + stop_offset, entry - target_offset)
+        }
+    }
+}
+
+/// Rejects bars whose close is far from the recent closes
+///
+/// Guards strategies against single bad prints (fat fingers, bad ticks).
+/// The close is compared with the mean of the last `lookback` closes; no bar
+/// is rejected until the window holds that many. Runners count rejected
+/// closes among them, so after a lasting move to a new price level bars are
+/// accepted again once the mean catches up.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SpikeFilter {
+    /// Reject closes more than `threshold` standard deviations from the mean
+    ///
+    /// Nothing is rejected while the recent closes are perfectly flat.
+    StdDev { lookback: usize, threshold: f64 },
+    /// Reject closes more than `percent` percent away from the mean
+    Percent { lookback: usize, percent: f64 },
+}
+
+impl SpikeFilter {
+    /// Closes needed before bars are checked
+    pub fn lookback(&self) -> usize {
+        match *self {
+            SpikeFilter::StdDev { lookback, .. } | SpikeFilter::Percent { lookback, .. } => lookback,
+        }
+    }
+
+    /// Reason `close` is an outlier against `recent` closes, if it is one
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::runner::SpikeFilter;
+    ///
+    /// let filter = SpikeFilter::Percent { lookback: 3, percent: 10.0 };
+    /// assert!(filter.rejection(&[100.0, 101.0, 99.0], 150.0).is_some());
+    /// assert!(filter.rejection(&[100.0, 101.0, 99.0], 105.0).is_none());
+    /// // Too few closes to judge
+    /// assert!(filter.rejection(&[100.0], 150.0).is_none());
+    /// ```
+    pub fn rejection(&self, recent: &[f64], close: f64) -> Option<String> {
+        let lookback = self.lookback();
+        if lookback == 0 || recent.len() < lookback {
+            return None;
+        }
+        let recent = &recent[recent.len() - lookback..];
+        let mean = recent.iter().sum::<f64>() / lookback as f64;
+        let deviation = (close - mean).abs();
+
+        match *self {
+            SpikeFilter::StdDev { threshold, .. } => {
+                let variance = recent.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / lookback as f64;
+                let std_dev = variance.sqrt();
+                if std_dev == 0.0 || deviation <= threshold * std_dev {
+                    return None;
+                }
+                Some(format!(
+                    "close {:.2} is {:.1} standard deviations from the mean {:.2}",
+                    close,
+                    deviation / std_dev,
+                    mean
+                ))
+            }
+            SpikeFilter::Percent { percent, .. } => {
+                let deviation_pct = deviation / mean.abs() * 100.0;
+                if mean == 0.0 || deviation_pct <= percent {
+                    return None;
+                }
+                Some(format!("close {:.2} is {:.1}% from the mean {:.2}", close, deviation_pct, mean))
+            }
+        }
+    }
+}
 
 /// Configuration for a SymbolRunner
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunnerConfig {
-    /// Stop runner on first error (vs continue)
+    /// Stop runner on first error (vs continue); overrides `error_policy`
     pub stop_on_error: bool,
 
+    /// How tick errors are handled when `stop_on_error` is off
+    #[serde(default)]
+    pub error_policy: ErrorPolicy,
+
     /// Log every action execution
     pub log_actions: bool,
 
     /// Log position updates
     pub log_positions: bool,
 
+    /// Log, at debug level, each tick the strategy takes no action on
+    ///
+    /// Shows a strategy is being called but choosing to stay idle. The
+    /// ticks are counted in `RunnerStats::no_action_ticks` either way.
+    #[serde(default)]
+    pub log_no_action: bool,
+
+    /// Emit a `StrategyPassed` event when the strategy passes with a reason
+    ///
+    /// The reason is kept in `RunnerStats::last_no_action_reason` either way.
+    #[serde(default)]
+    pub emit_no_action_reasons: bool,
+
     /// Enable performance metrics collection
     pub collect_metrics: bool,
+
+    /// Minimum time between closing a position and opening the next one
+    ///
+    /// Measured in milliseconds of bar time. Entries signalled before the
+    /// interval has elapsed are suppressed, whatever the previous trade's
+    /// outcome. `None` disables the check.
+    #[serde(default)]
+    pub min_time_between_trades_ms: Option<i64>,
+
+    /// Window after an exit in which the same signal can't re-enter
+    ///
+    /// Measured in milliseconds of bar time from the exit. While it lasts,
+    /// an opportunity whose `signal` matches the one the last position was
+    /// entered on is ignored, until the signal changes (a different signal,
+    /// or no opportunity at all). `None` disables the guard.
+    #[serde(default)]
+    pub reentry_guard_ms: Option<i64>,
+
+    /// Position sides the strategy may open
+    #[serde(default)]
+    pub allowed_sides: SideRestriction,
+
+    /// Close positions automatically when a stop loss or take profit is hit
+    ///
+    /// When `false`, the position is left open and the runner emits a
+    /// `StopLossTriggered` / `TakeProfitTriggered` event instead, so the
+    /// strategy decides when to exit from `manage_position`.
+    #[serde(default = "default_auto_exit")]
+    pub auto_exit: bool,
+
+    /// Free-form labels for grouping runners (e.g., `env=prod`, `variant=fast`)
+    ///
+    /// Not used by the runner itself; see `TradingEngine::runners_with_tag`.
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+
+    /// Simulated slippage on every fill, in basis points
+    ///
+    /// Buys fill above and sells below the strategy's price. Both prices are
+    /// reported in `PositionOpened` / `PositionClosed` events.
+    #[serde(default)]
+    pub slippage_bps: f64,
+
+    /// Maker/taker fees charged on fills, by volume tier (none by default)
+    ///
+    /// Fees are taken out of realized P&L; see `StateMachine::set_commission`.
+    #[serde(default)]
+    pub commission: CommissionSchedule,
+
+    /// Check stops and targets against each bar's high/low instead of its close
+    ///
+    /// Catches stops pierced within a bar that closed back above them, filling
+    /// at the stop or target price. Recommended for backtests on candles.
+    #[serde(default)]
+    pub intrabar_exits: bool,
+
+    /// How positions denominate P&L (linear by default; inverse for inverse perpetuals)
+    #[serde(default)]
+    pub contract_type: ContractType,
+
+    /// Smallest entry the venue accepts, in the quote currency
+    ///
+    /// Entries whose value (price × quantity; the quantity itself for
+    /// inverse contracts) falls below this are suppressed with a warning
+    /// event. `None` disables the check.
+    #[serde(default)]
+    pub min_notional: Option<f64>,
+
+    /// Quantity increment the venue accepts
+    ///
+    /// Entry quantities are rounded to the nearest multiple before any other
+    /// check; entries that round to zero are suppressed. `None` disables rounding.
+    #[serde(default)]
+    pub lot_step: Option<f64>,
+
+    /// Limit `reduce` actions to the open quantity (the default)
+    ///
+    /// A reduce larger than the position closes it with a warning event
+    /// instead of reversing it. When `false`, the excess opens a position on
+    /// the other side, subject to the same checks as any other entry.
+    #[serde(default = "default_reduce_only")]
+    pub reduce_only: bool,
+
+    /// Skip bars whose close is an outlier against the recent closes
+    ///
+    /// A rejected bar never reaches the window or the strategy; the runner
+    /// emits a warning event instead. `None` accepts every bar.
+    #[serde(default)]
+    pub spike_filter: Option<SpikeFilter>,
+
+    /// Decimals used for formatted prices in snapshots (e.g., 2 for BTC, 8 for low-priced alts)
+    #[serde(default = "default_price_precision")]
+    pub price_precision: u32,
+
+    /// Session in which new positions may be opened: `(start, end, timezone)`
+    ///
+    /// Outside the session the runner doesn't look for opportunities and
+    /// suppresses entries; open positions are still managed. The start is
+    /// inclusive and the end exclusive, and a start after the end spans
+    /// midnight. `None` trades around the clock.
+    #[serde(default)]
+    pub trading_hours: Option<(NaiveTime, NaiveTime, Tz)>,
+
+    /// Delay between a strategy's entries/exits and their fills
+    ///
+    /// Applied on top of `slippage_bps`. Stop-loss and take-profit exits
+    /// are not delayed.
+    #[serde(default)]
+    pub latency: LatencyModel,
+
+    /// Whether entries and exits fill on the signal bar or the next bar's open
+    #[serde(default)]
+    pub fill_timing: FillTiming,
+
+    /// Directory for per-runner stats history (`<runner_id>.csv`), or `None` to disable
+    ///
+    /// A row with the runner's [`RunnerStats`](super::RunnerStats) is
+    /// appended on the first tick and then every `stats_history_interval_ms`
+    /// of bar time.
+    #[serde(default)]
+    pub stats_history_path: Option<PathBuf>,
+
+    /// Bar time between stats history rows (milliseconds)
+    #[serde(default = "default_stats_history_interval_ms")]
+    pub stats_history_interval_ms: i64,
+
+    /// Paper account balance funding positions, or `None` for unlimited funds
+    ///
+    /// Entries whose notional value exceeds the available balance are
+    /// suppressed with a warning. Realized P&L is added to the balance.
+    #[serde(default)]
+    pub starting_balance: Option<f64>,
+
+    /// Largest entry allowed, as a percent of the paper account balance
+    ///
+    /// Entries whose notional value exceeds this share of the balance are
+    /// clamped or rejected according to `oversize_policy`, with a warning
+    /// event. Needs `starting_balance`; `None` disables the cap.
+    #[serde(default)]
+    pub max_position_pct: Option<f64>,
+
+    /// How entries over `max_position_pct` are handled
+    #[serde(default)]
+    pub oversize_policy: OversizePolicy,
+
+    /// Mark the runner stale after this many milliseconds without data
+    ///
+    /// Surfaces a dead feed: the runner emits a warning and reports
+    /// `RunnerStatus::Stale` until the next tick arrives.
+    #[serde(default)]
+    pub max_idle_ms: Option<u64>,
+
+    /// Seed the window with this many recent bars before the first live tick
+    ///
+    /// Bars come from the engine's [`BarHistory`](crate::sources::BarHistory),
+    /// e.g. Binance REST klines, so indicators are warm from the start.
+    /// Ignored when the engine has no history source.
+    #[serde(default)]
+    pub rest_warmup_bars: Option<usize>,
+
+    /// Other symbols whose bars the strategy can read, for pairs and baskets
+    ///
+    /// The runner still trades its own symbol, but also receives these
+    /// symbols' bars and passes recent windows of all of them to the strategy
+    /// as the global `symbols` table (see
+    /// [`MultiSymbolContext`](crate::strategy::MultiSymbolContext)). Fixed
+    /// when the runner is added.
+    #[serde(default)]
+    pub context_symbols: Vec<String>,
+
+    /// Stop the runner after this many ticks, or `None` to run until stopped
+    ///
+    /// Bounds a backtest to a fixed horizon: ticks that fail count too, and
+    /// the runner exits with reason "max_ticks reached".
+    #[serde(default)]
+    pub max_ticks: Option<u64>,
+
+    /// Ignore bars that are still forming (`is_final == false`)
+    ///
+    /// Sources that emit in-progress candles would otherwise have the
+    /// strategy act on partial bars. Off by default.
+    #[serde(default)]
+    pub final_bars_only: bool,
+
+    /// Place stop loss and take profit from the ATR on every entry
+    ///
+    /// Skipped until the window holds enough bars for the ATR. `None`
+    /// leaves the levels to the strategy.
+    #[serde(default)]
+    pub atr_stops: Option<AtrStopConfig>,
+
+    /// Unrealized P&L percent at which the stop moves to breakeven
+    ///
+    /// Once an open position is up by at least this much, its stop loss is
+    /// moved to the entry fill (slippage included) offset by the fees paid
+    /// per unit, unless it is already at or beyond it. `None` leaves stops
+    /// to the strategy.
+    #[serde(default)]
+    pub breakeven_trigger: Option<f64>,
+}
+
+fn default_auto_exit() -> bool {
+    true
+}
+
+fn default_reduce_only() -> bool {
+    true
+}
+
+fn default_price_precision() -> u32 {
+    DEFAULT_PRICE_PRECISION
+}
+
+fn default_stats_history_interval_ms() -> i64 {
+    60_000
 }
 
 impl Default for RunnerConfig {
     fn default() -> Self {
         Self {
             stop_on_error: false,
+            error_policy: ErrorPolicy::SkipTick,
             log_actions: true,
             log_positions: false,
+            log_no_action: false,
+            emit_no_action_reasons: false,
             collect_metrics: true,
+            min_time_between_trades_ms: None,
+            reentry_guard_ms: None,
+            allowed_sides: SideRestriction::Both,
+            auto_exit: true,
+            tags: HashMap::new(),
+            slippage_bps: 0.0,
+            commission: CommissionSchedule::default(),
+            intrabar_exits: false,
+            contract_type: ContractType::Linear,
+            min_notional: None,
+            lot_step: None,
+            reduce_only: true,
+            spike_filter: None,
+            price_precision: DEFAULT_PRICE_PRECISION,
+            trading_hours: None,
+            latency: LatencyModel::none(),
+            fill_timing: FillTiming::SignalClose,
+            stats_history_path: None,
+            stats_history_interval_ms: default_stats_history_interval_ms(),
+            starting_balance: None,
+            max_position_pct: None,
+            oversize_policy: OversizePolicy::Clamp,
+            max_idle_ms: None,
+            rest_warmup_bars: None,
+            context_symbols: Vec::new(),
+            max_ticks: None,
+            final_bars_only: false,
+            atr_stops: None,
+            breakeven_trigger: None,
         }
     }
 }
@@ -34,9 +542,41 @@ impl RunnerConfig {
     pub fn production() -> Self {
         Self {
             stop_on_error: true,
+            error_policy: ErrorPolicy::Stop,
             log_actions: true,
             log_positions: true,
+            log_no_action: false,
+            emit_no_action_reasons: false,
             collect_metrics: true,
+            min_time_between_trades_ms: None,
+            reentry_guard_ms: None,
+            allowed_sides: SideRestriction::Both,
+            auto_exit: true,
+            tags: HashMap::new(),
+            slippage_bps: 0.0,
+            commission: CommissionSchedule::default(),
+            intrabar_exits: false,
+            contract_type: ContractType::Linear,
+            min_notional: None,
+            lot_step: None,
+            reduce_only: true,
+            spike_filter: None,
+            price_precision: DEFAULT_PRICE_PRECISION,
+            trading_hours: None,
+            latency: LatencyModel::none(),
+            fill_timing: FillTiming::SignalClose,
+            stats_history_path: None,
+            stats_history_interval_ms: default_stats_history_interval_ms(),
+            starting_balance: None,
+            max_position_pct: None,
+            oversize_policy: OversizePolicy::Clamp,
+            max_idle_ms: None,
+            rest_warmup_bars: None,
+            context_symbols: Vec::new(),
+            max_ticks: None,
+            final_bars_only: false,
+            atr_stops: None,
+            breakeven_trigger: None,
         }
     }
 
@@ -44,9 +584,259 @@ impl RunnerConfig {
     pub fn development() -> Self {
         Self {
             stop_on_error: false,
+            error_policy: ErrorPolicy::SkipTick,
             log_actions: true,
             log_positions: false,
+            log_no_action: true,
+            emit_no_action_reasons: true,
             collect_metrics: false,
+            min_time_between_trades_ms: None,
+            reentry_guard_ms: None,
+            allowed_sides: SideRestriction::Both,
+            auto_exit: true,
+            tags: HashMap::new(),
+            slippage_bps: 0.0,
+            commission: CommissionSchedule::default(),
+            intrabar_exits: false,
+            contract_type: ContractType::Linear,
+            min_notional: None,
+            lot_step: None,
+            reduce_only: true,
+            spike_filter: None,
+            price_precision: DEFAULT_PRICE_PRECISION,
+            trading_hours: None,
+            latency: LatencyModel::none(),
+            fill_timing: FillTiming::SignalClose,
+            stats_history_path: None,
+            stats_history_interval_ms: default_stats_history_interval_ms(),
+            starting_balance: None,
+            max_position_pct: None,
+            oversize_policy: OversizePolicy::Clamp,
+            max_idle_ms: None,
+            rest_warmup_bars: None,
+            context_symbols: Vec::new(),
+            max_ticks: None,
+            final_bars_only: false,
+            atr_stops: None,
+            breakeven_trigger: None,
+        }
+    }
+
+    /// Add a tag to this configuration
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trading_engine::runner::RunnerConfig;
+    ///
+    /// let config = RunnerConfig::production().with_tag("env", "prod");
+    /// assert_eq!(config.tags.get("env").map(String::as_str), Some("prod"));
+    /// ```
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.insert(key.into(), value.into());
+        self
+    }
+
+    /// Only open positions between `start` and `end` in `timezone`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::NaiveTime;
+    /// use chrono_tz::America::New_York;
+    /// use trading_engine::runner::RunnerConfig;
+    ///
+    /// // US equity hours
+    /// let config = RunnerConfig::production().with_trading_hours(
+    ///     NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+    ///     NaiveTime::from_hms_opt(16, 0, 0).unwrap(),
+    ///     New_York,
+    /// );
+    /// assert!(config.trading_hours.is_some());
+    /// ```
+    pub fn with_trading_hours(mut self, start: NaiveTime, end: NaiveTime, timezone: Tz) -> Self {
+        self.trading_hours = Some((start, end, timezone));
+        self
+    }
+
+    /// Append stats history rows to `<dir>/<runner_id>.csv` every `interval_ms` of bar time
+    pub fn with_stats_history(mut self, dir: impl Into<PathBuf>, interval_ms: i64) -> Self {
+        self.stats_history_path = Some(dir.into());
+        self.stats_history_interval_ms = interval_ms;
+        self
+    }
+
+    /// Ignore a repeat of the last entry's signal for `window_ms` after an exit
+    pub fn with_reentry_guard(mut self, window_ms: i64) -> Self {
+        self.reentry_guard_ms = Some(window_ms);
+        self
+    }
+
+    /// Fund positions from a paper account starting at `balance`
+    pub fn with_starting_balance(mut self, balance: f64) -> Self {
+        self.starting_balance = Some(balance);
+        self
+    }
+
+    /// Cap entries at `pct` percent of the account balance, handled per `policy`
+    pub fn with_max_position_pct(mut self, pct: f64, policy: OversizePolicy) -> Self {
+        self.max_position_pct = Some(pct);
+        self.oversize_policy = policy;
+        self
+    }
+
+    /// Mark the runner stale after `max_idle_ms` milliseconds without data
+    pub fn with_max_idle_ms(mut self, max_idle_ms: u64) -> Self {
+        self.max_idle_ms = Some(max_idle_ms);
+        self
+    }
+
+    /// Seed the window with `bars` recent bars from the engine's history source
+    pub fn with_rest_warmup(mut self, bars: usize) -> Self {
+        self.rest_warmup_bars = Some(bars);
+        self
+    }
+
+    /// Let the strategy read `symbols`' bars alongside its own
+    pub fn with_context_symbols(mut self, symbols: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.context_symbols = symbols.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Stop the runner after `max_ticks` ticks
+    pub fn with_max_ticks(mut self, max_ticks: u64) -> Self {
+        self.max_ticks = Some(max_ticks);
+        self
+    }
+
+    /// Only process complete bars, skipping ones still forming
+    pub fn with_final_bars_only(mut self) -> Self {
+        self.final_bars_only = true;
+        self
+    }
+
+    /// Denominate P&L as for `contract_type`
+    pub fn with_contract_type(mut self, contract_type: ContractType) -> Self {
+        self.contract_type = contract_type;
+        self
+    }
+
+    /// Charge fees on fills per `commission`
+    pub fn with_commission(mut self, commission: CommissionSchedule) -> Self {
+        self.commission = commission;
+        self
+    }
+
+    /// Suppress entries worth less than `min_notional` in the quote currency
+    pub fn with_min_notional(mut self, min_notional: f64) -> Self {
+        self.min_notional = Some(min_notional);
+        self
+    }
+
+    /// Round entry quantities to the nearest multiple of `lot_step`
+    pub fn with_lot_step(mut self, lot_step: f64) -> Self {
+        self.lot_step = Some(lot_step);
+        self
+    }
+
+    /// Reject bars whose close `spike_filter` flags as an outlier
+    pub fn with_spike_filter(mut self, spike_filter: SpikeFilter) -> Self {
+        self.spike_filter = Some(spike_filter);
+        self
+    }
+
+    /// Let reduces larger than the position reverse it instead of just closing it
+    pub fn with_reversals(mut self) -> Self {
+        self.reduce_only = false;
+        self
+    }
+
+    /// Round `quantity` to the nearest multiple of the lot step, if one is set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::runner::RunnerConfig;
+    ///
+    /// let config = RunnerConfig::default().with_lot_step(0.01);
+    /// assert!((config.round_to_lot(0.1234) - 0.12).abs() < 1e-12);
+    /// assert_eq!(RunnerConfig::default().round_to_lot(0.1234), 0.1234);
+    /// ```
+    pub fn round_to_lot(&self, quantity: f64) -> f64 {
+        match self.lot_step {
+            Some(step) if step > 0.0 => (quantity / step).round() * step,
+            _ => quantity,
+        }
+    }
+
+    /// Log each tick the strategy takes no action on
+    pub fn with_log_no_action(mut self) -> Self {
+        self.log_no_action = true;
+        self
+    }
+
+    /// Emit a `StrategyPassed` event for each reasoned no-action
+    pub fn with_no_action_events(mut self) -> Self {
+        self.emit_no_action_reasons = true;
+        self
+    }
+
+    /// Place stops and targets `atr_stops` ATRs from each entry
+    pub fn with_atr_stops(mut self, atr_stops: AtrStopConfig) -> Self {
+        self.atr_stops = Some(atr_stops);
+        self
+    }
+
+    /// Move the stop to breakeven once a position is up `pct` percent
+    pub fn with_breakeven_trigger(mut self, pct: f64) -> Self {
+        self.breakeven_trigger = Some(pct);
+        self
+    }
+
+    /// Handle tick errors with `policy`, keeping `stop_on_error` in step
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.stop_on_error = policy == ErrorPolicy::Stop;
+        self.error_policy = policy;
+        self
+    }
+
+    /// The error policy in effect; `stop_on_error` overrides it with `Stop`
+    pub fn effective_error_policy(&self) -> ErrorPolicy {
+        if self.stop_on_error {
+            ErrorPolicy::Stop
+        } else {
+            self.error_policy
+        }
+    }
+
+    /// Delay strategy fills according to `latency`
+    pub fn with_latency(mut self, latency: LatencyModel) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Fill entries and exits at the open of the bar after the signal
+    pub fn with_next_bar_open_fills(mut self) -> Self {
+        self.fill_timing = FillTiming::NextBarOpen;
+        self
+    }
+
+    /// Check if a bar timestamp (Unix milliseconds) falls inside the trading session
+    ///
+    /// Always `true` when no trading hours are configured.
+    pub fn in_trading_hours(&self, timestamp_ms: i64) -> bool {
+        let Some((start, end, timezone)) = self.trading_hours else {
+            return true;
+        };
+        let Some(utc) = Utc.timestamp_millis_opt(timestamp_ms).single() else {
+            return false;
+        };
+        let time = utc.with_timezone(&timezone).time();
+
+        if start <= end {
+            start <= time && time < end
+        } else {
+            time >= start || time < end
         }
     }
 
@@ -54,9 +844,105 @@ impl RunnerConfig {
     pub fn quiet() -> Self {
         Self {
             stop_on_error: false,
+            error_policy: ErrorPolicy::SkipTick,
             log_actions: false,
             log_positions: false,
+            log_no_action: false,
+            emit_no_action_reasons: false,
             collect_metrics: true,
+            min_time_between_trades_ms: None,
+            reentry_guard_ms: None,
+            allowed_sides: SideRestriction::Both,
+            auto_exit: true,
+            tags: HashMap::new(),
+            slippage_bps: 0.0,
+            commission: CommissionSchedule::default(),
+            intrabar_exits: false,
+            contract_type: ContractType::Linear,
+            min_notional: None,
+            lot_step: None,
+            reduce_only: true,
+            spike_filter: None,
+            price_precision: DEFAULT_PRICE_PRECISION,
+            trading_hours: None,
+            latency: LatencyModel::none(),
+            fill_timing: FillTiming::SignalClose,
+            stats_history_path: None,
+            stats_history_interval_ms: default_stats_history_interval_ms(),
+            starting_balance: None,
+            max_position_pct: None,
+            oversize_policy: OversizePolicy::Clamp,
+            max_idle_ms: None,
+            rest_warmup_bars: None,
+            context_symbols: Vec::new(),
+            max_ticks: None,
+            final_bars_only: false,
+            atr_stops: None,
+            breakeven_trigger: None,
         }
     }
 }
+
+/// Everything needed to create one runner
+///
+/// Used with `TradingEngine::add_runners_batch`. The strategy is loaded when
+/// the runner is added, so a bad script is reported per runner.
+///
+/// # Example
+///
+/// ```
+/// use trading_engine::runner::{RunnerConfig, RunnerSpec};
+///
+/// let spec = RunnerSpec::new("btc_ema", "BTCUSDT", "strategies/ema_crossover.lua")
+///     .with_window_size(200)
+///     .with_config(RunnerConfig::production());
+/// assert_eq!(spec.window_size, Some(200));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunnerSpec {
+    /// Unique runner ID
+    pub runner_id: String,
+
+    /// Symbol to trade
+    pub symbol: String,
+
+    /// Path to the Lua strategy script
+    pub strategy_path: PathBuf,
+
+    /// Market data window size (engine default if `None`)
+    #[serde(default)]
+    pub window_size: Option<usize>,
+
+    /// Runner configuration (engine default if `None`)
+    #[serde(default)]
+    pub config: Option<RunnerConfig>,
+}
+
+impl RunnerSpec {
+    /// Create a spec using the engine's default window size and configuration
+    pub fn new(
+        runner_id: impl Into<String>,
+        symbol: impl Into<String>,
+        strategy_path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            runner_id: runner_id.into(),
+            symbol: symbol.into(),
+            strategy_path: strategy_path.into(),
+            window_size: None,
+            config: None,
+        }
+    }
+
+    /// Set the market data window size
+    pub fn with_window_size(mut self, window_size: usize) -> Self {
+        self.window_size = Some(window_size);
+        self
+    }
+
+    /// Set the runner configuration
+    pub fn with_config(mut self, config: RunnerConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! This module provides the `TradingEngine` struct that manages multiple
 //! `SymbolRunner` instances, each running in its own Tokio task.
+//! [`TradingEngine::new_deterministic`] instead drives runners inline, which
+//! keeps tests and replays independent of task scheduling.
 //!
 //! # Architecture
 //!
@@ -67,6 +69,8 @@
 //!         # volume: 0,
 //!         # bid: 0.0,
 //!         # ask: 0.0,
+//!         # bid_qty: None,
+//!         # ask_qty: None, is_final: true,
 //!     };
 //!     engine.feed_data(data).await?;
 //!
@@ -77,14 +81,27 @@
 //! ```
 
 use crate::error::{Result, TradingEngineError};
-use crate::events::RunnerEvent;
+use crate::events::{BatchEvent, RunnerEvent, SequencedEvent};
+use crate::indicators::{CustomIndicator, IndicatorCache, IndicatorRegistry};
 use crate::market_data::MarketData;
+use crate::sinks::EventSink;
+use crate::sources::{BarHistory, ReplayFeed};
 use crate::strategy::LuaStrategy;
-use super::{RunnerConfig, RunnerCommand, RunnerSnapshot, SymbolRunner};
+use super::{ContextSnapshot, RunnerConfig, RunnerCommand, RunnerSpec, RunnerSnapshot, RunnerStats, RunnerStatus, SymbolRunner};
+use crate::state_machine::{Position, State};
+use serde::{Deserialize, Serialize};
+use futures_util::FutureExt;
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use tokio::sync::mpsc;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch, Semaphore};
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// Default time to wait for a runner to answer a command
+const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_millis(100);
 
 /// Handle to a running symbol runner
 struct RunnerHandle {
@@ -100,11 +117,285 @@ struct RunnerHandle {
     /// Channel sender for commands (introspection)
     cmd_tx: mpsc::UnboundedSender<RunnerCommand>,
 
-    /// Task handle for the runner
-    task: JoinHandle<Result<()>>,
+    /// How the runner is driven
+    execution: RunnerExecution,
 
     /// Timestamp when runner was added
     started_at: std::time::Instant,
+
+    /// Labels from the runner's configuration
+    tags: HashMap<String, String>,
+
+    /// Lets a runner still queued for a slot start anyway, so it can shut down
+    unqueue: CancellationToken,
+
+    /// Whether the runner has a slot (always true without a runner limit)
+    started: watch::Receiver<bool>,
+}
+
+impl RunnerHandle {
+    /// Check if the runner is still waiting for a slot
+    ///
+    /// A queued runner processes nothing, commands included.
+    fn is_queued(&self) -> bool {
+        !*self.started.borrow()
+    }
+
+    /// Check if the runner has exited
+    fn is_finished(&self) -> bool {
+        match &self.execution {
+            RunnerExecution::Task(task) => task.is_finished(),
+            RunnerExecution::Inline(inline) => inline.is_finished(),
+        }
+    }
+
+    /// Process everything queued for an inline runner (no-op for tasks)
+    async fn drive(&self, event_tx: &mpsc::UnboundedSender<RunnerEvent>) {
+        if let RunnerExecution::Inline(inline) = &self.execution {
+            inline.drive(event_tx).await;
+        }
+    }
+}
+
+/// Delivers runner events from the engine's channel to its subscribers
+struct EventForwarder {
+    /// Plain event subscribers
+    subscribers: Arc<Mutex<Vec<mpsc::UnboundedSender<RunnerEvent>>>>,
+
+    /// Numbered event subscribers
+    sequenced: Arc<Mutex<Vec<mpsc::UnboundedSender<SequencedEvent>>>>,
+
+    /// Batched event subscribers
+    batched: Arc<Mutex<Vec<mpsc::UnboundedSender<BatchEvent>>>>,
+
+    /// Last-known snapshot per runner
+    cache: Arc<Mutex<HashMap<String, RunnerSnapshot>>>,
+
+    /// Sequence number of the last event forwarded
+    seq: u64,
+}
+
+impl EventForwarder {
+    /// Forward events received together, dropping subscribers that hung up
+    ///
+    /// Subscriber channels are unbounded, so one that doesn't read never
+    /// holds up delivery to the others.
+    fn forward(&mut self, events: Vec<RunnerEvent>) {
+        for event in &events {
+            // Keep the last-known snapshots current
+            TradingEngine::update_snapshot_cache(&self.cache, event);
+
+            // Number every event, whether or not anyone is listening
+            self.seq += 1;
+            let seq = self.seq;
+            self.sequenced.lock().retain(|tx| {
+                tx.send(SequencedEvent {
+                    seq,
+                    event: event.clone(),
+                })
+                .is_ok()
+            });
+
+            // Forward to all subscribers
+            self.subscribers.lock().retain(|tx| tx.send(event.clone()).is_ok());
+        }
+
+        let mut batch_subs = self.batched.lock();
+        if !batch_subs.is_empty() {
+            let batches = BatchEvent::group(events);
+            batch_subs.retain(|tx| batches.iter().all(|batch| tx.send(batch.clone()).is_ok()));
+        }
+    }
+}
+
+/// How a runner's loop is driven
+enum RunnerExecution {
+    /// Runs in its own Tokio task
+    Task(JoinHandle<Result<()>>),
+
+    /// Driven inline by the engine (deterministic mode)
+    Inline(Box<InlineRunner>),
+}
+
+/// A runner driven by the engine on each feed or command instead of a task
+struct InlineRunner {
+    /// The runner (locked while processing)
+    runner: tokio::sync::Mutex<SymbolRunner>,
+
+    /// Exit result, set once the runner has stopped
+    exit: Mutex<Option<Result<()>>>,
+}
+
+impl InlineRunner {
+    fn new(runner: SymbolRunner) -> Self {
+        Self {
+            runner: tokio::sync::Mutex::new(runner),
+            exit: Mutex::new(None),
+        }
+    }
+
+    /// Check if the runner has exited
+    fn is_finished(&self) -> bool {
+        self.exit.lock().is_some()
+    }
+
+    /// Process all queued data and commands, recording the exit if the runner stops
+    async fn drive(&self, event_tx: &mpsc::UnboundedSender<RunnerEvent>) {
+        let mut runner = self.runner.lock().await;
+        if self.is_finished() {
+            return;
+        }
+
+        let result = match runner.process_pending().await {
+            Ok(true) => return,
+            Ok(false) => Ok(()),
+            Err(e) => Err(e),
+        };
+
+        report_runner_exit(event_tx, &runner, &result);
+        *self.exit.lock() = Some(result);
+    }
+
+    /// Let the runner see its closed channel and return its exit result
+    async fn finish(self, event_tx: &mpsc::UnboundedSender<RunnerEvent>) -> Result<()> {
+        if let Some(result) = self.exit.into_inner() {
+            return result;
+        }
+
+        let mut runner = self.runner.into_inner();
+        let result = runner.process_pending().await.map(|_| ());
+        report_runner_exit(event_tx, &runner, &result);
+        result
+    }
+}
+
+/// Log a runner's exit and emit its `RunnerStopped` event
+///
+/// The reason is the runner's own reason for stopping, if it had one. A
+/// runner that finished stays `Finished`; an error exit is reported as `Error`.
+fn report_runner_exit(event_tx: &mpsc::UnboundedSender<RunnerEvent>, runner: &SymbolRunner, result: &Result<()>) {
+    let (runner_id, symbol) = (runner.runner_id(), runner.symbol());
+    let (reason, status) = match result {
+        Ok(()) => {
+            tracing::info!("Runner '{}' for {} completed successfully", runner_id, symbol);
+            let status = match runner.status() {
+                RunnerStatus::Finished => RunnerStatus::Finished,
+                _ => RunnerStatus::Stopped,
+            };
+            (runner.stop_reason().unwrap_or("Normal shutdown").to_string(), status)
+        }
+        Err(e) => {
+            tracing::error!("Runner '{}' for {} stopped with error: {}", runner_id, symbol, e);
+            (format!("Error: {}", e), RunnerStatus::Error)
+        }
+    };
+
+    let _ = event_tx.send(RunnerEvent::RunnerStopped {
+        runner_id: runner_id.to_string(),
+        reason,
+        status,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    });
+}
+
+/// Point-in-time counts describing a [`TradingEngine`]
+///
+/// Structured form of [`TradingEngine::summary`], for clients that need the
+/// numbers rather than a log line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineSummary {
+    /// Runners registered with the engine
+    pub total_runners: usize,
+
+    /// Runners whose task is still running
+    pub healthy_runners: usize,
+
+    /// Runners whose task has exited
+    pub unhealthy_runners: usize,
+
+    /// Distinct symbols with at least one runner
+    pub symbol_count: usize,
+
+    /// Average runners per symbol (0 with no symbols)
+    pub runners_per_symbol: f64,
+}
+
+/// How a runner ended when the engine shut down
+///
+/// Returned per runner by [`TradingEngine::shutdown_with_results`].
+#[derive(Debug)]
+pub struct RunnerShutdown {
+    /// The runner's exit result
+    pub result: Result<()>,
+
+    /// State captured just before the runner was stopped, including any
+    /// open position and its unrealized P&L
+    pub final_snapshot: Option<RunnerSnapshot>,
+}
+
+impl RunnerShutdown {
+    /// Position the runner still held at shutdown, if any
+    pub fn open_position(&self) -> Option<&Position> {
+        self.final_snapshot.as_ref()?.position.as_ref()
+    }
+}
+
+/// Metric a [`TradingEngine::strategy_comparison`] report is sorted by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonMetric {
+    WinRate,
+    ProfitFactor,
+    #[default]
+    TotalPnl,
+    TradeCount,
+}
+
+/// One runner's closed-trade performance in a strategy comparison
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyComparisonRow {
+    pub runner_id: String,
+
+    /// Positions closed
+    pub trades: u64,
+
+    /// Percent of closed trades with a profit
+    pub win_rate: f64,
+
+    /// Gross profit / gross loss (`None` with no losing trades)
+    pub profit_factor: Option<f64>,
+
+    /// Total realized P&L
+    pub total_pnl: f64,
+}
+
+impl StrategyComparisonRow {
+    fn from_stats(runner_id: String, stats: &RunnerStats) -> Self {
+        Self {
+            runner_id,
+            trades: stats.trades,
+            win_rate: stats.win_rate(),
+            profit_factor: stats.profit_factor(),
+            total_pnl: stats.total_pnl(),
+        }
+    }
+
+    /// Value of `metric` for this row, higher is better
+    ///
+    /// A profit factor with no losses ranks above any finite one when the
+    /// runner has made money at all.
+    fn metric(&self, metric: ComparisonMetric) -> f64 {
+        match metric {
+            ComparisonMetric::WinRate => self.win_rate,
+            ComparisonMetric::ProfitFactor => match self.profit_factor {
+                Some(factor) => factor,
+                None if self.total_pnl > 0.0 => f64::INFINITY,
+                None => 0.0,
+            },
+            ComparisonMetric::TotalPnl => self.total_pnl,
+            ComparisonMetric::TradeCount => self.trades as f64,
+        }
+    }
 }
 
 /// Multi-runner trading engine
@@ -157,6 +448,33 @@ pub struct TradingEngine {
     /// Event subscribers (shared)
     /// Multiple clients can subscribe to the event stream
     event_subscribers: Arc<Mutex<Vec<mpsc::UnboundedSender<RunnerEvent>>>>,
+
+    /// Subscribers to the numbered event stream (shared)
+    sequenced_subscribers: Arc<Mutex<Vec<mpsc::UnboundedSender<SequencedEvent>>>>,
+
+    /// Subscribers to the batched event stream (shared)
+    batched_subscribers: Arc<Mutex<Vec<mpsc::UnboundedSender<BatchEvent>>>>,
+
+    /// Drive runners inline instead of spawning tasks
+    deterministic: bool,
+
+    /// Last-known snapshot per runner (shared)
+    /// Refreshed on every successful query and kept current from the event
+    /// stream, so a busy runner can still be described when it times out
+    snapshot_cache: Arc<Mutex<HashMap<String, RunnerSnapshot>>>,
+
+    /// Custom indicators shared with every runner
+    indicator_registry: IndicatorRegistry,
+
+    /// Indicator values shared by the runners on each symbol
+    indicator_caches: HashMap<String, IndicatorCache>,
+
+    /// Permits for runner tasks allowed to run at once (`None` = unlimited)
+    runner_permits: Option<(usize, Arc<Semaphore>)>,
+
+    /// Recent bars for runners with `rest_warmup_bars` set
+    bar_history: Option<Arc<dyn BarHistory>>,
+
 }
 
 impl TradingEngine {
@@ -170,28 +488,41 @@ impl TradingEngine {
     /// let engine = TradingEngine::new();
     /// ```
     pub fn new() -> Self {
-        let (event_tx, mut event_rx) = mpsc::unbounded_channel::<RunnerEvent>();
-        let event_subscribers: Arc<Mutex<Vec<mpsc::UnboundedSender<RunnerEvent>>>> =
-            Arc::new(Mutex::new(Vec::new()));
-
-        // Spawn event forwarding task
-        let subscribers = event_subscribers.clone();
-        tokio::spawn(async move {
-            while let Some(event) = event_rx.recv().await {
-                // Forward to all subscribers
-                let mut subs = subscribers.lock().unwrap();
-                subs.retain(|tx| tx.send(event.clone()).is_ok());
-            }
-        });
+        Self::with_defaults(RunnerConfig::default(), 100)
+    }
 
-        Self {
-            runners: HashMap::new(),
-            subscriptions: HashMap::new(),
-            default_config: RunnerConfig::default(),
-            default_window_size: 100,
-            event_tx,
-            event_subscribers,
-        }
+    /// Create an engine that processes data inline
+    ///
+    /// Runners are not spawned as tasks. Instead, [`feed_data`](Self::feed_data)
+    /// runs each subscribed runner until its queue is empty before returning,
+    /// and commands (snapshots, pause, ...) are answered immediately. Results
+    /// therefore don't depend on task scheduling, which makes this suited to
+    /// tests and replays. Runners for one symbol are processed one after another.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use trading_engine::runner::TradingEngine;
+    /// # use trading_engine::strategy::LuaStrategy;
+    /// # use trading_engine::MarketData;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let data: MarketData = unimplemented!();
+    /// let mut engine = TradingEngine::new_deterministic();
+    /// engine.add_runner("btc_ema", "BTCUSDT", LuaStrategy::new("strategies/ema.lua")?)?;
+    ///
+    /// engine.feed_data(data).await?;
+    ///
+    /// // No need to wait: the tick has already been processed
+    /// let snapshot = engine.get_runner_snapshot("btc_ema").await.unwrap();
+    /// assert_eq!(snapshot.stats.ticks_processed, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_deterministic() -> Self {
+        let mut engine = Self::new();
+        engine.deterministic = true;
+        engine
     }
 
     /// Create an engine with custom defaults
@@ -213,14 +544,37 @@ impl TradingEngine {
         let (event_tx, mut event_rx) = mpsc::unbounded_channel::<RunnerEvent>();
         let event_subscribers: Arc<Mutex<Vec<mpsc::UnboundedSender<RunnerEvent>>>> =
             Arc::new(Mutex::new(Vec::new()));
+        let sequenced_subscribers: Arc<Mutex<Vec<mpsc::UnboundedSender<SequencedEvent>>>> =
+            Arc::new(Mutex::new(Vec::new()));
+        let batched_subscribers: Arc<Mutex<Vec<mpsc::UnboundedSender<BatchEvent>>>> =
+            Arc::new(Mutex::new(Vec::new()));
+
+        let snapshot_cache: Arc<Mutex<HashMap<String, RunnerSnapshot>>> =
+            Arc::new(Mutex::new(HashMap::new()));
 
         // Spawn event forwarding task
-        let subscribers = event_subscribers.clone();
+        let mut forwarder = EventForwarder {
+            subscribers: event_subscribers.clone(),
+            sequenced: sequenced_subscribers.clone(),
+            batched: batched_subscribers.clone(),
+            cache: snapshot_cache.clone(),
+            seq: 0,
+        };
         tokio::spawn(async move {
-            while let Some(event) = event_rx.recv().await {
-                // Forward to all subscribers
-                let mut subs = subscribers.lock().unwrap();
-                subs.retain(|tx| tx.send(event.clone()).is_ok());
+            while let Some(first) = event_rx.recv().await {
+                // Take everything queued since, so a tick's events from many
+                // runners can be batched
+                let mut events = vec![first];
+                while let Ok(event) = event_rx.try_recv() {
+                    events.push(event);
+                }
+
+                // A panic loses these events, not the stream: the locks
+                // don't poison, so forwarding carries on with the next ones
+                let count = events.len();
+                if catch_unwind(AssertUnwindSafe(|| forwarder.forward(events))).is_err() {
+                    tracing::error!("Event forwarding panicked, dropped {} events", count);
+                }
             }
         });
 
@@ -231,9 +585,179 @@ impl TradingEngine {
             default_window_size: window_size,
             event_tx,
             event_subscribers,
+            sequenced_subscribers,
+            batched_subscribers,
+            snapshot_cache,
+            deterministic: false,
+            indicator_registry: IndicatorRegistry::new(),
+            indicator_caches: HashMap::new(),
+            runner_permits: None,
+            bar_history: None,
+        }
+    }
+
+    /// Apply an event to the snapshot cache
+    ///
+    /// The event is folded into the runner's snapshot, if it has one. Entries
+    /// are only added and removed by the engine itself (when runners are
+    /// added, renamed or removed), so a late event can't bring back an ID
+    /// that is gone.
+    fn update_snapshot_cache(
+        cache: &Mutex<HashMap<String, RunnerSnapshot>>,
+        event: &RunnerEvent,
+    ) {
+        if let Some(snapshot) = cache.lock().get_mut(event.runner_id()) {
+            snapshot.apply_event(event);
+        }
+    }
+
+    /// Snapshot of a runner that hasn't processed anything yet
+    fn starting_snapshot(runner_id: &str, symbol: &str) -> RunnerSnapshot {
+        RunnerSnapshot::new(
+            runner_id.to_string(),
+            symbol.to_string(),
+            RunnerStatus::Starting,
+            State::Idle,
+            None,
+            ContextSnapshot::default(),
+            RunnerStats::new(),
+            Duration::ZERO,
+        )
+    }
+
+    /// Last-known snapshot of a runner, with uptime and tags brought up to date
+    ///
+    /// A queued runner has processed nothing, so without a cached snapshot
+    /// it gets a starting one.
+    fn cached_snapshot(cache: &HashMap<String, RunnerSnapshot>, handle: &RunnerHandle) -> Option<RunnerSnapshot> {
+        let mut snapshot = match cache.get(&handle.runner_id) {
+            Some(snapshot) => snapshot.clone(),
+            None if handle.is_queued() => Self::starting_snapshot(&handle.runner_id, &handle.symbol),
+            None => return None,
+        };
+        snapshot.uptime_secs = handle.started_at.elapsed().as_secs();
+        snapshot.tags = handle.tags.clone();
+        Some(snapshot)
+    }
+
+    /// Register a custom indicator for all runners
+    ///
+    /// Strategies read it with `indicators.custom(name)`. Runners that already
+    /// exist see the indicator from their next tick.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use trading_engine::runner::TradingEngine;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let engine = TradingEngine::new();
+    /// engine.register_indicator(
+    ///     "last_close_squared",
+    ///     Box::new(|window| window.latest().map(|bar| bar.close * bar.close)),
+    /// );
+    /// # }
+    /// ```
+    pub fn register_indicator(&self, name: impl Into<String>, indicator: CustomIndicator) {
+        self.indicator_registry.register(name, indicator);
+    }
+
+    /// Cap the number of runner tasks processing at once
+    ///
+    /// Every runner gets its own task; with thousands of runners that can
+    /// swamp the runtime. With a limit, a runner added beyond it is queued:
+    /// its task waits for a running runner to stop or be removed before it
+    /// starts. The tradeoff is that a queued runner processes nothing until
+    /// then. Data fed to it is buffered in its channel, and snapshot queries
+    /// are answered from the last-known snapshot without waiting. Inline runners
+    /// ([`new_deterministic`](Self::new_deterministic)) ignore the limit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trading_engine::runner::TradingEngine;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let engine = TradingEngine::new().with_max_active_runners(500);
+    /// assert_eq!(engine.max_active_runners(), Some(500));
+    /// # }
+    /// ```
+    pub fn with_max_active_runners(mut self, limit: usize) -> Self {
+        let limit = limit.max(1);
+        self.runner_permits = Some((limit, Arc::new(Semaphore::new(limit))));
+        self
+    }
+
+    /// Seed new runners' windows from `history` before their first live tick
+    ///
+    /// Only runners whose config sets `rest_warmup_bars` are seeded, with
+    /// that many bars, so their indicators are ready immediately.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use trading_engine::runner::{TradingEngine, RunnerConfig};
+    /// # use trading_engine::strategy::LuaStrategy;
+    /// use trading_engine::sources::{BinanceKlineHistory, BinanceRegion};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let mut engine = TradingEngine::new()
+    ///     .with_bar_history(BinanceKlineHistory::new("1m", BinanceRegion::US));
+    ///
+    /// let strategy = LuaStrategy::new("strategies/ema_crossover.lua")?;
+    /// let config = RunnerConfig::production().with_rest_warmup(200);
+    /// engine.add_runner_with_config("btc_ema", "BTCUSDT", strategy, 200, config)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_bar_history(mut self, history: impl BarHistory + 'static) -> Self {
+        self.bar_history = Some(Arc::new(history));
+        self
+    }
+
+    /// Limit on runner tasks processing at once, if any
+    pub fn max_active_runners(&self) -> Option<usize> {
+        self.runner_permits.as_ref().map(|(limit, _)| *limit)
+    }
+
+    /// IDs of runners waiting for a slot under the runner limit, sorted
+    pub fn queued_runner_ids(&self) -> Vec<String> {
+        let mut runner_ids: Vec<String> = self
+            .runners
+            .iter()
+            .filter(|(_, handle)| handle.is_queued())
+            .map(|(runner_id, _)| runner_id.clone())
+            .collect();
+        runner_ids.sort();
+        runner_ids
+    }
+
+    /// Number of runners currently processing (not queued or finished)
+    pub fn active_runner_count(&self) -> usize {
+        match &self.runner_permits {
+            Some((limit, permits)) => limit - permits.available_permits(),
+            None => self.runners.values().filter(|handle| !handle.is_finished()).count(),
         }
     }
 
+    /// Custom indicators shared with this engine's runners
+    pub fn indicator_registry(&self) -> &IndicatorRegistry {
+        &self.indicator_registry
+    }
+
+    /// Indicator values shared by the runners on `symbol`
+    ///
+    /// Every runner on a symbol reads SMA, EMA, RSI, Bollinger and linear
+    /// regression values through one cache, so an indicator several
+    /// strategies ask for is computed once per tick. `None` if no runner
+    /// watches the symbol.
+    pub fn indicator_cache(&self, symbol: &str) -> Option<&IndicatorCache> {
+        self.indicator_caches.get(symbol)
+    }
+
     /// Subscribe to all runner events
     ///
     /// Returns a channel receiver that will receive all events from all runners.
@@ -256,10 +780,69 @@ impl TradingEngine {
     /// ```
     pub fn subscribe_events(&self) -> mpsc::UnboundedReceiver<RunnerEvent> {
         let (tx, rx) = mpsc::unbounded_channel();
-        self.event_subscribers.lock().unwrap().push(tx);
+        self.event_subscribers.lock().push(tx);
+        rx
+    }
+
+    /// Subscribe to all runner events, numbered in forwarding order
+    ///
+    /// Like [`subscribe_events`](Self::subscribe_events), but each event
+    /// carries the engine-wide sequence number it was forwarded with. A
+    /// client that reconnects can skip events up to the last `seq` it saw,
+    /// and a jump of more than one means events were missed.
+    pub fn subscribe_sequenced_events(&self) -> mpsc::UnboundedReceiver<SequencedEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.sequenced_subscribers.lock().push(tx);
+        rx
+    }
+
+    /// Subscribe to runner events grouped per tick
+    ///
+    /// Events forwarded together that share a timestamp arrive as one
+    /// [`BatchEvent`], so a tick broadcast to many runners yields a single
+    /// message rather than a burst. Useful for clients that pay per message,
+    /// like a WebSocket. Batching is best effort: with runners in their own
+    /// tasks, events that arrive while the previous ones are being forwarded
+    /// start a new batch. A [deterministic](Self::new_deterministic) engine
+    /// queues a whole tick before forwarding, so each tick is one batch.
+    pub fn subscribe_batched_events(&self) -> mpsc::UnboundedReceiver<BatchEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.batched_subscribers.lock().push(tx);
         rx
     }
 
+    /// Register a sink that receives every runner event
+    ///
+    /// The sink is attached like a subscriber: it sees every event emitted
+    /// after registration, in order. It is driven from a blocking task so it
+    /// may do synchronous I/O. Write errors are logged and do not stop delivery.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use trading_engine::runner::TradingEngine;
+    /// # use trading_engine::sinks::{JsonlFileSink, Rotation};
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let engine = TradingEngine::new();
+    /// engine.add_event_sink(JsonlFileSink::new("logs/events.jsonl", Rotation::Daily)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_event_sink<S: EventSink>(&self, mut sink: S) {
+        let mut events = self.subscribe_events();
+        tokio::task::spawn_blocking(move || {
+            while let Some(event) = events.blocking_recv() {
+                if let Err(e) = sink.write_event(&event) {
+                    tracing::error!("Event sink '{}' failed to write event: {}", sink.sink_name(), e);
+                }
+            }
+            if let Err(e) = sink.flush() {
+                tracing::error!("Event sink '{}' failed to flush: {}", sink.sink_name(), e);
+            }
+        });
+    }
+
     /// Add a runner with default configuration
     ///
     /// # Arguments
@@ -368,6 +951,9 @@ impl TradingEngine {
         // Create channel for commands
         let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
 
+        let tags = config.tags.clone();
+        let context_symbols = config.context_symbols.clone();
+
         // Create runner with event channel and command channel
         let mut runner = SymbolRunner::new(
             runner_id.clone(),
@@ -378,7 +964,12 @@ impl TradingEngine {
         )
         .with_config(config)
         .with_event_channel(self.event_tx.clone())
-        .with_command_channel(cmd_rx);
+        .with_command_channel(cmd_rx)
+        .with_indicator_registry(self.indicator_registry.clone())
+        .with_indicator_cache(self.indicator_caches.entry(symbol.clone()).or_default().clone());
+        if let Some(history) = &self.bar_history {
+            runner = runner.with_bar_history(history.clone());
+        }
 
         // Emit RunnerStarted event
         let _ = self.event_tx.send(RunnerEvent::RunnerStarted {
@@ -386,42 +977,57 @@ impl TradingEngine {
             symbol: symbol.clone(),
             timestamp: chrono::Utc::now().timestamp_millis(),
         });
-
-        // Spawn task
-        let task_runner_id = runner_id.clone();
-        let task_symbol = symbol.clone();
-        let event_tx = self.event_tx.clone();
-        let task = tokio::spawn(async move {
-            tracing::info!("Starting runner '{}' for {}", task_runner_id, task_symbol);
-            let result = runner.run().await;
-            if let Err(ref e) = result {
-                tracing::error!(
-                    "Runner '{}' for {} stopped with error: {}",
-                    task_runner_id,
-                    task_symbol,
-                    e
-                );
-            } else {
-                tracing::info!(
-                    "Runner '{}' for {} completed successfully",
-                    task_runner_id,
-                    task_symbol
-                );
+        self.snapshot_cache
+            .lock()
+            .insert(runner_id.clone(), Self::starting_snapshot(&runner_id, &symbol));
+
+        let unqueue = CancellationToken::new();
+        let (started_tx, started) = watch::channel(true);
+        let execution = if self.deterministic {
+            tracing::info!("Starting inline runner '{}' for {}", runner_id, symbol);
+            RunnerExecution::Inline(Box::new(InlineRunner::new(runner)))
+        } else {
+            // Spawn task
+            let task_symbol = symbol.clone();
+            let event_tx = self.event_tx.clone();
+            let permits = self.runner_permits.as_ref().map(|(_, permits)| permits.clone());
+            let unqueue = unqueue.clone();
+
+            // A free slot is taken now, so runners queue in the order they're added
+            let permit = permits.as_ref().and_then(|permits| permits.clone().try_acquire_owned().ok());
+            if permits.is_some() && permit.is_none() {
+                tracing::info!("Runner '{}' queued until a runner slot frees", runner_id);
+                started_tx.send_replace(false);
             }
 
-            // Emit RunnerStopped event
-            let _ = event_tx.send(RunnerEvent::RunnerStopped {
-                runner_id: task_runner_id,
-                reason: if result.is_ok() {
-                    "Normal shutdown".to_string()
-                } else {
-                    format!("Error: {}", result.as_ref().unwrap_err())
-                },
-                timestamp: chrono::Utc::now().timestamp_millis(),
+            let task = tokio::spawn(async move {
+                // Held until the runner exits. A removed runner skips the
+                // queue so it can drain its channel and stop.
+                let _permit = match (permit, permits) {
+                    (Some(permit), _) => Some(permit),
+                    (None, Some(permits)) => tokio::select! {
+                        permit = permits.acquire_owned() => permit.ok(),
+                        _ = unqueue.cancelled() => None,
+                    },
+                    (None, None) => None,
+                };
+                started_tx.send_replace(true);
+                tracing::info!("Starting runner '{}' for {}", runner.runner_id(), task_symbol);
+
+                // A panic is reported like an error exit, so the runner shows
+                // up as failed instead of silently disappearing
+                let result = match AssertUnwindSafe(runner.run()).catch_unwind().await {
+                    Ok(result) => result,
+                    Err(_) => {
+                        tracing::error!("Runner '{}' for {} panicked", runner.runner_id(), task_symbol);
+                        Err(TradingEngineError::TaskPanic(runner.runner_id().to_string()))
+                    }
+                };
+                report_runner_exit(&event_tx, &runner, &result);
+                result
             });
-
-            result
-        });
+            RunnerExecution::Task(task)
+        };
 
         // Store handle
         self.runners.insert(
@@ -431,83 +1037,160 @@ impl TradingEngine {
                 symbol: symbol.clone(),
                 tx,
                 cmd_tx,
-                task,
+                execution,
                 started_at: std::time::Instant::now(),
+                tags,
+                unqueue,
+                started,
             },
         );
 
-        // Add to subscriptions
-        self.subscriptions
-            .entry(symbol.clone())
-            .or_insert_with(Vec::new)
-            .push(runner_id.clone());
+        // Add to subscriptions, along with any context symbols' feeds
+        for subscribed in std::iter::once(&symbol).chain(&context_symbols) {
+            let subs = self.subscriptions.entry(subscribed.clone()).or_insert_with(Vec::new);
+            if !subs.contains(&runner_id) {
+                subs.push(runner_id.clone());
+            }
+        }
 
         tracing::info!(
             "Added runner '{}' for symbol {} (total runners for {}: {})",
             runner_id,
             symbol,
             symbol,
-            self.subscriptions.get(&symbol).map(|v| v.len()).unwrap_or(0)
+            self.runner_count_for_symbol(&symbol)
         );
 
         Ok(())
     }
 
-    /// Remove a runner from the engine
+    /// Add several runners, continuing past failures
     ///
-    /// Closes the market data channel and waits for the runner to shut down.
+    /// Each spec is attempted in order; a runner that fails to load or
+    /// collides with an existing ID does not prevent the rest from starting.
     ///
     /// # Arguments
     ///
-    /// * `runner_id` - ID of the runner to remove
+    /// * `specs` - Runners to add
     ///
-    /// # Errors
+    /// # Returns
     ///
-    /// Returns error if runner doesn't exist or runner task panicked.
+    /// `(runner_id, outcome)` for every spec, in the order given.
     ///
     /// # Example
     ///
     /// ```no_run
-    /// # use trading_engine::runner::TradingEngine;
+    /// # use trading_engine::runner::{TradingEngine, RunnerSpec};
     /// # #[tokio::main]
-    /// # async fn main() -> anyhow::Result<()> {
-    /// # let mut engine = TradingEngine::new();
-    /// engine.remove_runner("btc_ema_1").await?;
-    /// # Ok(())
+    /// # async fn main() {
+    /// let mut engine = TradingEngine::new();
+    ///
+    /// let results = engine.add_runners_batch(vec![
+    ///     RunnerSpec::new("btc_ema", "BTCUSDT", "strategies/ema_crossover.lua"),
+    ///     RunnerSpec::new("eth_ema", "ETHUSDT", "strategies/ema_crossover.lua"),
+    /// ]);
+    /// for (runner_id, result) in results {
+    ///     if let Err(e) = result {
+    ///         eprintln!("{} failed: {}", runner_id, e);
+    ///     }
+    /// }
     /// # }
     /// ```
-    pub async fn remove_runner(&mut self, runner_id: &str) -> Result<()> {
-        let handle = self.runners.remove(runner_id).ok_or_else(|| {
-            TradingEngineError::RunnerNotFound(runner_id.to_string())
+    pub fn add_runners_batch(&mut self, specs: Vec<RunnerSpec>) -> Vec<(String, Result<()>)> {
+        specs
+            .into_iter()
+            .map(|spec| {
+                let runner_id = spec.runner_id.clone();
+                let result = self.add_runner_from_spec(spec);
+                if let Err(e) = &result {
+                    tracing::warn!("Failed to add runner '{}': {}", runner_id, e);
+                }
+                (runner_id, result)
+            })
+            .collect()
+    }
+
+    /// Load a spec's strategy and add the runner
+    fn add_runner_from_spec(&mut self, spec: RunnerSpec) -> Result<()> {
+        // Skip loading the strategy for an ID that is already taken
+        if self.runners.contains_key(&spec.runner_id) {
+            return Err(TradingEngineError::RunnerAlreadyExists(spec.runner_id));
+        }
+
+        let strategy = LuaStrategy::new(&spec.strategy_path)?;
+        let window_size = spec.window_size.unwrap_or(self.default_window_size);
+        let config = spec.config.unwrap_or_else(|| self.default_config.clone());
+
+        self.add_runner_with_config(spec.runner_id, spec.symbol, strategy, window_size, config)
+    }
+
+    /// Remove a runner from the engine
+    ///
+    /// Closes the market data channel and waits for the runner to shut down.
+    ///
+    /// # Arguments
+    ///
+    /// * `runner_id` - ID of the runner to remove
+    ///
+    /// # Errors
+    ///
+    /// Returns error if runner doesn't exist or runner task panicked.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use trading_engine::runner::TradingEngine;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let mut engine = TradingEngine::new();
+    /// engine.remove_runner("btc_ema_1").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn remove_runner(&mut self, runner_id: &str) -> Result<()> {
+        let handle = self.runners.remove(runner_id).ok_or_else(|| {
+            TradingEngineError::RunnerNotFound(runner_id.to_string())
         })?;
 
         tracing::info!("Removing runner '{}'", runner_id);
 
-        // Remove from subscriptions
-        if let Some(subs) = self.subscriptions.get_mut(&handle.symbol) {
+        // Forget the cached snapshot
+        self.snapshot_cache.lock().remove(runner_id);
+
+        // Remove from subscriptions, context symbols included
+        self.subscriptions.retain(|symbol, subs| {
             subs.retain(|id| id != runner_id);
             if subs.is_empty() {
-                self.subscriptions.remove(&handle.symbol);
+                self.indicator_caches.remove(symbol);
             }
-        }
+            !subs.is_empty()
+        });
 
         // Drop the sender to close the channel
         drop(handle.tx);
+        handle.unqueue.cancel();
+
+        // Wait for the runner to complete
+        let result = match handle.execution {
+            RunnerExecution::Task(task) => match task.await {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::error!("Runner '{}' task panicked: {}", runner_id, e);
+                    return Err(TradingEngineError::TaskPanic(runner_id.to_string()));
+                }
+            },
+            RunnerExecution::Inline(inline) => (*inline).finish(&self.event_tx).await,
+        };
 
-        // Wait for the task to complete
-        match handle.task.await {
-            Ok(Ok(())) => {
+        match result {
+            Ok(()) => {
                 tracing::info!("Runner '{}' removed successfully", runner_id);
                 Ok(())
             }
-            Ok(Err(e)) => {
+            Err(e) => {
                 tracing::error!("Runner '{}' returned error: {}", runner_id, e);
                 Err(e)
             }
-            Err(e) => {
-                tracing::error!("Runner '{}' task panicked: {}", runner_id, e);
-                Err(TradingEngineError::TaskPanic(runner_id.to_string()))
-            }
         }
     }
 
@@ -515,6 +1198,9 @@ impl TradingEngine {
     ///
     /// Broadcasts the data to ALL runners subscribed to the symbol.
     /// This allows multiple strategies to process the same data in parallel.
+    /// Indicators the runners ask for are computed once per tick and shared
+    /// through the symbol's [`indicator_cache`](Self::indicator_cache).
+    /// Runners whose channel has closed are skipped.
     ///
     /// # Arguments
     ///
@@ -522,7 +1208,7 @@ impl TradingEngine {
     ///
     /// # Errors
     ///
-    /// Returns error if no runners are watching this symbol or if any channel is closed.
+    /// Returns error if no runners are watching this symbol.
     ///
     /// # Example
     ///
@@ -542,6 +1228,9 @@ impl TradingEngine {
     ///     volume: 1000,
     ///     bid: 50045.0,
     ///     ask: 50055.0,
+    ///     bid_qty: None,
+    ///     ask_qty: None,
+    ///     is_final: true,
     /// };
     ///
     /// // Broadcasts to all runners watching BTCUSDT
@@ -550,21 +1239,115 @@ impl TradingEngine {
     /// # }
     /// ```
     pub async fn feed_data(&self, data: MarketData) -> Result<()> {
+        self.feed_data_counted(data).await.map(|_| ())
+    }
+
+    /// Feed market data to the runners watching its symbol, counting deliveries
+    ///
+    /// Like [`feed_data`](Self::feed_data), but the number of runners the
+    /// data reached is returned, including runners reading the
+    /// symbol as a context symbol. A count below
+    /// [`runner_count_for_symbol`](Self::runner_count_for_symbol) means some
+    /// runners have exited.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoRunnersForSymbol` if no runners are watching the symbol.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use trading_engine::runner::TradingEngine;
+    /// # use trading_engine::MarketData;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let engine = TradingEngine::new();
+    /// let data = MarketData::from_trade("BTCUSDT", 50000.0, 0.1, 1234567890);
+    /// let delivered = engine.feed_data_counted(data).await?;
+    /// println!("Delivered to {}/{} runners", delivered, engine.runner_count_for_symbol("BTCUSDT"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn feed_data_counted(&self, data: MarketData) -> Result<usize> {
         let runner_ids = self.subscriptions.get(&data.symbol).ok_or_else(|| {
             TradingEngineError::NoRunnersForSymbol(data.symbol.clone())
         })?;
 
-        // Broadcast to all runners watching this symbol
+        let mut delivered = 0;
         for runner_id in runner_ids {
-            if let Some(handle) = self.runners.get(runner_id) {
-                // Clone data for each runner
-                handle.tx.send(data.clone()).map_err(|_| {
-                    TradingEngineError::ChannelClosed(runner_id.clone())
-                })?;
+            let Some(handle) = self.runners.get(runner_id) else {
+                continue;
+            };
+            if handle.tx.send(data.clone()).is_err() {
+                tracing::debug!("Runner '{}' channel closed, skipping", runner_id);
+                continue;
             }
+            handle.drive(&self.event_tx).await;
+            delivered += 1;
         }
 
-        Ok(())
+        Ok(delivered)
+    }
+
+    /// Replay `feed` up to and including `stop_ts`, then snapshot every runner
+    ///
+    /// Feeds the feed's next bars timestamped at or before `stop_ts`, so a
+    /// backtest can be stepped through in segments by calling this with the
+    /// same feed and increasing cutoffs. The feed keeps track of how far the
+    /// replay has got. Bars for symbols without runners are skipped.
+    ///
+    /// Runner state carries over between calls. To replay from the first bar
+    /// again, [`reset`](crate::MarketDataSource::reset) the feed and add the
+    /// runners to a fresh engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidConfig` unless the engine is
+    /// [deterministic](Self::new_deterministic), since otherwise the
+    /// snapshots could be taken before the runners have caught up, and any
+    /// error from [`feed_data`](Self::feed_data) other than a symbol having
+    /// no runners.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use trading_engine::runner::TradingEngine;
+    /// # use trading_engine::strategy::LuaStrategy;
+    /// use trading_engine::sources::ReplayFeed;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let bars: Vec<trading_engine::MarketData> = vec![];
+    /// let mut engine = TradingEngine::new_deterministic();
+    /// engine.add_runner("btc_ema", "BTCUSDT", LuaStrategy::new("strategies/ema.lua")?)?;
+    /// let mut feed = ReplayFeed::new(bars);
+    ///
+    /// // Stop just before the trade being debugged, then step past it
+    /// let before = engine.run_backtest_until(&mut feed, 1_700_000_000_000).await?;
+    /// println!("{:?}", before["btc_ema"].position);
+    /// let after = engine.run_backtest_until(&mut feed, 1_700_000_060_000).await?;
+    /// println!("{:?}", after["btc_ema"].position);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn run_backtest_until(
+        &mut self,
+        feed: &mut ReplayFeed,
+        stop_ts: i64,
+    ) -> Result<HashMap<String, RunnerSnapshot>> {
+        if !self.deterministic {
+            return Err(TradingEngineError::InvalidConfig(
+                "run_backtest_until needs a deterministic engine".to_string(),
+            ));
+        }
+
+        for bar in feed.next_until(stop_ts) {
+            match self.feed_data(bar.clone()).await {
+                Ok(()) | Err(TradingEngineError::NoRunnersForSymbol(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(self.all_snapshots().await)
     }
 
     /// Feed market data to multiple symbols
@@ -588,8 +1371,8 @@ impl TradingEngine {
     /// # async fn main() -> anyhow::Result<()> {
     /// # let mut engine = TradingEngine::new();
     /// let batch = vec![
-    ///     MarketData { symbol: "BTCUSDT".to_string(), /* ... */ # timestamp: 0, open: 0.0, high: 0.0, low: 0.0, close: 0.0, volume: 0, bid: 0.0, ask: 0.0 },
-    ///     MarketData { symbol: "ETHUSDT".to_string(), /* ... */ # timestamp: 0, open: 0.0, high: 0.0, low: 0.0, close: 0.0, volume: 0, bid: 0.0, ask: 0.0 },
+    ///     MarketData { symbol: "BTCUSDT".to_string(), /* ... */ # timestamp: 0, open: 0.0, high: 0.0, low: 0.0, close: 0.0, volume: 0, bid: 0.0, ask: 0.0, bid_qty: None, ask_qty: None, is_final: true },
+    ///     MarketData { symbol: "ETHUSDT".to_string(), /* ... */ # timestamp: 0, open: 0.0, high: 0.0, low: 0.0, close: 0.0, volume: 0, bid: 0.0, ask: 0.0, bid_qty: None, ask_qty: None, is_final: true },
     /// ];
     /// engine.feed_batch(batch).await?;
     /// # Ok(())
@@ -602,7 +1385,7 @@ impl TradingEngine {
         Ok(())
     }
 
-    /// Get list of all runner IDs
+    /// Get list of all runner IDs, sorted
     ///
     /// # Example
     ///
@@ -612,12 +1395,14 @@ impl TradingEngine {
     /// let runner_ids = engine.runner_ids();
     /// ```
     pub fn runner_ids(&self) -> Vec<String> {
-        self.runners.keys().cloned().collect()
+        let mut runner_ids: Vec<String> = self.runners.keys().cloned().collect();
+        runner_ids.sort();
+        runner_ids
     }
 
     /// Get list of symbols being watched
     ///
-    /// Returns unique symbols that have at least one runner.
+    /// Returns unique symbols that have at least one runner, sorted.
     ///
     /// # Example
     ///
@@ -627,11 +1412,15 @@ impl TradingEngine {
     /// let symbols = engine.active_symbols();
     /// ```
     pub fn active_symbols(&self) -> Vec<String> {
-        self.subscriptions.keys().cloned().collect()
+        let mut symbols: Vec<String> = self.subscriptions.keys().cloned().collect();
+        symbols.sort();
+        symbols
     }
 
     /// Get runner count for a specific symbol
     ///
+    /// Runners that only read the symbol as a context symbol aren't counted.
+    ///
     /// # Example
     ///
     /// ```
@@ -640,13 +1429,12 @@ impl TradingEngine {
     /// let count = engine.runner_count_for_symbol("BTCUSDT");
     /// ```
     pub fn runner_count_for_symbol(&self, symbol: &str) -> usize {
-        self.subscriptions
-            .get(symbol)
-            .map(|v| v.len())
-            .unwrap_or(0)
+        self.runners_for_symbol(symbol).len()
     }
 
-    /// Get runner IDs watching a specific symbol
+    /// Get runner IDs trading a specific symbol
+    ///
+    /// Runners that only read the symbol as a context symbol aren't included.
     ///
     /// # Example
     ///
@@ -658,8 +1446,37 @@ impl TradingEngine {
     pub fn runners_for_symbol(&self, symbol: &str) -> Vec<String> {
         self.subscriptions
             .get(symbol)
+            .into_iter()
+            .flatten()
+            .filter(|runner_id| self.runners.get(*runner_id).is_some_and(|handle| handle.symbol == symbol))
             .cloned()
-            .unwrap_or_default()
+            .collect()
+    }
+
+    /// Get runner IDs carrying the tag `key=value`, sorted
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use trading_engine::runner::TradingEngine;
+    /// let engine = TradingEngine::new();
+    /// let prod_runners = engine.runners_with_tag("env", "prod");
+    /// ```
+    pub fn runners_with_tag(&self, key: &str, value: &str) -> Vec<String> {
+        let mut runner_ids = self.runners
+            .values()
+            .filter(|handle| handle.tags.get(key).map(String::as_str) == Some(value))
+            .map(|handle| handle.runner_id.clone())
+            .collect::<Vec<_>>();
+        runner_ids.sort();
+        runner_ids
+    }
+
+    /// Get the tags of a runner
+    ///
+    /// Returns `None` if the runner doesn't exist.
+    pub fn runner_tags(&self, runner_id: &str) -> Option<HashMap<String, String>> {
+        self.runners.get(runner_id).map(|handle| handle.tags.clone())
     }
 
     /// Get total number of active runners
@@ -736,7 +1553,7 @@ impl TradingEngine {
     /// }
     /// ```
     pub fn runner_is_healthy(&self, runner_id: &str) -> Option<bool> {
-        self.runners.get(runner_id).map(|h| !h.task.is_finished())
+        self.runners.get(runner_id).map(|h| !h.is_finished())
     }
 
     /// Get health status for all runners
@@ -758,7 +1575,7 @@ impl TradingEngine {
     pub fn health_check(&self) -> HashMap<String, bool> {
         self.runners
             .iter()
-            .map(|(id, handle)| (id.clone(), !handle.task.is_finished()))
+            .map(|(id, handle)| (id.clone(), !handle.is_finished()))
             .collect()
     }
 
@@ -779,7 +1596,7 @@ impl TradingEngine {
     pub fn unhealthy_runners(&self) -> Vec<String> {
         self.runners
             .iter()
-            .filter(|(_, handle)| handle.task.is_finished())
+            .filter(|(_, handle)| handle.is_finished())
             .map(|(id, _)| id.clone())
             .collect()
     }
@@ -791,6 +1608,38 @@ impl TradingEngine {
     ///
     /// # Example
     ///
+    /// ```no_run
+    /// # use trading_engine::runner::TradingEngine;
+    /// let engine = TradingEngine::new();
+    /// let summary = engine.summary_struct();
+    /// assert_eq!(summary.total_runners, 0);
+    /// assert_eq!(summary.runners_per_symbol, 0.0);
+    /// ```
+    pub fn summary_struct(&self) -> EngineSummary {
+        let total_runners = self.runner_count();
+        let symbol_count = self.active_symbols().len();
+        let unhealthy_runners = self.unhealthy_runners().len();
+
+        EngineSummary {
+            total_runners,
+            healthy_runners: total_runners - unhealthy_runners,
+            unhealthy_runners,
+            symbol_count,
+            runners_per_symbol: if symbol_count > 0 {
+                total_runners as f64 / symbol_count as f64
+            } else {
+                0.0
+            },
+        }
+    }
+
+    /// Get engine summary statistics as a multi-line string for logging
+    ///
+    /// See [`summary_struct`](Self::summary_struct) for the same numbers as
+    /// structured data.
+    ///
+    /// # Example
+    ///
     /// ```
     /// # use trading_engine::runner::TradingEngine;
     /// let engine = TradingEngine::new();
@@ -798,10 +1647,7 @@ impl TradingEngine {
     /// println!("{}", summary);
     /// ```
     pub fn summary(&self) -> String {
-        let total_runners = self.runner_count();
-        let total_symbols = self.active_symbols().len();
-        let unhealthy = self.unhealthy_runners();
-        let healthy_count = total_runners - unhealthy.len();
+        let summary = self.summary_struct();
 
         format!(
             "TradingEngine Summary:\n\
@@ -810,25 +1656,24 @@ impl TradingEngine {
              - Unhealthy: {}\n\
              - Symbols: {}\n\
              - Runners per symbol: {:.1}",
-            total_runners,
-            healthy_count,
-            unhealthy.len(),
-            total_symbols,
-            if total_symbols > 0 {
-                total_runners as f64 / total_symbols as f64
-            } else {
-                0.0
-            }
+            summary.total_runners,
+            summary.healthy_runners,
+            summary.unhealthy_runners,
+            summary.symbol_count,
+            summary.runners_per_symbol
         )
     }
 
     /// Shutdown all runners gracefully
     ///
-    /// Closes all channels and waits for all tasks to complete.
+    /// Closes all channels and waits for all tasks to complete. Positions
+    /// are not closed: the final snapshots of runners that still held one
+    /// are returned, ordered by runner ID, so they can be persisted or
+    /// reconciled.
     ///
     /// # Errors
     ///
-    /// Returns error if any runner task panicked.
+    /// Runner errors are logged rather than returned.
     ///
     /// # Example
     ///
@@ -838,28 +1683,36 @@ impl TradingEngine {
     /// # async fn main() -> anyhow::Result<()> {
     /// let mut engine = TradingEngine::new();
     /// // ... add runners and feed data ...
-    /// engine.shutdown().await?;
+    /// for snapshot in engine.shutdown().await? {
+    ///     println!("{} left a position open: {:?}", snapshot.runner_id, snapshot.position);
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn shutdown(mut self) -> Result<()> {
+    pub async fn shutdown(self) -> Result<Vec<RunnerSnapshot>> {
         tracing::info!("Shutting down engine with {} runners", self.runners.len());
 
-        let runner_ids: Vec<String> = self.runners.keys().cloned().collect();
-
-        for runner_id in runner_ids {
-            if let Err(e) = self.remove_runner(&runner_id).await {
-                tracing::error!("Error shutting down runner '{}': {}", runner_id, e);
-            }
-        }
-
-        tracing::info!("Engine shutdown complete");
-        Ok(())
+        let mut open: Vec<RunnerSnapshot> = self
+            .shutdown_with_results()
+            .await
+            .into_iter()
+            .filter_map(|(runner_id, shutdown)| {
+                if let Err(e) = &shutdown.result {
+                    tracing::error!("Error shutting down runner '{}': {}", runner_id, e);
+                }
+                shutdown.final_snapshot.filter(|snapshot| snapshot.position.is_some())
+            })
+            .collect();
+        open.sort_by(|a, b| a.runner_id.cmp(&b.runner_id));
+
+        tracing::info!("Engine shutdown complete, {} positions left open", open.len());
+        Ok(open)
     }
 
     /// Shutdown and collect results from all runners
     ///
-    /// Like `shutdown()` but returns a map of runner_id → result.
+    /// Like `shutdown()` but returns, for each runner ID, its exit result
+    /// and the snapshot taken just before it was stopped.
     ///
     /// # Example
     ///
@@ -870,20 +1723,24 @@ impl TradingEngine {
     /// let mut engine = TradingEngine::new();
     /// // ... add runners and feed data ...
     /// let results = engine.shutdown_with_results().await;
-    /// for (runner_id, result) in results {
-    ///     println!("{}: {:?}", runner_id, result);
+    /// for (runner_id, shutdown) in results {
+    ///     println!("{}: {:?}", runner_id, shutdown.result);
+    ///     if let Some(position) = shutdown.open_position() {
+    ///         println!("  left open: {:?}", position);
+    ///     }
     /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn shutdown_with_results(mut self) -> HashMap<String, Result<()>> {
+    pub async fn shutdown_with_results(mut self) -> HashMap<String, RunnerShutdown> {
         let mut results = HashMap::new();
 
         let runner_ids: Vec<String> = self.runners.keys().cloned().collect();
 
         for runner_id in runner_ids {
+            let final_snapshot = self.get_runner_snapshot(&runner_id).await;
             let result = self.remove_runner(&runner_id).await;
-            results.insert(runner_id, result);
+            results.insert(runner_id, RunnerShutdown { result, final_snapshot });
         }
 
         results
@@ -901,10 +1758,15 @@ impl TradingEngine {
     ///
     /// * `runner_id` - The unique ID of the runner to query
     ///
+    /// Waits up to 100ms for the runner to answer. See
+    /// [`get_runner_snapshot_with_timeout`](Self::get_runner_snapshot_with_timeout)
+    /// to choose the timeout.
+    ///
     /// # Returns
     ///
-    /// Returns `Some(RunnerSnapshot)` if the runner exists and is running,
-    /// or `None` if the runner doesn't exist or the command channel is closed.
+    /// Returns `Some(RunnerSnapshot)` if the runner exists. If the runner does
+    /// not answer in time, the last-known snapshot is returned instead.
+    /// Returns `None` if the runner doesn't exist.
     ///
     /// # Example
     ///
@@ -926,21 +1788,195 @@ impl TradingEngine {
     /// # }
     /// ```
     pub async fn get_runner_snapshot(&self, runner_id: &str) -> Option<RunnerSnapshot> {
+        self.get_runner_snapshot_with_timeout(runner_id, DEFAULT_COMMAND_TIMEOUT)
+            .await
+    }
+
+    /// Get a snapshot of a runner's state, waiting at most `timeout`
+    ///
+    /// A fresh snapshot from the runner refreshes the engine's cache. If the
+    /// runner is too busy to answer in time (or its command channel is
+    /// closed), the cached snapshot is returned with its uptime brought up to
+    /// date. The cache is maintained from the event stream, so it tracks state,
+    /// position and counters, but its context may lag behind the runner. A
+    /// runner queued for a slot can't answer, so it isn't asked.
+    ///
+    /// # Arguments
+    ///
+    /// * `runner_id` - The unique ID of the runner to query
+    /// * `timeout` - How long to wait for the runner to answer
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` only if the runner doesn't exist, or if it never
+    /// answered and no snapshot has been cached yet.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use trading_engine::runner::TradingEngine;
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let engine = TradingEngine::new();
+    /// // ... add runners ...
+    ///
+    /// let snapshot = engine
+    ///     .get_runner_snapshot_with_timeout("btc_ema", Duration::from_millis(250))
+    ///     .await;
+    /// # }
+    /// ```
+    pub async fn get_runner_snapshot_with_timeout(
+        &self,
+        runner_id: &str,
+        timeout: Duration,
+    ) -> Option<RunnerSnapshot> {
         // Get the runner handle
         let handle = self.runners.get(runner_id)?;
 
+        if handle.is_queued() {
+            return Self::cached_snapshot(&self.snapshot_cache.lock(), handle);
+        }
+
         // Create oneshot channel for response
         let (response_tx, response_rx) = tokio::sync::oneshot::channel();
 
-        // Send GetSnapshot command
+        // Send GetSnapshot command and wait for response
         let cmd = RunnerCommand::GetSnapshot { response: response_tx };
-        handle.cmd_tx.send(cmd).ok()?;
+        let fresh = match handle.cmd_tx.send(cmd) {
+            Ok(()) => {
+                handle.drive(&self.event_tx).await;
+                tokio::time::timeout(timeout, response_rx).await.ok().and_then(|r| r.ok())
+            }
+            Err(_) => None,
+        };
+
+        let mut cache = self.snapshot_cache.lock();
+        match fresh {
+            Some(snapshot) => {
+                cache.insert(runner_id.to_string(), snapshot.clone());
+                Some(snapshot)
+            }
+            None => {
+                tracing::debug!("Runner '{}' did not answer in {:?}, using cached snapshot", runner_id, timeout);
+                Self::cached_snapshot(&cache, handle)
+            }
+        }
+    }
+
+    /// Get snapshots of every runner in one call
+    ///
+    /// Sends the snapshot command to all runners at once and waits for the
+    /// answers together, so the whole call takes at most one command timeout
+    /// (100ms) rather than one per runner. Fresh snapshots refresh the cache.
+    /// Runners queued for a slot aren't asked; their cached snapshots are used.
+    ///
+    /// # Returns
+    ///
+    /// A map from runner ID to snapshot. Running runners that don't answer in
+    /// time are omitted.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use trading_engine::runner::TradingEngine;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let engine = TradingEngine::new();
+    /// // ... add runners ...
+    ///
+    /// for (runner_id, snapshot) in engine.all_snapshots().await {
+    ///     println!("{}: {}", runner_id, snapshot.state_str());
+    /// }
+    /// # }
+    /// ```
+    pub async fn all_snapshots(&self) -> HashMap<String, RunnerSnapshot> {
+        let pending = self.runners.iter().filter(|(_, handle)| !handle.is_queued()).filter_map(|(runner_id, handle)| {
+            let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+            let cmd = RunnerCommand::GetSnapshot { response: response_tx };
+            handle.cmd_tx.send(cmd).ok()?;
+            Some(async move {
+                handle.drive(&self.event_tx).await;
+                let snapshot = tokio::time::timeout(DEFAULT_COMMAND_TIMEOUT, response_rx)
+                    .await
+                    .ok()?
+                    .ok()?;
+                Some((runner_id.clone(), snapshot))
+            })
+        });
 
-        // Wait for response (with timeout to avoid hanging)
-        tokio::time::timeout(std::time::Duration::from_millis(100), response_rx)
+        let mut snapshots: HashMap<String, RunnerSnapshot> = futures_util::future::join_all(pending)
             .await
-            .ok()?
-            .ok()
+            .into_iter()
+            .flatten()
+            .collect();
+
+        let mut cache = self.snapshot_cache.lock();
+        for (runner_id, snapshot) in &snapshots {
+            cache.insert(runner_id.clone(), snapshot.clone());
+        }
+
+        // Queued runners can't answer
+        for (runner_id, handle) in self.runners.iter().filter(|(_, handle)| handle.is_queued()) {
+            if let Some(snapshot) = Self::cached_snapshot(&cache, handle) {
+                snapshots.insert(runner_id.clone(), snapshot);
+            }
+        }
+
+        snapshots
+    }
+
+    /// Compare closed-trade performance of every runner on `symbol`
+    ///
+    /// Rows are sorted best first by `sort_by`, with ties broken by runner ID.
+    /// Runners that don't answer in time are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoRunnersForSymbol` if no runner watches `symbol`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use trading_engine::runner::{ComparisonMetric, TradingEngine};
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let engine = TradingEngine::new();
+    /// // ... add runners ...
+    ///
+    /// for row in engine.strategy_comparison("BTCUSDT", ComparisonMetric::WinRate).await? {
+    ///     println!("{}: {:.1}% over {} trades", row.runner_id, row.win_rate, row.trades);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn strategy_comparison(
+        &self,
+        symbol: &str,
+        sort_by: ComparisonMetric,
+    ) -> Result<Vec<StrategyComparisonRow>> {
+        let runner_ids = self.runners_for_symbol(symbol);
+        if runner_ids.is_empty() {
+            return Err(TradingEngineError::NoRunnersForSymbol(symbol.to_string()));
+        }
+
+        let snapshots = futures_util::future::join_all(
+            runner_ids.iter().map(|runner_id| self.get_runner_snapshot(runner_id)),
+        )
+        .await;
+
+        let mut rows: Vec<StrategyComparisonRow> = snapshots
+            .into_iter()
+            .flatten()
+            .map(|snapshot| StrategyComparisonRow::from_stats(snapshot.runner_id, &snapshot.stats))
+            .collect();
+
+        rows.sort_by(|a, b| {
+            b.metric(sort_by)
+                .total_cmp(&a.metric(sort_by))
+                .then_with(|| a.runner_id.cmp(&b.runner_id))
+        });
+        Ok(rows)
     }
 
     /// Get recent price history from a runner's data window
@@ -950,10 +1986,15 @@ impl TradingEngine {
     /// * `runner_id` - The unique ID of the runner to query
     /// * `count` - Optional number of recent data points to retrieve (all if None)
     ///
+    /// Waits up to 100ms for the runner to answer. See
+    /// [`get_price_history_with_timeout`](Self::get_price_history_with_timeout)
+    /// to choose the timeout.
+    ///
     /// # Returns
     ///
     /// Returns `Some(Vec<MarketData>)` if the runner exists,
-    /// or `None` if the runner doesn't exist or the command channel is closed.
+    /// or `None` if the runner doesn't exist, the command channel is closed,
+    /// or the runner did not answer in time.
     ///
     /// # Example
     ///
@@ -977,6 +2018,28 @@ impl TradingEngine {
         &self,
         runner_id: &str,
         count: Option<usize>,
+    ) -> Option<Vec<MarketData>> {
+        self.get_price_history_with_timeout(runner_id, count, DEFAULT_COMMAND_TIMEOUT)
+            .await
+    }
+
+    /// Get recent price history from a runner, waiting at most `timeout`
+    ///
+    /// # Arguments
+    ///
+    /// * `runner_id` - The unique ID of the runner to query
+    /// * `count` - Optional number of recent data points to retrieve (all if None)
+    /// * `timeout` - How long to wait for the runner to answer
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if the runner doesn't exist, the command channel is
+    /// closed, or the runner did not answer in time.
+    pub async fn get_price_history_with_timeout(
+        &self,
+        runner_id: &str,
+        count: Option<usize>,
+        timeout: Duration,
     ) -> Option<Vec<MarketData>> {
         // Get the runner handle
         let handle = self.runners.get(runner_id)?;
@@ -990,9 +2053,10 @@ impl TradingEngine {
             response: response_tx,
         };
         handle.cmd_tx.send(cmd).ok()?;
+        handle.drive(&self.event_tx).await;
 
         // Wait for response (with timeout)
-        tokio::time::timeout(std::time::Duration::from_millis(100), response_rx)
+        tokio::time::timeout(timeout, response_rx)
             .await
             .ok()?
             .ok()
@@ -1018,8 +2082,9 @@ impl TradingEngine {
 
         handle.cmd_tx.send(cmd)
             .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))?;
+        handle.drive(&self.event_tx).await;
 
-        tokio::time::timeout(std::time::Duration::from_millis(100), response_rx)
+        tokio::time::timeout(DEFAULT_COMMAND_TIMEOUT, response_rx)
             .await
             .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))?
             .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))
@@ -1045,8 +2110,9 @@ impl TradingEngine {
 
         handle.cmd_tx.send(cmd)
             .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))?;
+        handle.drive(&self.event_tx).await;
 
-        tokio::time::timeout(std::time::Duration::from_millis(100), response_rx)
+        tokio::time::timeout(DEFAULT_COMMAND_TIMEOUT, response_rx)
             .await
             .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))?
             .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))
@@ -1069,36 +2135,350 @@ impl TradingEngine {
         let handle = self.runners.get(runner_id)
             .ok_or_else(|| TradingEngineError::RunnerNotFound(runner_id.to_string()))?;
 
-        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
-        let cmd = RunnerCommand::Stop { response: response_tx };
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+        let cmd = RunnerCommand::Stop { response: response_tx };
+
+        handle.cmd_tx.send(cmd)
+            .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))?;
+        handle.drive(&self.event_tx).await;
+
+        tokio::time::timeout(DEFAULT_COMMAND_TIMEOUT, response_rx)
+            .await
+            .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))?
+            .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))
+    }
+
+    /// Change a running runner's configuration without restarting it
+    ///
+    /// The runner keeps its position, context, window and stats. Most fields
+    /// take effect from the next tick, but some are fixed once the runner
+    /// starts and keep their current values:
+    ///
+    /// * `starting_balance` - the account already holds realized P&L
+    /// * `tags` - indexed by the engine when the runner was added
+    ///
+    /// The window size isn't part of the config and can't be changed live
+    /// either; remove and re-add the runner for that.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(true)` if every field was applied, `Ok(false)` if fixed
+    /// fields differed and were kept, or `Err` if the runner doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use trading_engine::runner::{RunnerConfig, TradingEngine};
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let engine = TradingEngine::new();
+    /// // ... add runners ...
+    ///
+    /// engine.update_runner_config("btc_ema", RunnerConfig::quiet()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn update_runner_config(&self, runner_id: &str, config: RunnerConfig) -> Result<bool> {
+        let handle = self.runners.get(runner_id)
+            .ok_or_else(|| TradingEngineError::RunnerNotFound(runner_id.to_string()))?;
+
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+        let cmd = RunnerCommand::UpdateConfig { config: Box::new(config), response: response_tx };
+
+        handle.cmd_tx.send(cmd)
+            .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))?;
+        handle.drive(&self.event_tx).await;
+
+        tokio::time::timeout(DEFAULT_COMMAND_TIMEOUT, response_rx)
+            .await
+            .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))?
+            .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))
+    }
+
+    /// Give a runner a new ID without restarting it
+    ///
+    /// The runner keeps its position, context, window and stats, and reports
+    /// the new ID in all later events and snapshots.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RunnerAlreadyExists` if `new_id` is taken, or `RunnerNotFound`
+    /// if there is no runner `old_id`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use trading_engine::runner::TradingEngine;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let mut engine = TradingEngine::new();
+    /// // ... add runners ...
+    ///
+    /// engine.rename_runner("btc_ema", "btc_ema_fast").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn rename_runner(&mut self, old_id: &str, new_id: &str) -> Result<()> {
+        if self.runners.contains_key(new_id) {
+            return Err(TradingEngineError::RunnerAlreadyExists(new_id.to_string()));
+        }
+        let handle = self.runners.get(old_id)
+            .ok_or_else(|| TradingEngineError::RunnerNotFound(old_id.to_string()))?;
+
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+        let cmd = RunnerCommand::Rename { runner_id: new_id.to_string(), response: response_tx };
+
+        handle.cmd_tx.send(cmd)
+            .map_err(|_| TradingEngineError::ChannelClosed(old_id.to_string()))?;
+        handle.drive(&self.event_tx).await;
+
+        tokio::time::timeout(DEFAULT_COMMAND_TIMEOUT, response_rx)
+            .await
+            .map_err(|_| TradingEngineError::ChannelClosed(old_id.to_string()))?
+            .map_err(|_| TradingEngineError::ChannelClosed(old_id.to_string()))?;
+
+        let mut handle = self.runners.remove(old_id).expect("runner checked above");
+        handle.runner_id = new_id.to_string();
+
+        for id in self.subscriptions.values_mut().flatten().filter(|id| id.as_str() == old_id) {
+            *id = new_id.to_string();
+        }
+
+        let mut cache = self.snapshot_cache.lock();
+        let mut snapshot = cache
+            .remove(old_id)
+            .unwrap_or_else(|| Self::starting_snapshot(new_id, &handle.symbol));
+        snapshot.runner_id = new_id.to_string();
+        cache.insert(new_id.to_string(), snapshot);
+        drop(cache);
+
+        self.runners.insert(new_id.to_string(), handle);
+        tracing::info!("Renamed runner '{}' to '{}'", old_id, new_id);
+        Ok(())
+    }
+
+    /// Exit every open position at the latest known price
+    ///
+    /// Sends a flatten command to each runner and reports the runners that
+    /// were in a position, sorted by ID, with the result of closing it.
+    /// Runners without a position are left alone, as are runners that have
+    /// stopped or are still queued for a slot. A runner's position is closed
+    /// at the close of the last bar it received.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use trading_engine::runner::TradingEngine;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let engine = TradingEngine::new();
+    /// for (runner_id, result) in engine.flatten_all().await? {
+    ///     if let Err(e) = result {
+    ///         eprintln!("Failed to flatten {}: {}", runner_id, e);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn flatten_all(&self) -> Result<Vec<(String, Result<()>)>> {
+        let mut runner_ids: Vec<String> = self
+            .runners
+            .iter()
+            .filter(|(_, handle)| !handle.is_finished() && !handle.is_queued())
+            .map(|(runner_id, _)| runner_id.clone())
+            .collect();
+        runner_ids.sort();
+
+        let mut results = Vec::new();
+        for runner_id in runner_ids {
+            match self.flatten_runner(&runner_id).await {
+                Ok(false) => {}
+                Ok(true) => results.push((runner_id, Ok(()))),
+                Err(e) => results.push((runner_id, Err(e))),
+            }
+        }
+
+        tracing::warn!("Flattened {} runner positions", results.len());
+        Ok(results)
+    }
+
+    /// Exit one runner's open position, returning whether it had one
+    async fn flatten_runner(&self, runner_id: &str) -> Result<bool> {
+        let handle = self.runners.get(runner_id)
+            .ok_or_else(|| TradingEngineError::RunnerNotFound(runner_id.to_string()))?;
+
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+        let cmd = RunnerCommand::Flatten { response: response_tx };
+
+        handle.cmd_tx.send(cmd)
+            .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))?;
+        handle.drive(&self.event_tx).await;
+
+        tokio::time::timeout(DEFAULT_COMMAND_TIMEOUT, response_rx)
+            .await
+            .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))?
+            .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))?
+    }
+}
+
+impl Default for TradingEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::test_support::{strategy_from_source, TempScript};
+    use crate::strategy::LuaStrategy;
+
+    #[tokio::test]
+    async fn test_engine_creation() {
+        let engine = TradingEngine::new();
+        assert_eq!(engine.runner_count(), 0);
+        assert!(engine.active_symbols().is_empty());
+        assert!(engine.runner_ids().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_max_active_runners_queues_excess() {
+        let mut engine = TradingEngine::new().with_max_active_runners(2);
+        for i in 0..5 {
+            let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+                .expect("Failed to load test strategy");
+            engine.add_runner(format!("runner_{}", i), "BTCUSDT", strategy).unwrap();
+        }
+
+        // All five exist, the first two added run
+        assert_eq!(engine.runner_count(), 5);
+        assert_eq!(engine.active_runner_count(), 2);
+        assert_eq!(engine.queued_runner_ids(), vec!["runner_2", "runner_3", "runner_4"]);
+
+        // Queued runners are answered from the cache without waiting on them
+        let start = tokio::time::Instant::now();
+        let snapshots = engine.all_snapshots().await;
+        assert_eq!(snapshots.len(), 5);
+        assert_eq!(snapshots["runner_3"].status, RunnerStatus::Starting);
+        assert!(start.elapsed() < DEFAULT_COMMAND_TIMEOUT);
+
+        // Removing a running runner lets a queued one start
+        engine.remove_runner("runner_0").await.unwrap();
+        let mut waits: Vec<_> = ["runner_2", "runner_3", "runner_4"]
+            .into_iter()
+            .map(|runner_id| {
+                let mut started = engine.runners[runner_id].started.clone();
+                Box::pin(async move { started.wait_for(|started| *started).await.is_ok() })
+            })
+            .collect();
+        let (started, _, _) = futures_util::future::select_all(waits.drain(..)).await;
+        assert!(started);
+
+        assert_eq!(engine.runner_count(), 4);
+        assert_eq!(engine.active_runner_count(), 2);
+        assert_eq!(engine.queued_runner_ids().len(), 2);
+
+        // Shutdown doesn't wait out a command timeout per queued runner
+        let start = tokio::time::Instant::now();
+        let results = engine.shutdown_with_results().await;
+        assert_eq!(results.len(), 4);
+        assert!(results.values().all(|shutdown| shutdown.final_snapshot.is_some()));
+        assert!(start.elapsed() < DEFAULT_COMMAND_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn test_feed_data_counted_reports_deliveries() {
+        let mut engine = TradingEngine::new_deterministic();
+        for runner_id in ["btc_a", "btc_b"] {
+            let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+                .expect("Failed to load test strategy");
+            engine.add_runner(runner_id, "BTCUSDT", strategy).unwrap();
+        }
+
+        let bar = |timestamp| MarketData::from_trade("BTCUSDT", 50000.0, 1.0, timestamp);
+        assert_eq!(engine.feed_data_counted(bar(0)).await.unwrap(), 2);
+
+        engine.remove_runner("btc_b").await.unwrap();
+        assert_eq!(engine.feed_data_counted(bar(1)).await.unwrap(), 1);
+
+        engine.remove_runner("btc_a").await.unwrap();
+        assert!(matches!(
+            engine.feed_data_counted(bar(2)).await,
+            Err(TradingEngineError::NoRunnersForSymbol(_))
+        ));
+    }
+
+    /// Canned klines a minute apart, standing in for the REST API
+    struct MockKlines {
+        requests: Arc<Mutex<Vec<(String, usize)>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl BarHistory for MockKlines {
+        async fn recent_bars(&self, symbol: &str, count: usize) -> Result<Vec<MarketData>> {
+            self.requests.lock().push((symbol.to_string(), count));
+            // Ends with the minute of the first live tick, which must be dropped
+            Ok((0..=count as i64)
+                .map(|i| MarketData::from_trade(symbol, 50000.0 + i as f64, 1.0, i * 60_000))
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rest_warmup_seeds_window_before_first_tick() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let mut engine = TradingEngine::new_deterministic().with_bar_history(MockKlines { requests: requests.clone() });
+
+        for (runner_id, config) in [
+            ("warm", RunnerConfig::quiet().with_rest_warmup(50)),
+            ("cold", RunnerConfig::quiet()),
+        ] {
+            let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+                .expect("Failed to load test strategy");
+            engine.add_runner_with_config(runner_id, "BTCUSDT", strategy, 100, config).unwrap();
+        }
+
+        // Nothing is fetched until the first live tick
+        assert!(requests.lock().is_empty());
+        engine.feed_data(MarketData::from_trade("BTCUSDT", 51000.0, 1.0, 50 * 60_000)).await.unwrap();
+        assert_eq!(*requests.lock(), vec![("BTCUSDT".to_string(), 50)]);
 
-        handle.cmd_tx.send(cmd)
-            .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))?;
+        // 50 historical bars, then the live tick
+        let history = engine.get_price_history("warm", None).await.unwrap();
+        assert_eq!(history.len(), 51);
+        assert_eq!(history[0].timestamp, 0);
+        assert_eq!(history[49].timestamp, 49 * 60_000);
+        assert_eq!(history[50].close, 51000.0);
 
-        tokio::time::timeout(std::time::Duration::from_millis(100), response_rx)
-            .await
-            .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))?
-            .map_err(|_| TradingEngineError::ChannelClosed(runner_id.to_string()))
+        assert_eq!(engine.get_price_history("cold", None).await.unwrap().len(), 1);
+
+        // Only the first tick triggers a fetch
+        engine.feed_data(MarketData::from_trade("BTCUSDT", 51010.0, 1.0, 51 * 60_000)).await.unwrap();
+        assert_eq!(requests.lock().len(), 1);
     }
-}
 
-impl Default for TradingEngine {
-    fn default() -> Self {
-        Self::new()
+    /// History source that never answers
+    struct HangingKlines;
+
+    #[async_trait::async_trait]
+    impl BarHistory for HangingKlines {
+        async fn recent_bars(&self, _symbol: &str, _count: usize) -> Result<Vec<MarketData>> {
+            std::future::pending().await
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::strategy::LuaStrategy;
+    #[tokio::test(start_paused = true)]
+    async fn test_hung_warmup_fetch_is_skipped() {
+        let mut engine = TradingEngine::new_deterministic().with_bar_history(HangingKlines);
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+        engine
+            .add_runner_with_config("warm", "BTCUSDT", strategy, 100, RunnerConfig::quiet().with_rest_warmup(50))
+            .unwrap();
 
-    #[tokio::test]
-    async fn test_engine_creation() {
-        let engine = TradingEngine::new();
-        assert_eq!(engine.runner_count(), 0);
-        assert!(engine.active_symbols().is_empty());
-        assert!(engine.runner_ids().is_empty());
+        // The tick is processed without history once the fetch times out
+        engine.feed_data(MarketData::from_trade("BTCUSDT", 51000.0, 1.0, 60_000)).await.unwrap();
+        assert_eq!(engine.get_price_history("warm", None).await.unwrap().len(), 1);
     }
 
     #[tokio::test]
@@ -1136,6 +2516,63 @@ mod tests {
         assert!(runners.contains(&"btc_rsi".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_runners_with_tag() {
+        let mut engine = TradingEngine::new();
+        let strategy = || {
+            LuaStrategy::new("../lua-strategies/test_strategy.lua")
+                .expect("Failed to load test strategy")
+        };
+
+        let prod = RunnerConfig::default().with_tag("env", "prod");
+        let staging = RunnerConfig::default().with_tag("env", "staging");
+        engine.add_runner_with_config("btc_prod", "BTCUSDT", strategy(), 50, prod.clone()).unwrap();
+        engine.add_runner_with_config("eth_prod", "ETHUSDT", strategy(), 50, prod).unwrap();
+        engine.add_runner_with_config("btc_staging", "BTCUSDT", strategy(), 50, staging).unwrap();
+        engine.add_runner("btc_untagged", "BTCUSDT", strategy()).unwrap();
+
+        let mut prod_runners = engine.runners_with_tag("env", "prod");
+        prod_runners.sort();
+        assert_eq!(prod_runners, vec!["btc_prod", "eth_prod"]);
+        assert_eq!(engine.runners_with_tag("env", "staging"), vec!["btc_staging"]);
+        assert!(engine.runners_with_tag("env", "dev").is_empty());
+        assert!(engine.runner_tags("btc_untagged").unwrap().is_empty());
+
+        let snapshot = engine.get_runner_snapshot("btc_staging").await.unwrap();
+        assert_eq!(snapshot.tags.get("env").map(String::as_str), Some("staging"));
+    }
+
+    #[tokio::test]
+    async fn test_add_runners_batch_partial_failure() {
+        let mut engine = TradingEngine::new();
+
+        let results = engine.add_runners_batch(vec![
+            RunnerSpec::new("btc_ok", "BTCUSDT", "../lua-strategies/test_strategy.lua"),
+            RunnerSpec::new("btc_bad", "BTCUSDT", "../lua-strategies/does_not_exist.lua"),
+            RunnerSpec::new("eth_ok", "ETHUSDT", "../lua-strategies/test_strategy.lua")
+                .with_window_size(20),
+        ]);
+
+        let ids: Vec<&str> = results.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["btc_ok", "btc_bad", "eth_ok"]);
+        assert!(results[0].1.is_ok());
+        assert!(results[1].1.is_err());
+        assert!(results[2].1.is_ok());
+
+        assert!(engine.has_runner("btc_ok"));
+        assert!(!engine.has_runner("btc_bad"));
+        assert!(engine.has_runner("eth_ok"));
+
+        // A duplicate ID is reported without touching the existing runner
+        let results = engine.add_runners_batch(vec![RunnerSpec::new(
+            "btc_ok",
+            "ETHUSDT",
+            "../lua-strategies/test_strategy.lua",
+        )]);
+        assert!(matches!(results[0].1, Err(TradingEngineError::RunnerAlreadyExists(_))));
+        assert_eq!(engine.runner_symbol("btc_ok"), Some("BTCUSDT".to_string()));
+    }
+
     #[tokio::test]
     async fn test_add_duplicate_runner_id() {
         let mut engine = TradingEngine::new();
@@ -1210,6 +2647,9 @@ mod tests {
             volume: 1000,
             bid: 50045.0,
             ask: 50055.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         };
 
         engine.feed_data(data).await.unwrap();
@@ -1236,6 +2676,9 @@ mod tests {
             volume: 1000,
             bid: 50045.0,
             ask: 50055.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         };
 
         // Should broadcast to both runners without error
@@ -1256,12 +2699,36 @@ mod tests {
             volume: 1000,
             bid: 50045.0,
             ask: 50055.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         };
 
         let result = engine.feed_data(data).await;
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_runner_ids_and_symbols_sorted() {
+        let mut engine = TradingEngine::new();
+        let strategy = || LuaStrategy::new("../lua-strategies/test_strategy.lua").unwrap();
+        for (runner_id, symbol) in [
+            ("sol_ema", "SOLUSDT"),
+            ("btc_rsi", "BTCUSDT"),
+            ("eth_ema", "ETHUSDT"),
+            ("btc_ema", "BTCUSDT"),
+            ("ada_ema", "ADAUSDT"),
+        ] {
+            let config = RunnerConfig::default().with_tag("env", "prod");
+            engine.add_runner_with_config(runner_id, symbol, strategy(), 50, config).unwrap();
+        }
+
+        let expected_ids = vec!["ada_ema", "btc_ema", "btc_rsi", "eth_ema", "sol_ema"];
+        assert_eq!(engine.runner_ids(), expected_ids);
+        assert_eq!(engine.runners_with_tag("env", "prod"), expected_ids);
+        assert_eq!(engine.active_symbols(), vec!["ADAUSDT", "BTCUSDT", "ETHUSDT", "SOLUSDT"]);
+    }
+
     #[tokio::test]
     async fn test_multiple_symbols_and_runners() {
         let mut engine = TradingEngine::new();
@@ -1379,6 +2846,29 @@ mod tests {
         assert!(summary.contains("Runners per symbol: 2.0"));
     }
 
+    #[tokio::test]
+    async fn test_summary_struct() {
+        let mut engine = TradingEngine::new();
+        let strategy1 = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+        let strategy2 = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+
+        engine.add_runner("btc_ema", "BTCUSDT", strategy1).unwrap();
+        engine.add_runner("btc_rsi", "BTCUSDT", strategy2).unwrap();
+
+        assert_eq!(
+            engine.summary_struct(),
+            EngineSummary {
+                total_runners: 2,
+                healthy_runners: 2,
+                unhealthy_runners: 0,
+                symbol_count: 1,
+                runners_per_symbol: 2.0,
+            }
+        );
+    }
+
     #[tokio::test]
     async fn test_event_aggregation() {
         let mut engine = TradingEngine::new();
@@ -1433,6 +2923,595 @@ mod tests {
         assert_eq!(event1.runner_id(), event2.runner_id());
     }
 
+    #[tokio::test]
+    async fn test_run_backtest_until_stops_at_cutoff() {
+        let mut engine = TradingEngine::new_deterministic();
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+        engine.add_runner("btc", "BTCUSDT", strategy).unwrap();
+        let bars: Vec<MarketData> = (0..10)
+            .map(|i| MarketData::from_trade("BTCUSDT", 50000.0 + i as f64, 1.0, 1000 * i))
+            .collect();
+        let mut feed = ReplayFeed::new(bars.clone());
+
+        let snapshots = engine.run_backtest_until(&mut feed, 4500).await.unwrap();
+        assert_eq!(snapshots["btc"].stats.ticks_processed, 5);
+        assert_eq!(feed.remaining(), 5);
+        let history = engine.get_price_history("btc", None).await.unwrap();
+        assert_eq!(history.last().unwrap().timestamp, 4000);
+
+        // The same cutoff again replays nothing; a later one picks up where it stopped
+        let snapshots = engine.run_backtest_until(&mut feed, 4500).await.unwrap();
+        assert_eq!(snapshots["btc"].stats.ticks_processed, 5);
+        let snapshots = engine.run_backtest_until(&mut feed, 9000).await.unwrap();
+        assert_eq!(snapshots["btc"].stats.ticks_processed, 10);
+
+        // A different feed replays from its own first bar
+        let mut other = ReplayFeed::new(bars[..3].to_vec());
+        let snapshots = engine.run_backtest_until(&mut other, 9000).await.unwrap();
+        assert_eq!(snapshots["btc"].stats.ticks_processed, 13);
+
+        // Only deterministic engines can replay
+        assert!(matches!(
+            TradingEngine::new().run_backtest_until(&mut feed, 9000).await,
+            Err(TradingEngineError::InvalidConfig(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_does_not_block_others() {
+        let mut engine = TradingEngine::new_deterministic();
+        // Never read, and hung up straight away
+        let _slow = engine.subscribe_events();
+        drop(engine.subscribe_events());
+        let mut events = engine.subscribe_events();
+
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+        engine.add_runner("btc", "BTCUSDT", strategy).unwrap();
+        for i in 0..200 {
+            engine.feed_data(MarketData::from_trade("BTCUSDT", 50000.0, 1.0, 1000 * i)).await.unwrap();
+        }
+
+        let mut ticks = 0;
+        while ticks < 200 {
+            let event = tokio::time::timeout(Duration::from_secs(1), events.recv()).await.unwrap().unwrap();
+            if matches!(event, RunnerEvent::TickReceived { .. }) {
+                ticks += 1;
+            }
+        }
+        assert_eq!(engine.event_subscribers.lock().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_sequenced_events_strictly_increase() {
+        let mut engine = TradingEngine::new();
+        let mut events = engine.subscribe_sequenced_events();
+
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+        engine.add_runner("btc_ema", "BTCUSDT", strategy).unwrap();
+        for i in 0..3 {
+            let data = MarketData {
+                symbol: "BTCUSDT".to_string(),
+                timestamp: 1234567890 + i,
+                open: 50000.0,
+                high: 50100.0,
+                low: 49900.0,
+                close: 50050.0,
+                volume: 1000,
+                bid: 50045.0,
+                ask: 50055.0,
+                bid_qty: None,
+                ask_qty: None,
+                is_final: true,
+            };
+            engine.feed_data(data).await.unwrap();
+        }
+        engine.remove_runner("btc_ema").await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        let mut seqs = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            seqs.push(event.seq);
+        }
+        assert!(seqs.len() > 2);
+        assert_eq!(seqs[0], 1);
+        assert!(seqs.windows(2).all(|pair| pair[1] == pair[0] + 1));
+    }
+
+    #[tokio::test]
+    async fn test_max_ticks_reported_as_stop_reason() {
+        let mut engine = TradingEngine::new_deterministic();
+        let mut events = engine.subscribe_events();
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+        engine
+            .add_runner_with_config("btc_ema", "BTCUSDT", strategy, 50, RunnerConfig::quiet().with_max_ticks(2))
+            .unwrap();
+
+        for i in 0..3 {
+            let data = MarketData {
+                symbol: "BTCUSDT".to_string(),
+                timestamp: 1234567890 + i,
+                open: 50000.0,
+                high: 50100.0,
+                low: 49900.0,
+                close: 50050.0,
+                volume: 1000,
+                bid: 50045.0,
+                ask: 50055.0,
+                bid_qty: None,
+                ask_qty: None,
+                is_final: true,
+            };
+            engine.feed_data(data).await.unwrap();
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+        let mut reasons = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let RunnerEvent::RunnerStopped { reason, status, .. } = event {
+                reasons.push(reason);
+                assert_eq!(status, RunnerStatus::Finished);
+            }
+        }
+        assert_eq!(reasons, vec!["max_ticks reached".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_panicked_runner_kept_with_error_status() {
+        let strategy = strategy_from_source(
+            "panic",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    indicators.custom("boom")
+    return nil
+end
+function filter_commitment(market_data, context, indicators)
+    return nil
+end
+function manage_position(market_data, context, indicators)
+    return nil
+end
+"#,
+        );
+
+        let mut engine = TradingEngine::new();
+        engine.register_indicator("boom", Box::new(|_| panic!("indicator bug")));
+        engine.add_runner("btc_panic", "BTCUSDT", strategy).unwrap();
+
+        let snapshot = engine.get_runner_snapshot("btc_panic").await.unwrap();
+        assert_eq!(snapshot.status, RunnerStatus::Starting);
+
+        engine.feed_data(MarketData::from_trade("BTCUSDT", 50000.0, 1.0, 0)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Still listed, reported as failed rather than vanishing
+        assert!(engine.has_runner("btc_panic"));
+        assert_eq!(engine.runner_is_healthy("btc_panic"), Some(false));
+        let snapshot = engine.get_runner_snapshot("btc_panic").await.unwrap();
+        assert_eq!(snapshot.status, RunnerStatus::Error);
+
+        assert!(matches!(
+            engine.remove_runner("btc_panic").await,
+            Err(TradingEngineError::TaskPanic(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_idle_runner_goes_stale_until_next_tick() {
+        let mut engine = TradingEngine::new();
+        let mut events = engine.subscribe_events();
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua").unwrap();
+        let config = RunnerConfig::quiet().with_max_idle_ms(50);
+        engine.add_runner_with_config("btc_idle", "BTCUSDT", strategy, 50, config).unwrap();
+
+        engine.feed_data(MarketData::from_trade("BTCUSDT", 50000.0, 1.0, 0)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let snapshot = engine.get_runner_snapshot("btc_idle").await.unwrap();
+        assert_eq!(snapshot.status, RunnerStatus::Running);
+
+        // The feed goes quiet past the threshold
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let snapshot = engine.get_runner_snapshot("btc_idle").await.unwrap();
+        assert_eq!(snapshot.status, RunnerStatus::Stale);
+        assert!(snapshot.status.is_active());
+
+        let mut stale_warnings = 0;
+        while let Ok(event) = events.try_recv() {
+            if let RunnerEvent::Error { error, severity, .. } = event {
+                assert_eq!(severity, crate::events::ErrorSeverity::Warning);
+                assert!(error.contains("stale"));
+                stale_warnings += 1;
+            }
+        }
+        assert_eq!(stale_warnings, 1);
+
+        // The next tick revives it
+        engine.feed_data(MarketData::from_trade("BTCUSDT", 50010.0, 1.0, 1)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let snapshot = engine.get_runner_snapshot("btc_idle").await.unwrap();
+        assert_eq!(snapshot.status, RunnerStatus::Running);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_reports_open_positions() {
+        // Enters long and holds
+        let script = TempScript::new(
+            "shutdown_hold",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    return { signal = "bullish" }
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 1.0 }
+end
+function manage_position(market_data, context, indicators)
+    return nil
+end
+"#,
+        );
+
+        let mut engine = TradingEngine::new_deterministic();
+        engine.add_runner("btc_hold", "BTCUSDT", LuaStrategy::new(script.path()).unwrap()).unwrap();
+        engine.add_runner("eth_idle", "ETHUSDT", LuaStrategy::new(script.path()).unwrap()).unwrap();
+
+        for (i, price) in [50000.0, 50000.0, 50500.0].into_iter().enumerate() {
+            engine.feed_data(MarketData::from_trade("BTCUSDT", price, 1.0, 1000 * i as i64)).await.unwrap();
+        }
+
+        let open = engine.shutdown().await.unwrap();
+
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].runner_id, "btc_hold");
+        let position = open[0].position.as_ref().unwrap();
+        assert_eq!(position.entry_price(), 50000.0);
+        assert_eq!(position.unrealized_pnl(), Some(500.0));
+    }
+
+    #[tokio::test]
+    async fn test_tick_to_ten_runners_is_one_batch() {
+        let mut engine = TradingEngine::new_deterministic();
+        let mut batches = engine.subscribe_batched_events();
+        for i in 0..10 {
+            let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+                .expect("Failed to load test strategy");
+            engine.add_runner(format!("btc_{}", i), "BTCUSDT", strategy).unwrap();
+        }
+
+        engine.feed_data(MarketData::from_trade("BTCUSDT", 50000.0, 1.0, 60_000)).await.unwrap();
+
+        // Skip the RunnerStarted batches
+        let batch = loop {
+            let batch = tokio::time::timeout(Duration::from_secs(1), batches.recv()).await.unwrap().unwrap();
+            if batch.timestamp == Some(60_000) {
+                break batch;
+            }
+        };
+
+        let mut ticked: Vec<&str> = batch
+            .events
+            .iter()
+            .filter(|event| matches!(event, RunnerEvent::TickReceived { .. }))
+            .map(RunnerEvent::runner_id)
+            .collect();
+        ticked.sort();
+        ticked.dedup();
+        assert_eq!(ticked.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_pairs_strategy_reads_context_symbol() {
+        // Goes long BTC once it trades above 17 ETH
+        let strategy = strategy_from_source(
+            "pairs",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    local btc, eth = symbols.BTCUSDT, symbols.ETHUSDT
+    if btc and eth and btc.close / eth.close > 17 then
+        return { signal = "ratio_high" }
+    end
+    return nil
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 1.0 }
+end
+function manage_position(market_data, context, indicators)
+    return nil
+end
+"#,
+        );
+
+        let mut engine = TradingEngine::new_deterministic();
+        let config = RunnerConfig::quiet().with_context_symbols(["ETHUSDT"]);
+        engine
+            .add_runner_with_config("btc_eth_pair", "BTCUSDT", strategy, 50, config)
+            .unwrap();
+
+        // Ratio 16.7, then 17.2 once ETH falls
+        for (i, eth) in [3000.0, 2900.0].into_iter().enumerate() {
+            let timestamp = 60_000 * i as i64;
+            engine.feed_data(MarketData::from_trade("ETHUSDT", eth, 1.0, timestamp)).await.unwrap();
+            engine.feed_data(MarketData::from_trade("BTCUSDT", 50000.0, 1.0, timestamp)).await.unwrap();
+            assert!(engine.get_runner_snapshot("btc_eth_pair").await.unwrap().position.is_none());
+        }
+        engine.feed_data(MarketData::from_trade("BTCUSDT", 50000.0, 1.0, 120_000)).await.unwrap();
+
+        // ETH bars feed the context without counting as ticks
+        let snapshot = engine.get_runner_snapshot("btc_eth_pair").await.unwrap();
+        assert!(snapshot.position.is_some());
+        assert_eq!(snapshot.stats.ticks_processed, 3);
+
+        // Reading ETH as context doesn't make it an ETH runner
+        assert_eq!(engine.runners_for_symbol("BTCUSDT"), vec!["btc_eth_pair"]);
+        assert_eq!(engine.runner_count_for_symbol("ETHUSDT"), 0);
+        assert!(matches!(
+            engine.strategy_comparison("ETHUSDT", ComparisonMetric::TotalPnl).await,
+            Err(TradingEngineError::NoRunnersForSymbol(_))
+        ));
+
+        engine.remove_runner("btc_eth_pair").await.unwrap();
+        assert!(engine.active_symbols().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_flatten_all_closes_every_position() {
+        // Enters long and holds
+        let script = TempScript::new(
+            "flatten",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    return { signal = "bullish" }
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 1.0 }
+end
+function manage_position(market_data, context, indicators)
+    return nil
+end
+"#,
+        );
+
+        let mut engine = TradingEngine::new_deterministic();
+        engine.add_runner("btc_hold", "BTCUSDT", LuaStrategy::new(script.path()).unwrap()).unwrap();
+        engine.add_runner("eth_hold", "ETHUSDT", LuaStrategy::new(script.path()).unwrap()).unwrap();
+        engine.add_runner("sol_idle", "SOLUSDT", LuaStrategy::new(script.path()).unwrap()).unwrap();
+        engine.add_runner("btc_stopped", "BTCUSDT", LuaStrategy::new(script.path()).unwrap()).unwrap();
+        assert!(engine.stop_runner("btc_stopped").await.unwrap());
+
+        for i in 0..3 {
+            for (symbol, price) in [("BTCUSDT", 50000.0), ("ETHUSDT", 3000.0)] {
+                let data = MarketData::from_trade(symbol, price + i as f64, 1.0, 1000 * i);
+                engine.feed_data(data).await.unwrap();
+            }
+        }
+        for runner_id in ["btc_hold", "eth_hold"] {
+            assert!(engine.get_runner_snapshot(runner_id).await.unwrap().position.is_some());
+        }
+
+        // The stopped runner is skipped rather than reported as a failure
+        let results = engine.flatten_all().await.unwrap();
+        let flattened: Vec<&str> = results.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(flattened, vec!["btc_hold", "eth_hold"]);
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+
+        for runner_id in ["btc_hold", "eth_hold"] {
+            let snapshot = engine.get_runner_snapshot(runner_id).await.unwrap();
+            assert!(snapshot.position.is_none());
+            assert_eq!(snapshot.current_state, State::Idle);
+        }
+
+        // Nothing left to close
+        assert!(engine.flatten_all().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rename_runner_keeps_position() {
+        // Enters long and holds
+        let script = TempScript::new(
+            "rename",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    return { signal = "bullish" }
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 1.0 }
+end
+function manage_position(market_data, context, indicators)
+    return nil
+end
+"#,
+        );
+
+        let mut engine = TradingEngine::new_deterministic();
+        engine.add_runner("btc", "BTCUSDT", LuaStrategy::new(script.path()).unwrap()).unwrap();
+        engine.add_runner("btc_other", "BTCUSDT", LuaStrategy::new(script.path()).unwrap()).unwrap();
+
+        for i in 0..3 {
+            engine.feed_data(MarketData::from_trade("BTCUSDT", 50000.0, 1.0, 1000 * i)).await.unwrap();
+        }
+
+        assert!(matches!(
+            engine.rename_runner("btc", "btc_other").await,
+            Err(TradingEngineError::RunnerAlreadyExists(_))
+        ));
+        engine.rename_runner("btc", "btc_hold").await.unwrap();
+
+        assert!(!engine.has_runner("btc"));
+        assert_eq!(engine.runners_for_symbol("BTCUSDT"), vec!["btc_hold", "btc_other"]);
+
+        let mut events = engine.subscribe_events();
+        engine.feed_data(MarketData::from_trade("BTCUSDT", 50100.0, 1.0, 3000)).await.unwrap();
+
+        let snapshot = engine.get_runner_snapshot("btc_hold").await.unwrap();
+        assert_eq!(snapshot.runner_id, "btc_hold");
+        assert!(snapshot.position.is_some());
+        assert_eq!(snapshot.stats.ticks_processed, 4);
+
+        // Events from the renamed runner carry the new ID
+        let event = tokio::time::timeout(tokio::time::Duration::from_millis(100), async {
+            loop {
+                let event = events.recv().await.unwrap();
+                if let crate::events::RunnerEvent::PositionUpdated { ref runner_id, timestamp: 3000, .. } = event {
+                    if runner_id != "btc_other" {
+                        break event;
+                    }
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(event.runner_id(), "btc_hold");
+    }
+
+    #[tokio::test]
+    async fn test_rename_runner_before_start_event_leaves_no_stale_snapshot() {
+        let mut engine = TradingEngine::new();
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+        engine.add_runner("btc", "BTCUSDT", strategy).unwrap();
+
+        // Rename before the forwarder has seen RunnerStarted
+        engine.rename_runner("btc", "btc_hold").await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+        let cache = engine.snapshot_cache.lock();
+        assert!(!cache.contains_key("btc"));
+        assert_eq!(cache.get("btc_hold").unwrap().runner_id, "btc_hold");
+    }
+
+    #[tokio::test]
+    async fn test_runners_on_a_symbol_share_indicator_cache() {
+        let strategy = || LuaStrategy::new("../lua-strategies/test_strategy.lua").unwrap();
+        let mut engine = TradingEngine::new_deterministic();
+        engine.add_runner("btc_a", "BTCUSDT", strategy()).unwrap();
+        engine.add_runner("btc_b", "BTCUSDT", strategy()).unwrap();
+        engine.add_runner("eth", "ETHUSDT", strategy()).unwrap();
+
+        for i in 0..20 {
+            engine.feed_data(MarketData::from_trade("BTCUSDT", 50000.0 + i as f64, 1.0, i)).await.unwrap();
+            engine.feed_data(MarketData::from_trade("ETHUSDT", 3000.0 + i as f64, 1.0, i)).await.unwrap();
+        }
+
+        // The second BTC runner reads what the first computed
+        let btc = engine.indicator_cache("BTCUSDT").unwrap();
+        assert!(btc.hits() > 0);
+        assert_eq!(btc.hits(), btc.misses());
+
+        // A lone runner has no one to share with
+        assert_eq!(engine.indicator_cache("ETHUSDT").unwrap().hits(), 0);
+
+        engine.remove_runner("eth").await.unwrap();
+        assert!(engine.indicator_cache("ETHUSDT").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_strategy_comparison_sorts_runners() {
+        // Enters on one bar and exits on the next, long or short
+        let churn_strategy = |name: &str, entry: &str| {
+            strategy_from_source(
+                name,
+                &format!(
+                    r#"
+function detect_opportunity(market_data, context, indicators)
+    return {{ signal = "setup" }}
+end
+function filter_commitment(market_data, context, indicators)
+    return {{ action = "{}", price = market_data.close, quantity = 1.0 }}
+end
+function manage_position(market_data, context, indicators)
+    return {{ action = "exit", price = market_data.close }}
+end
+"#,
+                    entry
+                ),
+            )
+        };
+
+        let mut engine = TradingEngine::new_deterministic();
+        engine.add_runner("btc_short", "BTCUSDT", churn_strategy("compare_short", "enter_short")).unwrap();
+        engine.add_runner("btc_long", "BTCUSDT", churn_strategy("compare_long", "enter_long")).unwrap();
+
+        // Steadily rising prices: longs win, shorts lose
+        for i in 0..10 {
+            let price = 50000.0 + 100.0 * i as f64;
+            engine.feed_data(MarketData::from_trade("BTCUSDT", price, 1.0, 1000 * i)).await.unwrap();
+        }
+
+        let rows = engine.strategy_comparison("BTCUSDT", ComparisonMetric::TotalPnl).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].runner_id, "btc_long");
+        assert!(rows[0].trades > 0);
+        assert_eq!(rows[0].win_rate, 100.0);
+        assert_eq!(rows[0].profit_factor, None);
+        assert!(rows[0].total_pnl > 0.0);
+        assert_eq!(rows[1].runner_id, "btc_short");
+        assert_eq!(rows[1].win_rate, 0.0);
+        assert_eq!(rows[1].profit_factor, Some(0.0));
+        assert!(rows[1].total_pnl < 0.0);
+
+        let rows = engine.strategy_comparison("BTCUSDT", ComparisonMetric::ProfitFactor).await.unwrap();
+        assert_eq!(rows[0].runner_id, "btc_long");
+
+        assert!(matches!(
+            engine.strategy_comparison("ETHUSDT", ComparisonMetric::TotalPnl).await,
+            Err(TradingEngineError::NoRunnersForSymbol(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_event_sink_logs_events() {
+        use crate::sinks::{JsonlFileSink, Rotation};
+
+        let dir = std::env::temp_dir().join(format!("engine_event_sink_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("events.jsonl");
+
+        let mut engine = TradingEngine::new();
+        engine.add_event_sink(JsonlFileSink::new(&path, Rotation::Never).unwrap());
+
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+        engine.add_runner("btc_ema", "BTCUSDT", strategy).unwrap();
+
+        for i in 0..2 {
+            let data = MarketData {
+                symbol: "BTCUSDT".to_string(),
+                timestamp: 1234567890 + i,
+                open: 50000.0,
+                high: 50100.0,
+                low: 49900.0,
+                close: 50050.0,
+                volume: 1000,
+                bid: 50045.0,
+                ask: 50055.0,
+                bid_qty: None,
+                ask_qty: None,
+                is_final: true,
+            };
+            engine.feed_data(data).await.unwrap();
+        }
+
+        engine.remove_runner("btc_ema").await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        let types: Vec<String> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let value: serde_json::Value = serde_json::from_str(line).unwrap();
+                value["type"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(
+            types,
+            vec!["RunnerStarted", "TickReceived", "TickReceived", "RunnerStopped"]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[tokio::test]
     async fn test_get_runner_snapshot() {
         let mut engine = TradingEngine::new();
@@ -1456,6 +3535,9 @@ mod tests {
             volume: 1000,
             bid: 50045.0,
             ask: 50055.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         };
         engine.feed_data(data).await.unwrap();
 
@@ -1471,6 +3553,24 @@ mod tests {
         assert!(snapshot.stats.ticks_processed >= 1);
     }
 
+    #[tokio::test]
+    async fn test_all_snapshots() {
+        let mut engine = TradingEngine::new();
+        let strategy = || LuaStrategy::new("../lua-strategies/test_strategy.lua").unwrap();
+        engine.add_runner("btc_ema", "BTCUSDT", strategy()).unwrap();
+        engine.add_runner("eth_ema", "ETHUSDT", strategy()).unwrap();
+        engine.add_runner("sol_ema", "SOLUSDT", strategy()).unwrap();
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        let snapshots = engine.all_snapshots().await;
+
+        assert_eq!(snapshots.len(), 3);
+        assert_eq!(snapshots["btc_ema"].symbol, "BTCUSDT");
+        assert_eq!(snapshots["eth_ema"].symbol, "ETHUSDT");
+        assert_eq!(snapshots["sol_ema"].symbol, "SOLUSDT");
+    }
+
     #[tokio::test]
     async fn test_get_price_history() {
         let mut engine = TradingEngine::new();
@@ -1494,6 +3594,9 @@ mod tests {
                 volume: 1000,
                 bid: 50045.0 + (i as f64) * 10.0,
                 ask: 50055.0 + (i as f64) * 10.0,
+                bid_qty: None,
+                ask_qty: None,
+                is_final: true,
             };
             engine.feed_data(data).await.unwrap();
             tokio::time::sleep(tokio::time::Duration::from_millis(5)).await;
@@ -1516,6 +3619,64 @@ mod tests {
         assert_eq!(last.close, 50050.0 + 40.0); // 4th data point (0-indexed)
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_slow_runner_returns_cached_snapshot() {
+        // Strategy that blocks the runner for a while on every tick
+        let strategy = strategy_from_source(
+            "slow",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    local start = os.clock()
+    while os.clock() - start < 0.3 do end
+    return nil
+end
+function filter_commitment(market_data, context, indicators) return nil end
+function manage_position(market_data, context, indicators) return nil end
+"#,
+        );
+
+        let mut engine = TradingEngine::new();
+        engine.add_runner("btc_slow", "BTCUSDT", strategy).unwrap();
+
+        let data = MarketData {
+            symbol: "BTCUSDT".to_string(),
+            timestamp: 1234567890,
+            open: 50000.0,
+            high: 50100.0,
+            low: 49900.0,
+            close: 50050.0,
+            volume: 1000,
+            bid: 50045.0,
+            ask: 50055.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
+        };
+        engine.feed_data(data).await.unwrap();
+
+        // Runner is now stuck in the strategy
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let started = std::time::Instant::now();
+        let snapshot = engine
+            .get_runner_snapshot_with_timeout("btc_slow", Duration::from_millis(20))
+            .await
+            .expect("cached snapshot should be returned on timeout");
+        assert!(started.elapsed() < Duration::from_millis(200));
+        assert_eq!(snapshot.runner_id, "btc_slow");
+        assert_eq!(snapshot.symbol, "BTCUSDT");
+        // Starting until the first tick's event reaches the cache, which the
+        // busy worker may be holding up
+        assert!(snapshot.status.is_active());
+
+        // A patient caller gets a fresh snapshot once the tick completes
+        let snapshot = engine
+            .get_runner_snapshot_with_timeout("btc_slow", Duration::from_secs(2))
+            .await
+            .unwrap();
+        assert_eq!(snapshot.stats.ticks_processed, 1);
+    }
+
     #[tokio::test]
     async fn test_snapshot_nonexistent_runner() {
         let engine = TradingEngine::new();
@@ -0,0 +1,91 @@
+//! Periodic stats history written to CSV
+
+use super::RunnerStats;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Column names of a stats history file
+const HEADER: &str =
+    "timestamp,ticks_processed,actions_executed,errors,error_rate,avg_tick_us,min_tick_us,max_tick_us";
+
+/// Path of a runner's stats history file inside `dir`
+pub fn stats_history_file(dir: &Path, runner_id: &str) -> PathBuf {
+    dir.join(format!("{}.csv", runner_id))
+}
+
+/// Append one `(timestamp, stats)` row to the CSV file at `path`
+///
+/// The file and its parent directories are created as needed, and a header
+/// row is written to a new or empty file. Tick durations are in microseconds.
+///
+/// # Errors
+///
+/// Returns an I/O error if the file cannot be created or written.
+///
+/// # Example
+///
+/// ```no_run
+/// use trading_engine::runner::{append_stats_snapshot, RunnerStats};
+///
+/// let stats = RunnerStats::new();
+/// append_stats_snapshot("stats/btc_ema.csv".as_ref(), 1_700_000_000_000, &stats)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn append_stats_snapshot(path: &Path, timestamp: i64, stats: &RunnerStats) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if file.metadata()?.len() == 0 {
+        writeln!(file, "{}", HEADER)?;
+    }
+
+    // No ticks yet leaves the minimum at Duration::MAX
+    let min_tick_us = if stats.ticks_processed == 0 {
+        0
+    } else {
+        stats.min_tick_duration.as_micros()
+    };
+
+    writeln!(
+        file,
+        "{},{},{},{},{},{},{},{}",
+        timestamp,
+        stats.ticks_processed,
+        stats.actions_executed,
+        stats.errors,
+        stats.error_rate(),
+        stats.avg_tick_duration.as_micros(),
+        min_tick_us,
+        stats.max_tick_duration.as_micros(),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_header_written_once() {
+        let dir = std::env::temp_dir().join(format!("stats_history_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = stats_history_file(&dir, "btc_ema");
+
+        let mut stats = RunnerStats::new();
+        append_stats_snapshot(&path, 0, &stats).unwrap();
+        stats.record_tick(Duration::from_micros(250));
+        append_stats_snapshot(&path, 1000, &stats).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines, vec![HEADER, "0,0,0,0,0,0,0,0", "1000,1,0,0,0,250,250,250"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -18,7 +18,7 @@
 //! ```no_run
 //! use trading_engine::runner::SymbolRunner;
 //! use trading_engine::strategy::LuaStrategy;
-//! use tokio::sync::mpsc;
+//! use tokio::sync::{mpsc, oneshot};
 //!
 //! #[tokio::main]
 //! async fn main() -> anyhow::Result<()> {
@@ -47,21 +47,33 @@
 
 use crate::error::Result;
 use crate::events::{ErrorSeverity, RunnerEvent};
+use crate::indicators::{IndicatorCache, IndicatorRegistry};
 use crate::market_data::{MarketData, MarketDataWindow};
-use crate::state_machine::{Action, State, StateMachine};
-use crate::strategy::{IndicatorApi, LuaStrategy};
-use tokio::sync::mpsc;
+use crate::sources::BarHistory;
+use crate::state_machine::{Account, Action, ContractType, ExitTrigger, Side, State, StateMachine};
+use crate::strategy::{table_to_action, IndicatorApi, LuaStrategy, MultiSymbolContext};
+use tokio::sync::{mpsc, oneshot};
+use tokio_util::sync::CancellationToken;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
 use std::time::Instant;
 
 mod config;
 mod stats;
 mod engine;
 mod snapshot;
+mod backtest;
+mod history;
 
-pub use config::RunnerConfig;
+pub use config::{AtrStopConfig, ErrorPolicy, FillTiming, LatencyModel, OversizePolicy, RunnerConfig, RunnerSpec, SideRestriction, SpikeFilter};
 pub use stats::RunnerStats;
-pub use engine::TradingEngine;
-pub use snapshot::{RunnerCommand, RunnerSnapshot, ContextSnapshot, RunnerStatus};
+pub use engine::{ComparisonMetric, EngineSummary, RunnerShutdown, StrategyComparisonRow, TradingEngine};
+pub use backtest::{run_backtest, run_backtest_with_indicators, BacktestReport, EquityPoint};
+pub use history::{append_stats_snapshot, stats_history_file};
+pub use snapshot::{RunnerCommand, RunnerSnapshot, ContextSnapshot, FormattedPrices, RunnerStatus, DEFAULT_PRICE_PRECISION};
+
+/// Longest a runner waits for warmup history before trading without it
+const WARMUP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
 
 /// Per-symbol trading orchestrator
 ///
@@ -106,6 +118,67 @@ pub struct SymbolRunner {
 
     /// Optional command channel for state introspection
     command_rx: Option<mpsc::UnboundedReceiver<RunnerCommand>>,
+
+    /// Bar timestamp at which the last position was closed
+    last_exit_timestamp: Option<i64>,
+
+    /// Signal the last position was entered on, while the re-entry guard holds
+    entry_signal: Option<String>,
+
+    /// P&L already reported by partial reduces of the open position
+    reduced_pnl: f64,
+
+    /// Quantity already closed by partial reduces of the open position
+    reduced_quantity: f64,
+
+    /// Indicators the strategy has used that are still warming up
+    pending_indicators: HashSet<(String, usize)>,
+
+    /// Recent closes the spike filter compares against, rejected ones included
+    spike_reference: VecDeque<f64>,
+
+    /// Indicators already announced as ready
+    ready_indicators: HashSet<(String, usize)>,
+
+    /// Indicators already reported as needing more bars than the window holds
+    oversized_indicators: HashSet<(String, usize)>,
+
+    /// Custom indicators available to the strategy
+    indicator_registry: IndicatorRegistry,
+
+    /// Indicator values shared with other runners on the symbol, if any
+    indicator_cache: Option<IndicatorCache>,
+
+    /// Current bar's values of indicators precomputed by a backtest
+    precomputed: HashMap<String, f64>,
+
+    /// Source for `rest_warmup_bars`, until the window has been seeded
+    bar_history: Option<Arc<dyn BarHistory>>,
+
+    /// Windows of this and the `context_symbols` for the strategy, if any are configured
+    multi_symbol: Option<MultiSymbolContext>,
+
+    /// Entry or exit waiting out the configured latency
+    pending_order: Option<PendingOrder>,
+
+    /// Optional external shutdown signal
+    cancellation: Option<CancellationToken>,
+
+    /// Bar timestamp of the last stats history row
+    last_stats_snapshot: Option<i64>,
+
+    /// Why the runner stopped itself, if it did
+    stop_reason: Option<String>,
+}
+
+/// An order delayed by [`LatencyModel`] or [`FillTiming::NextBarOpen`]
+#[derive(Debug, Clone)]
+struct PendingOrder {
+    /// The strategy's action, with its original price
+    action: Action,
+
+    /// Bars left until it fills
+    bars_left: u32,
 }
 
 impl SymbolRunner {
@@ -160,11 +233,39 @@ impl SymbolRunner {
             start_time: Instant::now(),
             event_tx: None,
             command_rx: None,
+            last_exit_timestamp: None,
+            entry_signal: None,
+            reduced_pnl: 0.0,
+            reduced_quantity: 0.0,
+            pending_indicators: HashSet::new(),
+            spike_reference: VecDeque::new(),
+            ready_indicators: HashSet::new(),
+            oversized_indicators: HashSet::new(),
+            indicator_registry: IndicatorRegistry::new(),
+            indicator_cache: None,
+            precomputed: HashMap::new(),
+            bar_history: None,
+            multi_symbol: None,
+            pending_order: None,
+            cancellation: None,
+            last_stats_snapshot: None,
+            stop_reason: None,
         }
     }
 
     /// Create a runner with custom configuration
     pub fn with_config(mut self, config: RunnerConfig) -> Self {
+        self.state_machine.set_auto_exit(config.auto_exit);
+        self.state_machine.set_slippage_bps(config.slippage_bps);
+        self.state_machine.set_commission(config.commission.clone());
+        self.state_machine.set_intrabar_exits(config.intrabar_exits);
+        self.state_machine.set_contract_type(config.contract_type);
+        self.state_machine.set_reduce_only(config.reduce_only);
+        self.state_machine.set_account(config.starting_balance.map(Account::new));
+        self.multi_symbol = (!config.context_symbols.is_empty()).then(|| {
+            let symbols = std::iter::once(self.symbol.clone()).chain(config.context_symbols.iter().cloned());
+            MultiSymbolContext::new(symbols, self.window.capacity())
+        });
         self.config = config;
         self
     }
@@ -209,6 +310,66 @@ impl SymbolRunner {
         self
     }
 
+    /// Make custom indicators available to the strategy as `indicators.custom(name)`
+    pub fn with_indicator_registry(mut self, registry: IndicatorRegistry) -> Self {
+        self.indicator_registry = registry;
+        self
+    }
+
+    /// Share indicator values with the other runners holding `cache`
+    ///
+    /// Values are keyed by the window's closes, so runners with different
+    /// window sizes can share a cache safely.
+    pub fn with_indicator_cache(mut self, cache: IndicatorCache) -> Self {
+        self.indicator_cache = Some(cache);
+        self
+    }
+
+    /// Seed the window from `history` before the first tick, if the config
+    /// sets `rest_warmup_bars`
+    pub fn with_bar_history(mut self, history: Arc<dyn BarHistory>) -> Self {
+        self.bar_history = Some(history);
+        self
+    }
+
+    /// Push bars into the window without running the strategy on them
+    ///
+    /// Lets indicators start from history instead of warming up on live data.
+    pub fn seed_window(&mut self, bars: impl IntoIterator<Item = MarketData>) {
+        for bar in bars {
+            self.record_spike_reference(bar.close);
+            self.window.push(bar);
+        }
+    }
+
+    /// Stop [`run`](Self::run) when `token` is cancelled
+    ///
+    /// Lets many runners be shut down together without closing their data
+    /// channels. Commands already queued, such as snapshot requests, are
+    /// answered before the loop exits.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use trading_engine::runner::SymbolRunner;
+    /// # use trading_engine::strategy::LuaStrategy;
+    /// # use tokio::sync::mpsc;
+    /// use tokio_util::sync::CancellationToken;
+    ///
+    /// # let strategy = LuaStrategy::new("test.lua").unwrap();
+    /// # let (_, data_rx) = mpsc::unbounded_channel();
+    /// let shutdown = CancellationToken::new();
+    /// let runner = SymbolRunner::new("id".to_string(), "BTC".to_string(), strategy, data_rx, 50)
+    ///     .with_cancellation(shutdown.child_token());
+    ///
+    /// // Later, from anywhere:
+    /// shutdown.cancel();
+    /// ```
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
     /// Get the runner ID
     pub fn runner_id(&self) -> &str {
         &self.runner_id
@@ -219,6 +380,16 @@ impl SymbolRunner {
         &self.symbol
     }
 
+    /// Why the runner stopped itself (e.g. "max_ticks reached"), if it did
+    pub fn stop_reason(&self) -> Option<&str> {
+        self.stop_reason.as_deref()
+    }
+
+    /// Current lifecycle status
+    pub fn status(&self) -> RunnerStatus {
+        self.status
+    }
+
     /// Emit an event (if event channel is configured)
     fn emit_event(&self, event: RunnerEvent) {
         if let Some(tx) = &self.event_tx {
@@ -264,12 +435,120 @@ impl SymbolRunner {
                 tracing::info!("Runner {} stopped", self.runner_id);
                 let _ = response.send(true);
             }
+            RunnerCommand::Flatten { response } => self.flatten(response),
+            RunnerCommand::UpdateConfig { config, response } => {
+                let _ = response.send(self.update_config(*config));
+            }
+            RunnerCommand::Rename { runner_id, response } => {
+                tracing::info!("Runner {} renamed to {}", self.runner_id, runner_id);
+                self.runner_id = runner_id;
+                let _ = response.send(true);
+            }
+        }
+    }
+
+    /// Swap in a new configuration, keeping fields fixed at startup
+    ///
+    /// `starting_balance` funds an account that already holds realized P&L,
+    /// and `tags` and `context_symbols` are indexed by the engine, so all
+    /// three keep their current values. Returns whether the new config was
+    /// applied in full.
+    fn update_config(&mut self, mut config: RunnerConfig) -> bool {
+        let applied_in_full = config.starting_balance == self.config.starting_balance
+            && config.tags == self.config.tags
+            && config.context_symbols == self.config.context_symbols;
+        if !applied_in_full {
+            tracing::warn!(
+                "Runner {}: starting_balance, tags and context_symbols can't change while running, keeping current values",
+                self.runner_id
+            );
+        }
+        config.starting_balance = self.config.starting_balance;
+        config.tags = std::mem::take(&mut self.config.tags);
+        config.context_symbols = std::mem::take(&mut self.config.context_symbols);
+
+        self.state_machine.set_auto_exit(config.auto_exit);
+        self.state_machine.set_slippage_bps(config.slippage_bps);
+        self.state_machine.set_commission(config.commission.clone());
+        self.state_machine.set_intrabar_exits(config.intrabar_exits);
+        self.state_machine.set_contract_type(config.contract_type);
+        self.state_machine.set_reduce_only(config.reduce_only);
+        self.config = config;
+        tracing::info!("Runner {} config updated", self.runner_id);
+        applied_in_full
+    }
+
+    /// Exit the open position at the latest bar's close and report back
+    ///
+    /// Any pending delayed order is dropped. Responds `Ok(false)` if there
+    /// is no position to close.
+    fn flatten(&mut self, response: oneshot::Sender<Result<bool>>) {
+        if self.state_machine.position().is_none() {
+            let _ = response.send(Ok(false));
+            return;
+        }
+        let Some((price, timestamp)) = self.window.latest().map(|bar| (bar.close, bar.timestamp)) else {
+            let _ = response.send(Err(crate::error::TradingEngineError::InvalidData(
+                "No price to flatten at".to_string(),
+            )));
+            return;
+        };
+
+        self.pending_order = None;
+        let state_before = *self.state_machine.current_state();
+        let action = Action::ExitPosition { price };
+        tracing::warn!("Symbol {}: Flattening position at {}", self.symbol, price);
+
+        if let Err(e) = self.state_machine.execute(action.clone()) {
+            let _ = response.send(Err(e));
+            return;
+        }
+        self.report_action(action, true, price, "Flattened", timestamp);
+        self.emit_event(RunnerEvent::StateTransition {
+            runner_id: self.runner_id.clone(),
+            from: state_before,
+            to: *self.state_machine.current_state(),
+            reason: "Flattened".to_string(),
+            timestamp,
+        });
+
+        self.record_exit(timestamp);
+        let _ = response.send(Ok(true));
+    }
+
+    /// Count an executed action and emit the events for what it did
+    ///
+    /// Emits `ActionExecuted`, then `PositionOpened` for an entry,
+    /// `PositionClosed` with `close_reason` if it closed the position held
+    /// before it, or a partial close for a reduce.
+    fn report_action(&mut self, action: Action, had_position: bool, requested_price: f64, close_reason: &str, timestamp: i64) {
+        self.stats.record_action();
+        let is_entry = action.is_entry();
+        let reduced = matches!(action, Action::ReducePosition { .. });
+        self.emit_event(RunnerEvent::ActionExecuted {
+            runner_id: self.runner_id.clone(),
+            action,
+            timestamp,
+        });
+
+        if is_entry {
+            self.emit_position_opened(requested_price, timestamp);
+        } else if had_position && self.state_machine.position().is_none() {
+            self.emit_position_closed(requested_price, close_reason, timestamp);
+        } else if reduced {
+            self.emit_partial_close(requested_price, timestamp);
         }
     }
 
+    /// Note that the position closed at `timestamp`
+    fn record_exit(&mut self, timestamp: i64) {
+        self.last_exit_timestamp = Some(timestamp);
+        self.state_machine.context_mut().remove::<i64>("bars_held");
+    }
+
     /// Create a snapshot of the current runner state
     fn create_snapshot(&self) -> RunnerSnapshot {
-        RunnerSnapshot::new(
+        let mut snapshot = RunnerSnapshot::new(
             self.runner_id.clone(),
             self.symbol.clone(),
             self.status,
@@ -279,6 +558,9 @@ impl SymbolRunner {
             self.stats.clone(),
             self.start_time.elapsed(),
         )
+        .with_price_precision(self.config.price_precision);
+        snapshot.tags = self.config.tags.clone();
+        snapshot
     }
 
     /// Create a snapshot of the strategy context
@@ -330,12 +612,38 @@ impl SymbolRunner {
     /// 4. Executes actions returned by the strategy
     /// 5. Updates the state machine
     ///
-    /// The loop runs until the channel is closed or an unrecoverable error occurs.
+    /// The loop runs until the channel is closed, the runner is stopped or
+    /// cancelled (see [`with_cancellation`](Self::with_cancellation)), or an
+    /// unrecoverable error occurs.
     pub async fn run(&mut self) -> Result<()> {
         tracing::info!("Starting SymbolRunner for {}", self.symbol);
+        let cancellation = self.cancellation.clone();
+        let mut last_data = tokio::time::Instant::now();
 
         loop {
+            // Only a live runner can go stale, and only once per silence
+            let idle_deadline = self
+                .config
+                .max_idle_ms
+                .filter(|_| matches!(self.status, RunnerStatus::Starting | RunnerStatus::Running))
+                .map(|ms| last_data + std::time::Duration::from_millis(ms));
+
             tokio::select! {
+                // Handle external shutdown
+                _ = async {
+                    match &cancellation {
+                        Some(token) => token.cancelled().await,
+                        None => std::future::pending().await, // Never resolves without a token
+                    }
+                } => {
+                    tracing::info!("Runner for {} cancelled, shutting down", self.symbol);
+                    // Answer queued snapshot requests with the final state
+                    while let Some(cmd) = self.command_rx.as_mut().and_then(|rx| rx.try_recv().ok()) {
+                        self.handle_command(cmd);
+                    }
+                    break;
+                },
+
                 // Handle incoming market data
                 data_result = self.data_receiver.recv() => {
                     let market_data = match data_result {
@@ -345,53 +653,21 @@ impl SymbolRunner {
                             break;
                         }
                     };
+                    last_data = tokio::time::Instant::now();
 
-                    // Check if stopped
-                    if self.status.is_stopped() {
-                        tracing::info!("Runner {} is stopped, exiting", self.runner_id);
+                    if !self.handle_data(market_data).await? {
                         break;
                     }
+                },
 
-                    // Validate symbol matches
-                    if market_data.symbol != self.symbol {
-                        tracing::warn!(
-                            "Received data for {} but runner is for {}",
-                            market_data.symbol,
-                            self.symbol
-                        );
-                        continue;
-                    }
-
-                    // Skip tick processing if paused
-                    if !self.status.is_active() {
-                        continue;
-                    }
-
-                    // Process the tick
-                    if let Err(e) = self.process_tick(market_data.clone()).await {
-                        tracing::error!("Error processing tick for {}: {}", self.symbol, e);
-
-                        // Emit error event
-                        let severity = if self.config.stop_on_error {
-                            ErrorSeverity::Critical
-                        } else {
-                            ErrorSeverity::Error
-                        };
-
-                        self.emit_event(RunnerEvent::Error {
-                            runner_id: self.runner_id.clone(),
-                            error: e.to_string(),
-                            severity,
-                            timestamp: market_data.timestamp,
-                        });
-
-                        if self.config.stop_on_error {
-                            return Err(e);
-                        }
-
-                        // Continue on error if configured
-                        self.stats.record_error();
+                // Flag a dead feed
+                _ = async {
+                    match idle_deadline {
+                        Some(deadline) => tokio::time::sleep_until(deadline).await,
+                        None => std::future::pending().await, // Never resolves without a watchdog
                     }
+                } => {
+                    self.mark_stale();
                 },
 
                 // Handle introspection commands
@@ -412,10 +688,215 @@ impl SymbolRunner {
         Ok(())
     }
 
+    /// Mark the runner stale after `max_idle_ms` without data and warn
+    fn mark_stale(&mut self) {
+        let idle_ms = self.config.max_idle_ms.unwrap_or_default();
+        tracing::warn!("Runner {} received no data for {}ms, marking stale", self.runner_id, idle_ms);
+        self.status = RunnerStatus::Stale;
+        self.emit_event(RunnerEvent::Error {
+            runner_id: self.runner_id.clone(),
+            error: format!("No data received for {}ms, runner is stale", idle_ms),
+            severity: ErrorSeverity::Warning,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        });
+    }
+
+    /// Process all data and commands that are already queued, without waiting
+    ///
+    /// This drives the runner inline instead of from [`run`](Self::run), so
+    /// the caller knows every queued tick and command has been handled when it
+    /// returns. Commands are handled before data.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(true)` while the runner can keep going, `Ok(false)` once it has
+    /// stopped or its data channel has closed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the tick error if processing fails and `stop_on_error` is set.
+    pub async fn process_pending(&mut self) -> Result<bool> {
+        loop {
+            while let Some(cmd) = self.command_rx.as_mut().and_then(|rx| rx.try_recv().ok()) {
+                self.handle_command(cmd);
+            }
+
+            match self.data_receiver.try_recv() {
+                Ok(market_data) => {
+                    if !self.handle_data(market_data).await? {
+                        return Ok(false);
+                    }
+                }
+                Err(mpsc::error::TryRecvError::Empty) => return Ok(!self.status.is_stopped()),
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    tracing::info!("Channel closed for {}, shutting down", self.symbol);
+                    return Ok(false);
+                }
+            }
+        }
+    }
+
+    /// Process one bar inline, bypassing the data channel
+    ///
+    /// Lets a caller drive the runner bar by bar, e.g. for backtests over
+    /// stored history. The bar goes through the same checks as channel data:
+    /// it is skipped if it's for another symbol or the runner is paused.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(false)` once the runner has been stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns the tick error if processing fails and `stop_on_error` is set.
+    pub async fn step(&mut self, market_data: MarketData) -> Result<bool> {
+        self.handle_data(market_data).await
+    }
+
+    /// Handle one market data message from the channel
+    ///
+    /// Returns `Ok(false)` if the runner is stopped and should exit.
+    async fn handle_data(&mut self, market_data: MarketData) -> Result<bool> {
+        // Check if stopped
+        if self.status.is_stopped() {
+            tracing::info!("Runner {} is stopped, exiting", self.runner_id);
+            return Ok(false);
+        }
+
+        // Validate symbol matches
+        if market_data.symbol != self.symbol {
+            // Bars for context symbols only update the strategy's view of them
+            if let Some(context) = self.multi_symbol.as_mut().filter(|context| context.contains(&market_data.symbol)) {
+                if market_data.is_final || !self.config.final_bars_only {
+                    context.push(market_data);
+                }
+                return Ok(true);
+            }
+
+            tracing::warn!(
+                "Received data for {} but runner is for {}",
+                market_data.symbol,
+                self.symbol
+            );
+            return Ok(true);
+        }
+
+        // Skip tick processing if paused
+        if !self.status.is_active() {
+            return Ok(true);
+        }
+
+        // Warm the window up from history before the first live tick
+        if let Some(history) = self.bar_history.take() {
+            self.warm_up_from(history.as_ref(), market_data.timestamp).await;
+        }
+
+        // Skip candles that are still forming
+        if self.config.final_bars_only && !market_data.is_final {
+            tracing::trace!("Runner {} skipping non-final bar at {}", self.runner_id, market_data.timestamp);
+            return Ok(true);
+        }
+
+        // Skip bad prints before they reach the window
+        let spike = self.spike_rejection(&market_data);
+        self.record_spike_reference(market_data.close);
+        if let Some(reason) = spike {
+            tracing::warn!("Runner {} rejected bar at {}: {}", self.runner_id, market_data.timestamp, reason);
+            self.emit_event(RunnerEvent::Error {
+                runner_id: self.runner_id.clone(),
+                error: format!("Bar rejected as a spike: {}", reason),
+                severity: ErrorSeverity::Warning,
+                timestamp: market_data.timestamp,
+            });
+            return Ok(true);
+        }
+
+        // Process the tick
+        if let Err(e) = self.process_tick(market_data.clone()).await {
+            tracing::error!("Error processing tick for {}: {}", self.symbol, e);
+
+            // Emit error event
+            let policy = self.config.effective_error_policy();
+            let severity = if policy == ErrorPolicy::SkipTick {
+                ErrorSeverity::Error
+            } else {
+                ErrorSeverity::Critical
+            };
+
+            self.emit_event(RunnerEvent::Error {
+                runner_id: self.runner_id.clone(),
+                error: e.to_string(),
+                severity,
+                timestamp: market_data.timestamp,
+            });
+
+            // A retried error that failed again escalates like any other
+            if policy != ErrorPolicy::SkipTick {
+                self.status = RunnerStatus::Error;
+                return Err(e);
+            }
+
+            // Continue on error if configured
+            self.stats.record_error();
+        }
+
+        // Stop at the configured horizon
+        if let Some(max_ticks) = self.config.max_ticks {
+            if self.stats.ticks_processed + self.stats.errors >= max_ticks {
+                tracing::info!("Runner {} reached max_ticks ({}), stopping", self.runner_id, max_ticks);
+                self.status = RunnerStatus::Finished;
+                self.stop_reason = Some("max_ticks reached".to_string());
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Seed the window with `rest_warmup_bars` bars from `history` ending before `before`
+    ///
+    /// A failed fetch, or one taking longer than 10 seconds, is reported as a
+    /// warning; the runner then warms up on live data as usual.
+    async fn warm_up_from(&mut self, history: &dyn BarHistory, before: i64) {
+        let Some(count) = self.config.rest_warmup_bars.filter(|&count| count > 0) else {
+            return;
+        };
+
+        let fetched = match tokio::time::timeout(WARMUP_TIMEOUT, history.recent_bars(&self.symbol, count)).await {
+            Ok(result) => result.map_err(|e| e.to_string()),
+            Err(_) => Err(format!("no response within {:?}", WARMUP_TIMEOUT)),
+        };
+        match fetched {
+            Ok(bars) => {
+                // The live tick may already be in the history
+                let bars: Vec<MarketData> = bars.into_iter().filter(|bar| bar.timestamp < before).collect();
+                tracing::info!("Runner {} seeded its window with {} historical bars", self.runner_id, bars.len());
+                self.seed_window(bars);
+            }
+            Err(e) => {
+                tracing::warn!("Runner {} could not fetch warmup bars: {}", self.runner_id, e);
+                self.emit_event(RunnerEvent::Error {
+                    runner_id: self.runner_id.clone(),
+                    error: format!("Warmup history unavailable: {}", e),
+                    severity: ErrorSeverity::Warning,
+                    timestamp: before,
+                });
+            }
+        }
+    }
+
     /// Process a single market data tick
     async fn process_tick(&mut self, market_data: MarketData) -> Result<()> {
         let tick_start = Instant::now();
 
+        // The first tick ends the starting phase, and any tick ends a stale one
+        if self.status == RunnerStatus::Stale {
+            tracing::info!("Runner {} received data again, no longer stale", self.runner_id);
+        }
+        if matches!(self.status, RunnerStatus::Starting | RunnerStatus::Stale) {
+            self.status = RunnerStatus::Running;
+        }
+
         // Emit tick received event
         self.emit_event(RunnerEvent::TickReceived {
             runner_id: self.runner_id.clone(),
@@ -425,6 +906,10 @@ impl SymbolRunner {
 
         // Update window
         self.window.push(market_data.clone());
+        if let Some(context) = &mut self.multi_symbol {
+            context.push(market_data.clone());
+            self.strategy.set_symbols(context)?;
+        }
 
         // Update context with latest data
         self.state_machine
@@ -435,53 +920,187 @@ impl SymbolRunner {
             .set("latest_timestamp", market_data.timestamp);
 
         // Create indicator API
-        let indicator_api = IndicatorApi::new(self.window.clone());
+        let mut indicator_api = IndicatorApi::new(self.window.clone())
+            .with_registry(self.indicator_registry.clone())
+            .with_precomputed(self.precomputed.clone());
+        if let Some(cache) = &self.indicator_cache {
+            indicator_api = indicator_api.with_cache(cache);
+        }
 
         // Track state before strategy execution
         let state_before = *self.state_machine.current_state();
+        let had_position = self.state_machine.position().is_some();
+
+        // Count bars held and extend the water-marks so manage_position can
+        // see how long it's been in and how far price has run
+        if let Some(position) = self.state_machine.position_mut() {
+            position.record_bar();
+            position.record_range(market_data.high, market_data.low);
+            let bars_held = position.bars_held() as i64;
+            self.state_machine.context_mut().set("bars_held", bars_held);
+        }
+
+        // A delayed order fills in place of calling the strategy
+        let mut delayed_price = None;
+        let action = if let Some(mut pending) = self.pending_order.take() {
+            pending.bars_left = pending.bars_left.saturating_sub(1);
+            if pending.bars_left > 0 {
+                self.pending_order = Some(pending);
+                None
+            } else if pending.action.is_exit() && !had_position {
+                // The position was already closed by a stop loss or take profit
+                None
+            } else {
+                let requested_price = pending.action.price();
+                let filled = pending.action.with_price(self.config.fill_timing.fill_price(&market_data));
+                if let Some(reason) = self.balance_block_reason(&filled) {
+                    // The price moved beyond what the account can cover
+                    self.warn_entry_suppressed(&filled, &reason, market_data.timestamp);
+                    None
+                } else {
+                    delayed_price = requested_price;
+                    Some(filled)
+                }
+            }
+        } else {
+            // Under RetryOnce a transient strategy error gets a second call
+            let mut retries_left = u32::from(self.config.effective_error_policy() == ErrorPolicy::RetryOnce);
+            let mut action = loop {
+                let result = match self.state_machine.current_state() {
+                    State::Idle => self.handle_idle(&market_data, &indicator_api),
+                    State::Analyzing => self.handle_analyzing(&market_data, &indicator_api),
+                    State::InPosition => self.handle_in_position(&market_data, &indicator_api),
+                };
+                match result {
+                    Err(e) if retries_left > 0 && e.is_transient() => {
+                        retries_left -= 1;
+                        tracing::warn!("Runner {} retrying tick after transient error: {}", self.runner_id, e);
+                        self.emit_event(RunnerEvent::Error {
+                            runner_id: self.runner_id.clone(),
+                            error: format!("Retrying after transient error: {}", e),
+                            severity: ErrorSeverity::Warning,
+                            timestamp: market_data.timestamp,
+                        });
+                    }
+                    result => break result?,
+                }
+            };
+
+            // A pass, reasoned or not, is not an action
+            if action.as_ref().is_some_and(Action::is_no_action) {
+                if let Some(Action::NoActionWithReason { reason }) = action.take() {
+                    self.record_pass_reason(reason, market_data.timestamp);
+                }
+            }
+
+            if action.is_none() {
+                self.stats.record_no_action();
+                if self.config.log_no_action {
+                    tracing::debug!(
+                        "Symbol {}: No action in {:?} at {} ({})",
+                        self.symbol,
+                        self.state_machine.current_state(),
+                        market_data.timestamp,
+                        self.stats.last_no_action_reason.as_deref().unwrap_or("no reason given")
+                    );
+                }
+            }
+
+            if let Some(act) = action.take() {
+                action = self.screen_entry(act, market_data.timestamp);
+            }
 
-        // Call strategy based on current state
-        let action = match self.state_machine.current_state() {
-            State::Idle => self.handle_idle(&market_data, &indicator_api)?,
-            State::Analyzing => self.handle_analyzing(&market_data, &indicator_api)?,
-            State::InPosition => self.handle_in_position(&market_data, &indicator_api)?,
+            // Hold entries and exits back for the configured latency
+            if let Some(act) = action.take_if(|act| act.is_entry() || act.is_exit()) {
+                match self.config.fill_timing.delay(self.config.latency.sample()) {
+                    0 => action = Some(act),
+                    bars_left => {
+                        tracing::debug!(
+                            "Symbol {}: Delaying {:?} by {} bars",
+                            self.symbol,
+                            act,
+                            bars_left
+                        );
+                        self.pending_order = Some(PendingOrder { action: act, bars_left });
+                    }
+                }
+            }
+
+            action
         };
 
+        // Announce the strategy's indicators as they warm up
+        self.announce_ready_indicators(&indicator_api, market_data.timestamp);
+
         // Execute action if returned
-        if let Some(act) = action.clone() {
+        if let Some(mut act) = action.clone() {
             if self.config.log_actions {
                 tracing::info!("Symbol {}: Executing action: {:?}", self.symbol, act);
             }
 
-            // Check if this is a position opening action
-            let is_position_open = act.is_entry();
+            let requested_price = delayed_price
+                .or_else(|| act.price())
+                .unwrap_or(market_data.close);
+
+            // A reduce past the open quantity closes the position here; any
+            // reversal is a separate entry that goes through the entry checks
+            let mut reversal = None;
+            if let Action::ReducePosition { price, quantity } = act {
+                self.warn_reduce_clamped(quantity, market_data.timestamp);
+                if let Some(position) = self.state_machine.position() {
+                    let open = position.quantity();
+                    if quantity > open {
+                        if !self.config.reduce_only {
+                            let quantity = quantity - open;
+                            reversal = Some(match position.side().opposite() {
+                                Side::Long => Action::EnterLong { price, quantity },
+                                Side::Short => Action::EnterShort { price, quantity },
+                            });
+                        }
+                        act = Action::ReducePosition { price, quantity: open };
+                    }
+                }
+            }
 
             self.state_machine.execute(act.clone())?;
-            self.stats.record_action();
+            self.report_action(act, had_position, requested_price, "Strategy exit", market_data.timestamp);
 
-            // Emit action executed event
-            self.emit_event(RunnerEvent::ActionExecuted {
-                runner_id: self.runner_id.clone(),
-                action: act,
-                timestamp: market_data.timestamp,
-            });
+            if let Some(reversal) = reversal.and_then(|act| self.screen_entry(act, market_data.timestamp)) {
+                self.state_machine.execute(reversal.clone())?;
+                self.report_action(reversal, false, requested_price, "Strategy exit", market_data.timestamp);
+            }
+        }
 
-            // Emit position opened event if entering position
-            if is_position_open {
-                if let Some(position) = self.state_machine.position() {
-                    self.emit_event(RunnerEvent::PositionOpened {
+        // Update state machine (handles auto-exits)
+        let position_before_update = self.state_machine.position().is_some();
+        let trigger = self.state_machine.update(&market_data);
+        let state_after = *self.state_machine.current_state();
+
+        // With auto-exit disabled, report the hit and leave the exit to the strategy
+        if !self.config.auto_exit {
+            match trigger {
+                Some(ExitTrigger::StopLoss { price, stop }) => {
+                    self.emit_event(RunnerEvent::StopLossTriggered {
+                        runner_id: self.runner_id.clone(),
+                        price,
+                        stop_loss: stop,
+                        timestamp: market_data.timestamp,
+                    });
+                }
+                Some(ExitTrigger::TakeProfit { price, target }) => {
+                    self.emit_event(RunnerEvent::TakeProfitTriggered {
                         runner_id: self.runner_id.clone(),
-                        position: position.clone(),
+                        price,
+                        take_profit: target,
                         timestamp: market_data.timestamp,
                     });
                 }
+                None => {}
             }
         }
 
-        // Update state machine (handles auto-exits)
-        let position_before = self.state_machine.position().cloned();
-        self.state_machine.update(&market_data);
-        let state_after = *self.state_machine.current_state();
+        // Lock in breakeven once the position is far enough in profit
+        self.move_stop_to_breakeven(market_data.timestamp);
 
         // Emit state transition event if state changed
         if state_before != state_after {
@@ -497,32 +1116,36 @@ impl SymbolRunner {
         // Emit position update or closed event
         if let Some(position) = self.state_machine.position() {
             // Position still active - emit update
-            if let Some(unrealized_pnl) = position.unrealized_pnl() {
+            if let (Some(unrealized_pnl), Some(unrealized_pnl_pct)) =
+                (position.unrealized_pnl(), position.unrealized_pnl_pct())
+            {
                 self.emit_event(RunnerEvent::PositionUpdated {
                     runner_id: self.runner_id.clone(),
                     current_price: market_data.close,
                     unrealized_pnl,
+                    unrealized_pnl_pct,
                     timestamp: market_data.timestamp,
                 });
             }
-        } else if position_before.is_some() {
-            // Position was closed
-            if let Some(pos) = position_before {
-                if let Some(realized_pnl) = pos.realized_pnl() {
-                    self.emit_event(RunnerEvent::PositionClosed {
-                        runner_id: self.runner_id.clone(),
-                        exit_price: pos.current_price(),
-                        realized_pnl,
-                        reason: "Position closed".to_string(),
-                        timestamp: market_data.timestamp,
-                    });
-                }
-            }
+        } else if position_before_update {
+            // Position was closed by a stop loss or take profit
+            let (requested_price, reason) = match trigger {
+                Some(ExitTrigger::StopLoss { price, .. }) => (price, "Stop loss hit"),
+                Some(ExitTrigger::TakeProfit { price, .. }) => (price, "Take profit hit"),
+                None => (market_data.close, "Position closed"),
+            };
+            self.emit_position_closed(requested_price, reason, market_data.timestamp);
+        }
+
+        // Remember when the last position was closed
+        if had_position && self.state_machine.position().is_none() {
+            self.record_exit(market_data.timestamp);
         }
 
         // Record statistics
         let tick_duration = tick_start.elapsed();
         self.stats.record_tick(tick_duration);
+        self.record_stats_history(market_data.timestamp);
 
         // Log position updates
         if self.config.log_positions {
@@ -542,20 +1165,477 @@ impl SymbolRunner {
         Ok(())
     }
 
-    /// Handle Idle state - look for opportunities
-    fn handle_idle(
-        &mut self,
-        market_data: &MarketData,
-        indicator_api: &IndicatorApi,
-    ) -> Result<Option<Action>> {
-        let opportunity = self.strategy.detect_opportunity(
-            market_data,
-            self.state_machine.context(),
-            indicator_api,
-        )?;
-
-        if let Some(opp_table) = opportunity {
-            // Update context with opportunity data
+    /// Keep the strategy's reason for passing, emitting it if configured
+    fn record_pass_reason(&mut self, reason: String, timestamp: i64) {
+        if self.config.emit_no_action_reasons {
+            self.emit_event(RunnerEvent::StrategyPassed {
+                runner_id: self.runner_id.clone(),
+                state: *self.state_machine.current_state(),
+                reason: reason.clone(),
+                timestamp,
+            });
+        }
+        self.stats.record_no_action_reason(reason);
+    }
+
+    /// Emit a one-time IndicatorReady event for each indicator that has warmed up
+    ///
+    /// Indicators needing more bars than the window holds will never warm
+    /// up; each is reported once as a warning instead.
+    fn announce_ready_indicators(&mut self, indicator_api: &IndicatorApi, timestamp: i64) {
+        for request in indicator_api.requested() {
+            if indicator_api.exceeds_window(&request.0, request.1) {
+                self.warn_oversized_indicator(request, indicator_api.window_capacity(), timestamp);
+            } else if !self.ready_indicators.contains(&request) {
+                self.pending_indicators.insert(request);
+            }
+        }
+
+        let mut newly_ready: Vec<(String, usize)> = self
+            .pending_indicators
+            .iter()
+            .filter(|(kind, period)| indicator_api.is_ready(kind, *period))
+            .cloned()
+            .collect();
+        newly_ready.sort();
+
+        for (kind, period) in newly_ready {
+            self.pending_indicators.remove(&(kind.clone(), period));
+            self.ready_indicators.insert((kind.clone(), period));
+            self.emit_event(RunnerEvent::IndicatorReady {
+                runner_id: self.runner_id.clone(),
+                kind,
+                period,
+                timestamp,
+            });
+        }
+    }
+
+    /// Warn, the first time only, that an indicator can't fit in the window
+    fn warn_oversized_indicator(&mut self, request: (String, usize), capacity: usize, timestamp: i64) {
+        if !self.oversized_indicators.insert(request.clone()) {
+            return;
+        }
+
+        let (kind, period) = request;
+        tracing::warn!(
+            "Symbol {}: {}({}) needs more than the {}-bar window and will always be nil",
+            self.symbol,
+            kind,
+            period,
+            capacity
+        );
+        self.emit_event(RunnerEvent::Error {
+            runner_id: self.runner_id.clone(),
+            error: format!(
+                "Indicator {}({}) needs more bars than the window size of {}",
+                kind, period, capacity
+            ),
+            severity: ErrorSeverity::Warning,
+            timestamp,
+        });
+    }
+
+    /// Emit a PositionOpened event for the position the state machine just opened
+    ///
+    /// Also resets the per-position context and places ATR stops.
+    fn emit_position_opened(&mut self, requested_price: f64, timestamp: i64) {
+        self.state_machine.context_mut().set("bars_held", 0i64);
+        self.entry_signal = self.state_machine.context().get::<String>("signal").cloned();
+        self.reduced_pnl = 0.0;
+        self.reduced_quantity = 0.0;
+        self.place_atr_stops();
+        if let Some(position) = self.state_machine.position() {
+            self.emit_event(RunnerEvent::PositionOpened {
+                runner_id: self.runner_id.clone(),
+                position: position.clone(),
+                requested_price,
+                fill_price: position.entry_price(),
+                timestamp,
+            });
+        }
+    }
+
+    /// Emit a PositionClosed event for the part of the position a reduce closed
+    ///
+    /// Recorded in the stats like a full exit. The P&L is subtracted from what
+    /// the final close reports, so each realized dollar is counted once.
+    fn emit_partial_close(&mut self, requested_price: f64, timestamp: i64) {
+        let (Some(exit), Some(position)) = (self.state_machine.last_partial_exit(), self.state_machine.position()) else {
+            return;
+        };
+        let notional = position.notional(exit.quantity);
+        let realized_pnl_pct = if notional > 0.0 { exit.pnl / notional * 100.0 } else { 0.0 };
+        let (exit_price, realized_pnl) = (exit.price, exit.pnl);
+
+        self.reduced_pnl += realized_pnl;
+        self.reduced_quantity += exit.quantity;
+        self.stats.record_trade(realized_pnl);
+        self.emit_event(RunnerEvent::PositionClosed {
+            runner_id: self.runner_id.clone(),
+            requested_price,
+            exit_price,
+            realized_pnl,
+            realized_pnl_pct,
+            reason: "Reduced".to_string(),
+            timestamp,
+        });
+    }
+
+    /// Emit a PositionClosed event for the position the state machine just closed
+    ///
+    /// Also records the closed trade in the runner's stats. P&L already
+    /// reported by partial reduces of the position isn't counted again, and
+    /// the percentage is of the entry value of the quantity left to close.
+    fn emit_position_closed(&mut self, requested_price: f64, reason: &str, timestamp: i64) {
+        let Some(position) = self.state_machine.last_closed_position() else {
+            return;
+        };
+        let (Some(exit_price), Some(realized_pnl)) = (position.exit_price(), position.realized_pnl()) else {
+            return;
+        };
+        let realized_pnl = realized_pnl - std::mem::take(&mut self.reduced_pnl);
+        let remaining = position.original_quantity() - std::mem::take(&mut self.reduced_quantity);
+        let notional = position.notional(remaining);
+        let realized_pnl_pct = if notional > 0.0 { realized_pnl / notional * 100.0 } else { 0.0 };
+
+        self.stats.record_trade(realized_pnl);
+        self.emit_event(RunnerEvent::PositionClosed {
+            runner_id: self.runner_id.clone(),
+            requested_price,
+            exit_price,
+            realized_pnl,
+            realized_pnl_pct,
+            reason: reason.to_string(),
+            timestamp,
+        });
+    }
+
+    /// Append a stats history row if one is due at bar `timestamp`
+    ///
+    /// Write failures are logged and don't stop the runner.
+    fn record_stats_history(&mut self, timestamp: i64) {
+        let Some(dir) = &self.config.stats_history_path else {
+            return;
+        };
+        let due = self
+            .last_stats_snapshot
+            .is_none_or(|last| timestamp - last >= self.config.stats_history_interval_ms);
+        if !due {
+            return;
+        }
+
+        let path = stats_history_file(dir, &self.runner_id);
+        if let Err(e) = append_stats_snapshot(&path, timestamp, &self.stats) {
+            tracing::warn!("Runner {}: Failed to write stats history to {}: {}", self.runner_id, path.display(), e);
+        }
+        self.last_stats_snapshot = Some(timestamp);
+    }
+
+    /// Set ATR-based stop and target on a new position, if configured
+    ///
+    /// Levels the position already has are kept.
+    fn place_atr_stops(&mut self) {
+        let Some(atr_stops) = self.config.atr_stops else {
+            return;
+        };
+        let Some(atr) = self.window.atr(atr_stops.period) else {
+            tracing::debug!("Symbol {}: Not enough bars for ATR({}) stops", self.symbol, atr_stops.period);
+            return;
+        };
+        let Some(position) = self.state_machine.position_mut() else {
+            return;
+        };
+
+        let (stop, target) = atr_stops.levels(position.side(), position.entry_price(), atr);
+        if position.stop_loss().is_none() {
+            position.set_stop_loss(stop);
+        }
+        if position.take_profit().is_none() {
+            position.set_take_profit(target);
+        }
+    }
+
+    /// Move the open position's stop to breakeven once `breakeven_trigger` is reached
+    ///
+    /// Breakeven is the entry plus the fees paid so far per unit, above the
+    /// entry for longs and below it for shorts.
+    fn move_stop_to_breakeven(&mut self, timestamp: i64) {
+        let Some(trigger) = self.config.breakeven_trigger else {
+            return;
+        };
+        let Some(position) = self.state_machine.position_mut() else {
+            return;
+        };
+        if !position.unrealized_pnl_pct().is_some_and(|pct| pct >= trigger) {
+            return;
+        }
+
+        let fees_per_unit = position.fees() / position.quantity();
+        let breakeven = position.entry_price() + position.side().sign() * fees_per_unit;
+        let past_breakeven = position.stop_loss().is_some_and(|stop| {
+            if position.side().is_long() { stop >= breakeven } else { stop <= breakeven }
+        });
+        if !past_breakeven {
+            tracing::info!(
+                "Symbol {}: Moving stop to breakeven at {:.2} (at {})",
+                self.symbol,
+                breakeven,
+                timestamp
+            );
+            position.set_stop_loss(breakeven);
+        }
+    }
+
+    /// Reason an entry action must not be executed, if any
+    ///
+    /// Checks the configured side restriction, trading hours, minimum time
+    /// between trades and the paper account balance. Returns `None` for
+    /// non-entry actions.
+    fn entry_block_reason(&self, action: &Action, timestamp: i64) -> Option<String> {
+        let side = action.entry_side()?;
+
+        if !self.config.in_trading_hours(timestamp) {
+            return Some("outside trading hours".to_string());
+        }
+
+        if !self.config.allowed_sides.allows(side) {
+            return Some(format!(
+                "{} entries not allowed ({:?})",
+                side, self.config.allowed_sides
+            ));
+        }
+
+        if let Some(remaining) = self.entry_spacing_remaining(timestamp) {
+            return Some(format!("{}ms left of minimum time between trades", remaining));
+        }
+
+        if let Some(reason) = self.order_size_block_reason(action) {
+            return Some(reason);
+        }
+
+        self.balance_block_reason(action)
+    }
+
+    /// Run an entry through lot rounding, position sizing and the entry checks
+    ///
+    /// Returns `None`, after a warning event, if the entry is suppressed.
+    /// Other actions pass through unchanged.
+    fn screen_entry(&self, action: Action, timestamp: i64) -> Option<Action> {
+        // Snap entry sizes to the venue's lot step
+        let action = self.round_entry_to_lot(action);
+
+        // Keep entries within the configured share of the account
+        let action = self.cap_position_size(action, timestamp)?;
+
+        // Suppress entries the configuration doesn't allow
+        if let Some(reason) = self.entry_block_reason(&action, timestamp) {
+            self.warn_entry_suppressed(&action, &reason, timestamp);
+            return None;
+        }
+        Some(action)
+    }
+
+    /// Round an entry's quantity to the configured lot step
+    fn round_entry_to_lot(&self, action: Action) -> Action {
+        let Some(quantity) = action.quantity() else {
+            return action;
+        };
+        let rounded = self.config.round_to_lot(quantity);
+        if rounded != quantity {
+            tracing::debug!("Symbol {}: Rounded entry quantity {} to {}", self.symbol, quantity, rounded);
+        }
+        action.with_quantity(rounded)
+    }
+
+    /// Clamp or reject an entry worth more than `max_position_pct` of the balance
+    ///
+    /// Returns `None` if the entry is rejected.
+    fn cap_position_size(&self, action: Action, timestamp: i64) -> Option<Action> {
+        let (Some(max_pct), Some(account)) = (self.config.max_position_pct, self.state_machine.account()) else {
+            return Some(action);
+        };
+        let (Some(price), Some(quantity)) = (action.price(), action.quantity()) else {
+            return Some(action);
+        };
+
+        let max_notional = account.balance * max_pct / 100.0;
+        let (notional, max_quantity) = match self.config.contract_type {
+            ContractType::Linear => (price * quantity, max_notional / price),
+            ContractType::Inverse => (quantity, max_notional),
+        };
+        if notional <= max_notional {
+            return Some(action);
+        }
+
+        let reason = format!(
+            "notional {:.2} exceeds {}% of balance ({:.2})",
+            notional, max_pct, max_notional
+        );
+        match self.config.oversize_policy {
+            OversizePolicy::Reject => {
+                self.warn_entry_suppressed(&action, &reason, timestamp);
+                None
+            }
+            OversizePolicy::Clamp => {
+                // Round down so the clamped entry stays within the cap
+                let clamped = match self.config.lot_step {
+                    Some(step) if step > 0.0 => (max_quantity / step).floor() * step,
+                    _ => max_quantity,
+                };
+                tracing::warn!("Symbol {}: Clamped entry quantity {} to {}: {}", self.symbol, quantity, clamped, reason);
+                self.emit_event(RunnerEvent::Error {
+                    runner_id: self.runner_id.clone(),
+                    error: format!("Entry clamped to quantity {}: {}", clamped, reason),
+                    severity: ErrorSeverity::Warning,
+                    timestamp,
+                });
+                Some(action.with_quantity(clamped))
+            }
+        }
+    }
+
+    /// Reason an entry is too small for the venue's order size rules, if any
+    fn order_size_block_reason(&self, action: &Action) -> Option<String> {
+        let quantity = action.quantity()?;
+        if quantity <= 0.0 {
+            return Some(format!("quantity rounds to zero at lot step {:?}", self.config.lot_step));
+        }
+
+        let min_notional = self.config.min_notional?;
+        let notional = match self.config.contract_type {
+            ContractType::Linear => action.price()? * quantity,
+            ContractType::Inverse => quantity,
+        };
+        if notional >= min_notional {
+            return None;
+        }
+        Some(format!("notional {:.2} below minimum {:.2}", notional, min_notional))
+    }
+
+    /// Reason the paper account can't fund an entry action, if any
+    fn balance_block_reason(&self, action: &Action) -> Option<String> {
+        let account = self.state_machine.account()?;
+        let margin = self.state_machine.required_margin(action)?;
+        if account.can_afford(margin) {
+            return None;
+        }
+        Some(format!(
+            "insufficient balance: needs {:.2}, {:.2} available",
+            margin,
+            account.available()
+        ))
+    }
+
+    /// Log and emit a warning for an entry that won't be executed
+    fn warn_entry_suppressed(&self, action: &Action, reason: &str, timestamp: i64) {
+        tracing::warn!("Symbol {}: Suppressed {:?}: {}", self.symbol, action, reason);
+        self.emit_event(RunnerEvent::Error {
+            runner_id: self.runner_id.clone(),
+            error: format!("Entry suppressed: {}", reason),
+            severity: ErrorSeverity::Warning,
+            timestamp,
+        });
+    }
+
+    /// Reason the configured spike filter rejects `market_data`, if it does
+    fn spike_rejection(&self, market_data: &MarketData) -> Option<String> {
+        let filter = self.config.spike_filter?;
+        let recent: Vec<f64> = self.spike_reference.iter().copied().collect();
+        filter.rejection(&recent, market_data.close)
+    }
+
+    /// Add a close to the spike filter's reference, keeping `lookback` of them
+    ///
+    /// Rejected closes count too, so after a real gap in price the reference
+    /// moves to the new level and bars are accepted again.
+    fn record_spike_reference(&mut self, close: f64) {
+        let Some(filter) = self.config.spike_filter else {
+            return;
+        };
+        self.spike_reference.push_back(close);
+        while self.spike_reference.len() > filter.lookback() {
+            self.spike_reference.pop_front();
+        }
+    }
+
+    /// Warn when a reduce-only reduce asks for more than the open quantity
+    fn warn_reduce_clamped(&self, quantity: f64, timestamp: i64) {
+        let Some(open) = self.state_machine.position().map(|position| position.quantity()) else {
+            return;
+        };
+        if !self.config.reduce_only || quantity <= open {
+            return;
+        }
+
+        self.emit_event(RunnerEvent::Error {
+            runner_id: self.runner_id.clone(),
+            error: format!(
+                "Reduce of {} exceeds open quantity {}, closing the position instead",
+                quantity, open
+            ),
+            severity: ErrorSeverity::Warning,
+            timestamp,
+        });
+    }
+
+    /// Time left before a new entry is allowed, if any
+    ///
+    /// Returns `None` when no minimum spacing is configured, no position has
+    /// been closed yet, or the interval has already elapsed.
+    fn entry_spacing_remaining(&self, timestamp: i64) -> Option<i64> {
+        let min_spacing = self.config.min_time_between_trades_ms?;
+        let last_exit = self.last_exit_timestamp?;
+        let remaining = min_spacing - (timestamp - last_exit);
+        if remaining > 0 {
+            Some(remaining)
+        } else {
+            None
+        }
+    }
+
+    /// Check whether `signal` repeats the last entry's within the re-entry guard
+    fn is_repeat_signal(&self, signal: Option<&str>, timestamp: i64) -> bool {
+        let Some(window) = self.config.reentry_guard_ms else {
+            return false;
+        };
+        let in_window = self.last_exit_timestamp.is_some_and(|exit| timestamp - exit < window);
+        in_window && signal.is_some() && signal == self.entry_signal.as_deref()
+    }
+
+    /// Handle Idle state - look for opportunities
+    fn handle_idle(
+        &mut self,
+        market_data: &MarketData,
+        indicator_api: &IndicatorApi,
+    ) -> Result<Option<Action>> {
+        // No new opportunities outside the trading session
+        if !self.config.in_trading_hours(market_data.timestamp) {
+            return Ok(None);
+        }
+
+        let opportunity = self.strategy.detect_opportunity(
+            market_data,
+            self.state_machine.context(),
+            indicator_api,
+        )?;
+
+        // Don't re-enter on the signal that was just traded
+        let signal = opportunity.as_ref().and_then(|opp| opp.get::<_, String>("signal").ok());
+        if self.is_repeat_signal(signal.as_deref(), market_data.timestamp) {
+            tracing::debug!("Symbol {}: Ignoring repeated signal {:?}", self.symbol, signal);
+            return Ok(None);
+        }
+        // The signal changed or the window elapsed
+        self.entry_signal = None;
+
+        if let Some(opp_table) = opportunity {
+            // An explicit pass, possibly with a reason
+            if opp_table.get::<_, Option<String>>("action")?.as_deref() == Some("no_action") {
+                return table_to_action(&opp_table);
+            }
+
+            // Update context with opportunity data
             // Extract common fields if they exist
             if let Ok(signal) = opp_table.get::<_, String>("signal") {
                 self.state_machine.context_mut().set("signal", signal);
@@ -594,10 +1674,11 @@ impl SymbolRunner {
         market_data: &MarketData,
         indicator_api: &IndicatorApi,
     ) -> Result<Option<Action>> {
-        self.strategy.manage_position(
+        self.strategy.manage_position_with(
             market_data,
             self.state_machine.context(),
             indicator_api,
+            self.state_machine.position(),
         )
     }
 
@@ -612,6 +1693,11 @@ impl SymbolRunner {
         self.state_machine.position()
     }
 
+    /// Get the paper account, if `starting_balance` is configured
+    pub fn account(&self) -> Option<&Account> {
+        self.state_machine.account()
+    }
+
     /// Get uptime
     pub fn uptime(&self) -> std::time::Duration {
         self.start_time.elapsed()
@@ -622,6 +1708,7 @@ impl SymbolRunner {
 mod tests {
     use super::*;
     use crate::market_data::MarketData;
+    use crate::strategy::test_support::strategy_from_source;
 
     fn create_test_data(close: f64) -> MarketData {
         MarketData {
@@ -634,6 +1721,9 @@ mod tests {
             volume: 1000,
             bid: close - 5.0,
             ask: close + 5.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         }
     }
 
@@ -682,11 +1772,11 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_runner_events() {
-        let (data_tx, data_rx) = mpsc::unbounded_channel();
-        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+    async fn test_cancellation_stops_run() {
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
         let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
             .expect("Failed to load test strategy");
+        let token = CancellationToken::new();
 
         let mut runner = SymbolRunner::new(
             "test_runner".to_string(),
@@ -695,18 +1785,1344 @@ mod tests {
             data_rx,
             50
         )
-        .with_event_channel(event_tx);
+        .with_cancellation(token.clone());
 
-        // Send tick
-        data_tx.send(create_test_data(50000.0)).unwrap();
+        // The data channel stays open, so only the token can end the loop
+        let handle = tokio::spawn(async move { runner.run().await });
+        token.cancel();
 
-        // Process tick
-        let data = runner.data_receiver.recv().await.unwrap();
-        runner.process_tick(data).await.unwrap();
+        let result = tokio::time::timeout(std::time::Duration::from_secs(1), handle)
+            .await
+            .expect("Runner did not stop after cancellation")
+            .unwrap();
+        assert!(result.is_ok());
+    }
 
-        // Should have received TickReceived event
-        let event = event_rx.try_recv().unwrap();
-        assert_eq!(event.runner_id(), "test_runner");
-        assert!(matches!(event, RunnerEvent::TickReceived { .. }));
+    #[tokio::test]
+    async fn test_max_ticks_stops_runner() {
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+
+        let mut runner = SymbolRunner::new(
+            "test_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(RunnerConfig::quiet().with_max_ticks(5));
+
+        for i in 0..4 {
+            assert!(runner.step(create_test_data(50000.0 + i as f64)).await.unwrap());
+        }
+        assert!(!runner.step(create_test_data(50004.0)).await.unwrap());
+        assert!(runner.status.is_stopped());
+        assert_eq!(runner.status(), RunnerStatus::Finished);
+        assert_eq!(runner.stop_reason(), Some("max_ticks reached"));
+
+        // Later data is ignored
+        assert!(!runner.step(create_test_data(50005.0)).await.unwrap());
+        assert_eq!(runner.stats().ticks_processed, 5);
+    }
+
+    #[tokio::test]
+    async fn test_update_config_keeps_state() {
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+
+        let mut runner = SymbolRunner::new(
+            "test_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(RunnerConfig::quiet().with_starting_balance(10_000.0));
+
+        for i in 0..3 {
+            runner.step(create_test_data(50000.0 + i as f64)).await.unwrap();
+        }
+        assert!(!runner.config.log_actions);
+
+        let config = RunnerConfig { log_actions: true, ..runner.config.clone() };
+        let (response_tx, response_rx) = oneshot::channel();
+        runner.handle_command(RunnerCommand::UpdateConfig { config: Box::new(config), response: response_tx });
+        assert!(response_rx.await.unwrap());
+        assert!(runner.config.log_actions);
+        assert_eq!(runner.stats().ticks_processed, 3);
+        assert_eq!(runner.window.len(), 3);
+
+        // Fixed fields keep their values
+        let config = RunnerConfig::quiet().with_starting_balance(1.0);
+        let (response_tx, response_rx) = oneshot::channel();
+        runner.handle_command(RunnerCommand::UpdateConfig { config: Box::new(config), response: response_tx });
+        assert!(!response_rx.await.unwrap());
+        assert_eq!(runner.config.starting_balance, Some(10_000.0));
+        assert!(!runner.config.log_actions);
+
+        runner.step(create_test_data(50003.0)).await.unwrap();
+        assert_eq!(runner.stats().ticks_processed, 4);
+    }
+
+    #[tokio::test]
+    async fn test_final_bars_only_skips_forming_bar() {
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+
+        let mut runner = SymbolRunner::new(
+            "test_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(RunnerConfig::quiet().with_final_bars_only());
+
+        let forming = MarketData { is_final: false, ..create_test_data(50000.0) };
+        assert!(runner.step(forming).await.unwrap());
+        assert_eq!(runner.stats().ticks_processed, 0);
+
+        assert!(runner.step(create_test_data(50001.0)).await.unwrap());
+        assert_eq!(runner.stats().ticks_processed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_status_starting_until_first_tick() {
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+
+        let mut runner = SymbolRunner::new(
+            "test_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(RunnerConfig::quiet());
+
+        assert_eq!(runner.status(), RunnerStatus::Starting);
+        assert!(runner.status().is_active());
+        assert_eq!(runner.create_snapshot().status, RunnerStatus::Starting);
+
+        assert!(runner.step(create_test_data(50000.0)).await.unwrap());
+        assert_eq!(runner.status(), RunnerStatus::Running);
+    }
+
+    #[tokio::test]
+    async fn test_error_exit_sets_error_status() {
+        let strategy = strategy_from_source(
+            "error",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    error("strategy bug")
+end
+function filter_commitment(market_data, context, indicators)
+    return nil
+end
+function manage_position(market_data, context, indicators)
+    return nil
+end
+"#,
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let mut runner = SymbolRunner::new(
+            "test_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(RunnerConfig { stop_on_error: true, ..RunnerConfig::quiet() });
+
+        assert!(runner.step(create_test_data(50000.0)).await.is_err());
+        assert_eq!(runner.status(), RunnerStatus::Error);
+        assert!(runner.status().is_stopped());
+    }
+
+    #[tokio::test]
+    async fn test_retry_once_recovers_transient_error() {
+        let runner_for = |detect: &str, policy: ErrorPolicy| {
+            let strategy = strategy_from_source(
+                "retry",
+                &format!(
+                    r#"
+calls = 0
+function detect_opportunity(market_data, context, indicators)
+    calls = calls + 1
+{}
+end
+function filter_commitment(market_data, context, indicators)
+    return nil
+end
+function manage_position(market_data, context, indicators)
+    return nil
+end
+"#,
+                    detect
+                ),
+            );
+
+            let (_data_tx, data_rx) = mpsc::unbounded_channel();
+            SymbolRunner::new("retry_runner".to_string(), "BTCUSDT".to_string(), strategy, data_rx, 50)
+                .with_config(RunnerConfig::quiet().with_error_policy(policy))
+        };
+        let flaky = r#"    if calls == 1 then error("flaky") end
+    return { signal = "bullish" }"#;
+
+        // Skipping loses the tick
+        let mut runner = runner_for(flaky, ErrorPolicy::SkipTick);
+        assert!(runner.step(create_test_data(50000.0)).await.unwrap());
+        assert_eq!(runner.stats().errors, 1);
+        assert_eq!(*runner.state_machine.current_state(), State::Idle);
+
+        // The retry processes it
+        let mut runner = runner_for(flaky, ErrorPolicy::RetryOnce);
+        assert!(runner.step(create_test_data(50000.0)).await.unwrap());
+        assert_eq!(runner.stats().errors, 0);
+        assert_eq!(runner.stats().ticks_processed, 1);
+        assert_eq!(*runner.state_machine.current_state(), State::Analyzing);
+
+        // An error that persists through the retry stops the runner
+        let mut runner = runner_for(r#"    error("broken")"#, ErrorPolicy::RetryOnce);
+        assert!(runner.step(create_test_data(50000.0)).await.is_err());
+        assert_eq!(runner.status(), RunnerStatus::Error);
+
+        // A malformed return value is fatal and isn't retried
+        let mut runner = runner_for(r#"    if calls == 1 then return 42 end
+    return { signal = "bullish" }"#, ErrorPolicy::RetryOnce);
+        assert!(runner.step(create_test_data(50000.0)).await.is_err());
+        assert_eq!(runner.status(), RunnerStatus::Error);
+    }
+
+    #[tokio::test]
+    async fn test_retry_once_does_not_retry_invalid_reduce() {
+        // Enters, then sends an invalid reduce on the first manage call only
+        let strategy = strategy_from_source(
+            "retry_reduce",
+            r#"
+calls = 0
+function detect_opportunity(market_data, context, indicators)
+    return { signal = "bullish" }
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 1.0 }
+end
+function manage_position(market_data, context, indicators)
+    calls = calls + 1
+    if calls == 1 then
+        return { action = "reduce", price = market_data.close, quantity = -1.0 }
+    end
+    return nil
+end
+"#,
+        );
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let mut runner = SymbolRunner::new("retry_runner".to_string(), "BTCUSDT".to_string(), strategy, data_rx, 50)
+            .with_config(RunnerConfig::quiet().with_error_policy(ErrorPolicy::RetryOnce));
+
+        assert!(runner.step(create_test_data(50000.0)).await.unwrap());
+        assert!(runner.step(create_test_data(50000.0)).await.unwrap());
+        assert!(runner.position().is_some());
+
+        // A validation error fails the same way again, so it isn't retried
+        assert!(runner.step(create_test_data(50000.0)).await.is_err());
+        assert_eq!(runner.status(), RunnerStatus::Error);
+    }
+
+    #[tokio::test]
+    async fn test_spike_filter_rejects_outlier_bar() {
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+
+        let mut runner = SymbolRunner::new(
+            "test_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(RunnerConfig::quiet().with_spike_filter(SpikeFilter::StdDev { lookback: 10, threshold: 4.0 }))
+        .with_event_channel(event_tx);
+
+        // Stable series with a little noise
+        for i in 0..20 {
+            let close = 50000.0 + [-30.0, 10.0, 40.0, -20.0][i % 4];
+            assert!(runner.step(create_test_data(close)).await.unwrap());
+        }
+        assert_eq!(runner.stats().ticks_processed, 20);
+
+        // A 50% spike is dropped before reaching the window
+        assert!(runner.step(create_test_data(75000.0)).await.unwrap());
+        assert_eq!(runner.stats().ticks_processed, 20);
+        assert_eq!(runner.window.latest().unwrap().close, 49980.0);
+
+        // Normal bars keep flowing
+        assert!(runner.step(create_test_data(50025.0)).await.unwrap());
+        assert_eq!(runner.stats().ticks_processed, 21);
+
+        let mut events = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            events.push(event);
+        }
+        assert_eq!(warning_count(&events), 1);
+    }
+
+    #[tokio::test]
+    async fn test_spike_filter_accepts_sustained_gap() {
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+
+        let mut runner = SymbolRunner::new(
+            "test_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(RunnerConfig::quiet().with_spike_filter(SpikeFilter::Percent { lookback: 4, percent: 10.0 }));
+
+        for _ in 0..10 {
+            runner.step(create_test_data(50000.0)).await.unwrap();
+        }
+
+        // Price gaps to 75,000 and stays there
+        let mut processed = Vec::new();
+        for _ in 0..6 {
+            runner.step(create_test_data(75000.0)).await.unwrap();
+            processed.push(runner.stats().ticks_processed);
+        }
+
+        // Three bars rejected while the mean catches up, then the new level is accepted
+        assert_eq!(processed, vec![10, 10, 10, 11, 12, 13]);
+        assert_eq!(runner.window.latest().unwrap().close, 75000.0);
+    }
+
+    #[tokio::test]
+    async fn test_no_action_reason_recorded() {
+        let strategy = strategy_from_source(
+            "pass_reason",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    if market_data.close > 50001 then
+        return { action = "no_action", reason = "spread too wide" }
+    end
+    return { action = "no_action" }
+end
+function filter_commitment(market_data, context, indicators) return nil end
+function manage_position(market_data, context, indicators) return nil end
+"#,
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let mut runner = SymbolRunner::new(
+            "test_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(RunnerConfig::quiet().with_no_action_events())
+        .with_event_channel(event_tx);
+
+        // A bare pass leaves no reason behind
+        runner.step(create_test_data(50000.0)).await.unwrap();
+        assert_eq!(runner.stats().last_no_action_reason, None);
+
+        runner.step(create_test_data(50002.0)).await.unwrap();
+        assert_eq!(runner.stats().no_action_ticks, 2);
+        assert_eq!(runner.stats().actions_executed, 0);
+        assert_eq!(runner.state(), State::Idle);
+        assert_eq!(runner.stats().last_no_action_reason.as_deref(), Some("spread too wide"));
+        assert_eq!(
+            runner.create_snapshot().stats.last_no_action_reason.as_deref(),
+            Some("spread too wide")
+        );
+
+        let mut passes = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            if let RunnerEvent::StrategyPassed { state, reason, .. } = event {
+                passes.push((state, reason));
+            }
+        }
+        assert_eq!(passes, vec![(State::Idle, "spread too wide".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_no_action_ticks_counted() {
+        let strategy = strategy_from_source(
+            "no_action",
+            r#"
+function detect_opportunity(market_data, context, indicators) return nil end
+function filter_commitment(market_data, context, indicators) return nil end
+function manage_position(market_data, context, indicators) return nil end
+"#,
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let mut runner = SymbolRunner::new(
+            "test_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(RunnerConfig::quiet().with_log_no_action());
+
+        for i in 0..5 {
+            assert!(runner.step(create_test_data(50000.0 + i as f64)).await.unwrap());
+        }
+
+        assert_eq!(runner.stats().ticks_processed, 5);
+        assert_eq!(runner.stats().no_action_ticks, 5);
+        assert_eq!(runner.stats().actions_executed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_runner_events() {
+        let (data_tx, data_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+            .expect("Failed to load test strategy");
+
+        let mut runner = SymbolRunner::new(
+            "test_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_event_channel(event_tx);
+
+        // Send tick
+        data_tx.send(create_test_data(50000.0)).unwrap();
+
+        // Process tick
+        let data = runner.data_receiver.recv().await.unwrap();
+        runner.process_tick(data).await.unwrap();
+
+        // Should have received TickReceived event
+        let event = event_rx.try_recv().unwrap();
+        assert_eq!(event.runner_id(), "test_runner");
+        assert!(matches!(event, RunnerEvent::TickReceived { .. }));
+    }
+
+    /// Run a strategy that enters with `entry_action` and exits as often as
+    /// it can over `bars` one-second bars, returning every event emitted.
+    async fn run_churn_strategy(entry_action: &str, config: RunnerConfig, bars: i64) -> Vec<RunnerEvent> {
+        let timestamps: Vec<i64> = (0..bars).map(|i| i * 1000).collect();
+        run_churn_strategy_at(entry_action, config, &timestamps).await
+    }
+
+    /// Same as `run_churn_strategy`, with one bar at each of `timestamps`
+    async fn run_churn_strategy_at(entry_action: &str, config: RunnerConfig, timestamps: &[i64]) -> Vec<RunnerEvent> {
+        let strategy = strategy_from_source(
+            "churn",
+            &format!(
+                r#"
+function detect_opportunity(market_data, context, indicators)
+    return {{ signal = "bullish" }}
+end
+function filter_commitment(market_data, context, indicators)
+    return {{ action = "{}", price = market_data.close, quantity = 0.1 }}
+end
+function manage_position(market_data, context, indicators)
+    return {{ action = "exit", price = market_data.close }}
+end
+"#,
+                entry_action
+            ),
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let mut runner = SymbolRunner::new(
+            "churn_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(config)
+        .with_event_channel(event_tx);
+
+        for &timestamp in timestamps {
+            let mut data = create_test_data(50000.0);
+            data.timestamp = timestamp;
+            runner.process_tick(data).await.unwrap();
+        }
+
+        let mut events = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            events.push(event);
+        }
+        events
+    }
+
+    /// Bar timestamps of each position opened
+    fn entry_timestamps(events: &[RunnerEvent]) -> Vec<i64> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                RunnerEvent::PositionOpened { timestamp, .. } => Some(*timestamp),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Number of warning events emitted
+    fn warning_count(events: &[RunnerEvent]) -> usize {
+        events
+            .iter()
+            .filter(|event| matches!(event, RunnerEvent::Error { severity: ErrorSeverity::Warning, .. }))
+            .count()
+    }
+
+    #[tokio::test]
+    async fn test_entry_below_min_notional_suppressed() {
+        // 0.1 at 50,000 is worth 5,000
+        let config = RunnerConfig::quiet().with_min_notional(10_000.0);
+        let events = run_churn_strategy("enter_long", config, 3).await;
+
+        // Entry attempted on bars 1 and 2, never opened
+        assert!(entry_timestamps(&events).is_empty());
+        assert_eq!(warning_count(&events), 2);
+    }
+
+    #[tokio::test]
+    async fn test_entry_quantity_rounded_to_lot_step() {
+        let config = RunnerConfig::quiet().with_lot_step(0.03).with_min_notional(1_000.0);
+        let events = run_churn_strategy("enter_long", config, 2).await;
+
+        let quantities: Vec<f64> = events
+            .iter()
+            .filter_map(|event| match event {
+                RunnerEvent::PositionOpened { position, .. } => Some(position.quantity()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(quantities.len(), 1);
+        // 0.1 rounds to 3 steps of 0.03
+        assert!((quantities[0] - 0.09).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_entry_clamped_to_max_position() {
+        // 0.1 at 50,000 is worth 5,000; 20% of 10,000 allows 2,000
+        let config = RunnerConfig::quiet()
+            .with_starting_balance(10_000.0)
+            .with_max_position_pct(20.0, OversizePolicy::Clamp);
+        let events = run_churn_strategy("enter_long", config, 2).await;
+
+        let quantities: Vec<f64> = events
+            .iter()
+            .filter_map(|event| match event {
+                RunnerEvent::PositionOpened { position, .. } => Some(position.quantity()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(quantities.len(), 1);
+        assert!((quantities[0] * 50_000.0 - 2_000.0).abs() < 1e-9);
+        assert_eq!(warning_count(&events), 1);
+
+        // Rejected outright under the reject policy
+        let config = RunnerConfig::quiet()
+            .with_starting_balance(10_000.0)
+            .with_max_position_pct(20.0, OversizePolicy::Reject);
+        let events = run_churn_strategy("enter_long", config, 2).await;
+        assert!(entry_timestamps(&events).is_empty());
+        assert_eq!(warning_count(&events), 1);
+    }
+
+    /// Stop loss set on a long entry after five bars spanning `bar_range`
+    async fn atr_stop_after_bars(bar_range: f64) -> f64 {
+        // Enters long once five bars are in, then holds
+        let strategy = strategy_from_source(
+            "atr",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    if indicators.bar_count() >= 5 then
+        return { signal = "bullish" }
+    end
+    return nil
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 0.1 }
+end
+function manage_position(market_data, context, indicators)
+    return nil
+end
+"#,
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let atr_stops = AtrStopConfig { period: 3, stop_mult: 2.0, target_mult: 3.0 };
+        let mut runner = SymbolRunner::new(
+            "atr_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(RunnerConfig::quiet().with_atr_stops(atr_stops));
+
+        for i in 0..6 {
+            let mut data = create_test_data(50000.0);
+            data.timestamp = 1000 * i;
+            data.high = 50000.0 + bar_range / 2.0;
+            data.low = 50000.0 - bar_range / 2.0;
+            runner.process_tick(data).await.unwrap();
+        }
+
+        let position = runner.position().expect("Entry should have opened a position");
+        let atr = runner.window.atr(3).unwrap();
+        assert!((atr - bar_range).abs() < 1e-9);
+        assert_eq!(position.stop_loss(), Some(position.entry_price() - 2.0 * atr));
+        assert_eq!(position.take_profit(), Some(position.entry_price() + 3.0 * atr));
+        position.entry_price() - position.stop_loss().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_atr_stops_scale_with_volatility() {
+        let calm = atr_stop_after_bars(20.0).await;
+        let volatile = atr_stop_after_bars(400.0).await;
+
+        assert_eq!(calm, 40.0);
+        assert_eq!(volatile, 800.0);
+    }
+
+    #[tokio::test]
+    async fn test_breakeven_stop_after_trigger() {
+        let strategy = strategy_from_source(
+            "breakeven",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    return { signal = "bullish" }
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 0.1 }
+end
+function manage_position(market_data, context, indicators)
+    return nil
+end
+"#,
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let mut runner = SymbolRunner::new(
+            "breakeven_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(RunnerConfig::quiet().with_breakeven_trigger(1.0))
+        .with_event_channel(event_tx);
+
+        // Analyze, enter at 50,000, then +0.5% (below the trigger)
+        for (i, close) in [50000.0, 50000.0, 50250.0].into_iter().enumerate() {
+            let mut data = create_test_data(close);
+            data.timestamp = i as i64 * 1000;
+            runner.process_tick(data).await.unwrap();
+        }
+        assert_eq!(runner.position().unwrap().stop_loss(), None);
+
+        // +1.2% moves the stop to the entry
+        let mut data = create_test_data(50600.0);
+        data.timestamp = 3000;
+        runner.process_tick(data).await.unwrap();
+        assert_eq!(runner.position().unwrap().stop_loss(), Some(50000.0));
+
+        // Back to the entry: stopped out flat
+        let mut data = create_test_data(50000.0);
+        data.timestamp = 4000;
+        runner.process_tick(data).await.unwrap();
+        assert!(runner.position().is_none());
+
+        let mut events = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            events.push(event);
+        }
+        assert!(events.iter().any(|event| matches!(
+            event,
+            RunnerEvent::PositionClosed { realized_pnl, reason, timestamp: 4000, .. }
+                if *realized_pnl == 0.0 && reason == "Stop loss hit"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_breakeven_stop_covers_entry_fees() {
+        let strategy = strategy_from_source(
+            "breakeven_fee",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    return { signal = "bullish" }
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 0.1 }
+end
+function manage_position(market_data, context, indicators)
+    return nil
+end
+"#,
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let config = RunnerConfig::quiet()
+            .with_breakeven_trigger(1.0)
+            .with_commission(crate::state_machine::CommissionSchedule::flat(0.0, 5.0));
+        let mut runner = SymbolRunner::new(
+            "breakeven_fee_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(config);
+
+        // Analyze, enter at 50,000 paying 2.5 on 0.1, then +1.2%
+        for (i, close) in [50000.0, 50000.0, 50600.0].into_iter().enumerate() {
+            let mut data = create_test_data(close);
+            data.timestamp = i as i64 * 1000;
+            runner.process_tick(data).await.unwrap();
+        }
+
+        // The stop sits 25 per unit above the entry to cover the entry fee
+        let stop = runner.position().unwrap().stop_loss().unwrap();
+        assert!((stop - 50025.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_atr_stop_levels_by_side() {
+        let atr_stops = AtrStopConfig { period: 14, stop_mult: 1.5, target_mult: 3.0 };
+        assert_eq!(atr_stops.levels(crate::state_machine::Side::Long, 100.0, 2.0), (97.0, 106.0));
+        assert_eq!(atr_stops.levels(crate::state_machine::Side::Short, 100.0, 2.0), (103.0, 94.0));
+    }
+
+    #[tokio::test]
+    async fn test_reentry_guard_waits_for_signal_change() {
+        // Bullish at or above 50000, no opportunity below
+        let strategy = strategy_from_source(
+            "reentry",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    if market_data.close >= 50000 then
+        return { signal = "bullish" }
+    end
+    return nil
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 0.1 }
+end
+function manage_position(market_data, context, indicators)
+    return { action = "exit", price = market_data.close }
+end
+"#,
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let mut runner = SymbolRunner::new(
+            "reentry_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(RunnerConfig::quiet().with_reentry_guard(60_000))
+        .with_event_channel(event_tx);
+
+        // Unchanging bullish signal, then a dip clears it, then bullish again
+        let closes = [50000.0, 50000.0, 50000.0, 50000.0, 50000.0, 50000.0, 49000.0, 50000.0, 50000.0];
+        for (i, close) in closes.into_iter().enumerate() {
+            let mut data = create_test_data(close);
+            data.timestamp = 1000 * i as i64;
+            runner.process_tick(data).await.unwrap();
+        }
+
+        let mut events = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            events.push(event);
+        }
+        assert_eq!(entry_timestamps(&events), vec![1000, 8000]);
+    }
+
+    #[tokio::test]
+    async fn test_min_time_between_trades() {
+        // Unthrottled: idle -> analyzing -> enter -> exit, repeating every 3 bars
+        let events = run_churn_strategy("enter_long", RunnerConfig::quiet(), 8).await;
+        assert_eq!(entry_timestamps(&events), vec![1000, 4000, 7000]);
+
+        // Exit at 2000 blocks entries until 5000, exit at 6000 until 9000
+        let config = RunnerConfig {
+            min_time_between_trades_ms: Some(3000),
+            ..RunnerConfig::quiet()
+        };
+        let events = run_churn_strategy("enter_long", config, 10).await;
+        assert_eq!(entry_timestamps(&events), vec![1000, 5000, 9000]);
+        assert_eq!(warning_count(&events), 2);
+    }
+
+    #[tokio::test]
+    async fn test_short_blocked_under_long_only() {
+        let config = RunnerConfig {
+            allowed_sides: SideRestriction::LongOnly,
+            ..RunnerConfig::quiet()
+        };
+        let events = run_churn_strategy("enter_short", config, 4).await;
+
+        // Entry attempted on bars 1..=3, never opened
+        assert!(entry_timestamps(&events).is_empty());
+        assert_eq!(warning_count(&events), 3);
+    }
+
+    #[tokio::test]
+    async fn test_entries_only_inside_trading_hours() {
+        use chrono::{NaiveTime, TimeZone, Utc};
+
+        // Half-hourly bars from 08:00 to 17:00 New York time (UTC-5 in January)
+        let first_bar = Utc.with_ymd_and_hms(2024, 1, 15, 13, 0, 0).unwrap().timestamp_millis();
+        let half_hour = 30 * 60 * 1000;
+        let timestamps: Vec<i64> = (0..19).map(|i| first_bar + i * half_hour).collect();
+
+        let session = |start: (u32, u32), end: (u32, u32)| {
+            RunnerConfig::quiet().with_trading_hours(
+                NaiveTime::from_hms_opt(start.0, start.1, 0).unwrap(),
+                NaiveTime::from_hms_opt(end.0, end.1, 0).unwrap(),
+                chrono_tz::America::New_York,
+            )
+        };
+        let config = session((9, 30), (16, 0));
+        let events = run_churn_strategy_at("enter_long", config.clone(), &timestamps).await;
+
+        // Opportunity spotted at 09:30, then enter/exit/detect every 90 minutes;
+        // entries from the 16:00 close onwards are suppressed
+        let entries = entry_timestamps(&events);
+        let at = |hour: i64, minute: i64| first_bar + ((hour - 8) * 60 + minute) * 60 * 1000;
+        assert_eq!(entries, vec![at(10, 0), at(11, 30), at(13, 0), at(14, 30)]);
+        assert!(entries.iter().all(|&ts| config.in_trading_hours(ts)));
+        assert_eq!(warning_count(&events), 3);
+
+        // Without a session the same bars trade from the first one
+        let events = run_churn_strategy_at("enter_long", RunnerConfig::quiet(), &timestamps).await;
+        assert_eq!(entry_timestamps(&events).first(), Some(&at(8, 30)));
+
+        // A session spanning midnight
+        let overnight = session((22, 0), (2, 0));
+        assert!(overnight.in_trading_hours(at(23, 0)));
+        assert!(!overnight.in_trading_hours(at(12, 0)));
+    }
+
+    #[tokio::test]
+    async fn test_short_allowed_under_both() {
+        let config = RunnerConfig {
+            allowed_sides: SideRestriction::Both,
+            ..RunnerConfig::quiet()
+        };
+        let events = run_churn_strategy("enter_short", config, 4).await;
+
+        assert_eq!(entry_timestamps(&events), vec![1000]);
+        assert_eq!(warning_count(&events), 0);
+    }
+
+    #[tokio::test]
+    async fn test_slippage_reported_in_position_events() {
+        let config = RunnerConfig {
+            slippage_bps: 10.0,
+            ..RunnerConfig::quiet()
+        };
+        let events = run_churn_strategy("enter_long", config, 3).await;
+
+        // Long entry buys 10bps above the strategy's price
+        let opened = events
+            .iter()
+            .find_map(|event| match event {
+                RunnerEvent::PositionOpened { requested_price, fill_price, .. } => {
+                    Some((*requested_price, *fill_price))
+                }
+                _ => None,
+            })
+            .expect("position opened");
+        assert_eq!(opened, (50000.0, 50050.0));
+
+        // Exit sells 10bps below, and P&L reflects both fills
+        let closed = events
+            .iter()
+            .find_map(|event| match event {
+                RunnerEvent::PositionClosed { requested_price, exit_price, realized_pnl, .. } => {
+                    Some((*requested_price, *exit_price, *realized_pnl))
+                }
+                _ => None,
+            })
+            .expect("position closed");
+        assert_eq!((closed.0, closed.1), (50000.0, 49950.0));
+        assert!((closed.2 + 10.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_entry_beyond_balance_suppressed() {
+        // Each entry is 0.1 BTC at 50,000: 5,000 of margin
+        let config = RunnerConfig::quiet().with_starting_balance(1_000.0);
+        let events = run_churn_strategy("enter_long", config, 3).await;
+        assert!(entry_timestamps(&events).is_empty());
+        assert!(events.iter().any(|event| matches!(
+            event,
+            RunnerEvent::Error { error, severity: ErrorSeverity::Warning, .. }
+                if error.contains("insufficient balance")
+        )));
+
+        let config = RunnerConfig::quiet().with_starting_balance(10_000.0);
+        let events = run_churn_strategy("enter_long", config, 3).await;
+        assert_eq!(entry_timestamps(&events).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stats_history_rows_per_interval() {
+        let dir = std::env::temp_dir().join(format!("runner_stats_history_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        // Ticks every 500ms for 5s, one row per second of bar time
+        let config = RunnerConfig::quiet().with_stats_history(&dir, 1000);
+        let timestamps: Vec<i64> = (0..=10).map(|i| i * 500).collect();
+        run_churn_strategy_at("enter_long", config, &timestamps).await;
+
+        let contents = std::fs::read_to_string(stats_history_file(&dir, "churn_runner")).unwrap();
+        let rows: Vec<&str> = contents.lines().skip(1).collect();
+        assert_eq!(rows.len(), 6);
+        assert!(rows[0].starts_with("0,1,"));
+        assert!(rows[5].starts_with("5000,11,"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bars_held_counts_ticks_in_position() {
+        let strategy = strategy_from_source(
+            "bars_held",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    return { signal = "bullish" }
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 0.1 }
+end
+function manage_position(market_data, context, indicators)
+    if context.bars_held >= 3 then
+        return { action = "exit", price = market_data.close }
+    end
+    return nil
+end
+"#,
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let mut runner = SymbolRunner::new("held_runner".to_string(), "BTCUSDT".to_string(), strategy, data_rx, 50)
+            .with_config(RunnerConfig::quiet());
+
+        // Analyze, enter, hold three bars and exit on the third, then start over
+        let mut held = Vec::new();
+        for i in 0..8 {
+            let mut data = create_test_data(50000.0);
+            data.timestamp = i * 1000;
+            runner.process_tick(data).await.unwrap();
+            held.push(runner.position().map(|p| p.bars_held()));
+        }
+        assert_eq!(held, vec![None, Some(0), Some(1), Some(2), None, None, Some(0), Some(1)]);
+        assert_eq!(runner.state_machine.context().get::<i64>("bars_held"), Some(&1));
+    }
+
+    #[tokio::test]
+    async fn test_indicator_ready_emitted_once() {
+        let strategy = strategy_from_source(
+            "warmup",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    indicators.rsi(3)
+    return nil
+end
+function filter_commitment(market_data, context, indicators)
+    return nil
+end
+function manage_position(market_data, context, indicators)
+    return nil
+end
+"#,
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let mut runner = SymbolRunner::new(
+            "warmup_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_event_channel(event_tx);
+
+        for i in 0..6 {
+            let mut data = create_test_data(50000.0 + i as f64);
+            data.timestamp = i * 1000;
+            runner.process_tick(data).await.unwrap();
+        }
+
+        let mut ready = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            if let RunnerEvent::IndicatorReady { kind, period, timestamp, .. } = event {
+                ready.push((kind, period, timestamp));
+            }
+        }
+
+        // RSI(3) needs 4 bars
+        assert_eq!(ready, vec![("rsi".to_string(), 3, 3000)]);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_indicator_warns_once() {
+        let strategy = strategy_from_source(
+            "oversized",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    indicators.ema(200)
+    return nil
+end
+function filter_commitment(market_data, context, indicators)
+    return nil
+end
+function manage_position(market_data, context, indicators)
+    return nil
+end
+"#,
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let mut runner = SymbolRunner::new(
+            "oversized_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_event_channel(event_tx);
+
+        for i in 0..5 {
+            runner.process_tick(create_test_data(50000.0 + i as f64)).await.unwrap();
+        }
+
+        let mut warnings = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            if let RunnerEvent::Error { error, severity: ErrorSeverity::Warning, .. } = event {
+                warnings.push(error);
+            }
+        }
+
+        assert_eq!(warnings, vec!["Indicator ema(200) needs more bars than the window size of 50"]);
+    }
+
+    #[tokio::test]
+    async fn test_reduces_report_trades_and_screen_reversals() {
+        // Enter 1.0 long, reduce 0.4, then reduce 1.0 against the 0.6 left
+        let strategy = strategy_from_source(
+            "reduce",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    return { signal = "bullish" }
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 1.0 }
+end
+function manage_position(market_data, context, indicators, position)
+    local quantity = position.quantity > 0.9 and 0.4 or 1.0
+    return { action = "reduce", price = market_data.close, quantity = quantity }
+end
+"#,
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let config = RunnerConfig {
+            allowed_sides: SideRestriction::LongOnly,
+            ..RunnerConfig::quiet().with_reversals()
+        };
+        let mut runner = SymbolRunner::new(
+            "reduce_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(config)
+        .with_event_channel(event_tx);
+
+        // Analyze, enter at 50,000, reduce at 51,000, then at 52,000
+        for (i, close) in [50000.0, 50000.0, 51000.0, 52000.0].into_iter().enumerate() {
+            let mut data = create_test_data(close);
+            data.timestamp = i as i64 * 1000;
+            runner.process_tick(data).await.unwrap();
+        }
+
+        let mut events = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            events.push(event);
+        }
+        let closes: Vec<(String, f64, f64)> = events
+            .iter()
+            .filter_map(|event| match event {
+                RunnerEvent::PositionClosed { reason, realized_pnl, realized_pnl_pct, .. } => {
+                    Some((reason.clone(), *realized_pnl, *realized_pnl_pct))
+                }
+                _ => None,
+            })
+            .collect();
+        // Each percentage is of the quantity that event closed: 0.4, then 0.6
+        assert_eq!(
+            closes,
+            vec![("Reduced".to_string(), 400.0, 2.0), ("Strategy exit".to_string(), 1200.0, 4.0)]
+        );
+        assert_eq!(runner.stats().trades, 2);
+        assert_eq!(runner.stats().gross_profit, 1600.0);
+
+        // The 0.4 short reversal is an entry, so long-only blocks it
+        assert!(runner.position().is_none());
+        assert_eq!(entry_timestamps(&events), vec![1000]);
+        assert_eq!(warning_count(&events), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reversal_reported_as_action() {
+        // Enter 1.0 long, then reduce 1.5 to flip to 0.5 short
+        let strategy = strategy_from_source(
+            "reversal",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    return { signal = "bullish" }
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 1.0 }
+end
+function manage_position(market_data, context, indicators)
+    return { action = "reduce", price = market_data.close, quantity = 1.5 }
+end
+"#,
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let mut runner = SymbolRunner::new(
+            "reversal_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(RunnerConfig::quiet().with_reversals())
+        .with_event_channel(event_tx);
+
+        for (i, close) in [50000.0, 50000.0, 51000.0].into_iter().enumerate() {
+            let mut data = create_test_data(close);
+            data.timestamp = i as i64 * 1000;
+            runner.process_tick(data).await.unwrap();
+        }
+
+        let mut events = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            events.push(event);
+        }
+        let actions: Vec<Action> = events
+            .iter()
+            .filter_map(|event| match event {
+                RunnerEvent::ActionExecuted { action, .. } if !action.is_no_action() => Some(action.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                Action::StartAnalyzing { reason: "Strategy detected opportunity".to_string() },
+                Action::EnterLong { price: 50000.0, quantity: 1.0 },
+                Action::ReducePosition { price: 51000.0, quantity: 1.0 },
+                Action::EnterShort { price: 51000.0, quantity: 0.5 },
+            ]
+        );
+        assert_eq!(runner.stats().actions_executed, 4);
+        assert_eq!(entry_timestamps(&events), vec![1000, 2000]);
+        assert!(runner.position().is_some_and(|position| position.side() == Side::Short));
+    }
+
+    #[tokio::test]
+    async fn test_strategy_only_tightens_stop() {
+        // Trails the stop 500 below the close, never moving it down
+        let strategy = strategy_from_source(
+            "trail",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    return { signal = "bullish" }
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 0.1 }
+end
+function manage_position(market_data, context, indicators, position)
+    local new_stop = market_data.close - 500.0
+    if position.stop_loss == nil or new_stop > position.stop_loss then
+        return { action = "update_stop_loss", new_stop = new_stop }
+    end
+    return nil
+end
+"#,
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let mut runner = SymbolRunner::new(
+            "trail_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(RunnerConfig::quiet());
+
+        // Analyze, then enter at 50,000 without a stop
+        let mut stops = Vec::new();
+        for (i, close) in [50000.0, 50000.0, 51000.0, 50800.0, 51200.0].into_iter().enumerate() {
+            let mut data = create_test_data(close);
+            data.timestamp = i as i64 * 1000;
+            runner.process_tick(data).await.unwrap();
+            stops.push(runner.position().and_then(|position| position.stop_loss()));
+        }
+
+        // Set at 51,000, kept through the dip, raised again at 51,200
+        assert_eq!(
+            stops,
+            vec![None, None, Some(50500.0), Some(50500.0), Some(50700.0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_water_marks_track_extremes_and_reset() {
+        // Chandelier exit 1,500 below the highest high since entry
+        let strategy = strategy_from_source(
+            "chandelier",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    return { signal = "bullish" }
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 0.1 }
+end
+function manage_position(market_data, context, indicators, position)
+    if market_data.close < position.highest_since_entry - 1500.0 then
+        return { action = "exit", price = market_data.close }
+    end
+    return nil
+end
+"#,
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let mut runner = SymbolRunner::new(
+            "chandelier_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(RunnerConfig::quiet());
+
+        // Bars span close - 20 to close + 10
+        let mut marks = Vec::new();
+        for (i, close) in [50000.0, 50000.0, 51000.0, 52000.0, 50800.0, 50400.0, 50400.0, 50400.0, 49000.0]
+            .into_iter()
+            .enumerate()
+        {
+            let mut data = create_test_data(close);
+            data.timestamp = i as i64 * 1000;
+            runner.process_tick(data).await.unwrap();
+            marks.push(runner.position().map(|position| {
+                (position.highest_since_entry(), position.lowest_since_entry())
+            }));
+        }
+
+        assert_eq!(
+            marks,
+            vec![
+                None,
+                // Entered at 50,000
+                Some((50000.0, 50000.0)),
+                Some((51010.0, 50000.0)),
+                Some((52010.0, 50000.0)),
+                Some((52010.0, 50000.0)),
+                // 50,400 is more than 1,500 below the 52,010 high
+                None,
+                None,
+                // Re-entered at 50,400 with fresh water-marks
+                Some((50400.0, 50400.0)),
+                Some((50400.0, 48980.0)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stop_hit_without_auto_exit_keeps_position() {
+        let strategy = strategy_from_source(
+            "stop",
+            r#"
+function detect_opportunity(market_data, context, indicators)
+    return { signal = "bullish" }
+end
+function filter_commitment(market_data, context, indicators)
+    return { action = "enter_long", price = market_data.close, quantity = 0.1 }
+end
+function manage_position(market_data, context, indicators)
+    return { action = "update_stop_loss", new_stop = 49000.0 }
+end
+"#,
+        );
+
+        let (_data_tx, data_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let config = RunnerConfig {
+            auto_exit: false,
+            ..RunnerConfig::quiet()
+        };
+        let mut runner = SymbolRunner::new(
+            "stop_runner".to_string(),
+            "BTCUSDT".to_string(),
+            strategy,
+            data_rx,
+            50
+        )
+        .with_config(config)
+        .with_event_channel(event_tx);
+
+        // Analyze, enter, set the stop, then trade through it
+        for (i, close) in [50000.0, 50000.0, 50000.0, 48000.0].into_iter().enumerate() {
+            let mut data = create_test_data(close);
+            data.timestamp = i as i64 * 1000;
+            runner.process_tick(data).await.unwrap();
+        }
+
+        let mut events = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            events.push(event);
+        }
+
+        assert!(events.iter().any(|event| matches!(
+            event,
+            RunnerEvent::StopLossTriggered { price, stop_loss, timestamp: 3000, .. }
+                if *price == 48000.0 && *stop_loss == 49000.0
+        )));
+        assert!(!events.iter().any(|event| matches!(event, RunnerEvent::PositionClosed { .. })));
+        assert!(runner.position().is_some());
+        assert_eq!(runner.state(), State::InPosition);
     }
 }
@@ -3,9 +3,10 @@
 //! This module provides types for querying runner state on-demand via a command channel.
 //! Complements the event system (push) with pull-based state queries.
 
+use crate::events::{ErrorSeverity, RunnerEvent};
 use crate::market_data::MarketData;
 use crate::state_machine::{Position, State};
-use crate::runner::RunnerStats;
+use crate::runner::{RunnerConfig, RunnerStats};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
@@ -15,18 +16,26 @@ use tokio::sync::oneshot;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum RunnerStatus {
+    /// Runner was added and is waiting for its first tick
+    Starting,
     /// Runner is actively processing ticks
     Running,
+    /// No tick arrived within `max_idle_ms`; returns to running on the next tick
+    Stale,
     /// Runner is paused (not processing ticks, preserving state)
     Paused,
     /// Runner has been stopped and cannot be resumed
     Stopped,
+    /// Runner exited with an error or panicked; the engine keeps it until removed
+    Error,
+    /// Runner reached its configured end (e.g. `max_ticks` in a backtest)
+    Finished,
 }
 
 impl RunnerStatus {
     /// Check if runner can process ticks
     pub fn is_active(&self) -> bool {
-        matches!(self, RunnerStatus::Running)
+        matches!(self, RunnerStatus::Starting | RunnerStatus::Running | RunnerStatus::Stale)
     }
 
     /// Check if runner is paused
@@ -34,15 +43,20 @@ impl RunnerStatus {
         matches!(self, RunnerStatus::Paused)
     }
 
-    /// Check if runner is stopped
+    /// Check if runner is done processing (stopped, failed or finished)
     pub fn is_stopped(&self) -> bool {
-        matches!(self, RunnerStatus::Stopped)
+        matches!(self, RunnerStatus::Stopped | RunnerStatus::Error | RunnerStatus::Finished)
+    }
+
+    /// Check if runner exited with an error
+    pub fn is_error(&self) -> bool {
+        matches!(self, RunnerStatus::Error)
     }
 }
 
 impl Default for RunnerStatus {
     fn default() -> Self {
-        RunnerStatus::Running
+        RunnerStatus::Starting
     }
 }
 
@@ -80,6 +94,31 @@ pub enum RunnerCommand {
         /// Channel to send confirmation response.
         response: oneshot::Sender<bool>,
     },
+
+    /// Exit the open position at the latest known price.
+    Flatten {
+        /// Channel to send `Ok(true)` if a position was closed, `Ok(false)` if there was none.
+        response: oneshot::Sender<crate::error::Result<bool>>,
+    },
+
+    /// Replace the runner's configuration in place.
+    ///
+    /// Fields that can't change while running keep their current values;
+    /// see [`TradingEngine::update_runner_config`](crate::runner::TradingEngine::update_runner_config).
+    UpdateConfig {
+        /// The new configuration.
+        config: Box<RunnerConfig>,
+        /// Channel to send `true` if every field was applied, `false` if some were kept.
+        response: oneshot::Sender<bool>,
+    },
+
+    /// Change the ID the runner reports in its events and snapshots.
+    Rename {
+        /// The new runner ID.
+        runner_id: String,
+        /// Channel to send confirmation response.
+        response: oneshot::Sender<bool>,
+    },
 }
 
 /// A point-in-time snapshot of a runner's complete state.
@@ -117,6 +156,51 @@ pub struct RunnerSnapshot {
 
     /// Timestamp when this snapshot was taken (milliseconds since Unix epoch).
     pub snapshot_timestamp: i64,
+
+    /// Labels from the runner's configuration.
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+
+    /// Number of decimals prices are displayed with for this symbol.
+    #[serde(default = "default_price_precision")]
+    pub price_precision: u32,
+
+    /// Prices formatted to `price_precision` decimals, for display.
+    #[serde(default)]
+    pub formatted: FormattedPrices,
+
+    /// Open position's unrealized P&L as a percentage of its cost (if in a trade).
+    #[serde(default)]
+    pub unrealized_pnl_pct: Option<f64>,
+}
+
+/// Default number of decimals for displayed prices.
+pub const DEFAULT_PRICE_PRECISION: u32 = 2;
+
+fn default_price_precision() -> u32 {
+    DEFAULT_PRICE_PRECISION
+}
+
+/// Display strings for the prices in a snapshot.
+///
+/// Each field is `None` when the underlying price is unknown (e.g., no
+/// position is open). The raw `f64` values stay available on the snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct FormattedPrices {
+    /// Latest close seen by the runner.
+    pub latest_price: Option<String>,
+
+    /// Position entry price.
+    pub entry_price: Option<String>,
+
+    /// Position mark price.
+    pub current_price: Option<String>,
+
+    /// Position stop loss.
+    pub stop_loss: Option<String>,
+
+    /// Position take profit.
+    pub take_profit: Option<String>,
 }
 
 /// Snapshot of the strategy context.
@@ -159,7 +243,58 @@ impl RunnerSnapshot {
             stats,
             uptime_secs: uptime.as_secs(),
             snapshot_timestamp: chrono::Utc::now().timestamp_millis(),
+            tags: HashMap::new(),
+            price_precision: DEFAULT_PRICE_PRECISION,
+            formatted: FormattedPrices::default(),
+            unrealized_pnl_pct: None,
         }
+        .with_price_precision(DEFAULT_PRICE_PRECISION)
+    }
+
+    /// Set the display precision and reformat prices.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trading_engine::runner::{ContextSnapshot, RunnerSnapshot, RunnerStats, RunnerStatus};
+    /// use trading_engine::state_machine::State;
+    /// use std::time::Duration;
+    ///
+    /// let mut context = ContextSnapshot::default();
+    /// context.numbers.insert("latest_price".to_string(), 0.000012345);
+    ///
+    /// let snapshot = RunnerSnapshot::new(
+    ///     "pepe_runner".to_string(),
+    ///     "PEPEUSDT".to_string(),
+    ///     RunnerStatus::Running,
+    ///     State::Idle,
+    ///     None,
+    ///     context,
+    ///     RunnerStats::new(),
+    ///     Duration::from_secs(0),
+    /// )
+    /// .with_price_precision(8);
+    /// assert_eq!(snapshot.formatted.latest_price.as_deref(), Some("0.00001234"));
+    /// ```
+    pub fn with_price_precision(mut self, decimals: u32) -> Self {
+        self.price_precision = decimals;
+        self.refresh_formatted();
+        self
+    }
+
+    /// Recompute `formatted` and `unrealized_pnl_pct` from the raw prices.
+    pub fn refresh_formatted(&mut self) {
+        let decimals = self.price_precision as usize;
+        let format = |value: f64| format!("{:.*}", decimals, value);
+
+        self.formatted = FormattedPrices {
+            latest_price: self.context.numbers.get("latest_price").copied().map(format),
+            entry_price: self.position.as_ref().map(|p| format(p.entry_price())),
+            current_price: self.position.as_ref().map(|p| format(p.current_price())),
+            stop_loss: self.position.as_ref().and_then(|p| p.stop_loss()).map(format),
+            take_profit: self.position.as_ref().and_then(|p| p.take_profit()).map(format),
+        };
+        self.unrealized_pnl_pct = self.position.as_ref().and_then(|p| p.unrealized_pnl_pct());
     }
 
     /// Check if the runner is currently in a position.
@@ -175,6 +310,62 @@ impl RunnerSnapshot {
             State::InPosition => "InPosition",
         }
     }
+
+    /// Fold a runner event into this snapshot.
+    ///
+    /// Used by the engine to keep a last-known snapshot current between
+    /// direct queries. The result is approximate: context is not carried by
+    /// events, and tick durations are not tracked, so only counters, state,
+    /// position and status are updated. Events for other runners are ignored.
+    /// Warnings aren't failed ticks, so they don't count as errors.
+    pub fn apply_event(&mut self, event: &RunnerEvent) {
+        if event.runner_id() != self.runner_id {
+            return;
+        }
+
+        match event {
+            RunnerEvent::TickReceived { .. } => {
+                self.stats.ticks_processed += 1;
+                if matches!(self.status, RunnerStatus::Starting | RunnerStatus::Stale) {
+                    self.status = RunnerStatus::Running;
+                }
+            }
+            RunnerEvent::StateTransition { to, .. } => {
+                self.current_state = *to;
+            }
+            RunnerEvent::ActionExecuted { .. } => {
+                self.stats.actions_executed += 1;
+            }
+            RunnerEvent::PositionOpened { position, .. } => {
+                self.position = Some(position.clone());
+            }
+            RunnerEvent::PositionUpdated { current_price, .. } => {
+                if let Some(position) = self.position.as_mut() {
+                    position.update_current_price(*current_price);
+                }
+            }
+            RunnerEvent::PositionClosed { .. } => {
+                self.position = None;
+            }
+            RunnerEvent::Error { severity, .. } => {
+                if *severity != ErrorSeverity::Warning {
+                    self.stats.errors += 1;
+                }
+            }
+            RunnerEvent::RunnerStopped { status, .. } => {
+                self.status = *status;
+            }
+            RunnerEvent::StrategyPassed { reason, .. } => {
+                self.stats.record_no_action_reason(reason.clone());
+            }
+            RunnerEvent::RunnerStarted { .. }
+            | RunnerEvent::IndicatorReady { .. }
+            | RunnerEvent::StopLossTriggered { .. }
+            | RunnerEvent::TakeProfitTriggered { .. }
+            | RunnerEvent::StatsUpdate { .. } => {}
+        }
+        self.refresh_formatted();
+    }
 }
 
 #[cfg(test)]
@@ -229,6 +420,35 @@ mod tests {
         assert_eq!(snapshot.position.as_ref().unwrap().entry_price(), 50000.0);
     }
 
+    #[test]
+    fn test_snapshot_formatted_prices() {
+        let mut position = Position::new(50000.129, 0.1, Side::Long, 1234567890);
+        position.update_current_price(50250.5);
+        position.set_stop_loss(49000.0);
+
+        let snapshot = RunnerSnapshot::new(
+            "btc_runner".to_string(),
+            "BTCUSDT".to_string(),
+            RunnerStatus::Running,
+            State::InPosition,
+            Some(position),
+            ContextSnapshot::default(),
+            RunnerStats::new(),
+            Duration::from_secs(60),
+        )
+        .with_price_precision(2);
+
+        assert_eq!(snapshot.price_precision, 2);
+        assert_eq!(snapshot.formatted.entry_price.as_deref(), Some("50000.13"));
+        assert_eq!(snapshot.formatted.current_price.as_deref(), Some("50250.50"));
+        assert_eq!(snapshot.formatted.stop_loss.as_deref(), Some("49000.00"));
+        assert_eq!(snapshot.formatted.take_profit, None);
+        assert_eq!(snapshot.formatted.latest_price, None);
+
+        // Raw values are kept
+        assert_eq!(snapshot.position.as_ref().unwrap().entry_price(), 50000.129);
+    }
+
     #[test]
     fn test_snapshot_serialization() {
         let snapshot = RunnerSnapshot::new(
@@ -254,6 +474,77 @@ mod tests {
         assert_eq!(deserialized.status, RunnerStatus::Running);
     }
 
+    #[test]
+    fn test_snapshot_apply_event() {
+        let mut snapshot = RunnerSnapshot::new(
+            "btc_runner".to_string(),
+            "BTCUSDT".to_string(),
+            RunnerStatus::Running,
+            State::Idle,
+            None,
+            ContextSnapshot::default(),
+            RunnerStats::new(),
+            Duration::from_secs(0),
+        );
+
+        snapshot.apply_event(&RunnerEvent::StateTransition {
+            runner_id: "btc_runner".to_string(),
+            from: State::Idle,
+            to: State::InPosition,
+            reason: "entered".to_string(),
+            timestamp: 1,
+        });
+        snapshot.apply_event(&RunnerEvent::PositionOpened {
+            runner_id: "btc_runner".to_string(),
+            position: Position::new(50000.0, 0.1, Side::Long, 1),
+            requested_price: 50000.0,
+            fill_price: 50000.0,
+            timestamp: 1,
+        });
+        snapshot.apply_event(&RunnerEvent::PositionUpdated {
+            runner_id: "btc_runner".to_string(),
+            current_price: 51000.0,
+            unrealized_pnl: 100.0,
+            unrealized_pnl_pct: 2.0,
+            timestamp: 2,
+        });
+
+        assert_eq!(snapshot.current_state, State::InPosition);
+        assert_eq!(snapshot.position.as_ref().unwrap().current_price(), 51000.0);
+
+        // Events for other runners are ignored
+        snapshot.apply_event(&RunnerEvent::RunnerStopped {
+            runner_id: "eth_runner".to_string(),
+            reason: "done".to_string(),
+            status: RunnerStatus::Stopped,
+            timestamp: 3,
+        });
+        assert_eq!(snapshot.status, RunnerStatus::Running);
+
+        snapshot.apply_event(&RunnerEvent::PositionClosed {
+            runner_id: "btc_runner".to_string(),
+            requested_price: 51000.0,
+            exit_price: 51000.0,
+            realized_pnl: 100.0,
+            realized_pnl_pct: 2.0,
+            reason: "take profit".to_string(),
+            timestamp: 3,
+        });
+        assert!(!snapshot.has_position());
+
+        // Warnings and notices aren't counted as errors
+        let error = |severity| RunnerEvent::Error {
+            runner_id: "btc_runner".to_string(),
+            error: "bad tick".to_string(),
+            severity,
+            timestamp: 4,
+        };
+        snapshot.apply_event(&error(ErrorSeverity::Warning));
+        assert_eq!(snapshot.stats.errors, 0);
+        snapshot.apply_event(&error(ErrorSeverity::Error));
+        assert_eq!(snapshot.stats.errors, 1);
+    }
+
     #[test]
     fn test_context_snapshot() {
         let mut context = ContextSnapshot::default();
@@ -15,6 +15,30 @@ pub struct RunnerStats {
     /// Total errors encountered
     pub errors: u64,
 
+    /// Ticks where the strategy was called and returned no action
+    #[serde(default)]
+    pub no_action_ticks: u64,
+
+    /// Reason given with the most recent no-action, if the strategy gave one
+    #[serde(default)]
+    pub last_no_action_reason: Option<String>,
+
+    /// Positions closed
+    #[serde(default)]
+    pub trades: u64,
+
+    /// Positions closed with a positive realized P&L
+    #[serde(default)]
+    pub winning_trades: u64,
+
+    /// Sum of realized P&L over winning trades
+    #[serde(default)]
+    pub gross_profit: f64,
+
+    /// Sum of realized losses over losing trades, as a positive number
+    #[serde(default)]
+    pub gross_loss: f64,
+
     /// Average tick processing time
     pub avg_tick_duration: Duration,
 
@@ -35,6 +59,12 @@ impl RunnerStats {
             ticks_processed: 0,
             actions_executed: 0,
             errors: 0,
+            no_action_ticks: 0,
+            last_no_action_reason: None,
+            trades: 0,
+            winning_trades: 0,
+            gross_profit: 0.0,
+            gross_loss: 0.0,
             avg_tick_duration: Duration::ZERO,
             min_tick_duration: Duration::MAX,
             max_tick_duration: Duration::ZERO,
@@ -64,6 +94,27 @@ impl RunnerStats {
         self.actions_executed += 1;
     }
 
+    /// Record a tick the strategy took no action on
+    pub fn record_no_action(&mut self) {
+        self.no_action_ticks += 1;
+    }
+
+    /// Record the reason the strategy gave for passing
+    pub fn record_no_action_reason(&mut self, reason: impl Into<String>) {
+        self.last_no_action_reason = Some(reason.into());
+    }
+
+    /// Record a closed position's realized P&L
+    pub fn record_trade(&mut self, realized_pnl: f64) {
+        self.trades += 1;
+        if realized_pnl > 0.0 {
+            self.winning_trades += 1;
+            self.gross_profit += realized_pnl;
+        } else {
+            self.gross_loss -= realized_pnl;
+        }
+    }
+
     /// Record an error
     pub fn record_error(&mut self) {
         self.errors += 1;
@@ -85,6 +136,29 @@ impl RunnerStats {
         (self.actions_executed as f64 / self.ticks_processed as f64) * 100.0
     }
 
+    /// Get win rate (percent of closed trades with a profit)
+    pub fn win_rate(&self) -> f64 {
+        if self.trades == 0 {
+            return 0.0;
+        }
+        (self.winning_trades as f64 / self.trades as f64) * 100.0
+    }
+
+    /// Get profit factor (gross profit / gross loss)
+    ///
+    /// `None` when there are no losses to divide by.
+    pub fn profit_factor(&self) -> Option<f64> {
+        if self.gross_loss == 0.0 {
+            return None;
+        }
+        Some(self.gross_profit / self.gross_loss)
+    }
+
+    /// Get total realized P&L over all closed trades
+    pub fn total_pnl(&self) -> f64 {
+        self.gross_profit - self.gross_loss
+    }
+
     /// Reset all statistics
     pub fn reset(&mut self) {
         *self = Self::new();
@@ -142,4 +216,19 @@ mod tests {
 
         assert_eq!(stats.action_rate(), 5.0); // 5 actions per 100 ticks
     }
+
+    #[test]
+    fn test_trade_stats() {
+        let mut stats = RunnerStats::new();
+        assert_eq!(stats.profit_factor(), None);
+
+        stats.record_trade(300.0);
+        stats.record_trade(100.0);
+        stats.record_trade(-200.0);
+
+        assert_eq!(stats.trades, 3);
+        assert!((stats.win_rate() - 66.666).abs() < 0.01);
+        assert_eq!(stats.profit_factor(), Some(2.0));
+        assert_eq!(stats.total_pnl(), 200.0);
+    }
 }
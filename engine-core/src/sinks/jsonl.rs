@@ -0,0 +1,275 @@
+//! JSON Lines file sink with size or daily rotation.
+
+use super::EventSink;
+use crate::events::RunnerEvent;
+use crate::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// When a [`JsonlFileSink`] starts a new file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    /// Never rotate; keep appending to the same file
+    Never,
+    /// Rotate before the active file would exceed this many bytes
+    Size(u64),
+    /// Rotate when the UTC date changes
+    Daily,
+}
+
+/// Event sink that appends each event as one JSON line
+///
+/// Events are written to the active file at `path` and each line is written
+/// straight through to the OS, so nothing is lost if the process exits.
+/// When the rotation policy triggers, the active file is renamed and a fresh
+/// one is started at `path`:
+///
+/// - [`Rotation::Size`]: `events.jsonl` → `events.1.jsonl`, `events.2.jsonl`, ...
+/// - [`Rotation::Daily`]: `events.jsonl` → `events.2024-12-15.jsonl`
+///
+/// # Example
+///
+/// ```no_run
+/// use trading_engine::sinks::{JsonlFileSink, Rotation};
+///
+/// // Start a new file every 100 MB
+/// let sink = JsonlFileSink::new("logs/events.jsonl", Rotation::Size(100 * 1024 * 1024))?;
+/// # Ok::<(), trading_engine::TradingEngineError>(())
+/// ```
+pub struct JsonlFileSink {
+    /// Path of the active file
+    path: PathBuf,
+
+    /// Rotation policy
+    rotation: Rotation,
+
+    /// Handle to the active file (append mode)
+    file: File,
+
+    /// Bytes in the active file
+    size: u64,
+
+    /// UTC date the active file belongs to
+    day: NaiveDate,
+}
+
+impl JsonlFileSink {
+    /// Open (or create) the active file at `path`
+    ///
+    /// Missing parent directories are created. An existing file is appended to.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TradingEngineError::IoError`](crate::TradingEngineError::IoError)
+    /// if the directory or file cannot be created.
+    pub fn new(path: impl Into<PathBuf>, rotation: Rotation) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        let file = Self::open(&path)?;
+        let metadata = file.metadata()?;
+
+        // An existing file belongs to the day it was last written
+        let day = metadata
+            .modified()
+            .map(|t| DateTime::<Utc>::from(t).date_naive())
+            .unwrap_or_else(|_| Utc::now().date_naive());
+
+        Ok(Self {
+            path,
+            rotation,
+            file,
+            size: metadata.len(),
+            day,
+        })
+    }
+
+    /// Path of the active file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Open a file for appending, creating it if needed
+    fn open(path: &Path) -> Result<File> {
+        Ok(OpenOptions::new().create(true).append(true).open(path)?)
+    }
+
+    /// Check whether the active file must be rotated before writing `incoming` bytes
+    fn should_rotate(&self, incoming: u64, today: NaiveDate) -> bool {
+        // Never rotate away an empty file
+        if self.size == 0 {
+            return false;
+        }
+        match self.rotation {
+            Rotation::Never => false,
+            Rotation::Size(max_bytes) => self.size + incoming > max_bytes,
+            Rotation::Daily => today != self.day,
+        }
+    }
+
+    /// Rename the active file out of the way and start a new one
+    fn rotate(&mut self, today: NaiveDate) -> Result<()> {
+        let label = match self.rotation {
+            Rotation::Daily => Some(self.day.format("%Y-%m-%d").to_string()),
+            _ => None,
+        };
+        let rotated = self.rotated_path(label.as_deref());
+
+        self.file.flush()?;
+        fs::rename(&self.path, &rotated)?;
+        tracing::info!("Rotated event log {} -> {}", self.path.display(), rotated.display());
+
+        self.file = Self::open(&self.path)?;
+        self.size = 0;
+        self.day = today;
+        Ok(())
+    }
+
+    /// Pick an unused name for a rotated file
+    ///
+    /// With a label: `stem.label.ext`, then `stem.label.1.ext`, ...
+    /// Without: `stem.1.ext`, `stem.2.ext`, ...
+    fn rotated_path(&self, label: Option<&str>) -> PathBuf {
+        let stem = self
+            .path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let ext = self
+            .path
+            .extension()
+            .map(|e| e.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "jsonl".to_string());
+
+        let mut n = 0;
+        loop {
+            let name = match (label, n) {
+                (Some(label), 0) => format!("{}.{}.{}", stem, label, ext),
+                (Some(label), n) => format!("{}.{}.{}.{}", stem, label, n, ext),
+                (None, n) => format!("{}.{}.{}", stem, n + 1, ext),
+            };
+            let candidate = self.path.with_file_name(name);
+            if !candidate.exists() {
+                return candidate;
+            }
+            n += 1;
+        }
+    }
+}
+
+impl EventSink for JsonlFileSink {
+    fn write_event(&mut self, event: &RunnerEvent) -> Result<()> {
+        let mut line = serde_json::to_vec(event)?;
+        line.push(b'\n');
+
+        let today = Utc::now().date_naive();
+        if self.should_rotate(line.len() as u64, today) {
+            self.rotate(today)?;
+        }
+
+        self.file.write_all(&line)?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.file.flush()?;
+        Ok(())
+    }
+
+    fn sink_name(&self) -> &str {
+        "jsonl"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_log_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn started_event(runner_id: &str) -> RunnerEvent {
+        RunnerEvent::RunnerStarted {
+            runner_id: runner_id.to_string(),
+            symbol: "BTCUSDT".to_string(),
+            timestamp: 1234567890,
+        }
+    }
+
+    #[test]
+    fn test_writes_one_json_line_per_event() {
+        let dir = temp_log_dir("jsonl_sink_lines");
+        let path = dir.join("events.jsonl");
+        let mut sink = JsonlFileSink::new(&path, Rotation::Never).unwrap();
+
+        sink.write_event(&started_event("a")).unwrap();
+        sink.write_event(&started_event("b")).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let ids: Vec<String> = contents
+            .lines()
+            .map(|line| {
+                let event: RunnerEvent = serde_json::from_str(line).unwrap();
+                event.runner_id().to_string()
+            })
+            .collect();
+        assert_eq!(ids, vec!["a", "b"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rotates_by_size() {
+        let dir = temp_log_dir("jsonl_sink_rotation");
+        let path = dir.join("events.jsonl");
+        let line_len = serde_json::to_vec(&started_event("a")).unwrap().len() as u64 + 1;
+
+        // Room for two lines per file
+        let mut sink = JsonlFileSink::new(&path, Rotation::Size(line_len * 2)).unwrap();
+        for id in ["a", "b", "c", "d", "e"] {
+            sink.write_event(&started_event(id)).unwrap();
+        }
+
+        let read_ids = |p: &Path| -> Vec<String> {
+            fs::read_to_string(p)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str::<RunnerEvent>(line).unwrap().runner_id().to_string())
+                .collect()
+        };
+        assert_eq!(read_ids(&dir.join("events.1.jsonl")), vec!["a", "b"]);
+        assert_eq!(read_ids(&dir.join("events.2.jsonl")), vec!["c", "d"]);
+        assert_eq!(read_ids(&path), vec!["e"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rotates_by_day() {
+        let dir = temp_log_dir("jsonl_sink_daily");
+        let path = dir.join("events.jsonl");
+        let mut sink = JsonlFileSink::new(&path, Rotation::Daily).unwrap();
+        sink.write_event(&started_event("a")).unwrap();
+
+        // Pretend the active file was started yesterday
+        let yesterday = Utc::now().date_naive().pred_opt().unwrap();
+        sink.day = yesterday;
+        sink.write_event(&started_event("b")).unwrap();
+
+        let rotated = dir.join(format!("events.{}.jsonl", yesterday.format("%Y-%m-%d")));
+        assert_eq!(fs::read_to_string(&rotated).unwrap().lines().count(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
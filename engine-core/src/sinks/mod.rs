@@ -0,0 +1,60 @@
+//! Event sink abstraction and implementations.
+//!
+//! This module provides the [`EventSink`] trait for persisting [`RunnerEvent`]s
+//! outside the process, complementing the channel-based subscribers returned by
+//! [`TradingEngine::subscribe_events`](crate::runner::TradingEngine::subscribe_events).
+//!
+//! # Available Sinks
+//!
+//! - [`JsonlFileSink`] - Appends each event as a JSON line to a rotating file
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use trading_engine::runner::TradingEngine;
+//! use trading_engine::sinks::{JsonlFileSink, Rotation};
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let engine = TradingEngine::new();
+//!
+//!     // Persist every event, starting a new file each day
+//!     let sink = JsonlFileSink::new("logs/events.jsonl", Rotation::Daily)?;
+//!     engine.add_event_sink(sink);
+//!     Ok(())
+//! }
+//! ```
+
+use crate::events::RunnerEvent;
+use crate::Result;
+
+/// Destination for runner events.
+///
+/// Sinks are driven from a dedicated blocking task, so implementations may
+/// perform synchronous I/O. Implementations must be `Send` to be moved onto
+/// that task.
+pub trait EventSink: Send + 'static {
+    /// Persist a single event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event could not be written. The engine logs
+    /// the error and keeps delivering subsequent events.
+    fn write_event(&mut self, event: &RunnerEvent) -> Result<()>;
+
+    /// Flush any buffered output.
+    ///
+    /// Called once the event stream ends. The default implementation does nothing.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// A string identifier for this sink (e.g., "jsonl").
+    fn sink_name(&self) -> &str;
+}
+
+// Module declarations
+pub mod jsonl;
+
+// Re-exports
+pub use jsonl::{JsonlFileSink, Rotation};
@@ -2,14 +2,17 @@
 //!
 //! This module provides real-time cryptocurrency market data from Binance via WebSocket.
 //! It subscribes to both kline (candlestick) and bookTicker streams to provide complete
-//! OHLCV data with accurate bid/ask prices.
+//! OHLCV data with accurate bid/ask prices. Alternatively, it can subscribe to the
+//! aggTrade stream and emit one tick per trade.
 //!
 //! # Features
 //!
 //! - Real-time kline/candlestick data (1s to 1M intervals)
+//! - Trade-by-trade data from the aggTrade stream ([`BinanceMode::AggTrade`])
 //! - Live bid/ask prices from bookTicker stream (no approximations)
 //! - Support for multiple symbols simultaneously
 //! - Automatic ping/pong keepalive (20s interval)
+//! - Seamless reconnect ahead of Binance's 24h connection limit
 //! - Regional endpoint support (Binance.com and Binance.US)
 //! - Only emits completed klines (filters partial candles)
 //!
@@ -75,8 +78,10 @@
 //!
 //! - **WebSocket URL**: `wss://stream.binance.{com|us}:9443/stream`
 //! - **Stream Format**: `{symbol}@kline_{interval}/{symbol}@bookTicker`
+//!   (or `{symbol}@aggTrade/{symbol}@bookTicker` in aggTrade mode)
 //! - **Ping Interval**: 20 seconds
 //! - **Timeout**: 60 seconds
+//! - **Connection Lifetime**: 24 hours; the feed reconnects 5 minutes early
 //! - **Rate Limits**: 5 messages/sec per connection
 //!
 //! # See Also
@@ -96,8 +101,67 @@ use url::Url;
 
 const BINANCE_WS_URL: &str = "wss://stream.binance.com:9443";
 const BINANCE_US_WS_URL: &str = "wss://stream.binance.us:9443";
+const BINANCE_REST_URL: &str = "https://api.binance.com";
+const BINANCE_US_REST_URL: &str = "https://api.binance.us";
+/// Longest a REST request may take before it is abandoned
+const REST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Most klines Binance returns for one REST request
+const MAX_KLINES_PER_REQUEST: usize = 1000;
 const PING_INTERVAL: Duration = Duration::from_secs(20);
 const PONG_TIMEOUT: Duration = Duration::from_secs(60);
+/// Binance closes every connection after 24 hours
+const MAX_CONNECTION_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+/// How long before the connection limit the feed reconnects
+const RECONNECT_MARGIN: Duration = Duration::from_secs(5 * 60);
+/// Wait between early reconnect attempts that fail
+const RECONNECT_RETRY_DELAY: Duration = Duration::from_secs(30);
+/// Longest a connection attempt may take
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Binance drops connections that send more than 5 messages per second
+const CONTROL_MESSAGES_PER_SEC: f64 = 5.0;
+
+/// Token bucket pacing the control messages a client sends
+///
+/// Holds up to `capacity` tokens, refilled at `rate` per second. Each
+/// message takes one token; when none is left, [`acquire`](Self::acquire)
+/// waits for the next one, so bursts go out in order, spaced at `1 / rate`.
+#[derive(Debug)]
+struct ControlRateLimiter {
+    capacity: f64,
+    rate: f64,
+    tokens: f64,
+    last_refill: tokio::time::Instant,
+}
+
+impl ControlRateLimiter {
+    fn new(rate: f64) -> Self {
+        Self {
+            capacity: rate,
+            rate,
+            tokens: rate,
+            last_refill: tokio::time::Instant::now(),
+        }
+    }
+
+    /// Wait until a message may be sent, and take its token
+    async fn acquire(&mut self) {
+        self.refill();
+        if self.tokens < 1.0 {
+            let wait = Duration::from_secs_f64((1.0 - self.tokens) / self.rate);
+            tracing::debug!("Control message rate limit reached, waiting {:?}", wait);
+            tokio::time::sleep(wait).await;
+            self.refill();
+        }
+        self.tokens = (self.tokens - 1.0).max(0.0);
+    }
+
+    fn refill(&mut self) {
+        let now = tokio::time::Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = now;
+    }
+}
 
 /// Binance region for endpoint selection
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -108,6 +172,16 @@ pub enum BinanceRegion {
     US,
 }
 
+/// Which Binance stream drives the feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BinanceMode {
+    /// One tick per completed kline
+    #[default]
+    Kline,
+    /// One tick per aggregated trade (open = high = low = close = trade price)
+    AggTrade,
+}
+
 /// Binance kline/candlestick data structure
 #[derive(Debug, Deserialize, Serialize)]
 struct BinanceKline {
@@ -149,6 +223,31 @@ struct KlineData {
     quote_volume: String,
 }
 
+/// Binance aggregated trade data structure
+#[derive(Debug, Deserialize, Serialize)]
+struct AggTrade {
+    #[serde(rename = "e")]
+    event_type: String,
+    #[serde(rename = "E")]
+    event_time: i64,
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "a")]
+    agg_trade_id: i64,
+    #[serde(rename = "p")]
+    price: String,
+    #[serde(rename = "q")]
+    quantity: String,
+    #[serde(rename = "f")]
+    first_trade_id: i64,
+    #[serde(rename = "l")]
+    last_trade_id: i64,
+    #[serde(rename = "T")]
+    trade_time: i64,
+    #[serde(rename = "m")]
+    is_buyer_maker: bool,
+}
+
 /// Binance book ticker data structure
 #[derive(Debug, Clone, Deserialize, Serialize)]
 struct BookTicker {
@@ -173,9 +272,38 @@ struct CombinedStream {
     data: serde_json::Value,
 }
 
+/// Binance error payload, e.g. for an invalid subscription
+#[derive(Debug, Deserialize)]
+struct BinanceError {
+    code: i64,
+    msg: String,
+}
+
+/// Error in response to a request (`{"error": {...}, "id": 1}`)
+#[derive(Debug, Deserialize)]
+struct BinanceErrorResponse {
+    error: BinanceError,
+}
+
+/// Response to a subscribe/unsubscribe request (`{"result": null, "id": 1}`)
+#[derive(Debug, Deserialize)]
+struct SubscriptionAck {
+    result: serde_json::Value,
+    id: serde_json::Value,
+}
+
 impl KlineData {
     /// Convert Binance kline data to our MarketData format
-    fn to_market_data(&self, bid: f64, ask: f64) -> Result<MarketData> {
+    ///
+    /// Bid/ask quantities are only known when a bookTicker has been cached
+    /// for the symbol, so they are passed through as options.
+    fn to_market_data(
+        &self,
+        bid: f64,
+        ask: f64,
+        bid_qty: Option<f64>,
+        ask_qty: Option<f64>,
+    ) -> Result<MarketData> {
         let open = self.open.parse::<f64>()
             .map_err(|e| crate::error::TradingEngineError::ParseError(format!("Invalid open price: {}", e)))?;
         let high = self.high.parse::<f64>()
@@ -197,6 +325,33 @@ impl KlineData {
             volume: volume_f64 as u64,
             bid,
             ask,
+            bid_qty,
+            ask_qty,
+            is_final: self.is_closed,
+        })
+    }
+}
+
+impl AggTrade {
+    /// Convert a trade to a single-price MarketData tick
+    fn to_market_data(
+        &self,
+        bid: f64,
+        ask: f64,
+        bid_qty: Option<f64>,
+        ask_qty: Option<f64>,
+    ) -> Result<MarketData> {
+        let price = self.price.parse::<f64>()
+            .map_err(|e| crate::error::TradingEngineError::ParseError(format!("Invalid trade price: {}", e)))?;
+        let quantity = self.quantity.parse::<f64>()
+            .map_err(|e| crate::error::TradingEngineError::ParseError(format!("Invalid trade quantity: {}", e)))?;
+
+        Ok(MarketData {
+            bid,
+            ask,
+            bid_qty,
+            ask_qty,
+            ..MarketData::from_trade(self.symbol.to_uppercase(), price, quantity, self.trade_time)
         })
     }
 }
@@ -206,14 +361,34 @@ impl KlineData {
 /// Subscribes to both kline and bookTicker streams to get:
 /// - OHLCV data from klines
 /// - Real-time bid/ask prices from bookTicker
+///
+/// In [`BinanceMode::AggTrade`], the aggTrade stream replaces klines and
+/// every trade becomes a tick.
+///
+/// Streams are chosen when connecting, so the feed needs at least one symbol,
+/// given at construction or through `subscribe`, before `connect` is called.
+/// Connecting without symbols fails with `TradingEngineError::InvalidData`.
 pub struct BinanceFeed {
     symbols: Vec<String>,
     interval: String,
     region: BinanceRegion,
+    mode: BinanceMode,
     ws_stream: Option<tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>>,
     last_ping: Option<tokio::time::Instant>,
-    /// Cache of latest bid/ask prices per symbol
+    /// Cache of latest bid/ask prices and quantities per symbol
     book_tickers: HashMap<String, BookTicker>,
+    /// Id of the last subscribe/unsubscribe request sent
+    last_request_id: u64,
+    /// Paces subscribe/unsubscribe requests and pings under Binance's limit
+    control_limiter: ControlRateLimiter,
+    /// When the current connection was opened
+    connected_at: Option<tokio::time::Instant>,
+    /// Age at which the server closes a connection
+    max_connection_age: Duration,
+    /// No early reconnect is tried before this, after one failed
+    reconnect_retry_at: Option<tokio::time::Instant>,
+    /// WebSocket endpoint replacing the region's
+    endpoint: Option<String>,
 }
 
 impl BinanceFeed {
@@ -252,37 +427,254 @@ impl BinanceFeed {
     /// );
     /// ```
     pub fn new_with_region(symbols: Vec<String>, interval: String, region: BinanceRegion) -> Self {
+        Self::new_with_mode(symbols, interval, region, BinanceMode::Kline)
+    }
+
+    /// Create a feed that emits one tick per aggregated trade
+    ///
+    /// Each tick has open = high = low = close = trade price and volume = trade
+    /// quantity, with bid/ask taken from the latest bookTicker.
+    ///
+    /// # Example
+    /// ```
+    /// use trading_engine::sources::{BinanceFeed, BinanceMode, BinanceRegion};
+    ///
+    /// let feed = BinanceFeed::new_agg_trades(vec!["BTCUSDT".to_string()], BinanceRegion::US);
+    /// assert_eq!(feed.mode(), BinanceMode::AggTrade);
+    /// ```
+    pub fn new_agg_trades(symbols: Vec<String>, region: BinanceRegion) -> Self {
+        Self::new_with_mode(symbols, String::new(), region, BinanceMode::AggTrade)
+    }
+
+    /// Create a new Binance feed with specified region and stream mode
+    ///
+    /// # Arguments
+    /// * `symbols` - Trading pair symbols (e.g., "BTCUSDT", "ETHUSDT")
+    /// * `interval` - Kline interval (ignored in aggTrade mode)
+    /// * `region` - Binance region (International or US)
+    /// * `mode` - Kline or aggTrade stream
+    pub fn new_with_mode(
+        symbols: Vec<String>,
+        interval: String,
+        region: BinanceRegion,
+        mode: BinanceMode,
+    ) -> Self {
         Self {
             symbols,
             interval,
             region,
+            mode,
             ws_stream: None,
             last_ping: None,
             book_tickers: HashMap::new(),
+            last_request_id: 0,
+            control_limiter: ControlRateLimiter::new(CONTROL_MESSAGES_PER_SEC),
+            connected_at: None,
+            max_connection_age: MAX_CONNECTION_AGE,
+            reconnect_retry_at: None,
+            endpoint: None,
+        }
+    }
+
+    /// Connect to `endpoint` (e.g. `wss://testnet.binance.vision`) instead of the region's
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Override the server's connection limit (24 hours by default)
+    ///
+    /// The feed reconnects shortly before a connection reaches this age:
+    /// five minutes early, or a tenth of the limit if that is sooner. A failed
+    /// attempt is retried after 30 seconds, or a fifth of that margin if that
+    /// is sooner, while ticks keep coming from the old connection.
+    pub fn with_max_connection_age(mut self, age: Duration) -> Self {
+        self.max_connection_age = age;
+        self
+    }
+
+    /// Stream mode selected at construction
+    pub fn mode(&self) -> BinanceMode {
+        self.mode
+    }
+
+    /// Symbols the feed is subscribed to
+    pub fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+
+    /// How long before the server's limit a connection is replaced
+    fn reconnect_margin(&self) -> Duration {
+        RECONNECT_MARGIN.min(self.max_connection_age / 10)
+    }
+
+    /// Age at which a connection is replaced, ahead of the server's limit
+    fn reconnect_after(&self) -> Duration {
+        self.max_connection_age - self.reconnect_margin()
+    }
+
+    /// Check if the live connection is close enough to the limit to replace
+    ///
+    /// After a failed attempt, the next one waits for the retry delay.
+    fn reconnect_due(&self) -> bool {
+        let now = tokio::time::Instant::now();
+        self.connected_at
+            .is_some_and(|connected_at| now - connected_at >= self.reconnect_after())
+            && self.reconnect_retry_at.is_none_or(|retry_at| now >= retry_at)
+    }
+
+    /// Open a WebSocket for the current symbols' streams
+    ///
+    /// Gives up after `CONNECT_TIMEOUT`, or at `deadline` if that is sooner.
+    async fn open_stream(
+        &self,
+        deadline: Option<tokio::time::Instant>,
+    ) -> Result<tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>> {
+        let url = self.build_url();
+        tracing::info!("Connecting to Binance WebSocket: {}", url);
+
+        let url = Url::parse(&url)
+            .map_err(|e| crate::error::TradingEngineError::ParseError(
+                format!("Invalid WebSocket URL: {}", e)
+            ))?;
+
+        let wait = match deadline {
+            Some(deadline) => CONNECT_TIMEOUT.min(deadline.saturating_duration_since(tokio::time::Instant::now())),
+            None => CONNECT_TIMEOUT,
+        };
+        let (ws_stream, response) = timeout(wait, connect_async(url)).await
+            .map_err(|_| crate::error::TradingEngineError::WebSocketError(
+                format!("Failed to connect: no response within {:?}", wait)
+            ))?
+            .map_err(|e| crate::error::TradingEngineError::WebSocketError(
+                format!("Failed to connect: {}", e)
+            ))?;
+
+        tracing::info!("Connected to Binance, response status: {}", response.status());
+        Ok(ws_stream)
+    }
+
+    /// Replace the connection before the server closes it
+    ///
+    /// The new connection is opened before the old one is closed, with the
+    /// current symbols (including `update_subscriptions` changes) baked into
+    /// its URL. Cached book tickers are kept, so ticks carry on without a gap.
+    /// If the new connection can't be opened by `deadline`, the old one stays
+    /// in use and the attempt is retried after a delay.
+    async fn reconnect(&mut self, deadline: Option<tokio::time::Instant>) {
+        tracing::info!("Binance connection nearing its {:?} limit, reconnecting", self.max_connection_age);
+        let ws_stream = match self.open_stream(deadline).await {
+            Ok(ws_stream) => ws_stream,
+            Err(e) => {
+                let delay = RECONNECT_RETRY_DELAY.min(self.reconnect_margin() / 5);
+                tracing::warn!("Early Binance reconnect failed, retrying in {:?}: {}", delay, e);
+                self.reconnect_retry_at = Some(tokio::time::Instant::now() + delay);
+                return;
+            }
+        };
+
+        if let Some(mut old) = self.ws_stream.replace(ws_stream) {
+            // The old connection is going away anyway
+            if let Err(e) = old.close(None).await {
+                tracing::debug!("Failed to close old Binance connection: {}", e);
+            }
+        }
+        self.connected_at = Some(tokio::time::Instant::now());
+        self.last_ping = self.connected_at;
+        self.reconnect_retry_at = None;
+    }
+
+    /// Add and remove symbols without reconnecting
+    ///
+    /// On a live connection this sends Binance `SUBSCRIBE`/`UNSUBSCRIBE`
+    /// requests for the symbols' streams, so the other symbols keep their
+    /// data and cached book tickers. Before `connect` it only changes the
+    /// symbol list. Symbols already subscribed (or not subscribed, when
+    /// removing) are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TradingEngineError::WebSocketError` if a request can't be
+    /// sent. The connection should then be rebuilt with `connect`.
+    pub async fn update_subscriptions(&mut self, added: &[String], removed: &[String]) -> Result<()> {
+        let added: Vec<String> = added
+            .iter()
+            .filter(|symbol| !self.symbols.contains(symbol))
+            .cloned()
+            .collect();
+        let removed: Vec<String> = removed
+            .iter()
+            .filter(|symbol| self.symbols.contains(symbol))
+            .cloned()
+            .collect();
+
+        if self.ws_stream.is_some() {
+            if !removed.is_empty() {
+                let streams = removed.iter().flat_map(|symbol| self.streams_for(symbol)).collect();
+                self.send_request("UNSUBSCRIBE", streams).await?;
+            }
+            if !added.is_empty() {
+                let streams = added.iter().flat_map(|symbol| self.streams_for(symbol)).collect();
+                self.send_request("SUBSCRIBE", streams).await?;
+            }
+        }
+
+        self.symbols.retain(|symbol| !removed.contains(symbol));
+        for symbol in &removed {
+            self.book_tickers.remove(symbol);
         }
+        self.symbols.extend(added);
+        tracing::info!("Updated subscriptions, now subscribed to: {:?}", self.symbols);
+        Ok(())
+    }
+
+    /// Send a subscription request over the live connection
+    async fn send_request(&mut self, method: &str, streams: Vec<String>) -> Result<()> {
+        self.last_request_id += 1;
+        let request = serde_json::json!({
+            "method": method,
+            "params": streams,
+            "id": self.last_request_id,
+        });
+
+        if let Some(stream) = &mut self.ws_stream {
+            self.control_limiter.acquire().await;
+            stream.send(Message::Text(request.to_string())).await
+                .map_err(|e| crate::error::TradingEngineError::WebSocketError(
+                    format!("Failed to send {} request: {}", method, e)
+                ))?;
+        }
+        Ok(())
+    }
+
+    /// Stream names for one symbol: kline (or aggTrade) and bookTicker
+    fn streams_for(&self, symbol: &str) -> [String; 2] {
+        // Binance stream names are lowercase
+        let symbol_lower = symbol.to_lowercase();
+        let data_stream = match self.mode {
+            BinanceMode::Kline => format!("{}@kline_{}", symbol_lower, self.interval),
+            BinanceMode::AggTrade => format!("{}@aggTrade", symbol_lower),
+        };
+        [data_stream, format!("{}@bookTicker", symbol_lower)]
     }
 
-    /// Build WebSocket URL with stream names for combined kline + bookTicker
+    /// Build WebSocket URL with stream names for combined kline (or aggTrade) + bookTicker
     fn build_url(&self) -> String {
-        let base_url = match self.region {
-            BinanceRegion::International => BINANCE_WS_URL,
-            BinanceRegion::US => BINANCE_US_WS_URL,
+        let base_url = match (&self.endpoint, self.region) {
+            (Some(endpoint), _) => endpoint.as_str(),
+            (None, BinanceRegion::International) => BINANCE_WS_URL,
+            (None, BinanceRegion::US) => BINANCE_US_WS_URL,
         };
 
         if self.symbols.is_empty() {
             return format!("{}/ws", base_url);
         }
 
-        // Convert symbols to lowercase (Binance requirement)
-        let mut streams: Vec<String> = Vec::new();
-
-        for symbol in &self.symbols {
-            let symbol_lower = symbol.to_lowercase();
-            // Add kline stream
-            streams.push(format!("{}@kline_{}", symbol_lower, self.interval));
-            // Add bookTicker stream
-            streams.push(format!("{}@bookTicker", symbol_lower));
-        }
+        let streams: Vec<String> = self
+            .symbols
+            .iter()
+            .flat_map(|symbol| self.streams_for(symbol))
+            .collect();
 
         // Use combined stream endpoint
         format!("{}/stream?streams={}", base_url, streams.join("/"))
@@ -306,6 +698,12 @@ impl BinanceFeed {
                             format!("Failed to parse kline: {}", e)
                         ))?;
                     return self.handle_kline(kline).await;
+                } else if text.contains("\"e\":\"aggTrade\"") {
+                    let trade: AggTrade = serde_json::from_str(&text)
+                        .map_err(|e| crate::error::TradingEngineError::ParseError(
+                            format!("Failed to parse aggTrade: {}", e)
+                        ))?;
+                    return self.handle_agg_trade(trade).await;
                 } else if text.contains("\"u\":") && text.contains("\"b\":") {
                     let ticker: BookTicker = serde_json::from_str(&text)
                         .map_err(|e| crate::error::TradingEngineError::ParseError(
@@ -314,6 +712,21 @@ impl BinanceFeed {
                     return self.handle_book_ticker(ticker).await;
                 }
 
+                // Errors would otherwise leave the feed waiting for data that never comes
+                let error = serde_json::from_str::<BinanceErrorResponse>(&text)
+                    .map(|response| response.error)
+                    .or_else(|_| serde_json::from_str::<BinanceError>(&text));
+                if let Ok(error) = error {
+                    return Err(crate::error::TradingEngineError::WebSocketError(
+                        format!("Binance error {}: {}", error.code, error.msg)
+                    ));
+                }
+
+                if let Ok(ack) = serde_json::from_str::<SubscriptionAck>(&text) {
+                    tracing::debug!("Binance acknowledged request {}: {}", ack.id, ack.result);
+                    return Ok(None);
+                }
+
                 tracing::warn!("Unknown message format: {}", text);
                 Ok(None)
             }
@@ -349,6 +762,12 @@ impl BinanceFeed {
                     format!("Failed to parse kline data: {}", e)
                 ))?;
             self.handle_kline(kline).await
+        } else if stream_name.contains("@aggTrade") {
+            let trade: AggTrade = serde_json::from_value(data)
+                .map_err(|e| crate::error::TradingEngineError::ParseError(
+                    format!("Failed to parse aggTrade data: {}", e)
+                ))?;
+            self.handle_agg_trade(trade).await
         } else if stream_name.contains("@bookTicker") {
             let ticker: BookTicker = serde_json::from_value(data)
                 .map_err(|e| crate::error::TradingEngineError::ParseError(
@@ -365,39 +784,70 @@ impl BinanceFeed {
         // Only return completed candles
         if kline.kline.is_closed {
             let symbol = kline.kline.symbol.to_uppercase();
-
-            // Get bid/ask from cached bookTicker, or estimate if not available
-            let (bid, ask) = if let Some(ticker) = self.book_tickers.get(&symbol) {
-                let bid = ticker.best_bid.parse::<f64>()
-                    .map_err(|e| crate::error::TradingEngineError::ParseError(
-                        format!("Invalid bid price: {}", e)
-                    ))?;
-                let ask = ticker.best_ask.parse::<f64>()
-                    .map_err(|e| crate::error::TradingEngineError::ParseError(
-                        format!("Invalid ask price: {}", e)
-                    ))?;
-                (bid, ask)
-            } else {
-                // Fallback: estimate from close price
-                let close = kline.kline.close.parse::<f64>()
-                    .map_err(|e| crate::error::TradingEngineError::ParseError(
-                        format!("Invalid close price: {}", e)
-                    ))?;
-                let spread = close * 0.001;
-                (close - spread / 2.0, close + spread / 2.0)
-            };
+            let close = kline.kline.close.parse::<f64>()
+                .map_err(|e| crate::error::TradingEngineError::ParseError(
+                    format!("Invalid close price: {}", e)
+                ))?;
+            let (bid, ask, bid_qty, ask_qty) = self.quote(&symbol, close)?;
 
             tracing::info!(
                 "Completed kline for {}: close={}, bid={}, ask={}",
                 symbol, kline.kline.close, bid, ask
             );
 
-            Ok(Some(kline.kline.to_market_data(bid, ask)?))
+            Ok(Some(kline.kline.to_market_data(bid, ask, bid_qty, ask_qty)?))
         } else {
             Ok(None)
         }
     }
 
+    /// Handle aggTrade data
+    async fn handle_agg_trade(&mut self, trade: AggTrade) -> Result<Option<MarketData>> {
+        let symbol = trade.symbol.to_uppercase();
+        let price = trade.price.parse::<f64>()
+            .map_err(|e| crate::error::TradingEngineError::ParseError(
+                format!("Invalid trade price: {}", e)
+            ))?;
+        let (bid, ask, bid_qty, ask_qty) = self.quote(&symbol, price)?;
+
+        tracing::debug!(
+            "Trade for {}: price={}, qty={}, bid={}, ask={}",
+            symbol, trade.price, trade.quantity, bid, ask
+        );
+
+        Ok(Some(trade.to_market_data(bid, ask, bid_qty, ask_qty)?))
+    }
+
+    /// Latest bid/ask and quantities for a symbol
+    ///
+    /// Uses the cached bookTicker, or estimates a 0.1% spread around `price`
+    /// (with unknown quantities) if none has arrived yet.
+    fn quote(&self, symbol: &str, price: f64) -> Result<(f64, f64, Option<f64>, Option<f64>)> {
+        if let Some(ticker) = self.book_tickers.get(symbol) {
+            let bid = ticker.best_bid.parse::<f64>()
+                .map_err(|e| crate::error::TradingEngineError::ParseError(
+                    format!("Invalid bid price: {}", e)
+                ))?;
+            let ask = ticker.best_ask.parse::<f64>()
+                .map_err(|e| crate::error::TradingEngineError::ParseError(
+                    format!("Invalid ask price: {}", e)
+                ))?;
+            let bid_qty = ticker.best_bid_qty.parse::<f64>()
+                .map_err(|e| crate::error::TradingEngineError::ParseError(
+                    format!("Invalid bid quantity: {}", e)
+                ))?;
+            let ask_qty = ticker.best_ask_qty.parse::<f64>()
+                .map_err(|e| crate::error::TradingEngineError::ParseError(
+                    format!("Invalid ask quantity: {}", e)
+                ))?;
+            Ok((bid, ask, Some(bid_qty), Some(ask_qty)))
+        } else {
+            // Fallback: estimate from the last price
+            let spread = price * 0.001;
+            Ok((price - spread / 2.0, price + spread / 2.0, None, None))
+        }
+    }
+
     /// Handle bookTicker data
     async fn handle_book_ticker(&mut self, ticker: BookTicker) -> Result<Option<MarketData>> {
         let symbol = ticker.symbol.to_uppercase();
@@ -409,59 +859,16 @@ impl BinanceFeed {
         // Cache the latest bid/ask
         self.book_tickers.insert(symbol, ticker);
 
-        // Don't return market data for bookTicker (only for completed klines)
+        // Don't return market data for bookTicker (only for completed klines or trades)
         Ok(None)
     }
 
-    /// Send ping to keep connection alive
-    async fn send_ping(&mut self) -> Result<()> {
-        if let Some(stream) = &mut self.ws_stream {
-            stream.send(Message::Ping(vec![])).await
-                .map_err(|e| crate::error::TradingEngineError::WebSocketError(
-                    format!("Failed to send ping: {}", e)
-                ))?;
-            self.last_ping = Some(tokio::time::Instant::now());
-            tracing::trace!("Sent ping to server");
-        }
-        Ok(())
-    }
-}
-
-#[async_trait]
-impl MarketDataSource for BinanceFeed {
-    async fn connect(&mut self) -> Result<()> {
-        let url = self.build_url();
-        tracing::info!("Connecting to Binance WebSocket: {}", url);
-
-        let url = Url::parse(&url)
-            .map_err(|e| crate::error::TradingEngineError::ParseError(
-                format!("Invalid WebSocket URL: {}", e)
-            ))?;
-
-        let (ws_stream, response) = connect_async(url).await
-            .map_err(|e| crate::error::TradingEngineError::WebSocketError(
-                format!("Failed to connect: {}", e)
-            ))?;
-
-        tracing::info!("Connected to Binance, response status: {}", response.status());
-
-        self.ws_stream = Some(ws_stream);
-        self.last_ping = Some(tokio::time::Instant::now());
-
-        Ok(())
-    }
-
-    async fn subscribe(&mut self, symbols: Vec<String>) -> Result<()> {
-        // Update symbols
-        self.symbols = symbols;
-        tracing::info!("Subscribed to symbols: {:?} with interval {}", self.symbols, self.interval);
-
-        // Note: Binance uses URL-based subscriptions, so we need to reconnect
-        // with the new symbols if we want to change subscriptions after connecting
-        Ok(())
-    }
-
-    async fn next_tick(&mut self) -> Result<MarketData> {
+    /// Read messages until a completed kline (or trade, in aggTrade mode) arrives
+    ///
+    /// Each read is bounded by `PONG_TIMEOUT`. If a `deadline` is given, reads
+    /// are also bounded by it and `Ok(None)` is returned once it passes.
+    /// A connection nearing Binance's 24h limit is replaced between reads.
+    async fn read_tick(&mut self, deadline: Option<tokio::time::Instant>) -> Result<Option<MarketData>> {
         // Check if we need to send a ping
         if let Some(last_ping) = self.last_ping {
             if last_ping.elapsed() >= PING_INTERVAL {
@@ -469,21 +876,31 @@ impl MarketDataSource for BinanceFeed {
             }
         }
 
-        // Keep reading messages until we get a completed kline
+        // Keep reading messages until we get a completed kline or trade
         loop {
+            if self.reconnect_due() {
+                self.reconnect(deadline).await;
+            }
+
             // Get mutable reference to stream within loop scope
             let stream = self.ws_stream.as_mut()
                 .ok_or_else(|| crate::error::TradingEngineError::WebSocketError(
                     "Not connected".to_string()
                 ))?;
 
+            // Wait no longer than the pong timeout or the caller's deadline
+            let wait = match deadline {
+                Some(deadline) => PONG_TIMEOUT.min(deadline.saturating_duration_since(tokio::time::Instant::now())),
+                None => PONG_TIMEOUT,
+            };
+
             // Wait for next message with timeout
-            let msg_result = timeout(PONG_TIMEOUT, stream.next()).await;
+            let msg_result = timeout(wait, stream.next()).await;
 
             match msg_result {
                 Ok(Some(Ok(msg))) => {
                     if let Some(market_data) = self.handle_message(msg).await? {
-                        return Ok(market_data);
+                        return Ok(Some(market_data));
                     }
                     // Continue loop if no market data returned (e.g., bookTicker update)
                 }
@@ -497,6 +914,10 @@ impl MarketDataSource for BinanceFeed {
                         "Stream ended unexpectedly".to_string()
                     ));
                 }
+                Err(_) if wait < PONG_TIMEOUT => {
+                    // Caller's deadline elapsed before a tick arrived
+                    return Ok(None);
+                }
                 Err(_) => {
                     return Err(crate::error::TradingEngineError::WebSocketError(
                         format!("No message received within {:?}", PONG_TIMEOUT)
@@ -506,6 +927,62 @@ impl MarketDataSource for BinanceFeed {
         }
     }
 
+    /// Send ping to keep connection alive
+    async fn send_ping(&mut self) -> Result<()> {
+        if let Some(stream) = &mut self.ws_stream {
+            self.control_limiter.acquire().await;
+            stream.send(Message::Ping(vec![])).await
+                .map_err(|e| crate::error::TradingEngineError::WebSocketError(
+                    format!("Failed to send ping: {}", e)
+                ))?;
+            self.last_ping = Some(tokio::time::Instant::now());
+            tracing::trace!("Sent ping to server");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MarketDataSource for BinanceFeed {
+    async fn connect(&mut self) -> Result<()> {
+        // A bare endpoint would connect fine but never deliver data
+        if self.symbols.is_empty() {
+            return Err(crate::error::TradingEngineError::InvalidData(
+                "no symbols subscribed".to_string()
+            ));
+        }
+
+        self.ws_stream = Some(self.open_stream(None).await?);
+        self.connected_at = Some(tokio::time::Instant::now());
+        self.last_ping = self.connected_at;
+        self.reconnect_retry_at = None;
+
+        Ok(())
+    }
+
+    async fn subscribe(&mut self, symbols: Vec<String>) -> Result<()> {
+        // Update symbols (at least one is needed before connecting)
+        self.symbols = symbols;
+        tracing::info!("Subscribed to symbols: {:?} with interval {}", self.symbols, self.interval);
+
+        // Note: the symbols are baked into the URL when connecting; use
+        // `update_subscriptions` to change them on a live connection
+        Ok(())
+    }
+
+    async fn next_tick(&mut self) -> Result<MarketData> {
+        match self.read_tick(None).await? {
+            Some(market_data) => Ok(market_data),
+            None => Err(crate::error::TradingEngineError::WebSocketError(
+                format!("No message received within {:?}", PONG_TIMEOUT)
+            )),
+        }
+    }
+
+    async fn next_tick_timeout(&mut self, dur: Duration) -> Result<Option<MarketData>> {
+        self.read_tick(Some(tokio::time::Instant::now() + dur)).await
+    }
+
     async fn disconnect(&mut self) -> Result<()> {
         if let Some(mut stream) = self.ws_stream.take() {
             stream.close(None).await
@@ -514,6 +991,8 @@ impl MarketDataSource for BinanceFeed {
                 ))?;
             tracing::info!("Disconnected from Binance");
         }
+        self.connected_at = None;
+        self.reconnect_retry_at = None;
         Ok(())
     }
 
@@ -521,3 +1000,363 @@ impl MarketDataSource for BinanceFeed {
         "binance"
     }
 }
+
+/// Recent klines from the Binance REST API
+///
+/// Fetches `GET /api/v3/klines` for the configured interval, keeping only
+/// closed klines. Bars are stamped with the kline close time, like the live
+/// kline stream. Binance returns at most 1000 klines per request, so larger
+/// counts are capped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use trading_engine::sources::{BarHistory, BinanceKlineHistory, BinanceRegion};
+///
+/// # async fn example() -> trading_engine::Result<()> {
+/// let history = BinanceKlineHistory::new("1m", BinanceRegion::US);
+/// let bars = history.recent_bars("BTCUSDT", 200).await?;
+/// println!("{} bars, last close {:.2}", bars.len(), bars[bars.len() - 1].close);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BinanceKlineHistory {
+    interval: String,
+    region: BinanceRegion,
+    client: reqwest::Client,
+}
+
+impl BinanceKlineHistory {
+    /// Fetch klines of `interval` (e.g. "1m") from `region`
+    pub fn new(interval: impl Into<String>, region: BinanceRegion) -> Self {
+        Self {
+            interval: interval.into(),
+            region,
+            client: reqwest::Client::builder()
+                .timeout(REST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    fn klines_url(&self) -> String {
+        let base = match self.region {
+            BinanceRegion::International => BINANCE_REST_URL,
+            BinanceRegion::US => BINANCE_US_REST_URL,
+        };
+        format!("{}/api/v3/klines", base)
+    }
+}
+
+#[async_trait]
+impl BarHistory for BinanceKlineHistory {
+    async fn recent_bars(&self, symbol: &str, count: usize) -> Result<Vec<MarketData>> {
+        // One extra for the kline still forming, which is dropped
+        let limit = (count + 1).min(MAX_KLINES_PER_REQUEST);
+        let response = self
+            .client
+            .get(self.klines_url())
+            .query(&[("symbol", symbol.to_uppercase()), ("interval", self.interval.clone()), ("limit", limit.to_string())])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| crate::error::TradingEngineError::IoError(std::io::Error::other(format!("Kline request failed: {}", e))))?;
+        let body = response
+            .text()
+            .await
+            .map_err(|e| crate::error::TradingEngineError::IoError(std::io::Error::other(format!("Kline response failed: {}", e))))?;
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut bars = parse_rest_klines(&body, symbol, now).map_err(crate::error::TradingEngineError::ParseError)?;
+        let excess = bars.len().saturating_sub(count);
+        bars.drain(..excess);
+        Ok(bars)
+    }
+}
+
+/// Parse a `/api/v3/klines` response body into bars
+///
+/// Each kline is an array `[open time, open, high, low, close, volume,
+/// close time, ...]` with prices as strings. Klines closing after `now`
+/// are still forming and left out.
+fn parse_rest_klines(body: &str, symbol: &str, now: i64) -> std::result::Result<Vec<MarketData>, String> {
+    let klines: Vec<Vec<serde_json::Value>> =
+        serde_json::from_str(body).map_err(|e| format!("Invalid klines response: {}", e))?;
+
+    let mut bars = Vec::with_capacity(klines.len());
+    for kline in klines {
+        let number = |index: usize| -> std::result::Result<f64, String> {
+            match kline.get(index) {
+                Some(serde_json::Value::String(value)) => value.parse().map_err(|e| format!("Invalid kline field {}: {}", index, e)),
+                Some(serde_json::Value::Number(value)) => value.as_f64().ok_or_else(|| format!("Invalid kline field {}", index)),
+                _ => Err(format!("Missing kline field {}", index)),
+            }
+        };
+        let close_time = number(6)? as i64;
+        if close_time > now {
+            continue;
+        }
+        let close = number(4)?;
+        bars.push(MarketData {
+            symbol: symbol.to_uppercase(),
+            timestamp: close_time,
+            open: number(1)?,
+            high: number(2)?,
+            low: number(3)?,
+            close,
+            volume: number(5)? as u64,
+            bid: close,
+            ask: close,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
+        });
+    }
+    Ok(bars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Two closed 1m klines and one still forming at 1672531319999
+    const REST_KLINES_JSON: &str = r#"[
+        [1672531200000, "42000.00", "42100.00", "41950.00", "42050.00", "12.5", 1672531259999, "0", 100, "0", "0", "0"],
+        [1672531260000, "42050.00", "42200.00", "42000.00", "42150.00", "8.25", 1672531319999, "0", 80, "0", "0", "0"],
+        [1672531320000, "42150.00", "42160.00", "42100.00", "42110.00", "1.0", 1672531379999, "0", 10, "0", "0", "0"]
+    ]"#;
+
+    #[test]
+    fn test_parse_rest_klines_drops_forming_kline() {
+        let bars = parse_rest_klines(REST_KLINES_JSON, "btcusdt", 1672531320000).unwrap();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].symbol, "BTCUSDT");
+        assert_eq!(bars[0].timestamp, 1672531259999);
+        assert_eq!((bars[1].open, bars[1].high, bars[1].low, bars[1].close), (42050.0, 42200.0, 42000.0, 42150.0));
+        assert_eq!(bars[1].volume, 8);
+        assert!(bars.iter().all(|bar| bar.is_final));
+    }
+
+    const AGG_TRADE_JSON: &str = r#"{
+        "e": "aggTrade",
+        "E": 1672515782136,
+        "s": "BTCUSDT",
+        "a": 12345,
+        "p": "42000.50",
+        "q": "3.25",
+        "f": 100,
+        "l": 105,
+        "T": 1672515782130,
+        "m": true,
+        "M": true
+    }"#;
+
+    #[test]
+    fn test_parse_agg_trade() {
+        let trade: AggTrade = serde_json::from_str(AGG_TRADE_JSON).unwrap();
+        assert_eq!(trade.symbol, "BTCUSDT");
+        assert_eq!(trade.agg_trade_id, 12345);
+        assert_eq!(trade.trade_time, 1672515782130);
+        assert!(trade.is_buyer_maker);
+
+        let data = trade.to_market_data(41999.0, 42001.0, Some(1.5), Some(2.0)).unwrap();
+        assert_eq!(data.timestamp, 1672515782130);
+        assert_eq!((data.open, data.high, data.low, data.close), (42000.5, 42000.5, 42000.5, 42000.5));
+        assert_eq!(data.volume, 3);
+        assert_eq!((data.bid, data.ask), (41999.0, 42001.0));
+        assert_eq!((data.bid_qty, data.ask_qty), (Some(1.5), Some(2.0)));
+    }
+
+    #[tokio::test]
+    async fn test_agg_trade_uses_cached_book_ticker() {
+        let mut feed = BinanceFeed::new_agg_trades(vec!["BTCUSDT".to_string()], BinanceRegion::US);
+        assert!(feed.build_url().ends_with("streams=btcusdt@aggTrade/btcusdt@bookTicker"));
+
+        let ticker = serde_json::json!({
+            "u": 1, "s": "BTCUSDT", "b": "41999.00", "B": "1.5", "a": "42001.00", "A": "2.0"
+        });
+        assert!(feed.handle_stream_data("btcusdt@bookTicker", ticker).await.unwrap().is_none());
+
+        let trade: serde_json::Value = serde_json::from_str(AGG_TRADE_JSON).unwrap();
+        let data = feed
+            .handle_stream_data("btcusdt@aggTrade", trade)
+            .await
+            .unwrap()
+            .expect("trade should produce a tick");
+        assert_eq!(data.symbol, "BTCUSDT");
+        assert_eq!(data.close, 42000.5);
+        assert_eq!((data.bid, data.ask), (41999.0, 42001.0));
+    }
+
+    #[tokio::test]
+    async fn test_error_payload_is_reported() {
+        let mut feed = BinanceFeed::new(vec!["BTCUSDT".to_string()], "1m".to_string());
+
+        let wrapped = r#"{"error":{"code":2,"msg":"Invalid request: unknown stream"},"id":1}"#;
+        let err = feed.handle_message(Message::Text(wrapped.to_string())).await.unwrap_err();
+        assert!(err.to_string().contains("Binance error 2: Invalid request: unknown stream"));
+
+        let bare = r#"{"code":-1121,"msg":"Invalid symbol."}"#;
+        let err = feed.handle_message(Message::Text(bare.to_string())).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid symbol."));
+    }
+
+    #[tokio::test]
+    async fn test_subscription_ack_is_ignored() {
+        let mut feed = BinanceFeed::new(vec!["BTCUSDT".to_string()], "1m".to_string());
+
+        let ack = r#"{"result":null,"id":1}"#;
+        let result = feed.handle_message(Message::Text(ack.to_string())).await.unwrap();
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_subscriptions_before_connect() {
+        let mut feed = BinanceFeed::new(
+            vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()],
+            "1m".to_string(),
+        );
+        let ticker = serde_json::json!({
+            "u": 1, "s": "ETHUSDT", "b": "2999.00", "B": "1.5", "a": "3001.00", "A": "2.0"
+        });
+        feed.handle_stream_data("ethusdt@bookTicker", ticker).await.unwrap();
+
+        feed.update_subscriptions(&["SOLUSDT".to_string(), "BTCUSDT".to_string()], &["ETHUSDT".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(feed.symbols(), ["BTCUSDT".to_string(), "SOLUSDT".to_string()]);
+        assert!(!feed.book_tickers.contains_key("ETHUSDT"));
+        assert!(feed.build_url().ends_with(
+            "streams=btcusdt@kline_1m/btcusdt@bookTicker/solusdt@kline_1m/solusdt@bookTicker"
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_control_messages_are_paced() {
+        let mut limiter = ControlRateLimiter::new(CONTROL_MESSAGES_PER_SEC);
+        let start = tokio::time::Instant::now();
+
+        let mut sent_at = Vec::new();
+        for _ in 0..10 {
+            limiter.acquire().await;
+            sent_at.push(start.elapsed());
+        }
+
+        // A full bucket lets five through at once, then one every 200ms
+        assert!(sent_at[..5].iter().all(|&t| t == Duration::ZERO));
+        for pair in sent_at[4..].windows(2) {
+            assert!(pair[1] - pair[0] >= Duration::from_millis(199), "{:?}", sent_at);
+        }
+        assert!(sent_at[9] >= Duration::from_millis(999));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reconnect_is_due_before_connection_limit() {
+        let mut feed = BinanceFeed::new(vec!["BTCUSDT".to_string()], "1m".to_string())
+            .with_max_connection_age(Duration::from_secs(60));
+        assert!(!feed.reconnect_due());
+
+        feed.connected_at = Some(tokio::time::Instant::now());
+        tokio::time::advance(Duration::from_secs(50)).await;
+        assert!(!feed.reconnect_due());
+
+        // A tenth of a short limit is the margin: due at 54s, before the 60s limit
+        tokio::time::advance(Duration::from_secs(4)).await;
+        assert!(feed.reconnect_due());
+
+        // The full-length limit keeps the five minute margin
+        let feed = BinanceFeed::new(vec!["BTCUSDT".to_string()], "1m".to_string());
+        assert_eq!(feed.reconnect_after(), MAX_CONNECTION_AGE - RECONNECT_MARGIN);
+    }
+
+    /// Serve `connections` WebSocket clients on a local port, then stop accepting
+    ///
+    /// Each connection streams an aggTrade every 10ms until the client goes
+    /// away; only the first also gets a bookTicker. Returns the endpoint and
+    /// how long each finished connection lasted.
+    async fn serve_trades(connections: usize) -> (String, Arc<parking_lot::Mutex<Vec<Duration>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("ws://{}", listener.local_addr().unwrap());
+        let lifetimes = Arc::new(parking_lot::Mutex::new(Vec::new()));
+
+        let served = lifetimes.clone();
+        tokio::spawn(async move {
+            for i in 0..connections {
+                let (tcp, _) = listener.accept().await.unwrap();
+                let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+                let served = served.clone();
+                tokio::spawn(async move {
+                    let opened = std::time::Instant::now();
+                    if i == 0 {
+                        let ticker = r#"{"stream":"btcusdt@bookTicker","data":{"u":1,"s":"BTCUSDT","b":"41999.00","B":"1.5","a":"42001.00","A":"2.0"}}"#;
+                        let _ = ws.send(Message::Text(ticker.to_string())).await;
+                    }
+                    let trade = format!(r#"{{"stream":"btcusdt@aggTrade","data":{}}}"#, AGG_TRADE_JSON);
+                    while ws.send(Message::Text(trade.clone())).await.is_ok() {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                    }
+                    served.lock().push(opened.elapsed());
+                });
+            }
+            // Dropping the listener refuses further connections
+        });
+        (endpoint, lifetimes)
+    }
+
+    #[tokio::test]
+    async fn test_feed_reconnects_before_connection_limit() {
+        let (endpoint, lifetimes) = serve_trades(2).await;
+        let mut feed = BinanceFeed::new_agg_trades(vec!["BTCUSDT".to_string()], BinanceRegion::US)
+            .with_endpoint(endpoint)
+            .with_max_connection_age(Duration::from_secs(1));
+        feed.connect().await.unwrap();
+
+        // Reconnects at 900ms; ticks keep their cached quotes throughout
+        let start = std::time::Instant::now();
+        while start.elapsed() < Duration::from_millis(1300) {
+            let data = feed.next_tick().await.unwrap();
+            assert_eq!((data.bid, data.ask), (41999.0, 42001.0));
+        }
+
+        // The first connection was closed by the feed before the limit
+        let lifetimes = lifetimes.lock().clone();
+        assert_eq!(lifetimes.len(), 1);
+        assert!(lifetimes[0] > Duration::from_millis(800) && lifetimes[0] < Duration::from_secs(1), "{:?}", lifetimes);
+        feed.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_reconnect_keeps_old_connection() {
+        // Only one connection is accepted, so the early reconnect fails
+        let (endpoint, _) = serve_trades(1).await;
+        let mut feed = BinanceFeed::new_agg_trades(vec!["BTCUSDT".to_string()], BinanceRegion::US)
+            .with_endpoint(endpoint)
+            .with_max_connection_age(Duration::from_secs(1));
+        feed.connect().await.unwrap();
+        let connected_at = feed.connected_at;
+
+        let start = std::time::Instant::now();
+        while start.elapsed() < Duration::from_millis(1100) {
+            feed.next_tick().await.unwrap();
+        }
+
+        // Still on the first connection, with the next attempt backed off
+        assert_eq!(feed.connected_at, connected_at);
+        assert!(feed.reconnect_retry_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_connect_without_symbols_fails() {
+        let mut feed = BinanceFeed::new(vec![], "1m".to_string());
+
+        let result = tokio::time::timeout(std::time::Duration::from_secs(1), feed.connect())
+            .await
+            .expect("connect should fail immediately");
+        match result {
+            Err(crate::error::TradingEngineError::InvalidData(msg)) => assert_eq!(msg, "no symbols subscribed"),
+            other => panic!("expected InvalidData, got {:?}", other),
+        }
+    }
+}
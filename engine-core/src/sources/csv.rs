@@ -0,0 +1,168 @@
+//! Historical bars read from a CSV file.
+
+use super::TimestampUnit;
+use crate::MarketData;
+use std::io;
+use std::path::Path;
+
+/// Columns every file must have, in the order bars are built from them
+const REQUIRED_COLUMNS: [&str; 6] = ["timestamp", "open", "high", "low", "close", "volume"];
+
+/// Read every bar of a CSV file, ready to replay with [`ReplayFeed`](super::ReplayFeed)
+///
+/// The first line is a header naming the columns, which may come in any
+/// order: `timestamp`, `open`, `high`, `low`, `close` and `volume` are
+/// required, and a `symbol` column, if present, overrides `symbol`. Timestamps
+/// are read in `unit`, whole or fractional, and stored as milliseconds. Bid
+/// and ask are set to the close.
+///
+/// # Errors
+///
+/// Returns an `InvalidData` error naming the line if the header lacks a
+/// required column or a value doesn't parse.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use trading_engine::sources::{csv, ReplayFeed, TimestampUnit};
+///
+/// let bars = csv::read_bars("data/btcusdt_1m.csv", "BTCUSDT", TimestampUnit::Seconds)?;
+/// let feed = ReplayFeed::new(bars);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn read_bars(
+    path: impl AsRef<Path>,
+    symbol: &str,
+    unit: TimestampUnit,
+) -> io::Result<Vec<MarketData>> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)?;
+    parse_bars(&text, symbol, unit).map_err(|e| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e))
+    })
+}
+
+/// Parse CSV text into bars; see [`read_bars`]
+pub fn parse_bars(
+    text: &str,
+    symbol: &str,
+    unit: TimestampUnit,
+) -> std::result::Result<Vec<MarketData>, String> {
+    let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    let header: Vec<&str> = match lines.next() {
+        Some((_, line)) => line.split(',').map(str::trim).collect(),
+        None => return Ok(Vec::new()),
+    };
+    let index_of = |name: &str| header.iter().position(|column| *column == name);
+
+    let mut required = [0; 6];
+    for (slot, name) in required.iter_mut().zip(REQUIRED_COLUMNS) {
+        *slot = index_of(name).ok_or_else(|| format!("missing column '{}'", name))?;
+    }
+    let symbol_column = index_of("symbol");
+
+    lines
+        .map(|(index, line)| {
+            let values: Vec<&str> = line.split(',').map(str::trim).collect();
+            let value = |column: usize| {
+                values.get(column).copied().ok_or_else(|| {
+                    format!("line {}: missing '{}' value", index + 1, header[column])
+                })
+            };
+            let number = |slot: usize| {
+                let raw = value(required[slot])?;
+                raw.parse::<f64>().map_err(|_| {
+                    format!("line {}: '{}' is not a number in '{}'", index + 1, raw, REQUIRED_COLUMNS[slot])
+                })
+            };
+
+            // Whole timestamps stay exact; only fractional ones go through f64
+            let raw_timestamp = value(required[0])?;
+            let timestamp = match raw_timestamp.parse::<i64>() {
+                Ok(raw) => unit.to_millis(raw),
+                Err(_) => unit.to_millis_f64(number(0)?),
+            };
+            let close = number(4)?;
+
+            Ok(MarketData {
+                symbol: match symbol_column {
+                    Some(column) => value(column)?.to_string(),
+                    None => symbol.to_string(),
+                },
+                timestamp,
+                open: number(1)?,
+                high: number(2)?,
+                low: number(3)?,
+                close,
+                volume: number(5)? as u64,
+                bid: close,
+                ask: close,
+                bid_qty: None,
+                ask_qty: None,
+                is_final: true,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECONDS_CSV: &str = "\
+timestamp,open,high,low,close,volume
+1700000000,100.0,101.0,99.0,100.5,10
+1700000060,100.5,102.0,100.0,101.5,12
+1700000120.5,101.5,101.5,98.5,99.0,30";
+
+    #[test]
+    fn test_seconds_feed_normalized_to_millis() {
+        let path = std::env::temp_dir().join(format!("csv_seconds_{}.csv", std::process::id()));
+        std::fs::write(&path, SECONDS_CSV).unwrap();
+        let bars = read_bars(&path, "BTCUSDT", TimestampUnit::Seconds).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let timestamps: Vec<i64> = bars.iter().map(|bar| bar.timestamp).collect();
+        assert_eq!(timestamps, vec![1_700_000_000_000, 1_700_000_060_000, 1_700_000_120_500]);
+        assert_eq!(bars[1].close, 101.5);
+        assert_eq!(bars[0].symbol, "BTCUSDT");
+    }
+
+    #[test]
+    fn test_config_without_symbol_rejected() {
+        use crate::config::CsvConfig;
+        use crate::error::TradingEngineError;
+
+        let path = std::env::temp_dir().join(format!("csv_no_symbol_{}.csv", std::process::id()));
+        std::fs::write(&path, SECONDS_CSV).unwrap();
+        let mut config = CsvConfig {
+            path: path.to_string_lossy().into_owned(),
+            symbol: None,
+            timestamp_unit: TimestampUnit::Seconds,
+        };
+        let missing = config.feed();
+        config.symbol = Some("BTCUSDT".to_string());
+        let configured = config.feed();
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(missing, Err(TradingEngineError::ConfigError(_))));
+        assert!(configured.is_ok());
+    }
+
+    #[test]
+    fn test_columns_by_header_name() {
+        let csv = "symbol,volume,close,low,high,open,timestamp\nETHUSDT,5,10.5,9.0,11.0,10.0,2000";
+        let bars = parse_bars(csv, "BTCUSDT", TimestampUnit::default()).unwrap();
+        assert_eq!(bars[0].symbol, "ETHUSDT");
+        assert_eq!(bars[0].timestamp, 2000);
+        assert_eq!(bars[0].open, 10.0);
+        assert_eq!(bars[0].volume, 5);
+    }
+
+    #[test]
+    fn test_missing_column_rejected() {
+        let err = parse_bars("timestamp,open,high,low,close\n1,1,1,1,1", "X", TimestampUnit::Seconds)
+            .unwrap_err();
+        assert!(err.contains("volume"));
+    }
+}
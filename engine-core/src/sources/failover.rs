@@ -0,0 +1,327 @@
+//! Failover between a primary and a backup source.
+
+use super::*;
+use async_trait::async_trait;
+use tokio::time::Instant;
+
+/// Consecutive primary errors tolerated before failing over
+const DEFAULT_MAX_FAILURES: u32 = 3;
+
+/// How often the primary is retried while running on the secondary
+const DEFAULT_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long a probe waits for a primary tick before returning to the secondary
+const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Market data source that falls back to a secondary source when the primary stalls
+///
+/// Ticks come from the primary while it is healthy. If the primary produces
+/// no tick within `stall_timeout`, or fails several times in a row (3 by
+/// default), the feed fails over and serves ticks from the secondary. While
+/// on the secondary, the primary is probed every 30 seconds by default; the
+/// first tick it delivers switches the feed back. A probe waits at most one
+/// second by default, so it holds up the secondary's ticks only briefly.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use trading_engine::{MarketDataSource, sources::{BinanceFeed, FailoverSource, SimulatedFeed}};
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let primary = BinanceFeed::new(vec!["BTCUSDT".to_string()], "1m".to_string());
+///     let secondary = SimulatedFeed::new("BTCUSDT".to_string(), 50000.0);
+///     let mut feed = FailoverSource::new(primary, secondary, Duration::from_secs(90));
+///
+///     feed.connect().await?;
+///     feed.subscribe(vec!["BTCUSDT".to_string()]).await?;
+///     let data = feed.next_tick().await?;
+///     println!("{} from {}", data.close, feed.active_source_name());
+///     Ok(())
+/// }
+/// ```
+pub struct FailoverSource {
+    primary: Box<dyn MarketDataSource>,
+    secondary: Box<dyn MarketDataSource>,
+    stall_timeout: Duration,
+    max_failures: u32,
+    probe_interval: Duration,
+    probe_timeout: Duration,
+    /// Consecutive primary errors
+    failures: u32,
+    /// When the feed failed over, or last probed the primary (`None` while on the primary)
+    last_probe: Option<Instant>,
+}
+
+impl FailoverSource {
+    /// Serve ticks from `primary`, failing over to `secondary` when it stalls
+    ///
+    /// The primary counts as stalled when it produces no tick within
+    /// `stall_timeout`.
+    pub fn new(
+        primary: impl MarketDataSource + 'static,
+        secondary: impl MarketDataSource + 'static,
+        stall_timeout: Duration,
+    ) -> Self {
+        Self {
+            primary: Box::new(primary),
+            secondary: Box::new(secondary),
+            stall_timeout,
+            max_failures: DEFAULT_MAX_FAILURES,
+            probe_interval: DEFAULT_PROBE_INTERVAL,
+            probe_timeout: DEFAULT_PROBE_TIMEOUT,
+            failures: 0,
+            last_probe: None,
+        }
+    }
+
+    /// Fail over after `max_failures` consecutive primary errors (default 3)
+    pub fn with_max_failures(mut self, max_failures: u32) -> Self {
+        self.max_failures = max_failures.max(1);
+        self
+    }
+
+    /// Retry the primary this often while on the secondary (default 30s)
+    pub fn with_probe_interval(mut self, probe_interval: Duration) -> Self {
+        self.probe_interval = probe_interval;
+        self
+    }
+
+    /// Wait this long for a primary tick when probing (default 1s)
+    pub fn with_probe_timeout(mut self, probe_timeout: Duration) -> Self {
+        self.probe_timeout = probe_timeout;
+        self
+    }
+
+    /// Check whether ticks are currently coming from the secondary
+    pub fn is_on_secondary(&self) -> bool {
+        self.last_probe.is_some()
+    }
+
+    /// Name of the source ticks are currently coming from
+    pub fn active_source_name(&self) -> &str {
+        if self.is_on_secondary() {
+            self.secondary.source_name()
+        } else {
+            self.primary.source_name()
+        }
+    }
+
+    fn fail_over(&mut self, reason: &str) {
+        tracing::warn!(
+            "Failing over from {} to {}: {}",
+            self.primary.source_name(),
+            self.secondary.source_name(),
+            reason
+        );
+        self.failures = 0;
+        self.last_probe = Some(Instant::now());
+    }
+
+    /// Try the primary again if a probe is due, returning its tick if it has recovered
+    async fn probe_primary(&mut self) -> Option<MarketData> {
+        let last_probe = self.last_probe?;
+        if last_probe.elapsed() < self.probe_interval {
+            return None;
+        }
+
+        self.last_probe = Some(Instant::now());
+        match self.primary.next_tick_timeout(self.probe_timeout).await {
+            Ok(Some(data)) => {
+                tracing::info!("{} recovered, switching back from {}", self.primary.source_name(), self.secondary.source_name());
+                self.last_probe = None;
+                Some(data)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                tracing::debug!("{} still failing: {}", self.primary.source_name(), e);
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl MarketDataSource for FailoverSource {
+    async fn connect(&mut self) -> Result<()> {
+        self.secondary.connect().await?;
+        if let Err(e) = self.primary.connect().await {
+            self.fail_over(&format!("connect failed: {}", e));
+        }
+        Ok(())
+    }
+
+    async fn subscribe(&mut self, symbols: Vec<String>) -> Result<()> {
+        self.secondary.subscribe(symbols.clone()).await?;
+        if let Err(e) = self.primary.subscribe(symbols).await {
+            self.fail_over(&format!("subscribe failed: {}", e));
+        }
+        Ok(())
+    }
+
+    async fn next_tick(&mut self) -> Result<MarketData> {
+        while !self.is_on_secondary() {
+            match self.primary.next_tick_timeout(self.stall_timeout).await {
+                Ok(Some(data)) => {
+                    self.failures = 0;
+                    return Ok(data);
+                }
+                Ok(None) => {
+                    self.fail_over(&format!("no tick within {:?}", self.stall_timeout));
+                }
+                Err(e) => {
+                    self.failures += 1;
+                    if self.failures >= self.max_failures {
+                        self.fail_over(&format!("{} consecutive errors, last: {}", self.failures, e));
+                    } else {
+                        tracing::warn!("{} error ({} of {}): {}", self.primary.source_name(), self.failures, self.max_failures, e);
+                    }
+                }
+            }
+        }
+
+        if let Some(data) = self.probe_primary().await {
+            return Ok(data);
+        }
+        self.secondary.next_tick().await
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        self.primary.reset().await?;
+        self.secondary.reset().await
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        let primary = self.primary.disconnect().await;
+        self.secondary.disconnect().await?;
+        primary
+    }
+
+    fn source_name(&self) -> &str {
+        "failover"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::TradingEngineError;
+    use crate::sources::ReplayFeed;
+
+    /// Source that fails its first `failures` ticks, then replays `bars`
+    struct FlakySource {
+        failures: usize,
+        bars: ReplayFeed,
+    }
+
+    #[async_trait]
+    impl MarketDataSource for FlakySource {
+        async fn connect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn subscribe(&mut self, _symbols: Vec<String>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn next_tick(&mut self) -> Result<MarketData> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err(TradingEngineError::NotConnected);
+            }
+            self.bars.next_tick().await
+        }
+
+        async fn disconnect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn source_name(&self) -> &str {
+            "flaky"
+        }
+    }
+
+    fn bars(prices: &[f64]) -> ReplayFeed {
+        ReplayFeed::new(
+            prices
+                .iter()
+                .enumerate()
+                .map(|(i, &price)| MarketData::from_trade("BTCUSDT", price, 1.0, i as i64))
+                .collect(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_failing_primary_falls_over_to_secondary() {
+        let primary = FlakySource { failures: usize::MAX, bars: bars(&[]) };
+        let mut feed = FailoverSource::new(primary, bars(&[1.0, 2.0, 3.0]), Duration::from_secs(1));
+        feed.connect().await.unwrap();
+
+        let mut closes = Vec::new();
+        for _ in 0..3 {
+            closes.push(feed.next_tick().await.unwrap().close);
+        }
+
+        assert_eq!(closes, vec![1.0, 2.0, 3.0]);
+        assert!(feed.is_on_secondary());
+        assert_eq!(feed.active_source_name(), "replay");
+    }
+
+    /// Source that never produces a tick
+    struct SilentSource;
+
+    #[async_trait]
+    impl MarketDataSource for SilentSource {
+        async fn connect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn subscribe(&mut self, _symbols: Vec<String>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn next_tick(&mut self) -> Result<MarketData> {
+            std::future::pending().await
+        }
+
+        async fn disconnect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn source_name(&self) -> &str {
+            "silent"
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_probe_waits_only_probe_timeout() {
+        let mut feed = FailoverSource::new(SilentSource, bars(&[1.0, 2.0]), Duration::from_secs(60))
+            .with_probe_interval(Duration::ZERO)
+            .with_probe_timeout(Duration::from_millis(500));
+
+        // The first tick waits out the stall timeout before failing over
+        assert_eq!(feed.next_tick().await.unwrap().close, 1.0);
+
+        // Later probes hold the secondary back by the probe timeout only
+        let start = Instant::now();
+        assert_eq!(feed.next_tick().await.unwrap().close, 2.0);
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn test_switches_back_when_primary_recovers() {
+        let primary = FlakySource { failures: 3, bars: bars(&[100.0]) };
+        let mut feed = FailoverSource::new(primary, bars(&[1.0]), Duration::from_secs(1))
+            .with_max_failures(2)
+            .with_probe_interval(Duration::ZERO);
+
+        // Two errors fail over; the probe on the same call hits the third
+        assert_eq!(feed.next_tick().await.unwrap().close, 1.0);
+        assert!(feed.is_on_secondary());
+
+        // The next probe succeeds and switches back
+        assert_eq!(feed.next_tick().await.unwrap().close, 100.0);
+        assert!(!feed.is_on_secondary());
+    }
+}
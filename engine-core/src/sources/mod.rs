@@ -7,6 +7,17 @@
 //!
 //! - [`SimulatedFeed`] - Random walk price generation for testing
 //! - [`BinanceFeed`] - Real-time cryptocurrency data from Binance
+//! - [`ReplayFeed`] - Replays a fixed series of bars, e.g. for backtests
+//! - [`FailoverSource`] - Falls back to a second source when the first stalls
+//! - `ParquetFeed` - Streams historical bars from a Parquet file (`parquet` feature)
+//!
+//! [`csv::read_bars`] loads a CSV file of bars for a [`ReplayFeed`].
+//!
+//! # Timestamps
+//!
+//! Sources reading recorded data take a [`TimestampUnit`] for the raw stamps
+//! and convert on ingestion, so [`MarketData::timestamp`] is always Unix
+//! milliseconds once a bar leaves a source.
 //!
 //! # The MarketDataSource Trait
 //!
@@ -61,6 +72,7 @@
 
 use async_trait::async_trait;
 use crate::{MarketData, Result};
+use std::time::Duration;
 
 /// Asynchronous market data source interface.
 ///
@@ -142,6 +154,45 @@ pub trait MarketDataSource: Send + Sync {
     /// - A timeout occurs
     async fn next_tick(&mut self) -> Result<MarketData>;
 
+    /// Get the next market data tick, giving up after `dur`.
+    ///
+    /// Unlike wrapping [`next_tick()`](MarketDataSource::next_tick) in
+    /// `tokio::time::timeout`, an elapsed timeout is not an error: it is
+    /// reported as `Ok(None)` so callers can simply poll again.
+    ///
+    /// The default implementation wraps `next_tick()` in a timeout. Sources
+    /// with their own internal timeouts (e.g. Binance) override this so both
+    /// deadlines are honoured together.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(data))` - A tick arrived before the deadline
+    /// - `Ok(None)` - No tick arrived within `dur`
+    ///
+    /// # Errors
+    ///
+    /// Returns any error produced by the underlying source.
+    async fn next_tick_timeout(&mut self, dur: Duration) -> Result<Option<MarketData>> {
+        match tokio::time::timeout(dur, self.next_tick()).await {
+            Ok(result) => result.map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Rewind the source so the next tick is the first one again.
+    ///
+    /// Replay sources (files, recorded bars) seek back to the start so the same
+    /// data can be fed through several runs. Live feeds have no start to return
+    /// to, so the default implementation does nothing and the feed simply
+    /// carries on from where it is.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying source cannot be rewound.
+    async fn reset(&mut self) -> Result<()> {
+        Ok(())
+    }
+
     /// Disconnect from the data source.
     ///
     /// This should clean up all resources including closing connections,
@@ -160,12 +211,33 @@ pub trait MarketDataSource: Send + Sync {
     fn source_name(&self) -> &str;
 }
 
+/// Source of recent completed bars, fetched on demand
+///
+/// Used to seed a runner's window before its first live tick, so its
+/// indicators don't have to warm up on live data. See
+/// [`BinanceKlineHistory`] for the Binance REST implementation.
+#[async_trait]
+pub trait BarHistory: Send + Sync {
+    /// Fetch up to `count` of the most recent completed bars for `symbol`, oldest first
+    async fn recent_bars(&self, symbol: &str, count: usize) -> Result<Vec<MarketData>>;
+}
+
 // Module declarations
 pub mod simulated;
 pub mod binance;
-// pub mod csv;
+pub mod replay;
+pub mod failover;
+pub mod timestamp;
+pub mod csv;
+#[cfg(feature = "parquet")]
+pub mod parquet;
 // pub mod alpaca;
 
 // Re-exports
 pub use simulated::SimulatedFeed;
-pub use binance::{BinanceFeed, BinanceRegion};
+pub use binance::{BinanceFeed, BinanceKlineHistory, BinanceMode, BinanceRegion};
+pub use replay::ReplayFeed;
+pub use failover::FailoverSource;
+pub use timestamp::TimestampUnit;
+#[cfg(feature = "parquet")]
+pub use self::parquet::ParquetFeed;
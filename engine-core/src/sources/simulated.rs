@@ -5,10 +5,16 @@ use async_trait::async_trait;
 use rand::Rng;
 use std::time::Duration;
 
+/// Default delay between simulated ticks
+const DEFAULT_TICK_INTERVAL: Duration = Duration::from_millis(100);
+
 pub struct SimulatedFeed {
     symbol: String,
     current_price: f64,
     tick_count: u64,
+    tick_interval: Duration,
+    /// First bar's timestamp and spacing in ms (`None` stamps ticks with the wall clock)
+    timestamps: Option<(i64, i64)>,
 }
 
 impl SimulatedFeed {
@@ -17,8 +23,28 @@ impl SimulatedFeed {
             symbol,
             current_price: starting_price,
             tick_count: 0,
+            tick_interval: DEFAULT_TICK_INTERVAL,
+            timestamps: None,
         }
     }
+
+    /// Set the delay between generated ticks (default 100ms)
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.tick_interval = interval;
+        self
+    }
+
+    /// Get the delay between generated ticks
+    pub fn tick_interval(&self) -> Duration {
+        self.tick_interval
+    }
+
+    /// Stamp bars `start_timestamp`, `start_timestamp + interval_ms`, ...
+    /// instead of with the wall clock, for reproducible runs
+    pub fn with_timestamps(mut self, start_timestamp: i64, interval_ms: i64) -> Self {
+        self.timestamps = Some((start_timestamp, interval_ms));
+        self
+    }
 }
 
 #[async_trait]
@@ -35,7 +61,7 @@ impl MarketDataSource for SimulatedFeed {
 
     async fn next_tick(&mut self) -> Result<MarketData> {
         // Simulate delay between ticks
-        tokio::time::sleep(Duration::from_millis(100)).await;
+        tokio::time::sleep(self.tick_interval).await;
 
         let mut rng = rand::thread_rng();
 
@@ -60,11 +86,15 @@ impl MarketDataSource for SimulatedFeed {
         let bid = close - spread / 2.0;
         let ask = close + spread / 2.0;
 
+        let timestamp = match self.timestamps {
+            Some((start, interval_ms)) => start + self.tick_count as i64 * interval_ms,
+            None => chrono::Utc::now().timestamp_millis(),
+        };
         self.tick_count += 1;
 
         let data = MarketData {
             symbol: self.symbol.clone(),
-            timestamp: chrono::Utc::now().timestamp_millis(),
+            timestamp,
             open,
             high,
             low,
@@ -72,6 +102,9 @@ impl MarketDataSource for SimulatedFeed {
             volume,
             bid,
             ask,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         };
 
         Ok(data)
@@ -86,3 +119,39 @@ impl MarketDataSource for SimulatedFeed {
         "simulated"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_deterministic_timestamps() {
+        let mut feed = SimulatedFeed::new("BTCUSDT".to_string(), 50000.0)
+            .with_interval(Duration::ZERO)
+            .with_timestamps(1_700_000_000_000, 60_000);
+
+        let mut timestamps = Vec::new();
+        for _ in 0..4 {
+            timestamps.push(feed.next_tick().await.unwrap().timestamp);
+        }
+
+        assert_eq!(timestamps[0], 1_700_000_000_000);
+        assert!(timestamps.windows(2).all(|pair| pair[1] - pair[0] == 60_000));
+    }
+
+    #[tokio::test]
+    async fn test_config_start_without_interval_uses_tick_interval() {
+        let config = crate::config::SimulatedConfig {
+            symbol: "BTCUSDT".to_string(),
+            starting_price: 50000.0,
+            start_timestamp: Some(1_700_000_000_000),
+            interval_ms: None,
+        };
+        let mut feed = config.feed().with_interval(Duration::ZERO);
+
+        let first = feed.next_tick().await.unwrap().timestamp;
+        let second = feed.next_tick().await.unwrap().timestamp;
+        assert_eq!(first, 1_700_000_000_000);
+        assert_eq!(second - first, DEFAULT_TICK_INTERVAL.as_millis() as i64);
+    }
+}
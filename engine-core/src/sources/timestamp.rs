@@ -0,0 +1,84 @@
+//! Normalizing source timestamps to milliseconds.
+
+use serde::{Deserialize, Serialize};
+
+/// Unit of the raw timestamps a source reads
+///
+/// Files and feeds stamp bars in whatever unit their producer chose. Sources
+/// convert with [`to_millis`](TimestampUnit::to_millis) on ingestion, so
+/// [`MarketData::timestamp`](crate::MarketData::timestamp) is always Unix
+/// milliseconds by the time a bar reaches storage, indicators or strategies.
+///
+/// # Example
+///
+/// ```
+/// use trading_engine::sources::TimestampUnit;
+///
+/// assert_eq!(TimestampUnit::Seconds.to_millis(1_700_000_000), 1_700_000_000_000);
+/// assert_eq!(TimestampUnit::Nanoseconds.to_millis(1_700_000_000_123_456_789), 1_700_000_000_123);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampUnit {
+    Seconds,
+    #[default]
+    Milliseconds,
+    Microseconds,
+    Nanoseconds,
+}
+
+impl TimestampUnit {
+    /// Convert a raw timestamp in this unit to Unix milliseconds
+    ///
+    /// Finer units are truncated toward negative infinity, so sub-millisecond
+    /// stamps within the same millisecond map to the same bar time.
+    pub fn to_millis(self, raw: i64) -> i64 {
+        match self {
+            TimestampUnit::Seconds => raw.saturating_mul(1000),
+            TimestampUnit::Milliseconds => raw,
+            TimestampUnit::Microseconds => raw.div_euclid(1000),
+            TimestampUnit::Nanoseconds => raw.div_euclid(1_000_000),
+        }
+    }
+
+    /// Convert a fractional timestamp in this unit, e.g. `1700000000.25` seconds
+    ///
+    /// Rounds down to the millisecond like [`to_millis`](TimestampUnit::to_millis).
+    pub fn to_millis_f64(self, raw: f64) -> i64 {
+        let millis = match self {
+            TimestampUnit::Seconds => raw * 1000.0,
+            TimestampUnit::Milliseconds => raw,
+            TimestampUnit::Microseconds => raw / 1000.0,
+            TimestampUnit::Nanoseconds => raw / 1_000_000.0,
+        };
+        millis.floor() as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_millis() {
+        assert_eq!(TimestampUnit::Seconds.to_millis(60), 60_000);
+        assert_eq!(TimestampUnit::Milliseconds.to_millis(60_000), 60_000);
+        assert_eq!(TimestampUnit::Microseconds.to_millis(60_000_999), 60_000);
+        assert_eq!(TimestampUnit::Nanoseconds.to_millis(60_000_999_999), 60_000);
+        // Pre-epoch stamps round down rather than toward zero
+        assert_eq!(TimestampUnit::Microseconds.to_millis(-1), -1);
+    }
+
+    #[test]
+    fn test_fractional_seconds_keep_millis() {
+        assert_eq!(TimestampUnit::Seconds.to_millis_f64(1_700_000_000.25), 1_700_000_000_250);
+        assert_eq!(TimestampUnit::Milliseconds.to_millis_f64(1500.9), 1500);
+    }
+
+    #[test]
+    fn test_deserializes_lowercase() {
+        let unit: TimestampUnit = serde_json::from_str("\"seconds\"").unwrap();
+        assert_eq!(unit, TimestampUnit::Seconds);
+        assert_eq!(TimestampUnit::default(), TimestampUnit::Milliseconds);
+    }
+}
@@ -0,0 +1,66 @@
+//! Paper account balance
+//!
+//! Tracks the cash a state machine trades with, so positions are limited to
+//! what the account can cover.
+
+use serde::{Deserialize, Serialize};
+
+/// Running paper balance with margin held by the open position
+///
+/// Positions are unleveraged: opening one holds its full notional value
+/// (entry price × quantity) as margin, and closing it releases the margin
+/// and adds the realized P&L to the balance.
+///
+/// # Examples
+///
+/// ```
+/// use trading_engine::state_machine::Account;
+///
+/// let mut account = Account::new(10_000.0);
+/// account.reserve(5_000.0);
+/// assert_eq!(account.available(), 5_000.0);
+///
+/// // Closed with a 250 profit
+/// account.settle(5_000.0, 250.0);
+/// assert_eq!(account.balance, 10_250.0);
+/// assert_eq!(account.used_margin, 0.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Account {
+    /// Cash balance, including realized P&L
+    pub balance: f64,
+
+    /// Margin held by open positions
+    pub used_margin: f64,
+}
+
+impl Account {
+    /// Create an account with `balance` and no open positions
+    pub fn new(balance: f64) -> Self {
+        Self {
+            balance,
+            used_margin: 0.0,
+        }
+    }
+
+    /// Balance not held as margin
+    pub fn available(&self) -> f64 {
+        self.balance - self.used_margin
+    }
+
+    /// Check if a position needing `margin` can be opened
+    pub fn can_afford(&self, margin: f64) -> bool {
+        margin <= self.available()
+    }
+
+    /// Hold `margin` for a newly opened position
+    pub fn reserve(&mut self, margin: f64) {
+        self.used_margin += margin;
+    }
+
+    /// Release a closed position's `margin` and book its realized `pnl`
+    pub fn settle(&mut self, margin: f64, pnl: f64) {
+        self.used_margin = (self.used_margin - margin).max(0.0);
+        self.balance += pnl;
+    }
+}
@@ -38,6 +38,15 @@ pub enum Action {
         price: f64,
     },
 
+    /// Close part of the current position
+    ///
+    /// A quantity at or above the open quantity closes the position. See
+    /// [`StateMachine::set_reduce_only`](super::StateMachine::set_reduce_only).
+    ReducePosition {
+        price: f64,
+        quantity: f64,
+    },
+
     /// Update stop loss
     UpdateStopLoss {
         new_stop: f64,
@@ -48,6 +57,24 @@ pub enum Action {
         new_target: f64,
     },
 
+    /// Enter a position with take-profit levels to scale out at
+    ///
+    /// `entry` is an [`EnterLong`](Action::EnterLong) or
+    /// [`EnterShort`](Action::EnterShort); the ladder is set on the position
+    /// it opens. See [`Position::set_exit_ladder`](super::Position::set_exit_ladder).
+    EnterWithExitLadder {
+        entry: Box<Action>,
+        exit_ladder: Vec<ExitRung>,
+    },
+
+    /// Set the take-profit levels to scale out at
+    ///
+    /// Replaces any rungs the open position has left. See
+    /// [`Position::set_exit_ladder`](super::Position::set_exit_ladder).
+    SetExitLadder {
+        exit_ladder: Vec<ExitRung>,
+    },
+
     /// Transition to Analyzing state (opportunity detected)
     StartAnalyzing {
         reason: String,
@@ -60,6 +87,24 @@ pub enum Action {
 
     /// Do nothing
     NoAction,
+
+    /// Do nothing, recording why the strategy passed (e.g. "spread too wide")
+    NoActionWithReason {
+        reason: String,
+    },
+}
+
+/// One take-profit level of an exit ladder
+///
+/// When price reaches `price`, `fraction` of the position's original
+/// quantity is closed. See [`Position::set_exit_ladder`](super::Position::set_exit_ladder).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExitRung {
+    /// Price at which to scale out
+    pub price: f64,
+
+    /// Fraction of the original quantity to close (0.0-1.0)
+    pub fraction: f64,
 }
 
 /// Position side (Long or Short)
@@ -82,6 +127,47 @@ impl Side {
     pub fn is_short(&self) -> bool {
         matches!(self, Side::Short)
     }
+
+    /// The other side, e.g. for closing or reversing a position
+    pub fn opposite(self) -> Side {
+        match self {
+            Side::Long => Side::Short,
+            Side::Short => Side::Long,
+        }
+    }
+
+    /// Direction of profit: `1.0` for long, `-1.0` for short
+    ///
+    /// Multiplying a price move by the sign gives the move in the
+    /// position's favour.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::state_machine::position::Side;
+    ///
+    /// let (entry, exit) = (100.0, 90.0);
+    /// assert_eq!(Side::Short.sign() * (exit - entry), 10.0);
+    /// ```
+    pub fn sign(self) -> f64 {
+        match self {
+            Side::Long => 1.0,
+            Side::Short => -1.0,
+        }
+    }
+
+    /// Side a signed signal points to: long if positive, short if negative
+    ///
+    /// Returns `None` for zero or NaN.
+    pub fn from_signal(signal: f64) -> Option<Side> {
+        if signal > 0.0 {
+            Some(Side::Long)
+        } else if signal < 0.0 {
+            Some(Side::Short)
+        } else {
+            None
+        }
+    }
 }
 
 impl std::fmt::Display for Side {
@@ -96,27 +182,102 @@ impl std::fmt::Display for Side {
 impl Action {
     /// Check if this action enters a position
     pub fn is_entry(&self) -> bool {
-        matches!(self, Action::EnterLong { .. } | Action::EnterShort { .. })
+        match self {
+            Action::EnterLong { .. } | Action::EnterShort { .. } => true,
+            Action::EnterWithExitLadder { entry, .. } => entry.is_entry(),
+            _ => false,
+        }
     }
 
-    /// Check if this action exits a position
+    /// Check if this action exits all or part of a position
     pub fn is_exit(&self) -> bool {
-        matches!(self, Action::ExitPosition { .. })
+        matches!(self, Action::ExitPosition { .. } | Action::ReducePosition { .. })
+    }
+
+    /// Check if this action is a pass, with or without a reason
+    pub fn is_no_action(&self) -> bool {
+        matches!(self, Action::NoAction | Action::NoActionWithReason { .. })
     }
 
     /// Check if this action modifies an existing position
     pub fn is_modification(&self) -> bool {
         matches!(
             self,
-            Action::UpdateStopLoss { .. } | Action::UpdateTakeProfit { .. }
+            Action::UpdateStopLoss { .. } | Action::UpdateTakeProfit { .. } | Action::SetExitLadder { .. }
         )
     }
 
+    /// Get the requested price for entry and exit actions
+    pub fn price(&self) -> Option<f64> {
+        match self {
+            Action::EnterLong { price, .. }
+            | Action::EnterShort { price, .. }
+            | Action::ExitPosition { price }
+            | Action::ReducePosition { price, .. } => Some(*price),
+            Action::EnterWithExitLadder { entry, .. } => entry.price(),
+            _ => None,
+        }
+    }
+
+    /// Get the quantity of an entry action
+    pub fn quantity(&self) -> Option<f64> {
+        match self {
+            Action::EnterLong { quantity, .. } | Action::EnterShort { quantity, .. } => Some(*quantity),
+            Action::EnterWithExitLadder { entry, .. } => entry.quantity(),
+            _ => None,
+        }
+    }
+
+    /// Replace the quantity of an entry action
+    ///
+    /// Other actions are returned unchanged.
+    pub fn with_quantity(self, new_quantity: f64) -> Self {
+        match self {
+            Action::EnterLong { price, .. } => Action::EnterLong {
+                price,
+                quantity: new_quantity,
+            },
+            Action::EnterShort { price, .. } => Action::EnterShort {
+                price,
+                quantity: new_quantity,
+            },
+            Action::EnterWithExitLadder { entry, exit_ladder } => Action::EnterWithExitLadder {
+                entry: Box::new(entry.with_quantity(new_quantity)),
+                exit_ladder,
+            },
+            other => other,
+        }
+    }
+
+    /// Replace the requested price of an entry or exit action
+    ///
+    /// Other actions are returned unchanged.
+    pub fn with_price(self, new_price: f64) -> Self {
+        match self {
+            Action::EnterLong { quantity, .. } => Action::EnterLong {
+                price: new_price,
+                quantity,
+            },
+            Action::EnterShort { quantity, .. } => Action::EnterShort {
+                price: new_price,
+                quantity,
+            },
+            Action::ExitPosition { .. } => Action::ExitPosition { price: new_price },
+            Action::ReducePosition { quantity, .. } => Action::ReducePosition { price: new_price, quantity },
+            Action::EnterWithExitLadder { entry, exit_ladder } => Action::EnterWithExitLadder {
+                entry: Box::new(entry.with_price(new_price)),
+                exit_ladder,
+            },
+            other => other,
+        }
+    }
+
     /// Get the side for entry actions
     pub fn entry_side(&self) -> Option<Side> {
         match self {
             Action::EnterLong { .. } => Some(Side::Long),
             Action::EnterShort { .. } => Some(Side::Short),
+            Action::EnterWithExitLadder { entry, .. } => entry.entry_side(),
             _ => None,
         }
     }
@@ -140,9 +301,17 @@ mod tests {
         assert!(exit.is_exit());
         assert!(!exit.is_entry());
 
+        let reduce = Action::ReducePosition { price: 51000.0, quantity: 0.05 };
+        assert!(reduce.is_exit());
+
         let update_stop = Action::UpdateStopLoss { new_stop: 49000.0 };
         assert!(update_stop.is_modification());
         assert!(!update_stop.is_entry());
+
+        let pass = Action::NoActionWithReason { reason: "spread too wide".to_string() };
+        assert!(pass.is_no_action());
+        assert!(Action::NoAction.is_no_action());
+        assert!(!update_stop.is_no_action());
     }
 
     #[test]
@@ -153,6 +322,28 @@ mod tests {
         assert!(!Side::Short.is_long());
     }
 
+    #[test]
+    fn test_side_opposite_and_sign() {
+        assert_eq!(Side::Long.opposite(), Side::Short);
+        assert_eq!(Side::Short.opposite(), Side::Long);
+        assert_eq!(Side::Long.opposite().opposite(), Side::Long);
+
+        assert_eq!(Side::Long.sign(), 1.0);
+        assert_eq!(Side::Short.sign(), -1.0);
+        assert_eq!(Side::Short.opposite().sign(), -Side::Short.sign());
+    }
+
+    #[test]
+    fn test_side_from_signal() {
+        assert_eq!(Side::from_signal(0.75), Some(Side::Long));
+        assert_eq!(Side::from_signal(f64::MIN_POSITIVE), Some(Side::Long));
+        assert_eq!(Side::from_signal(-0.2), Some(Side::Short));
+        assert_eq!(Side::from_signal(f64::NEG_INFINITY), Some(Side::Short));
+        assert_eq!(Side::from_signal(0.0), None);
+        assert_eq!(Side::from_signal(-0.0), None);
+        assert_eq!(Side::from_signal(f64::NAN), None);
+    }
+
     #[test]
     fn test_side_display() {
         assert_eq!(format!("{}", Side::Long), "Long");
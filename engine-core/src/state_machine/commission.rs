@@ -0,0 +1,125 @@
+//! Trading commissions
+//!
+//! Fees charged on fills, with maker rebates and volume tiers like a real
+//! venue's fee schedule.
+
+use serde::{Deserialize, Serialize};
+
+/// Which side of the book a fill took
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Liquidity {
+    /// Resting order filled (take-profit and exit ladder exits)
+    Maker,
+    /// Order crossed the book (entries, strategy exits and stop losses)
+    Taker,
+}
+
+/// Fee rates from a cumulative volume upward
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CommissionTier {
+    /// Cumulative traded notional at which this tier starts
+    pub min_volume: f64,
+
+    /// Maker fee in basis points; negative for a rebate
+    pub maker_bps: f64,
+
+    /// Taker fee in basis points
+    pub taker_bps: f64,
+}
+
+/// Maker/taker fee rates, tiered by cumulative traded volume
+///
+/// Each fill pays `notional × bps / 10_000` at the rates of the highest tier
+/// whose `min_volume` the volume traded before it has reached. Negative
+/// rates are rebates. Without tiers, or below the lowest one, trading is free.
+///
+/// # Examples
+///
+/// ```
+/// use trading_engine::state_machine::{CommissionSchedule, Liquidity};
+///
+/// // 2 bps rebate / 5 bps fee, dropping to 3 bps taker after 1M traded
+/// let schedule = CommissionSchedule::flat(-2.0, 5.0).with_tier(1_000_000.0, -2.0, 3.0);
+///
+/// assert_eq!(schedule.fee(10_000.0, 0.0, Liquidity::Taker), 5.0);
+/// assert_eq!(schedule.fee(10_000.0, 0.0, Liquidity::Maker), -2.0);
+/// assert_eq!(schedule.fee(10_000.0, 1_500_000.0, Liquidity::Taker), 3.0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommissionSchedule {
+    /// Fee tiers, in any order
+    #[serde(default)]
+    pub tiers: Vec<CommissionTier>,
+}
+
+impl CommissionSchedule {
+    /// One set of rates for all volumes
+    pub fn flat(maker_bps: f64, taker_bps: f64) -> Self {
+        Self::default().with_tier(0.0, maker_bps, taker_bps)
+    }
+
+    /// Add a tier starting at `min_volume` of cumulative traded notional
+    pub fn with_tier(mut self, min_volume: f64, maker_bps: f64, taker_bps: f64) -> Self {
+        self.tiers.push(CommissionTier { min_volume, maker_bps, taker_bps });
+        self
+    }
+
+    /// Check if no fees are charged
+    pub fn is_free(&self) -> bool {
+        self.tiers.is_empty()
+    }
+
+    /// Tier in effect after `volume` has been traded
+    pub fn tier(&self, volume: f64) -> Option<&CommissionTier> {
+        self.tiers
+            .iter()
+            .filter(|tier| tier.min_volume <= volume)
+            .max_by(|a, b| a.min_volume.total_cmp(&b.min_volume))
+    }
+
+    /// Rate in basis points for a fill after `volume` has been traded
+    pub fn rate_bps(&self, volume: f64, liquidity: Liquidity) -> f64 {
+        self.tier(volume).map_or(0.0, |tier| match liquidity {
+            Liquidity::Maker => tier.maker_bps,
+            Liquidity::Taker => tier.taker_bps,
+        })
+    }
+
+    /// Fee on a fill of `notional`, after `volume` has been traded
+    ///
+    /// Negative for a rebate.
+    pub fn fee(&self, notional: f64, volume: f64, liquidity: Liquidity) -> f64 {
+        notional * self.rate_bps(volume, liquidity) / 10_000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_free_without_tiers() {
+        let schedule = CommissionSchedule::default();
+        assert!(schedule.is_free());
+        assert_eq!(schedule.fee(10_000.0, 0.0, Liquidity::Taker), 0.0);
+    }
+
+    #[test]
+    fn test_highest_reached_tier_applies() {
+        // Tiers out of order still resolve by volume
+        let schedule = CommissionSchedule::default()
+            .with_tier(5_000_000.0, -3.0, 2.0)
+            .with_tier(0.0, 1.0, 6.0)
+            .with_tier(1_000_000.0, 0.0, 4.0);
+
+        assert_eq!(schedule.rate_bps(999_999.0, Liquidity::Taker), 6.0);
+        assert_eq!(schedule.rate_bps(1_000_000.0, Liquidity::Taker), 4.0);
+        assert_eq!(schedule.rate_bps(7_000_000.0, Liquidity::Maker), -3.0);
+    }
+
+    #[test]
+    fn test_free_below_lowest_tier() {
+        let schedule = CommissionSchedule::default().with_tier(100_000.0, -1.0, 2.0);
+        assert_eq!(schedule.fee(10_000.0, 0.0, Liquidity::Maker), 0.0);
+    }
+}
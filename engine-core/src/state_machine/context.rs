@@ -158,6 +158,65 @@ impl Context {
     pub fn iter_booleans(&self) -> impl Iterator<Item = (&String, &bool)> {
         self.booleans.iter()
     }
+
+    /// Get every key across all value types, sorted and deduplicated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::state_machine::Context;
+    ///
+    /// let mut ctx = Context::new();
+    /// ctx.set("signal", "bullish".to_string());
+    /// ctx.set("confidence", 0.8);
+    /// assert_eq!(ctx.keys(), vec!["confidence", "signal"]);
+    /// ```
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .strings
+            .keys()
+            .chain(self.numbers.keys())
+            .chain(self.integers.keys())
+            .chain(self.booleans.keys())
+            .cloned()
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    /// Get all values as a flat JSON object
+    ///
+    /// Non-finite numbers become `null`. If the same key is stored under
+    /// several types, booleans take precedence over integers, integers over
+    /// numbers, and numbers over strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::state_machine::Context;
+    ///
+    /// let mut ctx = Context::new();
+    /// ctx.set("confidence", 0.8);
+    /// ctx.set("active", true);
+    /// assert_eq!(ctx.as_json()["active"], serde_json::json!(true));
+    /// ```
+    pub fn as_json(&self) -> serde_json::Value {
+        let mut map = serde_json::Map::new();
+        for (key, value) in &self.strings {
+            map.insert(key.clone(), serde_json::json!(value));
+        }
+        for (key, value) in &self.numbers {
+            map.insert(key.clone(), serde_json::json!(value));
+        }
+        for (key, value) in &self.integers {
+            map.insert(key.clone(), serde_json::json!(value));
+        }
+        for (key, value) in &self.booleans {
+            map.insert(key.clone(), serde_json::json!(value));
+        }
+        serde_json::Value::Object(map)
+    }
 }
 
 impl Default for Context {
@@ -281,6 +340,28 @@ mod tests {
         assert!(ctx.is_empty());
     }
 
+    #[test]
+    fn test_keys_and_json_cover_all_types() {
+        let mut ctx = Context::new();
+        ctx.set("signal", "bullish".to_string());
+        ctx.set("confidence", 0.85);
+        ctx.set("bars_in_trade", 7i64);
+        ctx.set("active", true);
+
+        assert_eq!(ctx.keys(), vec!["active", "bars_in_trade", "confidence", "signal"]);
+
+        let json = ctx.as_json();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "signal": "bullish",
+                "confidence": 0.85,
+                "bars_in_trade": 7,
+                "active": true,
+            })
+        );
+    }
+
     #[test]
     fn test_convenience_methods() {
         let mut ctx = Context::new();
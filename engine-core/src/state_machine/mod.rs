@@ -24,18 +24,25 @@ pub mod state;
 pub mod context;
 pub mod action;
 pub mod position;
+pub mod account;
+pub mod commission;
 
 pub use state::State;
 pub use context::Context;
-pub use action::{Action, Side};
-pub use position::Position;
+pub use action::{Action, ExitRung, Side};
+pub use position::{ContractType, Position};
+pub use account::Account;
+pub use commission::{CommissionSchedule, CommissionTier, Liquidity};
 
-use crate::{MarketData, Result};
+use crate::{MarketData, Result, TradingEngineError};
 use std::collections::VecDeque;
 
 /// Maximum number of state transitions to keep in history
 const MAX_TRANSITION_HISTORY: usize = 100;
 
+/// Maximum number of closed positions to keep in history
+const MAX_CLOSED_POSITIONS: usize = 100;
+
 /// Represents a state transition event
 #[derive(Debug, Clone)]
 pub struct Transition {
@@ -45,6 +52,27 @@ pub struct Transition {
     pub reason: String,
 }
 
+/// Part of a position closed by [`Action::ReducePosition`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PartialExit {
+    /// Fill price, after slippage
+    pub price: f64,
+    /// Quantity closed
+    pub quantity: f64,
+    /// Realized P&L of the closed part, net of its commission
+    pub pnl: f64,
+}
+
+/// A stop loss or take profit level reached by the open position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExitTrigger {
+    /// Price crossed the stop loss
+    StopLoss { price: f64, stop: f64 },
+
+    /// Price crossed the take profit target
+    TakeProfit { price: f64, target: f64 },
+}
+
 /// The main state machine for trading logic
 ///
 /// Manages the current state, context, and position for a single trading symbol.
@@ -73,6 +101,39 @@ pub struct StateMachine {
 
     /// History of state transitions
     transition_history: VecDeque<Transition>,
+
+    /// Close the position automatically when a stop or target is hit
+    auto_exit: bool,
+
+    /// Check stops and targets against the bar's high/low instead of its close
+    intrabar_exits: bool,
+
+    /// Slippage applied to every fill, in basis points
+    slippage_bps: f64,
+
+    /// How new positions denominate P&L
+    contract_type: ContractType,
+
+    /// Fees charged on fills
+    commission: CommissionSchedule,
+
+    /// Notional traded so far, selecting the commission tier
+    traded_volume: f64,
+
+    /// Never let a reduce flip the position to the other side
+    reduce_only: bool,
+
+    /// Most recently closed position
+    last_closed: Option<Position>,
+
+    /// Most recent partial close of the open position
+    last_partial_exit: Option<PartialExit>,
+
+    /// Closed positions, oldest first
+    closed_positions: VecDeque<Position>,
+
+    /// Paper account funding positions (`None` for unlimited funds)
+    account: Option<Account>,
 }
 
 impl StateMachine {
@@ -96,9 +157,243 @@ impl StateMachine {
             context: Context::new(),
             position: None,
             transition_history: VecDeque::new(),
+            auto_exit: true,
+            intrabar_exits: false,
+            slippage_bps: 0.0,
+            contract_type: ContractType::Linear,
+            commission: CommissionSchedule::default(),
+            traded_volume: 0.0,
+            reduce_only: true,
+            last_closed: None,
+            last_partial_exit: None,
+            closed_positions: VecDeque::new(),
+            account: None,
+        }
+    }
+
+    /// Check stops and targets against the bar's range
+    ///
+    /// When enabled, [`update`](Self::update) tests the bar's low (high for
+    /// shorts) against the stop loss and its high (low for shorts) against
+    /// the take profit, and fills at the stop or target price. This catches
+    /// stops pierced within a bar whose close recovered. When disabled (the
+    /// default), only the close is checked and exits fill at the close.
+    pub fn set_intrabar_exits(&mut self, enabled: bool) {
+        self.intrabar_exits = enabled;
+    }
+
+    /// Set the slippage applied to fills, in basis points
+    ///
+    /// Buys fill above and sells fill below the requested price, so entries
+    /// and exits both cost `bps / 10_000` of the price.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::state_machine::{StateMachine, Action};
+    ///
+    /// let mut sm = StateMachine::new("BTCUSDT".to_string());
+    /// sm.set_slippage_bps(10.0);
+    /// sm.execute(Action::EnterLong { price: 50000.0, quantity: 0.1 }).unwrap();
+    /// assert_eq!(sm.position().unwrap().entry_price(), 50050.0);
+    /// ```
+    pub fn set_slippage_bps(&mut self, bps: f64) {
+        self.slippage_bps = bps;
+    }
+
+    /// Get the slippage applied to fills, in basis points
+    pub fn slippage_bps(&self) -> f64 {
+        self.slippage_bps
+    }
+
+    /// Set how new positions denominate P&L
+    ///
+    /// With [`ContractType::Inverse`], quantities are in contracts of the
+    /// quote currency and P&L, margin and account balances are in the base
+    /// asset. An open position keeps the type it was entered with.
+    pub fn set_contract_type(&mut self, contract_type: ContractType) {
+        self.contract_type = contract_type;
+    }
+
+    /// Get how new positions denominate P&L
+    pub fn contract_type(&self) -> ContractType {
+        self.contract_type
+    }
+
+    /// Set the fees charged on fills
+    ///
+    /// Entries, strategy exits and stop losses pay the taker rate;
+    /// take-profit and exit ladder exits pay the maker rate. Fees are taken
+    /// out of the position's realized P&L, and every fill adds to the traded
+    /// volume that selects the tier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::state_machine::{Action, CommissionSchedule, StateMachine};
+    ///
+    /// let mut sm = StateMachine::new("BTCUSDT".to_string());
+    /// sm.set_commission(CommissionSchedule::flat(-1.0, 5.0));
+    /// sm.execute(Action::EnterLong { price: 50000.0, quantity: 0.1 }).unwrap();
+    /// sm.execute(Action::ExitPosition { price: 51000.0 }).unwrap();
+    ///
+    /// // 100 gross, less 5 bps on 5,000 in and 5,100 out
+    /// let closed = sm.last_closed_position().unwrap();
+    /// assert!((closed.realized_pnl().unwrap() - 94.95).abs() < 1e-9);
+    /// assert_eq!(sm.traded_volume(), 10_100.0);
+    /// ```
+    pub fn set_commission(&mut self, commission: CommissionSchedule) {
+        self.commission = commission;
+    }
+
+    /// Get the fees charged on fills
+    pub fn commission(&self) -> &CommissionSchedule {
+        &self.commission
+    }
+
+    /// Notional traded so far, across entries and exits
+    pub fn traded_volume(&self) -> f64 {
+        self.traded_volume
+    }
+
+    /// Fee on a fill of `quantity` at `price`, counting it toward the traded volume
+    fn charge_commission(&mut self, price: f64, quantity: f64, contract_type: ContractType, liquidity: Liquidity) -> f64 {
+        let notional = contract_type.notional(price, quantity);
+        let fee = self.commission.fee(notional, self.traded_volume, liquidity);
+        self.traded_volume += notional;
+        fee
+    }
+
+    /// Fund positions from a paper account, or `None` for unlimited funds
+    ///
+    /// With an account, [`execute`](Self::execute) rejects entries whose
+    /// notional value exceeds the available balance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::state_machine::{Account, Action, StateMachine};
+    ///
+    /// let mut sm = StateMachine::new("BTCUSDT".to_string());
+    /// sm.set_account(Some(Account::new(1_000.0)));
+    ///
+    /// // 0.1 BTC at 50,000 needs 5,000
+    /// assert!(sm.execute(Action::EnterLong { price: 50000.0, quantity: 0.1 }).is_err());
+    /// assert!(sm.position().is_none());
+    /// ```
+    pub fn set_account(&mut self, account: Option<Account>) {
+        self.account = account;
+    }
+
+    /// Get the paper account, if one is configured
+    pub fn account(&self) -> Option<&Account> {
+        self.account.as_ref()
+    }
+
+    /// Margin an entry action would hold, at its fill price after slippage
+    ///
+    /// Returns `None` for non-entry actions.
+    pub fn required_margin(&self, action: &Action) -> Option<f64> {
+        let side = action.entry_side()?;
+        let (price, quantity) = (action.price()?, action.quantity()?);
+        Some(self.contract_type.notional(self.fill_price(price, side.is_long()), quantity))
+    }
+
+    /// Get the most recently closed position
+    ///
+    /// Carries the exit price and realized P&L of the last exit.
+    pub fn last_closed_position(&self) -> Option<&Position> {
+        self.last_closed.as_ref()
+    }
+
+    /// Get the most recent partial close of the open position
+    ///
+    /// Set by a reduce that leaves part of the position open, and cleared
+    /// when a new position is entered.
+    pub fn last_partial_exit(&self) -> Option<&PartialExit> {
+        self.last_partial_exit.as_ref()
+    }
+
+    /// Get the closed positions still held in history, oldest first
+    ///
+    /// Keeps the last 100 positions closed since creation, the last
+    /// [`reset`](Self::reset) or the last
+    /// [`drain_closed_positions`](Self::drain_closed_positions).
+    pub fn closed_positions(&self) -> &VecDeque<Position> {
+        &self.closed_positions
+    }
+
+    /// Take the closed position history, leaving it empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::state_machine::{Action, StateMachine};
+    ///
+    /// let mut sm = StateMachine::new("BTCUSDT".to_string());
+    /// sm.execute(Action::EnterLong { price: 50000.0, quantity: 0.1 }).unwrap();
+    /// sm.execute(Action::ExitPosition { price: 51000.0 }).unwrap();
+    ///
+    /// let closed = sm.drain_closed_positions();
+    /// assert_eq!(closed.len(), 1);
+    /// assert!(sm.closed_positions().is_empty());
+    /// ```
+    pub fn drain_closed_positions(&mut self) -> Vec<Position> {
+        self.closed_positions.drain(..).collect()
+    }
+
+    /// Price actually paid or received for a requested price
+    fn fill_price(&self, price: f64, buying: bool) -> f64 {
+        let slippage = price * self.slippage_bps / 10_000.0;
+        if buying {
+            price + slippage
+        } else {
+            price - slippage
         }
     }
 
+    /// Enable or disable automatic exits on stop loss / take profit
+    ///
+    /// When disabled, [`update`](Self::update) still reports the trigger but
+    /// leaves the position open for the strategy to close.
+    pub fn set_auto_exit(&mut self, enabled: bool) {
+        self.auto_exit = enabled;
+    }
+
+    /// Check if automatic exits are enabled
+    pub fn auto_exit(&self) -> bool {
+        self.auto_exit
+    }
+
+    /// Enable or disable reduce-only semantics for [`Action::ReducePosition`]
+    ///
+    /// When enabled (the default), a reduce larger than the open quantity is
+    /// clamped and just closes the position, like an exchange reduce-only
+    /// order. When disabled, the excess opens a position on the other side.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::state_machine::{StateMachine, Action};
+    ///
+    /// let mut sm = StateMachine::new("BTCUSDT".to_string());
+    /// sm.set_reduce_only(false);
+    /// sm.execute(Action::EnterLong { price: 50000.0, quantity: 1.0 }).unwrap();
+    /// sm.execute(Action::ReducePosition { price: 51000.0, quantity: 1.5 }).unwrap();
+    ///
+    /// let pos = sm.position().unwrap();
+    /// assert!(pos.side().is_short());
+    /// assert_eq!(pos.quantity(), 0.5);
+    /// ```
+    pub fn set_reduce_only(&mut self, enabled: bool) {
+        self.reduce_only = enabled;
+    }
+
+    /// Check if reduces are limited to the open quantity
+    pub fn reduce_only(&self) -> bool {
+        self.reduce_only
+    }
+
     /// Get the current state
     pub fn current_state(&self) -> &State {
         &self.state
@@ -199,6 +494,16 @@ impl StateMachine {
     /// sm.execute(action).unwrap();
     /// ```
     pub fn execute(&mut self, action: Action) -> Result<()> {
+        if let (Some(account), Some(margin)) = (&self.account, self.required_margin(&action)) {
+            if !account.can_afford(margin) {
+                return Err(TradingEngineError::InvalidData(format!(
+                    "Insufficient balance: entry needs {:.2}, {:.2} available",
+                    margin,
+                    account.available()
+                )));
+            }
+        }
+
         match action {
             Action::EnterLong { price, quantity } => {
                 self.enter_position(price, quantity, Side::Long);
@@ -209,7 +514,11 @@ impl StateMachine {
             }
 
             Action::ExitPosition { price } => {
-                self.exit_position(price);
+                self.exit_position(price, Liquidity::Taker);
+            }
+
+            Action::ReducePosition { price, quantity } => {
+                self.reduce_position(price, quantity);
             }
 
             Action::UpdateStopLoss { new_stop } => {
@@ -234,6 +543,26 @@ impl StateMachine {
                 }
             }
 
+            Action::EnterWithExitLadder { entry, exit_ladder } => {
+                let opens = entry.is_entry();
+                self.execute(*entry)?;
+                if opens {
+                    self.execute(Action::SetExitLadder { exit_ladder })?;
+                }
+            }
+
+            Action::SetExitLadder { exit_ladder } => {
+                if let Some(pos) = self.position_mut() {
+                    let rungs = exit_ladder.len();
+                    pos.set_exit_ladder(exit_ladder);
+                    tracing::info!(
+                        symbol = %self.symbol,
+                        rungs = %rungs,
+                        "Set exit ladder"
+                    );
+                }
+            }
+
             Action::StartAnalyzing { reason } => {
                 if self.state.is_idle() {
                     self.transition_to(State::Analyzing, reason);
@@ -246,7 +575,7 @@ impl StateMachine {
                 }
             }
 
-            Action::NoAction => {
+            Action::NoAction | Action::NoActionWithReason { .. } => {
                 // Do nothing
             }
         }
@@ -262,34 +591,151 @@ impl StateMachine {
     /// # Arguments
     ///
     /// * `data` - New market data
-    pub fn update(&mut self, data: &MarketData) {
+    ///
+    /// # Returns
+    ///
+    /// The stop loss or take profit hit on this update, if any. The position
+    /// has been closed only if auto-exit is enabled.
+    pub fn update(&mut self, data: &MarketData) -> Option<ExitTrigger> {
         // Update context with latest data
         self.context.set_latest_price(data.close);
         self.context.set_latest_timestamp(data.timestamp);
 
         // Update position if we have one
-        if let Some(ref mut pos) = self.position {
-            pos.update_current_price(data.close);
+        let pos = self.position.as_mut()?;
+        pos.update_current_price(data.close);
+
+        // Scale out at ladder rungs unless the stop was hit first
+        let stop_hit = if self.intrabar_exits {
+            matches!(Self::intrabar_trigger(pos, data), Some(ExitTrigger::StopLoss { .. }))
+        } else {
+            pos.is_stop_loss_hit()
+        };
+        if self.auto_exit && !stop_hit {
+            if let Some(trigger) = self.scale_out_ladder(data) {
+                return Some(trigger);
+            }
+        }
+        let pos = self.position.as_mut()?;
+
+        let trigger = if self.intrabar_exits {
+            Self::intrabar_trigger(pos, data)
+        } else if pos.is_stop_loss_hit() {
+            pos.stop_loss().map(|stop| ExitTrigger::StopLoss { price: data.close, stop })
+        } else if pos.is_take_profit_hit() {
+            pos.take_profit().map(|target| ExitTrigger::TakeProfit { price: data.close, target })
+        } else {
+            None
+        }?;
 
-            // Auto-exit on stop loss or take profit
-            if pos.is_stop_loss_hit() {
+        let (exit_price, liquidity) = match trigger {
+            ExitTrigger::StopLoss { price, stop } => {
                 tracing::warn!(
                     symbol = %self.symbol,
-                    price = %data.close,
-                    stop = %pos.stop_loss().unwrap(),
+                    price = %price,
+                    stop = %stop,
                     "Stop loss hit"
                 );
-                self.exit_position(data.close);
-            } else if pos.is_take_profit_hit() {
+                (price, Liquidity::Taker)
+            }
+            ExitTrigger::TakeProfit { price, target } => {
                 tracing::info!(
                     symbol = %self.symbol,
-                    price = %data.close,
-                    target = %pos.take_profit().unwrap(),
+                    price = %price,
+                    target = %target,
                     "Take profit hit"
                 );
-                self.exit_position(data.close);
+                (price, Liquidity::Maker)
+            }
+        };
+
+        // Auto-exit on stop loss or take profit
+        if self.auto_exit {
+            self.exit_position(exit_price, liquidity);
+        }
+        Some(trigger)
+    }
+
+    /// Close part of the position at each exit ladder rung the bar reached
+    ///
+    /// Rungs fill at their price with intrabar exits, otherwise at the close.
+    /// The first partial exit moves the stop to breakeven. A rung that covers
+    /// the rest of the position closes it, returned as a take-profit trigger.
+    fn scale_out_ladder(&mut self, data: &MarketData) -> Option<ExitTrigger> {
+        loop {
+            let pos = self.position.as_mut()?;
+            let long = pos.side().is_long();
+            let reached = match (self.intrabar_exits, long) {
+                (true, true) => data.high,
+                (true, false) => data.low,
+                (false, _) => data.close,
+            };
+            let rung = pos.take_reached_rung(reached)?;
+            let price = if self.intrabar_exits { rung.price } else { data.close };
+            let quantity = rung.fraction * pos.original_quantity();
+
+            if quantity >= pos.quantity() - 1e-12 {
+                tracing::info!(symbol = %self.symbol, price = %price, "Final exit ladder rung hit");
+                self.exit_position(price, Liquidity::Maker);
+                return Some(ExitTrigger::TakeProfit { price, target: rung.price });
+            }
+
+            let fill = self.fill_price(price, !long);
+            let contract_type = self.position.as_ref()?.contract_type();
+            let fee = self.charge_commission(fill, quantity, contract_type, Liquidity::Maker);
+            let pos = self.position.as_mut()?;
+            let first = pos.quantity() == pos.original_quantity();
+            let pnl = pos.scale_out(fill, quantity);
+            pos.add_fee(fee);
+            let entry_price = pos.entry_price();
+            let notional = pos.notional(quantity);
+
+            // Move the stop to breakeven, unless it is already past it
+            if first {
+                let past_breakeven = pos
+                    .stop_loss()
+                    .is_some_and(|stop| if long { stop >= entry_price } else { stop <= entry_price });
+                if !past_breakeven {
+                    pos.set_stop_loss(entry_price);
+                }
+            }
+
+            if let Some(account) = &mut self.account {
+                account.settle(notional, pnl);
+            }
+            tracing::info!(
+                symbol = %self.symbol,
+                price = %fill,
+                quantity = %quantity,
+                pnl = %pnl,
+                "Scaled out at exit ladder rung"
+            );
+        }
+    }
+
+    /// Check the bar's range against the position's stop and target
+    ///
+    /// A hit fills at the stop or target price. When a bar reaches both,
+    /// the stop is assumed to have been hit first.
+    fn intrabar_trigger(pos: &Position, data: &MarketData) -> Option<ExitTrigger> {
+        let long = pos.side().is_long();
+        let (adverse, favorable) = if long {
+            (data.low, data.high)
+        } else {
+            (data.high, data.low)
+        };
+
+        if let Some(stop) = pos.stop_loss() {
+            if (long && adverse <= stop) || (!long && adverse >= stop) {
+                return Some(ExitTrigger::StopLoss { price: stop, stop });
+            }
+        }
+        if let Some(target) = pos.take_profit() {
+            if (long && favorable >= target) || (!long && favorable <= target) {
+                return Some(ExitTrigger::TakeProfit { price: target, target });
             }
         }
+        None
     }
 
     /// Enter a position
@@ -302,14 +748,23 @@ impl StateMachine {
     /// * `quantity` - Position size
     /// * `side` - Long or Short
     fn enter_position(&mut self, entry_price: f64, quantity: f64, side: Side) {
-        let position = Position::new(
+        let entry_price = self.fill_price(entry_price, side.is_long());
+        let mut position = Position::new(
             entry_price,
             quantity,
             side,
             chrono::Utc::now().timestamp_millis(),
-        );
+        )
+        .with_contract_type(self.contract_type);
+        let fee = self.charge_commission(entry_price, quantity, self.contract_type, Liquidity::Taker);
+        position.add_fee(fee);
+
+        if let Some(account) = &mut self.account {
+            account.reserve(position.notional(quantity));
+        }
 
         self.position = Some(position);
+        self.last_partial_exit = None;
         self.transition_to(
             State::InPosition,
             format!(
@@ -326,16 +781,24 @@ impl StateMachine {
     /// # Arguments
     ///
     /// * `exit_price` - Price at which position was exited
+    /// * `liquidity` - Whether the exit rested on the book or crossed it
     ///
     /// # Returns
     ///
     /// The closed position (if any)
-    fn exit_position(&mut self, exit_price: f64) -> Option<Position> {
+    fn exit_position(&mut self, exit_price: f64, liquidity: Liquidity) -> Option<Position> {
         if let Some(mut pos) = self.position.take() {
+            let exit_price = self.fill_price(exit_price, pos.side().is_short());
+            let fee = self.charge_commission(exit_price, pos.quantity(), pos.contract_type(), liquidity);
+            pos.add_fee(fee);
             pos.close(exit_price, chrono::Utc::now().timestamp_millis());
 
             let pnl = pos.realized_pnl().unwrap_or(0.0);
-            let pnl_pct = (pnl / (pos.entry_price() * pos.quantity())) * 100.0;
+            let pnl_pct = pos.realized_pnl_pct().unwrap_or(0.0);
+
+            if let Some(account) = &mut self.account {
+                account.settle(pos.notional(pos.quantity()), pnl - pos.scaled_out_pnl());
+            }
 
             self.transition_to(
                 State::Idle,
@@ -345,19 +808,93 @@ impl StateMachine {
                 ),
             );
 
+            self.last_closed = Some(pos.clone());
+            self.closed_positions.push_back(pos.clone());
+            if self.closed_positions.len() > MAX_CLOSED_POSITIONS {
+                self.closed_positions.pop_front();
+            }
             Some(pos)
         } else {
             None
         }
     }
 
+    /// Close `quantity` of the current position
+    ///
+    /// A reduce of at least the open quantity closes the position. Any excess
+    /// is dropped with a warning when reduce-only is enabled, and otherwise
+    /// opens a position of that size on the other side.
+    fn reduce_position(&mut self, price: f64, quantity: f64) {
+        debug_assert!(quantity.is_finite() && quantity > 0.0, "reduce quantity must be positive, got {}", quantity);
+        let Some(pos) = &self.position else {
+            return;
+        };
+        let side = pos.side();
+        let open = pos.quantity();
+
+        if quantity < open - 1e-12 {
+            let fill = self.fill_price(price, side.is_short());
+            let contract_type = pos.contract_type();
+            let fee = self.charge_commission(fill, quantity, contract_type, Liquidity::Taker);
+            let Some(pos) = self.position.as_mut() else {
+                return;
+            };
+            let pnl = pos.scale_out(fill, quantity);
+            pos.add_fee(fee);
+            let notional = pos.notional(quantity);
+            if let Some(account) = &mut self.account {
+                account.settle(notional, pnl);
+            }
+            self.last_partial_exit = Some(PartialExit { price: fill, quantity, pnl: pnl - fee });
+            tracing::info!(
+                symbol = %self.symbol,
+                price = %fill,
+                quantity = %quantity,
+                pnl = %pnl,
+                "Reduced position"
+            );
+            return;
+        }
+
+        self.exit_position(price, Liquidity::Taker);
+        let excess = quantity - open;
+        if excess <= 1e-12 {
+            return;
+        }
+
+        if self.reduce_only {
+            tracing::warn!(
+                symbol = %self.symbol,
+                quantity = %quantity,
+                open = %open,
+                "Reduce exceeds the open quantity, closed the position instead of reversing"
+            );
+            return;
+        }
+
+        let reversal = match side.opposite() {
+            Side::Long => Action::EnterLong { price, quantity: excess },
+            Side::Short => Action::EnterShort { price, quantity: excess },
+        };
+        if let Err(e) = self.execute(reversal) {
+            tracing::warn!(symbol = %self.symbol, "Couldn't reverse position: {}", e);
+        }
+    }
+
     /// Reset the state machine
     ///
-    /// Returns to Idle state, clears context and position.
+    /// Returns to Idle state, clears context and position. The account
+    /// balance is kept and the dropped position's margin is released.
     pub fn reset(&mut self) {
         self.state = State::Idle;
         self.context = Context::new();
         self.position = None;
+        if let Some(account) = &mut self.account {
+            account.used_margin = 0.0;
+        }
+        self.last_closed = None;
+        self.last_partial_exit = None;
+        self.closed_positions.clear();
         self.transition_history.clear();
 
         tracing::info!(symbol = %self.symbol, "State machine reset");
@@ -379,6 +916,9 @@ mod tests {
             volume: 100,
             bid: price - 1.0,
             ask: price + 1.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         }
     }
 
@@ -437,6 +977,128 @@ mod tests {
         assert!(sm.position().is_none());
     }
 
+    #[test]
+    fn test_entry_beyond_balance_rejected() {
+        let mut sm = StateMachine::new("BTCUSDT".to_string());
+        sm.set_account(Some(Account::new(4_000.0)));
+
+        let result = sm.execute(Action::EnterLong {
+            price: 50000.0,
+            quantity: 0.1,
+        });
+        assert!(result.is_err());
+        assert!(sm.position().is_none());
+        assert_eq!(sm.current_state(), &State::Idle);
+        assert_eq!(sm.account(), Some(&Account::new(4_000.0)));
+    }
+
+    #[test]
+    fn test_balance_after_winning_round_trip() {
+        let mut sm = StateMachine::new("BTCUSDT".to_string());
+        sm.set_account(Some(Account::new(10_000.0)));
+
+        sm.execute(Action::EnterLong {
+            price: 50000.0,
+            quantity: 0.1,
+        })
+        .unwrap();
+        let account = sm.account().unwrap();
+        assert_eq!(account.used_margin, 5_000.0);
+        assert_eq!(account.available(), 5_000.0);
+
+        sm.execute(Action::ExitPosition { price: 51000.0 }).unwrap();
+        let account = sm.account().unwrap();
+        assert!((account.balance - 10_100.0).abs() < 1e-9);
+        assert_eq!(account.used_margin, 0.0);
+    }
+
+    #[test]
+    fn test_exit_ladder_scales_out() {
+        let mut sm = StateMachine::new("BTCUSDT".to_string());
+        sm.set_account(Some(Account::new(1_000.0)));
+        sm.execute(Action::EnterWithExitLadder {
+            entry: Box::new(Action::EnterLong {
+                price: 100.0,
+                quantity: 1.0,
+            }),
+            exit_ladder: vec![
+                ExitRung { price: 120.0, fraction: 0.5 },
+                ExitRung { price: 110.0, fraction: 0.5 },
+            ],
+        })
+        .unwrap();
+        assert_eq!(sm.position().unwrap().exit_ladder().len(), 2);
+
+        assert!(sm.update(&create_test_data(105.0)).is_none());
+        assert_eq!(sm.position().unwrap().quantity(), 1.0);
+
+        // First rung: half the position closes and the stop moves to breakeven
+        assert!(sm.update(&create_test_data(110.0)).is_none());
+        let pos = sm.position().unwrap();
+        assert_eq!(pos.quantity(), 0.5);
+        assert_eq!(pos.scaled_out_pnl(), 5.0);
+        assert_eq!(pos.stop_loss(), Some(100.0));
+        assert_eq!(sm.account().unwrap().balance, 1_005.0);
+        assert_eq!(sm.account().unwrap().used_margin, 50.0);
+
+        assert!(sm.update(&create_test_data(115.0)).is_none());
+
+        // Second rung closes the rest
+        let trigger = sm.update(&create_test_data(120.0));
+        assert_eq!(trigger, Some(ExitTrigger::TakeProfit { price: 120.0, target: 120.0 }));
+        assert!(sm.position().is_none());
+        assert_eq!(sm.current_state(), &State::Idle);
+
+        let closed = sm.last_closed_position().unwrap();
+        assert_eq!(closed.realized_pnl(), Some(15.0));
+        assert_eq!(closed.original_quantity(), 1.0);
+        assert_eq!(sm.account().unwrap().balance, 1_015.0);
+        assert_eq!(sm.account().unwrap().used_margin, 0.0);
+    }
+
+    #[test]
+    fn test_exit_ladder_remainder_stopped_at_breakeven() {
+        let mut sm = StateMachine::new("BTCUSDT".to_string());
+        sm.execute(Action::EnterShort {
+            price: 100.0,
+            quantity: 2.0,
+        })
+        .unwrap();
+        sm.execute(Action::SetExitLadder {
+            exit_ladder: vec![ExitRung { price: 90.0, fraction: 0.25 }],
+        })
+        .unwrap();
+
+        assert!(sm.update(&create_test_data(90.0)).is_none());
+        assert_eq!(sm.position().unwrap().quantity(), 1.5);
+
+        // The rest is stopped out at the entry price
+        let trigger = sm.update(&create_test_data(100.0));
+        assert!(matches!(trigger, Some(ExitTrigger::StopLoss { .. })));
+        assert_eq!(sm.last_closed_position().unwrap().realized_pnl(), Some(5.0));
+    }
+
+    #[test]
+    fn test_closed_positions_retained() {
+        let mut sm = StateMachine::new("BTCUSDT".to_string());
+
+        sm.execute(Action::EnterLong { price: 50000.0, quantity: 0.1 }).unwrap();
+        sm.execute(Action::ExitPosition { price: 51000.0 }).unwrap();
+        sm.execute(Action::EnterShort { price: 51000.0, quantity: 0.2 }).unwrap();
+        sm.execute(Action::ExitPosition { price: 50500.0 }).unwrap();
+
+        let closed = sm.closed_positions();
+        assert_eq!(closed.len(), 2);
+        assert_eq!(closed[0].side(), Side::Long);
+        assert_eq!((closed[0].entry_price(), closed[0].exit_price()), (50000.0, Some(51000.0)));
+        assert!((closed[0].realized_pnl().unwrap() - 100.0).abs() < 1e-9);
+        assert_eq!(closed[1].side(), Side::Short);
+        assert!((closed[1].realized_pnl().unwrap() - 100.0).abs() < 1e-9);
+
+        assert_eq!(sm.drain_closed_positions().len(), 2);
+        assert!(sm.closed_positions().is_empty());
+    }
+
     #[test]
     fn test_update_with_data() {
         let mut sm = StateMachine::new("BTCUSDT".to_string());
@@ -471,6 +1133,173 @@ mod tests {
         assert!(sm.position().is_none());
     }
 
+    #[test]
+    fn test_stop_loss_without_auto_exit() {
+        let mut sm = StateMachine::new("BTCUSDT".to_string());
+        sm.set_auto_exit(false);
+
+        sm.execute(Action::EnterLong {
+            price: 50000.0,
+            quantity: 0.1,
+        })
+        .unwrap();
+        sm.execute(Action::UpdateStopLoss { new_stop: 49000.0 })
+            .unwrap();
+
+        // Stop is reported but the position stays open
+        let trigger = sm.update(&create_test_data(48500.0));
+        assert_eq!(
+            trigger,
+            Some(ExitTrigger::StopLoss { price: 48500.0, stop: 49000.0 })
+        );
+        assert_eq!(sm.current_state(), &State::InPosition);
+        assert!(sm.position().is_some());
+    }
+
+    #[test]
+    fn test_slippage_applied_to_fills() {
+        let mut sm = StateMachine::new("BTCUSDT".to_string());
+        sm.set_slippage_bps(10.0);
+
+        // Short entry sells below, exit buys above
+        sm.execute(Action::EnterShort {
+            price: 50000.0,
+            quantity: 0.1,
+        })
+        .unwrap();
+        assert_eq!(sm.position().unwrap().entry_price(), 49950.0);
+
+        sm.execute(Action::ExitPosition { price: 50000.0 }).unwrap();
+        let closed = sm.last_closed_position().unwrap();
+        assert_eq!(closed.exit_price(), Some(50050.0));
+        assert!((closed.realized_pnl().unwrap() + 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_maker_rebate_adds_to_realized_pnl() {
+        let enter = || Action::EnterLong { price: 100.0, quantity: 1.0 };
+        let mut sm = StateMachine::new("BTCUSDT".to_string());
+        sm.set_commission(CommissionSchedule::flat(-2.0, 5.0));
+
+        // Target fill rests on the book: 10 gross, 0.05 taker fee in, 0.022 rebate out
+        sm.execute(enter()).unwrap();
+        sm.execute(Action::UpdateTakeProfit { new_target: 110.0 }).unwrap();
+        sm.update(&create_test_data(110.0));
+        let maker_exit = sm.last_closed_position().unwrap();
+        assert!((maker_exit.fees() - 0.028).abs() < 1e-9);
+        assert!((maker_exit.realized_pnl().unwrap() - 9.972).abs() < 1e-9);
+
+        // The same trade closed by the strategy pays the taker fee instead
+        sm.execute(enter()).unwrap();
+        sm.execute(Action::ExitPosition { price: 110.0 }).unwrap();
+        let taker_exit = sm.last_closed_position().unwrap();
+        assert!((taker_exit.realized_pnl().unwrap() - 9.895).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_volume_tier_lowers_later_fees() {
+        let mut sm = StateMachine::new("BTCUSDT".to_string());
+        sm.set_commission(CommissionSchedule::default().with_tier(0.0, 0.0, 10.0).with_tier(1_000.0, 0.0, 5.0));
+        let round_trip = |sm: &mut StateMachine| {
+            sm.execute(Action::EnterLong { price: 100.0, quantity: 5.0 }).unwrap();
+            sm.execute(Action::ExitPosition { price: 100.0 }).unwrap();
+            sm.last_closed_position().unwrap().fees()
+        };
+
+        // 500 in and 500 out at 10 bps reaches the 1,000 tier
+        assert!((round_trip(&mut sm) - 1.0).abs() < 1e-9);
+        assert_eq!(sm.traded_volume(), 1_000.0);
+
+        // Now charged 5 bps
+        assert!((round_trip(&mut sm) - 0.5).abs() < 1e-9);
+        assert!((sm.last_closed_position().unwrap().realized_pnl().unwrap() + 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reduce_only_clamps_to_open_quantity() {
+        let mut sm = StateMachine::new("BTCUSDT".to_string());
+        sm.execute(Action::EnterLong {
+            price: 50000.0,
+            quantity: 1.0,
+        })
+        .unwrap();
+
+        sm.execute(Action::ReducePosition { price: 51000.0, quantity: 0.4 }).unwrap();
+        assert_eq!(sm.current_state(), &State::InPosition);
+        assert!((sm.position().unwrap().quantity() - 0.6).abs() < 1e-12);
+
+        // More than the remaining 0.6 closes the position without going short
+        sm.execute(Action::ReducePosition { price: 51000.0, quantity: 2.0 }).unwrap();
+        assert_eq!(sm.current_state(), &State::Idle);
+        assert!(sm.position().is_none());
+        assert!((sm.last_closed_position().unwrap().realized_pnl().unwrap() - 1000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_intrabar_stop_fills_at_stop() {
+        let mut sm = StateMachine::new("BTCUSDT".to_string());
+        sm.set_intrabar_exits(true);
+        sm.execute(Action::EnterLong {
+            price: 50000.0,
+            quantity: 0.1,
+        })
+        .unwrap();
+        sm.execute(Action::UpdateStopLoss { new_stop: 49000.0 })
+            .unwrap();
+
+        // Low pierces the stop but the bar closes above it
+        let mut data = create_test_data(49800.0);
+        data.low = 48800.0;
+        let trigger = sm.update(&data);
+
+        assert_eq!(
+            trigger,
+            Some(ExitTrigger::StopLoss { price: 49000.0, stop: 49000.0 })
+        );
+        assert!(sm.position().is_none());
+        assert_eq!(sm.last_closed_position().unwrap().exit_price(), Some(49000.0));
+    }
+
+    #[test]
+    fn test_close_only_ignores_intrabar_stop() {
+        let mut sm = StateMachine::new("BTCUSDT".to_string());
+        sm.execute(Action::EnterLong {
+            price: 50000.0,
+            quantity: 0.1,
+        })
+        .unwrap();
+        sm.execute(Action::UpdateStopLoss { new_stop: 49000.0 })
+            .unwrap();
+
+        let mut data = create_test_data(49800.0);
+        data.low = 48800.0;
+
+        assert_eq!(sm.update(&data), None);
+        assert!(sm.position().is_some());
+    }
+
+    #[test]
+    fn test_intrabar_short_take_profit() {
+        let mut sm = StateMachine::new("BTCUSDT".to_string());
+        sm.set_intrabar_exits(true);
+        sm.execute(Action::EnterShort {
+            price: 50000.0,
+            quantity: 0.1,
+        })
+        .unwrap();
+        sm.execute(Action::UpdateTakeProfit { new_target: 48000.0 })
+            .unwrap();
+
+        let mut data = create_test_data(48500.0);
+        data.low = 47900.0;
+
+        assert_eq!(
+            sm.update(&data),
+            Some(ExitTrigger::TakeProfit { price: 48000.0, target: 48000.0 })
+        );
+        assert_eq!(sm.last_closed_position().unwrap().exit_price(), Some(48000.0));
+    }
+
     #[test]
     fn test_take_profit_auto_exit() {
         let mut sm = StateMachine::new("BTCUSDT".to_string());
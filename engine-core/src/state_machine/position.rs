@@ -4,7 +4,45 @@
 
 use serde::{Deserialize, Serialize};
 
-pub use super::action::Side;
+pub use super::action::{ExitRung, Side};
+
+/// How a position's P&L is denominated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ContractType {
+    /// P&L in the quote currency: `(exit - entry) * quantity`
+    #[default]
+    Linear,
+    /// P&L in the base asset, as on inverse perpetuals: `quantity * (1/entry - 1/exit)`
+    Inverse,
+}
+
+impl ContractType {
+    /// Value of `quantity` at `price`, in the currency P&L is paid in
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::state_machine::position::ContractType;
+    ///
+    /// assert_eq!(ContractType::Linear.notional(50000.0, 2.0), 100000.0);
+    /// assert_eq!(ContractType::Inverse.notional(50000.0, 100000.0), 2.0);
+    /// ```
+    pub fn notional(self, price: f64, quantity: f64) -> f64 {
+        match self {
+            ContractType::Linear => price * quantity,
+            ContractType::Inverse => quantity / price,
+        }
+    }
+
+    /// P&L of closing `quantity` on `side`, entered at `entry_price`, at `exit_price`
+    pub fn pnl(self, side: Side, entry_price: f64, exit_price: f64, quantity: f64) -> f64 {
+        let per_unit = match self {
+            ContractType::Linear => exit_price - entry_price,
+            ContractType::Inverse => 1.0 / entry_price - 1.0 / exit_price,
+        };
+        side.sign() * per_unit * quantity
+    }
+}
 
 /// Represents an active or closed trading position
 ///
@@ -48,6 +86,38 @@ pub struct Position {
 
     /// Exit timestamp (if closed)
     exit_timestamp: Option<i64>,
+
+    /// Bars processed while the position was open, not counting the entry bar
+    #[serde(default)]
+    bars_held: u64,
+
+    /// Highest bar high after the entry bar, if any
+    #[serde(default)]
+    highest_since_entry: Option<f64>,
+
+    /// Lowest bar low after the entry bar, if any
+    #[serde(default)]
+    lowest_since_entry: Option<f64>,
+
+    /// Take-profit levels not yet reached, nearest first
+    #[serde(default)]
+    exit_ladder: Vec<ExitRung>,
+
+    /// Quantity already closed by scaling out
+    #[serde(default)]
+    scaled_out_quantity: f64,
+
+    /// P&L realized by scaling out
+    #[serde(default)]
+    scaled_out_pnl: f64,
+
+    /// Commissions paid on the position's fills, net of rebates
+    #[serde(default)]
+    fees: f64,
+
+    /// How P&L is denominated
+    #[serde(default)]
+    contract_type: ContractType,
 }
 
 impl Position {
@@ -80,19 +150,57 @@ impl Position {
             take_profit: None,
             exit_price: None,
             exit_timestamp: None,
+            bars_held: 0,
+            highest_since_entry: None,
+            lowest_since_entry: None,
+            exit_ladder: Vec::new(),
+            scaled_out_quantity: 0.0,
+            scaled_out_pnl: 0.0,
+            fees: 0.0,
+            contract_type: ContractType::Linear,
         }
     }
 
+    /// Denominate P&L as for `contract_type` (linear by default)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::state_machine::{Position, position::{ContractType, Side}};
+    ///
+    /// // 100,000 USD of contracts
+    /// let mut pos = Position::new(50000.0, 100000.0, Side::Long, 1234567890)
+    ///     .with_contract_type(ContractType::Inverse);
+    /// pos.close(62500.0, 1234567900);
+    ///
+    /// // 100,000 * (1/50,000 - 1/62,500) = 0.4 BTC
+    /// assert!((pos.realized_pnl().unwrap() - 0.4).abs() < 1e-9);
+    /// ```
+    pub fn with_contract_type(mut self, contract_type: ContractType) -> Self {
+        self.contract_type = contract_type;
+        self
+    }
+
+    /// Get how P&L is denominated
+    pub fn contract_type(&self) -> ContractType {
+        self.contract_type
+    }
+
     /// Get entry price
     pub fn entry_price(&self) -> f64 {
         self.entry_price
     }
 
-    /// Get position quantity
+    /// Get position quantity still open
     pub fn quantity(&self) -> f64 {
         self.quantity
     }
 
+    /// Get the quantity the position was opened with
+    pub fn original_quantity(&self) -> f64 {
+        self.quantity + self.scaled_out_quantity
+    }
+
     /// Get position side
     pub fn side(&self) -> Side {
         self.side
@@ -103,6 +211,64 @@ impl Position {
         self.entry_timestamp
     }
 
+    /// Get exit price (closed positions only)
+    pub fn exit_price(&self) -> Option<f64> {
+        self.exit_price
+    }
+
+    /// Get the number of bars held since entry
+    pub fn bars_held(&self) -> u64 {
+        self.bars_held
+    }
+
+    /// Count one more bar held
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::state_machine::{Position, position::Side};
+    ///
+    /// let mut pos = Position::new(50000.0, 0.1, Side::Long, 1234567890);
+    /// assert_eq!(pos.bars_held(), 0);
+    /// pos.record_bar();
+    /// assert_eq!(pos.bars_held(), 1);
+    /// ```
+    pub fn record_bar(&mut self) {
+        self.bars_held += 1;
+    }
+
+    /// Get the highest price reached since entry
+    ///
+    /// Starts at the entry price; the basis for chandelier-style long stops.
+    pub fn highest_since_entry(&self) -> f64 {
+        self.highest_since_entry.map_or(self.entry_price, |high| high.max(self.entry_price))
+    }
+
+    /// Get the lowest price reached since entry
+    ///
+    /// Starts at the entry price; the basis for chandelier-style short stops.
+    pub fn lowest_since_entry(&self) -> f64 {
+        self.lowest_since_entry.map_or(self.entry_price, |low| low.min(self.entry_price))
+    }
+
+    /// Extend the high/low water-marks with a bar's range
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::state_machine::{Position, position::Side};
+    ///
+    /// let mut pos = Position::new(50000.0, 0.1, Side::Long, 1234567890);
+    /// pos.record_range(50800.0, 49900.0);
+    /// pos.record_range(50500.0, 50100.0);
+    /// assert_eq!(pos.highest_since_entry(), 50800.0);
+    /// assert_eq!(pos.lowest_since_entry(), 49900.0);
+    /// ```
+    pub fn record_range(&mut self, high: f64, low: f64) {
+        self.highest_since_entry = Some(self.highest_since_entry.map_or(high, |mark| mark.max(high)));
+        self.lowest_since_entry = Some(self.lowest_since_entry.map_or(low, |mark| mark.min(low)));
+    }
+
     /// Get current price
     pub fn current_price(&self) -> f64 {
         self.current_price
@@ -163,9 +329,94 @@ impl Position {
         self.take_profit
     }
 
+    /// Set the take-profit levels to scale out at
+    ///
+    /// Rungs are kept nearest first (ascending prices for longs, descending
+    /// for shorts). Each rung's `fraction` is of the original quantity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::state_machine::{Position, position::{ExitRung, Side}};
+    ///
+    /// let mut pos = Position::new(50000.0, 1.0, Side::Long, 1234567890);
+    /// pos.set_exit_ladder(vec![
+    ///     ExitRung { price: 54000.0, fraction: 0.5 },
+    ///     ExitRung { price: 52000.0, fraction: 0.5 },
+    /// ]);
+    /// assert_eq!(pos.exit_ladder()[0].price, 52000.0);
+    /// ```
+    pub fn set_exit_ladder(&mut self, mut ladder: Vec<ExitRung>) {
+        ladder.sort_by(|a, b| a.price.partial_cmp(&b.price).unwrap_or(std::cmp::Ordering::Equal));
+        if self.side.is_short() {
+            ladder.reverse();
+        }
+        self.exit_ladder = ladder;
+    }
+
+    /// Get the take-profit levels not yet reached, nearest first
+    pub fn exit_ladder(&self) -> &[ExitRung] {
+        &self.exit_ladder
+    }
+
+    /// Remove and return the nearest rung if `price` has reached it
+    pub fn take_reached_rung(&mut self, price: f64) -> Option<ExitRung> {
+        let rung = *self.exit_ladder.first()?;
+        let reached = match self.side {
+            Side::Long => price >= rung.price,
+            Side::Short => price <= rung.price,
+        };
+        if !reached {
+            return None;
+        }
+        self.exit_ladder.remove(0);
+        Some(rung)
+    }
+
+    /// Close part of the position at `exit_price`
+    ///
+    /// At most the open quantity is closed. Returns the P&L realized by
+    /// this partial exit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::state_machine::{Position, position::Side};
+    ///
+    /// let mut pos = Position::new(50000.0, 1.0, Side::Long, 1234567890);
+    /// assert_eq!(pos.scale_out(52000.0, 0.25), 500.0);
+    /// assert_eq!(pos.quantity(), 0.75);
+    /// assert_eq!(pos.original_quantity(), 1.0);
+    /// ```
+    pub fn scale_out(&mut self, exit_price: f64, quantity: f64) -> f64 {
+        let quantity = quantity.min(self.quantity);
+        let pnl = self.contract_type.pnl(self.side, self.entry_price, exit_price, quantity);
+
+        self.quantity -= quantity;
+        self.scaled_out_quantity += quantity;
+        self.scaled_out_pnl += pnl;
+        pnl
+    }
+
+    /// Get the P&L realized so far by scaling out
+    pub fn scaled_out_pnl(&self) -> f64 {
+        self.scaled_out_pnl
+    }
+
+    /// Charge a commission on one of the position's fills (negative for a rebate)
+    pub fn add_fee(&mut self, fee: f64) {
+        self.fees += fee;
+    }
+
+    /// Get the commissions paid so far, net of rebates
+    pub fn fees(&self) -> f64 {
+        self.fees
+    }
+
     /// Calculate unrealized P&L
     ///
-    /// Returns P&L in dollars (not percentage).
+    /// Returns P&L in the quote currency (the base asset for inverse
+    /// contracts), not a percentage, on the quantity still open.
     ///
     /// # Examples
     ///
@@ -184,16 +435,30 @@ impl Position {
             return None;
         }
 
-        let price_diff = match self.side {
-            Side::Long => self.current_price - self.entry_price,
-            Side::Short => self.entry_price - self.current_price,
-        };
+        Some(self.contract_type.pnl(self.side, self.entry_price, self.current_price, self.quantity))
+    }
 
-        Some(price_diff * self.quantity)
+    /// Calculate unrealized P&L as a percentage of the open quantity's cost
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::state_machine::{Position, position::Side};
+    ///
+    /// let mut pos = Position::new(50000.0, 0.1, Side::Short, 1234567890);
+    /// pos.update_current_price(49000.0);
+    ///
+    /// // Short: 2% profit when price falls 2%
+    /// assert!((pos.unrealized_pnl_pct().unwrap() - 2.0).abs() < 1e-9);
+    /// ```
+    pub fn unrealized_pnl_pct(&self) -> Option<f64> {
+        percent_of(self.unrealized_pnl()?, self.notional(self.quantity))
     }
 
     /// Calculate realized P&L (for closed positions)
     ///
+    /// Includes P&L from any earlier partial exits, net of [`fees`](Self::fees).
+    ///
     /// # Examples
     ///
     /// ```
@@ -206,13 +471,22 @@ impl Position {
     /// ```
     pub fn realized_pnl(&self) -> Option<f64> {
         let exit_price = self.exit_price?;
+        let pnl = self.contract_type.pnl(self.side, self.entry_price, exit_price, self.quantity);
 
-        let price_diff = match self.side {
-            Side::Long => exit_price - self.entry_price,
-            Side::Short => self.entry_price - exit_price,
-        };
+        Some(pnl + self.scaled_out_pnl - self.fees)
+    }
 
-        Some(price_diff * self.quantity)
+    /// Calculate realized P&L as a percentage of the position's cost basis
+    ///
+    /// The cost basis is the entry value of the quantity originally
+    /// opened, so earlier partial exits are included.
+    pub fn realized_pnl_pct(&self) -> Option<f64> {
+        percent_of(self.realized_pnl()?, self.notional(self.original_quantity()))
+    }
+
+    /// Entry value of `quantity`, in the currency P&L is paid in
+    pub fn notional(&self, quantity: f64) -> f64 {
+        self.contract_type.notional(self.entry_price, quantity)
     }
 
     /// Check if position is closed
@@ -245,14 +519,8 @@ impl Position {
     /// assert!(pos.is_stop_loss_hit());
     /// ```
     pub fn is_stop_loss_hit(&self) -> bool {
-        if let Some(stop) = self.stop_loss {
-            match self.side {
-                Side::Long => self.current_price <= stop,
-                Side::Short => self.current_price >= stop,
-            }
-        } else {
-            false
-        }
+        self.stop_loss
+            .is_some_and(|stop| self.side.sign() * (self.current_price - stop) <= 0.0)
     }
 
     /// Check if take profit is hit
@@ -269,14 +537,8 @@ impl Position {
     /// assert!(pos.is_take_profit_hit());
     /// ```
     pub fn is_take_profit_hit(&self) -> bool {
-        if let Some(target) = self.take_profit {
-            match self.side {
-                Side::Long => self.current_price >= target,
-                Side::Short => self.current_price <= target,
-            }
-        } else {
-            false
-        }
+        self.take_profit
+            .is_some_and(|target| self.side.sign() * (self.current_price - target) >= 0.0)
     }
 
     /// Get position age in milliseconds
@@ -290,6 +552,14 @@ impl Position {
     }
 }
 
+/// `pnl` as a percentage of `cost`, or `None` for a zero cost
+fn percent_of(pnl: f64, cost: f64) -> Option<f64> {
+    if cost == 0.0 {
+        return None;
+    }
+    Some(pnl / cost.abs() * 100.0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -330,6 +600,63 @@ mod tests {
         assert!((pnl + 100.0).abs() < 0.01); // Negative P&L
     }
 
+    #[test]
+    fn test_pnl_pct_independent_of_quantity() {
+        for quantity in [0.1, 10.0] {
+            let mut pos = Position::new(50000.0, quantity, Side::Long, 1234567890);
+            pos.update_current_price(51000.0);
+            assert!((pos.unrealized_pnl_pct().unwrap() - 2.0).abs() < 1e-9);
+
+            pos.close(51000.0, 1234567900);
+            assert!((pos.realized_pnl_pct().unwrap() - 2.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_linear_and_inverse_pnl() {
+        // 100,000 contracts from 50,000 to 62,500 and back to 40,000
+        for (side, exit, linear, inverse) in [
+            (Side::Long, 62500.0, 1_250_000_000.0, 0.4),
+            (Side::Short, 62500.0, -1_250_000_000.0, -0.4),
+            (Side::Long, 40000.0, -1_000_000_000.0, -0.5),
+            (Side::Short, 40000.0, 1_000_000_000.0, 0.5),
+        ] {
+            let mut pos = Position::new(50000.0, 100000.0, side, 1234567890);
+            pos.close(exit, 1234567900);
+            assert!((pos.realized_pnl().unwrap() - linear).abs() < 1e-6);
+
+            let mut pos = Position::new(50000.0, 100000.0, side, 1234567890)
+                .with_contract_type(ContractType::Inverse);
+            pos.close(exit, 1234567900);
+            assert!((pos.realized_pnl().unwrap() - inverse).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_take_reached_rung() {
+        for (side, short_of, past) in [(Side::Long, 51000.0, 53000.0), (Side::Short, 53000.0, 51000.0)] {
+            let mut pos = Position::new(50000.0, 1.0, side, 1234567890);
+            pos.set_exit_ladder(vec![ExitRung { price: 52000.0, fraction: 0.5 }]);
+
+            assert!(pos.take_reached_rung(short_of).is_none());
+            assert!(pos.take_reached_rung(f64::NAN).is_none());
+            assert_eq!(pos.take_reached_rung(past).map(|rung| rung.price), Some(52000.0));
+            assert!(pos.exit_ladder().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_inverse_scale_out() {
+        let mut pos = Position::new(50000.0, 100000.0, Side::Long, 1234567890)
+            .with_contract_type(ContractType::Inverse);
+        assert!((pos.scale_out(62500.0, 50000.0) - 0.2).abs() < 1e-9);
+
+        pos.close(62500.0, 1234567900);
+        assert!((pos.realized_pnl().unwrap() - 0.4).abs() < 1e-9);
+        // 0.4 BTC on 2 BTC of entry value
+        assert!((pos.realized_pnl_pct().unwrap() - 20.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_stop_loss_long() {
         let mut pos = Position::new(50000.0, 0.1, Side::Long, 1234567890);
@@ -31,6 +31,9 @@
 //!     volume: 1000,
 //!     bid: 50499.0,
 //!     ask: 50501.0,
+//!     bid_qty: None,
+//!     ask_qty: None,
+//!     is_final: true,
 //! };
 //!
 //! storage.push(btc_data);
@@ -63,6 +66,9 @@
 //!         volume: 500,
 //!         bid: 3049.0,
 //!         ask: 3051.0,
+//!         bid_qty: None,
+//!         ask_qty: None,
+//!         is_final: true,
 //!     };
 //!     storage_clone.push(data);
 //! });
@@ -105,6 +111,9 @@ use crate::market_data::{MarketData, MarketDataWindow};
 ///     volume: 1000,
 ///     bid: 50499.0,
 ///     ask: 50501.0,
+///     bid_qty: None,
+///     ask_qty: None,
+///     is_final: true,
 /// };
 ///
 /// storage.push(data);
@@ -164,6 +173,9 @@ impl MarketDataStorage {
     ///     volume: 1000,
     ///     bid: 50499.0,
     ///     ask: 50501.0,
+    ///     bid_qty: None,
+    ///     ask_qty: None,
+    ///     is_final: true,
     /// };
     ///
     /// storage.push(data);
@@ -211,6 +223,9 @@ impl MarketDataStorage {
     ///     volume: 1000,
     ///     bid: 50499.0,
     ///     ask: 50501.0,
+    ///     bid_qty: None,
+    ///     ask_qty: None,
+    ///     is_final: true,
     /// };
     /// storage.push(data);
     ///
@@ -246,6 +261,9 @@ impl MarketDataStorage {
     ///     volume: 1000,
     ///     bid: 50499.0,
     ///     ask: 50501.0,
+    ///     bid_qty: None,
+    ///     ask_qty: None,
+    ///     is_final: true,
     /// };
     ///
     /// let eth_data = MarketData {
@@ -258,6 +276,9 @@ impl MarketDataStorage {
     ///     volume: 500,
     ///     bid: 3049.0,
     ///     ask: 3051.0,
+    ///     bid_qty: None,
+    ///     ask_qty: None,
+    ///     is_final: true,
     /// };
     ///
     /// storage.push(btc_data);
@@ -273,6 +294,33 @@ impl MarketDataStorage {
         windows.keys().cloned().collect()
     }
 
+    /// Get the latest close for every stored symbol.
+    ///
+    /// Reads all symbols under a single lock without cloning any window,
+    /// so it stays cheap for frequent polling (e.g., a ticker tape).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::{MarketDataStorage, MarketData};
+    ///
+    /// let storage = MarketDataStorage::new(100);
+    /// storage.push(MarketData::from_trade("BTCUSDT", 50000.0, 1.0, 1));
+    /// storage.push(MarketData::from_trade("BTCUSDT", 50100.0, 1.0, 2));
+    /// storage.push(MarketData::from_trade("ETHUSDT", 3000.0, 1.0, 1));
+    ///
+    /// let prices = storage.latest_prices();
+    /// assert_eq!(prices["BTCUSDT"], 50100.0);
+    /// assert_eq!(prices["ETHUSDT"], 3000.0);
+    /// ```
+    pub fn latest_prices(&self) -> HashMap<String, f64> {
+        let windows = self.windows.read();
+        windows
+            .iter()
+            .filter_map(|(symbol, window)| Some((symbol.clone(), window.latest()?.close)))
+            .collect()
+    }
+
     /// Get a clone of the underlying storage Arc.
     ///
     /// This is useful for advanced use cases where you need direct access to the
@@ -324,6 +372,9 @@ impl Clone for MarketDataStorage {
     ///     volume: 1000,
     ///     bid: 50499.0,
     ///     ask: 50501.0,
+    ///     bid_qty: None,
+    ///     ask_qty: None,
+    ///     is_final: true,
     /// };
     /// storage1.push(data);
     ///
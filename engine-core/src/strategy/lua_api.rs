@@ -4,22 +4,140 @@
 //! Rust types to Lua tables and vice versa.
 
 use crate::error::{Result, TradingEngineError};
+use crate::indicators::{Divergence, IndicatorCache, IndicatorRegistry, WindowIndicators};
 use crate::market_data::{MarketData, MarketDataWindow};
-use crate::state_machine::{Action, Context};
+use crate::state_machine::{Action, Context, ExitRung, Position};
 use mlua::{Lua, Table, Value};
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Indicators requested by a strategy, as (kind, period) pairs
+type IndicatorRequests = Arc<Mutex<HashSet<(String, usize)>>>;
 
 /// API for accessing indicators from Lua
 ///
 /// This struct wraps a MarketDataWindow and provides methods
 /// that can be called from Lua scripts to calculate indicators.
+/// Indicators called from Lua are recorded, so the runner can tell the
+/// strategy's indicators apart when announcing warmup.
 pub struct IndicatorApi {
     window: MarketDataWindow,
+    requests: IndicatorRequests,
+    registry: IndicatorRegistry,
+    cached: Option<WindowIndicators>,
+    precomputed: HashMap<String, f64>,
 }
 
 impl IndicatorApi {
     /// Create a new indicator API from a market data window
     pub fn new(window: MarketDataWindow) -> Self {
-        Self { window }
+        Self {
+            window,
+            requests: Arc::new(Mutex::new(HashSet::new())),
+            registry: IndicatorRegistry::new(),
+            cached: None,
+            precomputed: HashMap::new(),
+        }
+    }
+
+    /// Make the custom indicators in `registry` available
+    pub fn with_registry(mut self, registry: IndicatorRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Share close-based indicator values (SMA, EMA, RSI, Bollinger, linear
+    /// regression) through `cache`
+    ///
+    /// Values are looked up under the window's current closes, so APIs over
+    /// different windows never see each other's values.
+    pub fn with_cache(mut self, cache: &IndicatorCache) -> Self {
+        self.cached = Some(cache.for_window(&self.window));
+        self
+    }
+
+    /// Offer the current bar's values of indicators computed ahead of time
+    ///
+    /// Keys are [`Indicator::key`](crate::indicators::Indicator::key) names
+    /// from [`precompute_indicators`](crate::indicators::precompute_indicators).
+    pub fn with_precomputed(mut self, values: HashMap<String, f64>) -> Self {
+        self.precomputed = values;
+        self
+    }
+
+    /// Look up a precomputed indicator's value for the current bar
+    ///
+    /// Returns `None` if it wasn't precomputed or is still warming up.
+    pub fn precomputed(&self, key: &str) -> Option<f64> {
+        self.precomputed.get(key).copied().filter(|value| !value.is_nan())
+    }
+
+    /// Calculate a custom indicator by name
+    ///
+    /// Returns `None` if it isn't registered or has no value yet.
+    pub fn custom(&self, name: &str) -> Option<f64> {
+        self.registry.evaluate(name, &self.window)
+    }
+
+    /// Number of bars an indicator needs before it produces a value
+    ///
+    /// Known kinds are `"sma"`, `"ema"`, `"rsi"`, `"momentum"`, `"bollinger"`
+    /// and `"linreg"`.
+    /// Returns `None` for unknown kinds or a zero period.
+    pub fn bars_required(kind: &str, period: usize) -> Option<usize> {
+        if period == 0 {
+            return None;
+        }
+        match kind {
+            "sma" | "ema" | "bollinger" => Some(period),
+            "rsi" | "momentum" => Some(period + 1),
+            "linreg" if period >= 2 => Some(period),
+            _ => None,
+        }
+    }
+
+    /// Check if an indicator has warmed up
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::market_data::MarketDataWindow;
+    /// use trading_engine::strategy::IndicatorApi;
+    ///
+    /// let api = IndicatorApi::new(MarketDataWindow::new(100));
+    /// assert!(!api.is_ready("rsi", 14));
+    /// ```
+    pub fn is_ready(&self, kind: &str, period: usize) -> bool {
+        Self::bars_required(kind, period).is_some_and(|bars| self.window.len() >= bars)
+    }
+
+    /// Check if an indicator needs more bars than the window can ever hold
+    ///
+    /// Such an indicator never produces a value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trading_engine::market_data::MarketDataWindow;
+    /// use trading_engine::strategy::IndicatorApi;
+    ///
+    /// let api = IndicatorApi::new(MarketDataWindow::new(50));
+    /// assert!(api.exceeds_window("ema", 200));
+    /// assert!(!api.exceeds_window("ema", 50));
+    /// ```
+    pub fn exceeds_window(&self, kind: &str, period: usize) -> bool {
+        Self::bars_required(kind, period).is_some_and(|bars| bars > self.window.capacity())
+    }
+
+    /// Number of bars the window can hold
+    pub fn window_capacity(&self) -> usize {
+        self.window.capacity()
+    }
+
+    /// Indicators requested from Lua through this API
+    pub fn requested(&self) -> Vec<(String, usize)> {
+        self.requests.lock().iter().cloned().collect()
     }
 
     /// Get the close prices from the window
@@ -30,35 +148,125 @@ impl IndicatorApi {
 
     /// Calculate SMA
     pub fn sma(&self, period: usize) -> Option<f64> {
-        let closes = self.closes();
-        if closes.len() < period {
-            return None;
-        }
-        crate::indicators::simple_moving_average(&closes, period)
-            .last()
-            .copied()
+        cached_value(&self.cached, "sma", period, 0.0, || last_sma(&self.closes(), period))
     }
 
     /// Calculate EMA
     pub fn ema(&self, period: usize) -> Option<f64> {
-        let closes = self.closes();
-        if closes.len() < period {
-            return None;
-        }
-        crate::indicators::exponential_moving_average(&closes, period)
-            .last()
-            .copied()
+        cached_value(&self.cached, "ema", period, 0.0, || last_ema(&self.closes(), period))
     }
 
     /// Calculate RSI
     pub fn rsi(&self, period: usize) -> Option<f64> {
-        let closes = self.closes();
-        if closes.len() < period + 1 {
-            return None;
-        }
-        crate::indicators::relative_strength_index(&closes, period)
-            .last()
-            .copied()
+        cached_value(&self.cached, "rsi", period, 0.0, || last_rsi(&self.closes(), period))
+    }
+
+    /// Calculate EMA as it was `n` bars before the latest (0 = latest)
+    pub fn ema_ago(&self, period: usize, n: usize) -> Option<f64> {
+        cached_value(&self.cached, "ema_ago", period, n as f64, || ema_ago(&self.closes(), period, n))
+    }
+
+    /// Calculate RSI as it was `n` bars before the latest (0 = latest)
+    pub fn rsi_ago(&self, period: usize, n: usize) -> Option<f64> {
+        cached_value(&self.cached, "rsi_ago", period, n as f64, || rsi_ago(&self.closes(), period, n))
+    }
+
+    /// Calculate the linear regression slope over the last `period` closes
+    pub fn slope(&self, period: usize) -> Option<f64> {
+        cached_value(&self.cached, "slope", period, 0.0, || last_slope(&self.closes(), period))
+    }
+
+    /// Calculate the linear regression endpoint over the last `period` closes
+    pub fn linreg_value(&self, period: usize) -> Option<f64> {
+        cached_value(&self.cached, "linreg", period, 0.0, || last_linreg(&self.closes(), period))
+    }
+
+    /// Calculate momentum: the latest close minus the close `period` bars ago
+    pub fn momentum(&self, period: usize) -> Option<f64> {
+        self.window.momentum(period)
+    }
+
+    /// Calculate rate of change: momentum as a percentage of the earlier close
+    pub fn roc(&self, period: usize) -> Option<f64> {
+        self.window.roc(period)
+    }
+
+    /// Simple per-bar close returns across the window, from the second bar
+    pub fn returns(&self) -> Vec<f64> {
+        self.window.returns()
+    }
+
+    /// Per-bar log returns of close across the window, from the second bar
+    pub fn log_returns(&self) -> Vec<f64> {
+        self.window.log_returns()
+    }
+
+    /// Average bid/ask spread over the last `period` bars
+    pub fn avg_spread(&self, period: usize) -> Option<f64> {
+        self.window.avg_spread(period)
+    }
+
+    /// Widest bid/ask spread over the last `period` bars
+    pub fn max_spread(&self, period: usize) -> Option<f64> {
+        self.window.max_spread(period)
+    }
+
+    /// Consecutive rising (+) or falling (-) closes at the end of the window
+    pub fn streak(&self) -> i32 {
+        self.window.close_streak()
+    }
+
+    /// Detect divergence between the closes and an oscillator over the last `lookback` bars
+    ///
+    /// `kind` is `"rsi"` (RSI with `period`); use
+    /// [`macd_divergence`](Self::macd_divergence) for the MACD line. The
+    /// oscillator's warmup bars are skipped. See
+    /// [`detect_divergence`](crate::indicators::detect_divergence).
+    pub fn divergence(&self, kind: &str, period: usize, lookback: usize) -> Option<Divergence> {
+        divergence_of(&self.closes(), kind, period, lookback)
+    }
+
+    /// Detect divergence between the closes and the MACD line over the last `lookback` bars
+    ///
+    /// The MACD line is the `fast` EMA minus the `slow` EMA (12 and 26 are
+    /// the usual choice). `None` unless `0 < fast < slow`.
+    pub fn macd_divergence(&self, fast: usize, slow: usize, lookback: usize) -> Option<Divergence> {
+        macd_divergence_of(&self.closes(), fast, slow, lookback)
+    }
+
+    /// Check if RSI over `period` is above `threshold`
+    ///
+    /// False until the RSI has enough bars.
+    pub fn is_overbought(&self, period: usize, threshold: f64) -> bool {
+        self.rsi(period).is_some_and(|rsi| rsi > threshold)
+    }
+
+    /// Check if RSI over `period` is below `threshold`
+    ///
+    /// False until the RSI has enough bars.
+    pub fn is_oversold(&self, period: usize, threshold: f64) -> bool {
+        self.rsi(period).is_some_and(|rsi| rsi < threshold)
+    }
+
+    /// Check if the `fast` EMA is above the `slow` EMA
+    ///
+    /// False until both EMAs have enough bars.
+    pub fn is_uptrend(&self, fast: usize, slow: usize) -> bool {
+        fast > 0 && matches!((self.ema(fast), self.ema(slow)), (Some(fast), Some(slow)) if fast > slow)
+    }
+
+    /// Calculate Bollinger %B for the latest bar
+    pub fn bb_percent_b(&self, period: usize, num_std_dev: f64) -> Option<f64> {
+        cached_value(&self.cached, "bb_percent_b", period, num_std_dev, || {
+            last_percent_b(&self.closes(), period, num_std_dev)
+        })
+    }
+
+    /// Calculate Bollinger bandwidth for the latest bar
+    pub fn bb_bandwidth(&self, period: usize, num_std_dev: f64) -> Option<f64> {
+        cached_value(&self.cached, "bb_bandwidth", period, num_std_dev, || {
+            last_bandwidth(&self.closes(), period, num_std_dev)
+        })
     }
 
     /// Get the highest high over the full window
@@ -85,6 +293,25 @@ impl IndicatorApi {
         let len = self.window.len();
         self.window.avg_volume(len)
     }
+
+    /// Number of bars in the window
+    pub fn bar_count(&self) -> usize {
+        self.window.len()
+    }
+
+    /// The bar `n` positions back from the latest (0 = latest), if the window holds it
+    pub fn bar_ago(&self, n: usize) -> Option<&MarketData> {
+        let index = self.window.len().checked_sub(n)?.checked_sub(1)?;
+        self.window.get(index)
+    }
+
+    /// Milliseconds between the oldest and latest bar (0 with fewer than two bars)
+    pub fn time_span_ms(&self) -> i64 {
+        match (self.window.oldest(), self.window.latest()) {
+            (Some(oldest), Some(latest)) => latest.timestamp - oldest.timestamp,
+            _ => 0,
+        }
+    }
 }
 
 /// Convert MarketData to a Lua table
@@ -100,6 +327,37 @@ pub fn market_data_to_lua<'lua>(lua: &'lua Lua, data: &MarketData) -> Result<Tab
     table.set("bid", data.bid)?;
     table.set("ask", data.ask)?;
     table.set("mid_price", data.mid_price())?;
+    table.set("is_final", data.is_final)?;
+    // Book-derived fields are nil when the source has no quantities
+    table.set("bid_qty", data.bid_qty)?;
+    table.set("ask_qty", data.ask_qty)?;
+    table.set("microprice", data.microprice())?;
+    table.set("imbalance", data.imbalance())?;
+    Ok(table)
+}
+
+/// Convert an open Position to a Lua table
+///
+/// `stop_loss` and `take_profit` are nil when unset; `trailing_distance` is
+/// how far the stop sits from the current price (nil without a stop).
+/// `highest_since_entry` and `lowest_since_entry` are the price extremes
+/// since entry, including the current bar.
+pub fn position_to_lua<'lua>(lua: &'lua Lua, position: &Position) -> mlua::Result<Table<'lua>> {
+    let table = lua.create_table()?;
+    table.set("side", if position.side().is_long() { "long" } else { "short" })?;
+    table.set("entry_price", position.entry_price())?;
+    table.set("quantity", position.quantity())?;
+    table.set("current_price", position.current_price())?;
+    table.set("stop_loss", position.stop_loss())?;
+    table.set("take_profit", position.take_profit())?;
+    table.set(
+        "trailing_distance",
+        position.stop_loss().map(|stop| (position.current_price() - stop).abs()),
+    )?;
+    table.set("highest_since_entry", position.highest_since_entry())?;
+    table.set("lowest_since_entry", position.lowest_since_entry())?;
+    table.set("unrealized_pnl", position.unrealized_pnl())?;
+    table.set("unrealized_pnl_pct", position.unrealized_pnl_pct())?;
     Ok(table)
 }
 
@@ -131,6 +389,128 @@ pub fn context_to_lua<'lua>(lua: &'lua Lua, context: &Context) -> Result<Table<'
     Ok(table)
 }
 
+/// Divergence between `closes` and the oscillator named by `kind`
+fn divergence_of(closes: &[f64], kind: &str, period: usize, lookback: usize) -> Option<Divergence> {
+    match kind {
+        "rsi" if period > 0 => {
+            let rsi = crate::indicators::relative_strength_index(closes, period);
+            divergence_after_warmup(closes, &rsi, period, lookback)
+        }
+        _ => None,
+    }
+}
+
+/// Divergence between `closes` and the MACD line of `fast` and `slow` EMAs
+fn macd_divergence_of(closes: &[f64], fast: usize, slow: usize, lookback: usize) -> Option<Divergence> {
+    if fast == 0 || slow <= fast {
+        return None;
+    }
+    // Only the MACD line is used, so the signal period doesn't matter
+    let line = crate::indicators::macd(closes, fast, slow, 9).macd_line;
+    divergence_after_warmup(closes, &line, slow - 1, lookback)
+}
+
+/// Divergence between `closes` and `oscillator`, skipping the first `warmup` bars
+fn divergence_after_warmup(
+    closes: &[f64],
+    oscillator: &[f64],
+    warmup: usize,
+    lookback: usize,
+) -> Option<Divergence> {
+    if oscillator.len() <= warmup {
+        return None;
+    }
+    crate::indicators::detect_divergence(&closes[warmup..], &oscillator[warmup..], lookback)
+}
+
+/// Look up an indicator in the shared cache, if there is one, computing it on a miss
+fn cached_value(
+    cached: &Option<WindowIndicators>,
+    kind: &'static str,
+    period: usize,
+    param: f64,
+    compute: impl FnOnce() -> Option<f64>,
+) -> Option<f64> {
+    match cached {
+        Some(values) => values.get_or_compute(kind, period, param, compute),
+        None => compute(),
+    }
+}
+
+/// SMA of `closes` over `period`, once there are enough bars
+fn last_sma(closes: &[f64], period: usize) -> Option<f64> {
+    if closes.len() < period {
+        return None;
+    }
+    crate::indicators::simple_moving_average(closes, period).last().copied()
+}
+
+/// EMA of `closes` over `period`, once there are enough bars
+fn last_ema(closes: &[f64], period: usize) -> Option<f64> {
+    if closes.len() < period {
+        return None;
+    }
+    crate::indicators::exponential_moving_average(closes, period).last().copied()
+}
+
+/// RSI of `closes` over `period`, once there are enough bars
+fn last_rsi(closes: &[f64], period: usize) -> Option<f64> {
+    if period == 0 || closes.len() < period + 1 {
+        return None;
+    }
+    crate::indicators::relative_strength_index(closes, period).last().copied()
+}
+
+/// EMA of `closes` over `period` at `n` bars before the last, once warmed up there
+fn ema_ago(closes: &[f64], period: usize, n: usize) -> Option<f64> {
+    let index = closes.len().checked_sub(n.checked_add(1)?)?;
+    if period == 0 || index + 1 < period {
+        return None;
+    }
+    crate::indicators::exponential_moving_average(closes, period).get(index).copied()
+}
+
+/// RSI of `closes` over `period` at `n` bars before the last, once warmed up there
+fn rsi_ago(closes: &[f64], period: usize, n: usize) -> Option<f64> {
+    let index = closes.len().checked_sub(n.checked_add(1)?)?;
+    if period == 0 || index < period {
+        return None;
+    }
+    crate::indicators::relative_strength_index(closes, period).get(index).copied()
+}
+
+/// Linear regression slope over the last `period` closes
+fn last_slope(closes: &[f64], period: usize) -> Option<f64> {
+    crate::indicators::linreg_slope(closes, period).last().copied()
+}
+
+/// Linear regression endpoint over the last `period` closes
+fn last_linreg(closes: &[f64], period: usize) -> Option<f64> {
+    crate::indicators::linreg_value(closes, period).last().copied()
+}
+
+/// Bollinger %B of the latest close
+fn last_percent_b(closes: &[f64], period: usize, num_std_dev: f64) -> Option<f64> {
+    if period == 0 || closes.len() < period {
+        return None;
+    }
+    crate::indicators::bollinger_bands(closes, period, num_std_dev)
+        .percent_b(closes)
+        .last()
+        .copied()
+}
+
+/// Bollinger bandwidth of the latest bar
+fn last_bandwidth(closes: &[f64], period: usize, num_std_dev: f64) -> Option<f64> {
+    if period == 0 || closes.len() < period {
+        return None;
+    }
+    crate::indicators::bollinger_bands(closes, period, num_std_dev)
+        .bandwidth()
+        .last()
+        .copied()
+}
+
 /// Convert IndicatorApi to a Lua table with callable functions
 pub fn indicators_to_lua<'lua>(lua: &'lua Lua, api: &IndicatorApi) -> Result<Table<'lua>> {
     let table = lua.create_table()?;
@@ -138,44 +518,238 @@ pub fn indicators_to_lua<'lua>(lua: &'lua Lua, api: &IndicatorApi) -> Result<Tab
     // Create closures for each indicator function
     let closes = api.closes();
 
-    // SMA
-    let sma_closes = closes.clone();
-    let sma_fn = lua.create_function(move |_, period: usize| {
-        if sma_closes.len() < period {
-            return Ok(Value::Nil);
+    // Record each indicator the strategy asks for
+    let record = |kind: &'static str| {
+        let requests = api.requests.clone();
+        move |period: usize| {
+            requests.lock().insert((kind.to_string(), period));
+        }
+    };
+
+    // Close-based indicators, shared through the cache when there is one
+    let cached = |kind: &'static str, compute: fn(&[f64], usize) -> Option<f64>| {
+        let closes = closes.clone();
+        let cache = api.cached.clone();
+        let record = record(kind);
+        move |_: &Lua, period: usize| {
+            record(period);
+            Ok(cached_value(&cache, kind, period, 0.0, || compute(&closes, period)))
+        }
+    };
+    table.set("sma", lua.create_function(cached("sma", last_sma))?)?;
+    table.set("ema", lua.create_function(cached("ema", last_ema))?)?;
+    table.set("rsi", lua.create_function(cached("rsi", last_rsi))?)?;
+
+    // Close-based indicators as they were `n` bars ago
+    let cached_ago = |kind: &'static str, record_kind: &'static str, compute: fn(&[f64], usize, usize) -> Option<f64>| {
+        let closes = closes.clone();
+        let cache = api.cached.clone();
+        let record = record(record_kind);
+        move |_: &Lua, (period, n): (usize, usize)| {
+            record(period);
+            Ok(cached_value(&cache, kind, period, n as f64, || compute(&closes, period, n)))
+        }
+    };
+    table.set("ema_ago", lua.create_function(cached_ago("ema_ago", "ema", ema_ago))?)?;
+    table.set("rsi_ago", lua.create_function(cached_ago("rsi_ago", "rsi", rsi_ago))?)?;
+
+    // Bollinger %B and bandwidth
+    let bands = |kind: &'static str, compute: fn(&[f64], usize, f64) -> Option<f64>| {
+        let closes = closes.clone();
+        let cache = api.cached.clone();
+        let record = record("bollinger");
+        move |_: &Lua, (period, num_std_dev): (usize, f64)| {
+            record(period);
+            Ok(cached_value(&cache, kind, period, num_std_dev, || compute(&closes, period, num_std_dev)))
+        }
+    };
+    table.set("bb_percent_b", lua.create_function(bands("bb_percent_b", last_percent_b))?)?;
+    table.set("bb_bandwidth", lua.create_function(bands("bb_bandwidth", last_bandwidth))?)?;
+
+    // Linear regression slope and endpoint
+    let linreg = |kind: &'static str, compute: fn(&[f64], usize) -> Option<f64>| {
+        let closes = closes.clone();
+        let cache = api.cached.clone();
+        let record = record("linreg");
+        move |_: &Lua, period: usize| {
+            record(period);
+            Ok(cached_value(&cache, kind, period, 0.0, || compute(&closes, period)))
         }
-        match crate::indicators::simple_moving_average(&sma_closes, period).last() {
-            Some(&value) => Ok(Value::Number(value)),
+    };
+    table.set("slope", lua.create_function(linreg("slope", last_slope))?)?;
+    table.set("linreg_value", lua.create_function(linreg("linreg", last_linreg))?)?;
+
+    // Momentum
+    let momentum_window = api.window.clone();
+    let momentum_record = record("momentum");
+    let momentum_fn = lua.create_function(move |_, period: usize| {
+        momentum_record(period);
+        match momentum_window.momentum(period) {
+            Some(value) => Ok(Value::Number(value)),
             None => Ok(Value::Nil),
         }
     })?;
-    table.set("sma", sma_fn)?;
+    table.set("momentum", momentum_fn)?;
 
-    // EMA
-    let ema_closes = closes.clone();
-    let ema_fn = lua.create_function(move |_, period: usize| {
-        if ema_closes.len() < period {
-            return Ok(Value::Nil);
+    // Rate of change
+    let roc_window = api.window.clone();
+    let roc_record = record("momentum");
+    let roc_fn = lua.create_function(move |_, period: usize| {
+        roc_record(period);
+        match roc_window.roc(period) {
+            Some(value) => Ok(Value::Number(value)),
+            None => Ok(Value::Nil),
         }
-        match crate::indicators::exponential_moving_average(&ema_closes, period).last() {
-            Some(&value) => Ok(Value::Number(value)),
+    })?;
+    table.set("roc", roc_fn)?;
+
+    // Per-bar returns
+    let returns_window = api.window.clone();
+    table.set("returns", lua.create_function(move |_, ()| Ok(returns_window.returns()))?)?;
+    let log_returns_window = api.window.clone();
+    table.set("log_returns", lua.create_function(move |_, ()| Ok(log_returns_window.log_returns()))?)?;
+
+    // Bid/ask spread
+    let avg_spread_window = api.window.clone();
+    let avg_spread_fn = lua.create_function(move |_, period: usize| {
+        match avg_spread_window.avg_spread(period) {
+            Some(value) => Ok(Value::Number(value)),
             None => Ok(Value::Nil),
         }
     })?;
-    table.set("ema", ema_fn)?;
+    table.set("avg_spread", avg_spread_fn)?;
+
+    let max_spread_window = api.window.clone();
+    let max_spread_fn = lua.create_function(move |_, period: usize| {
+        match max_spread_window.max_spread(period) {
+            Some(value) => Ok(Value::Number(value)),
+            None => Ok(Value::Nil),
+        }
+    })?;
+    table.set("max_spread", max_spread_fn)?;
+
+    // Consecutive up/down closes
+    let streak = api.streak();
+    let streak_fn = lua.create_function(move |_, ()| Ok(streak))?;
+    table.set("streak", streak_fn)?;
+
+    // Price/oscillator divergence
+    let divergence_closes = closes.clone();
+    let divergence_record = record("rsi");
+    let divergence_fn = lua.create_function(
+        move |lua, (kind, period, lookback): (String, usize, usize)| {
+            if kind == "rsi" {
+                divergence_record(period);
+            }
+            match divergence_of(&divergence_closes, &kind, period, lookback) {
+                Some(divergence) => Ok(Value::String(lua.create_string(divergence.as_str())?)),
+                None => Ok(Value::Nil),
+            }
+        },
+    )?;
+    table.set("divergence", divergence_fn)?;
+
+    let macd_divergence_closes = closes.clone();
+    let macd_divergence_record = record("ema");
+    let macd_divergence_fn = lua.create_function(
+        move |lua, (fast, slow, lookback): (usize, usize, usize)| {
+            macd_divergence_record(fast);
+            macd_divergence_record(slow);
+            match macd_divergence_of(&macd_divergence_closes, fast, slow, lookback) {
+                Some(divergence) => Ok(Value::String(lua.create_string(divergence.as_str())?)),
+                None => Ok(Value::Nil),
+            }
+        },
+    )?;
+    table.set("macd_divergence", macd_divergence_fn)?;
+
+    // RSI signals
+    let overbought_closes = closes.clone();
+    let overbought_cache = api.cached.clone();
+    let overbought_record = record("rsi");
+    let overbought_fn = lua.create_function(move |_, (period, threshold): (usize, f64)| {
+        overbought_record(period);
+        let rsi = cached_value(&overbought_cache, "rsi", period, 0.0, || last_rsi(&overbought_closes, period));
+        Ok(rsi.is_some_and(|rsi| rsi > threshold))
+    })?;
+    table.set("is_overbought", overbought_fn)?;
+
+    let oversold_closes = closes.clone();
+    let oversold_cache = api.cached.clone();
+    let oversold_record = record("rsi");
+    let oversold_fn = lua.create_function(move |_, (period, threshold): (usize, f64)| {
+        oversold_record(period);
+        let rsi = cached_value(&oversold_cache, "rsi", period, 0.0, || last_rsi(&oversold_closes, period));
+        Ok(rsi.is_some_and(|rsi| rsi < threshold))
+    })?;
+    table.set("is_oversold", oversold_fn)?;
+
+    // EMA trend
+    let uptrend_closes = closes.clone();
+    let uptrend_cache = api.cached.clone();
+    let uptrend_record = record("ema");
+    let uptrend_fn = lua.create_function(move |_, (fast, slow): (usize, usize)| {
+        uptrend_record(fast);
+        uptrend_record(slow);
+        let ema = |period| cached_value(&uptrend_cache, "ema", period, 0.0, || last_ema(&uptrend_closes, period));
+        Ok(fast > 0 && matches!((ema(fast), ema(slow)), (Some(fast), Some(slow)) if fast > slow))
+    })?;
+    table.set("is_uptrend", uptrend_fn)?;
+
+    // Custom indicators
+    let custom_window = api.window.clone();
+    let custom_registry = api.registry.clone();
+    let custom_fn = lua.create_function(move |_, name: String| {
+        match custom_registry.evaluate(&name, &custom_window) {
+            Some(value) => Ok(Value::Number(value)),
+            None => Ok(Value::Nil),
+        }
+    })?;
+    table.set("custom", custom_fn)?;
+
+    // Indicators precomputed by the backtest driver
+    let precomputed = api.precomputed.clone();
+    let precomputed_fn = lua.create_function(move |_, key: String| {
+        Ok(precomputed.get(&key).copied().filter(|value| !value.is_nan()))
+    })?;
+    table.set("precomputed", precomputed_fn)?;
+
+    // Warmup check
+    let bar_count = closes.len();
+    let ready_requests = api.requests.clone();
+    let is_ready_fn = lua.create_function(move |_, (kind, period): (String, usize)| {
+        let bars = IndicatorApi::bars_required(&kind, period);
+        if bars.is_some() {
+            ready_requests.lock().insert((kind, period));
+        }
+        Ok(bars.is_some_and(|bars| bar_count >= bars))
+    })?;
+    table.set("is_ready", is_ready_fn)?;
 
-    // RSI
-    let rsi_closes = closes.clone();
-    let rsi_fn = lua.create_function(move |_, period: usize| {
-        if rsi_closes.len() < period + 1 {
-            return Ok(Value::Nil);
+    // Weighted signal blend
+    let blend_fn = lua.create_function(|_, signals: Table| {
+        let mut pairs = Vec::new();
+        for signal in signals.sequence_values::<Table>() {
+            let signal = signal?;
+            pairs.push((signal.get("value")?, signal.get("weight")?));
         }
-        match crate::indicators::relative_strength_index(&rsi_closes, period).last() {
-            Some(&value) => Ok(Value::Number(value)),
+        Ok(crate::indicators::blend(&pairs))
+    })?;
+    table.set("blend", blend_fn)?;
+
+    // Window history
+    let window_bars = api.bar_count();
+    table.set("bar_count", lua.create_function(move |_, ()| Ok(window_bars))?)?;
+    let time_span_ms = api.time_span_ms();
+    table.set("time_span_ms", lua.create_function(move |_, ()| Ok(time_span_ms))?)?;
+    let bar_ago_api = IndicatorApi::new(api.window.clone());
+    let bar_ago_fn = lua.create_function(move |lua, n: i64| {
+        match usize::try_from(n).ok().and_then(|n| bar_ago_api.bar_ago(n)) {
+            Some(bar) => Ok(Value::Table(market_data_to_lua(lua, bar).map_err(mlua::Error::external)?)),
             None => Ok(Value::Nil),
         }
     })?;
-    table.set("rsi", rsi_fn)?;
+    table.set("bar_ago", bar_ago_fn)?;
 
     // Window query functions
     table.set("high", api.high().unwrap_or(0.0))?;
@@ -202,17 +776,29 @@ pub fn table_to_action(table: &Table) -> Result<Option<Action>> {
         "enter_long" => {
             let price: f64 = table.get("price")?;
             let quantity: f64 = table.get("quantity")?;
-            Ok(Some(Action::EnterLong { price, quantity }))
+            Ok(Some(with_exit_ladder(table, Action::EnterLong { price, quantity })?))
         }
         "enter_short" => {
             let price: f64 = table.get("price")?;
             let quantity: f64 = table.get("quantity")?;
-            Ok(Some(Action::EnterShort { price, quantity }))
+            Ok(Some(with_exit_ladder(table, Action::EnterShort { price, quantity })?))
         }
         "exit" => {
             let price: f64 = table.get("price")?;
             Ok(Some(Action::ExitPosition { price }))
         }
+        "reduce" => {
+            let price: f64 = table.get("price")?;
+            let quantity: f64 = table.get("quantity")?;
+            // A negative reduce would grow the position, and zero does nothing
+            if !(quantity.is_finite() && quantity > 0.0) {
+                return Err(TradingEngineError::StrategyError(format!(
+                    "reduce quantity must be a positive number, got {}",
+                    quantity
+                )));
+            }
+            Ok(Some(Action::ReducePosition { price, quantity }))
+        }
         "update_stop_loss" => {
             let new_stop: f64 = table.get("new_stop")?;
             Ok(Some(Action::UpdateStopLoss { new_stop }))
@@ -221,12 +807,23 @@ pub fn table_to_action(table: &Table) -> Result<Option<Action>> {
             let new_target: f64 = table.get("new_target")?;
             Ok(Some(Action::UpdateTakeProfit { new_target }))
         }
+        "set_exit_ladder" => {
+            let exit_ladder = exit_ladder_from_lua(table.get("exit_ladder")?)?;
+            Ok(Some(Action::SetExitLadder { exit_ladder }))
+        }
         "start_analyzing" => {
             let reason: String = table
                 .get::<_, Option<String>>("reason")?
                 .unwrap_or_else(|| "Strategy signal".to_string());
             Ok(Some(Action::StartAnalyzing { reason }))
         }
+        "no_action" => {
+            let reason: Option<String> = table.get("reason")?;
+            Ok(Some(match reason {
+                Some(reason) => Action::NoActionWithReason { reason },
+                None => Action::NoAction,
+            }))
+        }
         "cancel_analysis" => {
             let reason: String = table
                 .get::<_, Option<String>>("reason")?
@@ -240,6 +837,46 @@ pub fn table_to_action(table: &Table) -> Result<Option<Action>> {
     }
 }
 
+/// Attach the optional `exit_ladder` of an entry action table to `entry`
+fn with_exit_ladder(table: &Table, entry: Action) -> Result<Action> {
+    match table.get::<_, Option<Table>>("exit_ladder")? {
+        Some(rungs) => Ok(Action::EnterWithExitLadder {
+            entry: Box::new(entry),
+            exit_ladder: exit_ladder_from_lua(rungs)?,
+        }),
+        None => Ok(entry),
+    }
+}
+
+/// Read an `exit_ladder` table
+///
+/// Each rung is a table `{ price = ..., fraction = ... }`. Fractions must be
+/// in (0, 1] and add up to at most 1.
+fn exit_ladder_from_lua(rungs: Table) -> Result<Vec<ExitRung>> {
+    let mut ladder = Vec::new();
+    for rung in rungs.sequence_values::<Table>() {
+        let rung = rung?;
+        let price: f64 = rung.get("price")?;
+        let fraction: f64 = rung.get("fraction")?;
+        if !(fraction > 0.0 && fraction <= 1.0) {
+            return Err(TradingEngineError::StrategyError(format!(
+                "exit_ladder fraction must be in (0, 1], got {}",
+                fraction
+            )));
+        }
+        ladder.push(ExitRung { price, fraction });
+    }
+
+    let total: f64 = ladder.iter().map(|rung| rung.fraction).sum();
+    if total > 1.0 + 1e-9 {
+        return Err(TradingEngineError::StrategyError(format!(
+            "exit_ladder fractions add up to {}, more than the whole position",
+            total
+        )));
+    }
+    Ok(ladder)
+}
+
 /// Update context from a Lua table
 ///
 /// This allows Lua scripts to set context values that will be
@@ -292,12 +929,42 @@ mod tests {
             volume: 1000,
             bid: 50450.0,
             ask: 50550.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         };
 
         let table = market_data_to_lua(&lua, &data).unwrap();
         assert_eq!(table.get::<_, String>("symbol").unwrap(), "BTCUSDT");
         assert_eq!(table.get::<_, f64>("close").unwrap(), 50500.0);
         assert_eq!(table.get::<_, f64>("mid_price").unwrap(), 50500.0);
+        assert!(table.get::<_, bool>("is_final").unwrap());
+        assert_eq!(table.get::<_, Option<f64>>("microprice").unwrap(), None);
+        assert_eq!(table.get::<_, Option<f64>>("imbalance").unwrap(), None);
+    }
+
+    #[test]
+    fn test_market_data_to_lua_book_fields() {
+        let lua = Lua::new();
+        let data = MarketData {
+            symbol: "BTCUSDT".to_string(),
+            timestamp: 1234567890,
+            open: 50000.0,
+            high: 51000.0,
+            low: 49000.0,
+            close: 50500.0,
+            volume: 1000,
+            bid: 100.0,
+            ask: 102.0,
+            bid_qty: Some(3.0),
+            ask_qty: Some(1.0),
+            is_final: true,
+        };
+
+        let table = market_data_to_lua(&lua, &data).unwrap();
+        assert_eq!(table.get::<_, f64>("bid_qty").unwrap(), 3.0);
+        assert_eq!(table.get::<_, f64>("microprice").unwrap(), 101.5);
+        assert_eq!(table.get::<_, f64>("imbalance").unwrap(), 0.5);
     }
 
     #[test]
@@ -312,6 +979,53 @@ mod tests {
         assert!(matches!(action, Some(Action::EnterLong { .. })));
     }
 
+    #[test]
+    fn test_table_to_action_exit_ladder() {
+        let lua = Lua::new();
+        let table: Table = lua
+            .load(r#"return {
+                action = "enter_long",
+                price = 100.0,
+                quantity = 1.0,
+                exit_ladder = { { price = 110.0, fraction = 0.5 }, { price = 120.0, fraction = 0.5 } },
+            }"#)
+            .eval()
+            .unwrap();
+
+        let ladder = vec![ExitRung { price: 110.0, fraction: 0.5 }, ExitRung { price: 120.0, fraction: 0.5 }];
+        assert_eq!(
+            table_to_action(&table).unwrap(),
+            Some(Action::EnterWithExitLadder {
+                entry: Box::new(Action::EnterLong { price: 100.0, quantity: 1.0 }),
+                exit_ladder: ladder.clone(),
+            })
+        );
+
+        // Later changes to the ladder
+        table.set("action", "set_exit_ladder").unwrap();
+        assert_eq!(
+            table_to_action(&table).unwrap(),
+            Some(Action::SetExitLadder { exit_ladder: ladder })
+        );
+
+        // More than the whole position
+        let table: Table = lua
+            .load(r#"return {
+                action = "set_exit_ladder",
+                exit_ladder = { { price = 90.0, fraction = 0.75 }, { price = 80.0, fraction = 0.5 } },
+            }"#)
+            .eval()
+            .unwrap();
+        let err = table_to_action(&table).unwrap_err();
+        assert!(err.to_string().contains("more than the whole position"), "{}", err);
+        assert!(!err.is_transient());
+
+        table.set("exit_ladder", lua.load("return { { price = 90.0, fraction = 0.0 } }").eval::<Table>().unwrap()).unwrap();
+        let err = table_to_action(&table).unwrap_err();
+        assert!(err.to_string().contains("fraction must be in (0, 1]"), "{}", err);
+        assert!(!err.is_transient());
+    }
+
     #[test]
     fn test_table_to_action_exit() {
         let lua = Lua::new();
@@ -323,6 +1037,482 @@ mod tests {
         assert!(matches!(action, Some(Action::ExitPosition { .. })));
     }
 
+    #[test]
+    fn test_table_to_action_reduce() {
+        let lua = Lua::new();
+        let table = lua.create_table().unwrap();
+        table.set("action", "reduce").unwrap();
+        table.set("price", 51000.0).unwrap();
+        table.set("quantity", 0.05).unwrap();
+
+        let action = table_to_action(&table).unwrap();
+        assert_eq!(action, Some(Action::ReducePosition { price: 51000.0, quantity: 0.05 }));
+
+        for quantity in [0.0, -0.05, f64::NAN] {
+            table.set("quantity", quantity).unwrap();
+            let err = table_to_action(&table).unwrap_err();
+            assert!(err.to_string().contains("reduce quantity must be a positive number"), "{}", err);
+            assert!(!err.is_transient());
+        }
+    }
+
+    #[test]
+    fn test_bollinger_indicators_flat_prices() {
+        let mut window = MarketDataWindow::new(10);
+        for i in 0..5 {
+            window.push(MarketData {
+                symbol: "BTCUSDT".to_string(),
+                timestamp: 1000 * i,
+                open: 100.0,
+                high: 100.0,
+                low: 100.0,
+                close: 100.0,
+                volume: 10,
+                bid: 100.0,
+                ask: 100.0,
+                bid_qty: None,
+                ask_qty: None,
+                is_final: true,
+            });
+        }
+        let api = IndicatorApi::new(window);
+
+        // Zero-width bands: %B sits at the midpoint instead of dividing by zero
+        assert_eq!(api.bb_percent_b(3, 2.0), Some(0.5));
+        assert_eq!(api.bb_bandwidth(3, 2.0), Some(0.0));
+        assert_eq!(api.bb_percent_b(10, 2.0), None);
+
+        let lua = Lua::new();
+        let table = indicators_to_lua(&lua, &api).unwrap();
+        lua.globals().set("indicators", table).unwrap();
+        let percent_b: f64 = lua.load("return indicators.bb_percent_b(3, 2.0)").eval().unwrap();
+        let bandwidth: f64 = lua.load("return indicators.bb_bandwidth(3, 2.0)").eval().unwrap();
+        let missing: Option<f64> = lua.load("return indicators.bb_bandwidth(10, 2.0)").eval().unwrap();
+        assert_eq!(percent_b, 0.5);
+        assert_eq!(bandwidth, 0.0);
+        assert_eq!(missing, None);
+    }
+
+    #[test]
+    fn test_slope_on_rising_closes() {
+        let mut window = MarketDataWindow::new(10);
+        for i in 0..6 {
+            let close = 100.0 + 2.0 * i as f64;
+            window.push(MarketData {
+                symbol: "BTCUSDT".to_string(),
+                timestamp: 1000 * i,
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 10,
+                bid: close,
+                ask: close,
+                bid_qty: None,
+                ask_qty: None,
+                is_final: true,
+            });
+        }
+        let api = IndicatorApi::new(window);
+        assert!((api.slope(5).unwrap() - 2.0).abs() < 1e-9);
+        assert!((api.linreg_value(5).unwrap() - 110.0).abs() < 1e-9);
+        assert_eq!(api.slope(7), None);
+
+        let lua = Lua::new();
+        let table = indicators_to_lua(&lua, &api).unwrap();
+        lua.globals().set("indicators", table).unwrap();
+        let slope: f64 = lua.load("return indicators.slope(5)").eval().unwrap();
+        assert!((slope - 2.0).abs() < 1e-9);
+        assert!(api.is_ready("linreg", 5));
+        assert_eq!(api.requested(), vec![("linreg".to_string(), 5)]);
+    }
+
+    #[test]
+    fn test_shared_cache_matches_uncached_values() {
+        // A zigzag so every indicator moves
+        let bars: Vec<MarketData> = (0..40)
+            .map(|i| MarketData::from_trade("BTCUSDT", 100.0 + (i % 7) as f64 * 1.5 - (i % 3) as f64, 1.0, i))
+            .collect();
+        let window = |size: usize| {
+            let mut window = MarketDataWindow::new(size);
+            for bar in &bars {
+                window.push(bar.clone());
+            }
+            window
+        };
+
+        let cache = IndicatorCache::new();
+        let script = "return { indicators.sma(10), indicators.ema(12), indicators.rsi(14), \
+                      indicators.bb_percent_b(20, 2.0), indicators.slope(8), tostring(indicators.is_uptrend(5, 15)) }";
+        let lua_values = |api: &IndicatorApi| {
+            let lua = Lua::new();
+            lua.globals().set("indicators", indicators_to_lua(&lua, api).unwrap()).unwrap();
+            lua.load(script).eval::<Vec<String>>().unwrap()
+        };
+
+        // Different window sizes hold different closes and must not mix
+        for size in [25, 30, 25, 30] {
+            let plain = IndicatorApi::new(window(size));
+            let shared = IndicatorApi::new(window(size)).with_cache(&cache);
+
+            for period in [5, 10, 20] {
+                assert_eq!(shared.sma(period), plain.sma(period));
+                assert_eq!(shared.ema(period), plain.ema(period));
+                assert_eq!(shared.rsi(period), plain.rsi(period));
+                assert_eq!(shared.bb_bandwidth(period, 2.0), plain.bb_bandwidth(period, 2.0));
+                assert_eq!(shared.linreg_value(period), plain.linreg_value(period));
+            }
+            assert_eq!(lua_values(&shared), lua_values(&plain));
+        }
+
+        // The second window of each size was answered from the cache
+        assert!(cache.hits() > 0);
+    }
+
+    #[test]
+    fn test_momentum_and_roc_from_lua() {
+        let mut window = MarketDataWindow::new(10);
+        for i in 0..6 {
+            let close = 100.0 + 2.0 * i as f64;
+            window.push(MarketData {
+                symbol: "BTCUSDT".to_string(),
+                timestamp: 1000 * i,
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 10,
+                bid: close,
+                ask: close,
+                bid_qty: None,
+                ask_qty: None,
+                is_final: true,
+            });
+        }
+        let api = IndicatorApi::new(window);
+        assert_eq!(api.momentum(5), Some(10.0));
+        assert_eq!(api.roc(5), Some(10.0));
+        assert!(api.is_ready("momentum", 5));
+        assert!(!api.is_ready("momentum", 6));
+
+        let lua = Lua::new();
+        let table = indicators_to_lua(&lua, &api).unwrap();
+        lua.globals().set("indicators", table).unwrap();
+        let momentum: f64 = lua.load("return indicators.momentum(5)").eval().unwrap();
+        assert_eq!(momentum, 10.0);
+        let roc: Option<f64> = lua.load("return indicators.roc(6)").eval().unwrap();
+        assert_eq!(roc, None);
+    }
+
+    #[test]
+    fn test_ema_ago_indexes_series_from_end() {
+        let mut window = MarketDataWindow::new(50);
+        for i in 0..30 {
+            let close = 100.0 + (i as f64 * 0.7).sin() * 5.0;
+            window.push(MarketData::from_trade("BTCUSDT", close, 1.0, 1000 * i));
+        }
+        let api = IndicatorApi::new(window);
+        let closes = api.closes();
+        let ema = crate::indicators::exponential_moving_average(&closes, 10);
+        let rsi = crate::indicators::relative_strength_index(&closes, 14);
+
+        let lua = Lua::new();
+        let table = indicators_to_lua(&lua, &api).unwrap();
+        lua.globals().set("indicators", table).unwrap();
+
+        let ema_1: f64 = lua.load("return indicators.ema_ago(10, 1)").eval().unwrap();
+        assert_eq!(ema_1, ema[ema.len() - 2]);
+        let ema_0: f64 = lua.load("return indicators.ema_ago(10, 0)").eval().unwrap();
+        assert_eq!(Some(ema_0), api.ema(10));
+        let rsi_5: f64 = lua.load("return indicators.rsi_ago(14, 5)").eval().unwrap();
+        assert_eq!(rsi_5, rsi[rsi.len() - 6]);
+
+        // Back past the warmup or the window
+        let before_warmup: Option<f64> = lua.load("return indicators.ema_ago(10, 21)").eval().unwrap();
+        assert_eq!(before_warmup, None);
+        assert!(api.ema_ago(10, 20).is_some());
+        assert_eq!(api.rsi_ago(14, 16), None);
+        assert_eq!(api.ema_ago(10, 30), None);
+    }
+
+    #[test]
+    fn test_returns_from_lua() {
+        let mut window = MarketDataWindow::new(10);
+        for (i, close) in [100.0, 110.0, 99.0].into_iter().enumerate() {
+            window.push(MarketData::from_trade("BTCUSDT", close, 1.0, 1000 * i as i64));
+        }
+        let api = IndicatorApi::new(window);
+
+        let lua = Lua::new();
+        let table = indicators_to_lua(&lua, &api).unwrap();
+        lua.globals().set("indicators", table).unwrap();
+        let returns: Vec<f64> = lua.load("return indicators.returns()").eval().unwrap();
+        assert_eq!(returns.len(), 2);
+        assert!((returns[1] + 0.10).abs() < 1e-12);
+        let log_returns: Vec<f64> = lua.load("return indicators.log_returns()").eval().unwrap();
+        assert!((log_returns[0] - (1.1f64).ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_divergence_from_lua() {
+        let mut window = MarketDataWindow::new(10);
+        for (i, &close) in [100.0, 98.0, 96.0, 94.0, 104.0, 100.0, 105.0, 103.0].iter().enumerate() {
+            window.push(MarketData {
+                symbol: "BTCUSDT".to_string(),
+                timestamp: 1000 * i as i64,
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 10,
+                bid: close,
+                ask: close,
+                bid_qty: None,
+                ask_qty: None,
+                is_final: true,
+            });
+        }
+        let api = IndicatorApi::new(window);
+
+        // Price peaks at 104 then 105; RSI(2) peaks lower the second time
+        assert_eq!(api.divergence("rsi", 2, 6), Some(Divergence::RegularBearish));
+        assert_eq!(api.divergence("rsi", 2, 7), None);
+        assert_eq!(api.divergence("stochastic", 2, 6), None);
+        assert_eq!(api.divergence("macd", 2, 6), None);
+
+        // The MACD(2, 3) line also peaks lower the second time
+        assert_eq!(api.macd_divergence(2, 3, 6), Some(Divergence::RegularBearish));
+        assert_eq!(api.macd_divergence(3, 3, 6), None);
+        assert_eq!(api.macd_divergence(0, 3, 6), None);
+
+        let lua = Lua::new();
+        let table = indicators_to_lua(&lua, &api).unwrap();
+        lua.globals().set("indicators", table).unwrap();
+        let divergence: String = lua.load(r#"return indicators.divergence("rsi", 2, 6)"#).eval().unwrap();
+        assert_eq!(divergence, "regular_bearish");
+        let divergence: String = lua.load(r#"return indicators.macd_divergence(2, 3, 6)"#).eval().unwrap();
+        assert_eq!(divergence, "regular_bearish");
+        let none: Option<String> = lua.load(r#"return indicators.macd_divergence(12, 26, 6)"#).eval().unwrap();
+        assert_eq!(none, None);
+    }
+
+    #[test]
+    fn test_signal_helpers_from_lua() {
+        let mut window = MarketDataWindow::new(20);
+        for i in 0..16 {
+            let close = 100.0 - 2.0 * i as f64 + if i % 3 == 0 { 1.0 } else { 0.0 };
+            window.push(MarketData {
+                symbol: "BTCUSDT".to_string(),
+                timestamp: 1000 * i as i64,
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 10,
+                bid: close,
+                ask: close,
+                bid_qty: None,
+                ask_qty: None,
+                is_final: true,
+            });
+        }
+        let api = IndicatorApi::new(window);
+        assert!(api.rsi(14).unwrap() < 30.0);
+        assert!(api.is_oversold(14, 30.0));
+        assert!(!api.is_overbought(14, 70.0));
+        assert!(!api.is_uptrend(5, 10));
+
+        let lua = Lua::new();
+        let table = indicators_to_lua(&lua, &api).unwrap();
+        lua.globals().set("indicators", table).unwrap();
+        let signals: (bool, bool, bool, bool) = lua
+            .load(r#"return indicators.is_oversold(14, 30), indicators.is_overbought(14, 70),
+                        indicators.is_uptrend(5, 10), indicators.is_oversold(20, 30)"#)
+            .eval()
+            .unwrap();
+
+        // Not enough bars for RSI(20) yet
+        assert_eq!(signals, (true, false, false, false));
+        assert!(api.requested().contains(&("rsi".to_string(), 14)));
+        assert!(api.requested().contains(&("ema".to_string(), 10)));
+    }
+
+    #[test]
+    fn test_window_history_from_lua() {
+        let mut window = MarketDataWindow::new(10);
+        for timestamp in [1_000, 61_000, 121_000] {
+            window.push(MarketData::from_trade("BTCUSDT", 100.0, 1.0, timestamp));
+        }
+        let api = IndicatorApi::new(window);
+        assert_eq!(api.bar_count(), 3);
+        assert_eq!(api.time_span_ms(), 120_000);
+
+        let lua = Lua::new();
+        let table = indicators_to_lua(&lua, &api).unwrap();
+        lua.globals().set("indicators", table).unwrap();
+        let (bars, span): (usize, i64) = lua
+            .load("return indicators.bar_count(), indicators.time_span_ms()")
+            .eval()
+            .unwrap();
+        assert_eq!((bars, span), (3, 120_000));
+
+        let empty = IndicatorApi::new(MarketDataWindow::new(10));
+        assert_eq!((empty.bar_count(), empty.time_span_ms()), (0, 0));
+    }
+
+    #[test]
+    fn test_blend_from_lua() {
+        let lua = Lua::new();
+        let table = indicators_to_lua(&lua, &IndicatorApi::new(MarketDataWindow::new(10))).unwrap();
+        lua.globals().set("indicators", table).unwrap();
+
+        let (score, clamped, empty): (f64, f64, f64) = lua
+            .load(
+                r#"return indicators.blend({ { value = 0.8, weight = 3 }, { value = -0.4, weight = 1 } }),
+                          indicators.blend({ { value = 5, weight = 1 } }),
+                          indicators.blend({})"#,
+            )
+            .eval()
+            .unwrap();
+        assert!((score - 0.5).abs() < 1e-12);
+        assert_eq!(clamped, 1.0);
+        assert_eq!(empty, 0.0);
+
+        let missing_weight = lua.load("return indicators.blend({ { value = 1 } })").eval::<f64>();
+        assert!(missing_weight.is_err());
+    }
+
+    #[test]
+    fn test_bar_ago_from_lua() {
+        let mut window = MarketDataWindow::new(10);
+        for (i, close) in [100.0, 101.0, 102.0, 103.0].into_iter().enumerate() {
+            window.push(MarketData::from_trade("BTCUSDT", close, 1.0, i as i64 * 1_000));
+        }
+        let api = IndicatorApi::new(window);
+        assert_eq!(api.bar_ago(0).map(|bar| bar.close), Some(103.0));
+        assert!(api.bar_ago(4).is_none());
+
+        let lua = Lua::new();
+        let table = indicators_to_lua(&lua, &api).unwrap();
+        lua.globals().set("indicators", table).unwrap();
+        let (latest, third, latest_ts, beyond, negative): (f64, f64, i64, Option<Table>, Option<Table>) = lua
+            .load(
+                "return indicators.bar_ago(0).close, indicators.bar_ago(2).close, \
+                 indicators.bar_ago(0).timestamp, indicators.bar_ago(4), indicators.bar_ago(-1)",
+            )
+            .eval()
+            .unwrap();
+        // bar_ago(2) is the third bar counting back from the latest
+        assert_eq!((latest, third, latest_ts), (103.0, 101.0, 3_000));
+        assert!(beyond.is_none() && negative.is_none());
+    }
+
+    #[test]
+    fn test_spread_from_lua() {
+        let mut window = MarketDataWindow::new(10);
+        for (timestamp, spread) in [(1_000, 3.0), (2_000, 1.0), (3_000, 2.0)] {
+            let mut data = MarketData::from_trade("BTCUSDT", 100.0, 1.0, timestamp);
+            data.ask += spread;
+            window.push(data);
+        }
+        let api = IndicatorApi::new(window);
+        assert_eq!(api.avg_spread(2), Some(1.5));
+        assert_eq!(api.max_spread(3), Some(3.0));
+
+        let lua = Lua::new();
+        let table = indicators_to_lua(&lua, &api).unwrap();
+        lua.globals().set("indicators", table).unwrap();
+        let (avg, max, none): (f64, f64, Option<f64>) = lua
+            .load("return indicators.avg_spread(3), indicators.max_spread(2), indicators.avg_spread(0)")
+            .eval()
+            .unwrap();
+        assert_eq!((avg, max, none), (2.0, 2.0, None));
+    }
+
+    #[test]
+    fn test_streak_from_lua() {
+        let mut window = MarketDataWindow::new(10);
+        for (i, close) in [100.0, 99.0, 98.0, 97.0].into_iter().enumerate() {
+            window.push(MarketData::from_trade("BTCUSDT", close, 1.0, i as i64));
+        }
+        let api = IndicatorApi::new(window);
+
+        let lua = Lua::new();
+        let table = indicators_to_lua(&lua, &api).unwrap();
+        lua.globals().set("indicators", table).unwrap();
+        let streak: i32 = lua.load("return indicators.streak()").eval().unwrap();
+        assert_eq!(streak, -3);
+    }
+
+    #[test]
+    fn test_custom_indicator_from_lua() {
+        let mut window = MarketDataWindow::new(10);
+        window.push(MarketData {
+            symbol: "BTCUSDT".to_string(),
+            timestamp: 1000,
+            open: 11.0,
+            high: 12.0,
+            low: 11.0,
+            close: 12.0,
+            volume: 10,
+            bid: 12.0,
+            ask: 12.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
+        });
+        let registry = IndicatorRegistry::new();
+        registry.register(
+            "last_close_squared",
+            Box::new(|window: &MarketDataWindow| window.latest().map(|bar| bar.close * bar.close)),
+        );
+        let api = IndicatorApi::new(window).with_registry(registry);
+        assert_eq!(api.custom("last_close_squared"), Some(144.0));
+
+        let lua = Lua::new();
+        let table = indicators_to_lua(&lua, &api).unwrap();
+        lua.globals().set("indicators", table).unwrap();
+        let value: f64 = lua.load(r#"return indicators.custom("last_close_squared")"#).eval().unwrap();
+        let missing: Option<f64> = lua.load(r#"return indicators.custom("unknown")"#).eval().unwrap();
+        assert_eq!(value, 144.0);
+        assert_eq!(missing, None);
+    }
+
+    #[test]
+    fn test_rsi_ready_after_period_plus_one_bars() {
+        let mut window = MarketDataWindow::new(50);
+        for i in 0..15 {
+            assert!(!IndicatorApi::new(window.clone()).is_ready("rsi", 14), "ready at {} bars", i);
+            window.push(MarketData {
+                symbol: "BTCUSDT".to_string(),
+                timestamp: 1000 * i,
+                open: 100.0,
+                high: 101.0,
+                low: 99.0,
+                close: 100.0 + i as f64,
+                volume: 10,
+                bid: 100.0,
+                ask: 100.0,
+                bid_qty: None,
+                ask_qty: None,
+                is_final: true,
+            });
+        }
+        let api = IndicatorApi::new(window);
+        assert!(api.is_ready("rsi", 14));
+        assert!(api.is_ready("sma", 15));
+        assert!(!api.is_ready("sma", 16));
+        assert!(!api.is_ready("unknown", 5));
+
+        // Lua sees the same answer, and the call is recorded
+        let lua = Lua::new();
+        let table = indicators_to_lua(&lua, &api).unwrap();
+        lua.globals().set("indicators", table).unwrap();
+        let ready: bool = lua.load(r#"return indicators.is_ready("rsi", 14)"#).eval().unwrap();
+        assert!(ready);
+        assert_eq!(api.requested(), vec![("rsi".to_string(), 14)]);
+    }
+
     #[test]
     fn test_context_to_lua() {
         let lua = Lua::new();
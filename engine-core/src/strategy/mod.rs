@@ -45,7 +45,7 @@
 //!     return nil
 //! end
 //!
-//! function manage_position(market_data, context, indicators)
+//! function manage_position(market_data, context, indicators, position)
 //!     -- Optional: trailing stop, position adjustments
 //!     return nil
 //! end
@@ -53,13 +53,20 @@
 
 use crate::error::Result;
 use crate::market_data::MarketData;
-use crate::state_machine::{Action, Context};
+use crate::state_machine::{Action, Context, Position};
 use mlua::{Lua, Table, Value};
 use std::path::PathBuf;
 
 mod lua_api;
+mod multi_symbol;
+pub mod params;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 pub use lua_api::IndicatorApi;
+pub use multi_symbol::MultiSymbolContext;
+pub(crate) use lua_api::table_to_action;
+pub use params::{ParamSchema, ParamValue, StrategyParams};
 
 /// A Lua-based trading strategy
 ///
@@ -90,6 +97,28 @@ impl LuaStrategy {
     ///     .expect("Failed to load strategy");
     /// ```
     pub fn new(script_path: impl Into<PathBuf>) -> Result<Self> {
+        Self::with_params(script_path, StrategyParams::new())
+    }
+
+    /// Create a new Lua strategy with injected parameters
+    ///
+    /// The params are visible to the script as the global `params` table.
+    /// If the script declares a `param_schema`, the params are checked
+    /// against it and loading fails on a missing, mistyped or out-of-range
+    /// param.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use trading_engine::strategy::{LuaStrategy, ParamValue, StrategyParams};
+    ///
+    /// let mut params = StrategyParams::new();
+    /// params.insert("fast_period".to_string(), ParamValue::Integer(10));
+    ///
+    /// let strategy = LuaStrategy::with_params("strategies/ema_crossover.lua", params)
+    ///     .expect("Failed to load strategy");
+    /// ```
+    pub fn with_params(script_path: impl Into<PathBuf>, params: StrategyParams) -> Result<Self> {
         let script_path = script_path.into();
         let strategy_name = script_path
             .file_stem()
@@ -98,6 +127,7 @@ impl LuaStrategy {
             .to_string();
 
         let lua = Lua::new();
+        lua.globals().set("params", params::params_to_lua(&lua, &params)?)?;
 
         // Load the strategy script
         let script_content = std::fs::read_to_string(&script_path)?;
@@ -105,6 +135,9 @@ impl LuaStrategy {
 
         // Validate required functions exist
         Self::validate_strategy(&lua)?;
+        Self::validate_params(&lua, &params).map_err(|e| {
+            crate::error::TradingEngineError::StrategyError(format!("Invalid params: {}", e))
+        })?;
 
         Ok(Self {
             lua,
@@ -138,6 +171,15 @@ impl LuaStrategy {
         Ok(())
     }
 
+    /// Validate injected params against the script's `param_schema`, if any
+    fn validate_params(lua: &Lua, params: &StrategyParams) -> std::result::Result<(), String> {
+        match lua.globals().get::<_, Value>("param_schema").map_err(|e| e.to_string())? {
+            Value::Nil => Ok(()),
+            Value::Table(table) => ParamSchema::from_lua(&table)?.validate(params),
+            _ => Err("param_schema must be a table".to_string()),
+        }
+    }
+
     /// Get the strategy name
     pub fn name(&self) -> &str {
         &self.strategy_name
@@ -148,6 +190,15 @@ impl LuaStrategy {
         &self.script_path
     }
 
+    /// Publish `context` as the global `symbols` table
+    ///
+    /// The table stays as set until the next call, so it is seen by every
+    /// strategy function that runs in between.
+    pub fn set_symbols(&self, context: &MultiSymbolContext) -> mlua::Result<()> {
+        let table = multi_symbol::symbols_to_lua(&self.lua, context)?;
+        self.lua.globals().set("symbols", table)
+    }
+
     /// Call detect_opportunity function
     ///
     /// This is called in the Idle state to scan for trading opportunities.
@@ -211,11 +262,28 @@ impl LuaStrategy {
     ///
     /// This is called in the InPosition state on every update to allow the strategy
     /// to manage the active position (trailing stops, partial exits, etc.)
+    /// The fourth `position` argument is nil; use
+    /// [`manage_position_with`](Self::manage_position_with) to pass it.
     pub fn manage_position(
         &self,
         market_data: &MarketData,
         context: &Context,
         indicator_api: &IndicatorApi,
+    ) -> Result<Option<Action>> {
+        self.manage_position_with(market_data, context, indicator_api, None)
+    }
+
+    /// Call manage_position function with the open position
+    ///
+    /// Like [`manage_position`](Self::manage_position), but `position`, if
+    /// given, is passed as the fourth argument, so the strategy can read its
+    /// stop and target.
+    pub fn manage_position_with(
+        &self,
+        market_data: &MarketData,
+        context: &Context,
+        indicator_api: &IndicatorApi,
+        position: Option<&Position>,
     ) -> Result<Option<Action>> {
         let globals = self.lua.globals();
         let func: mlua::Function = globals.get("manage_position")?;
@@ -223,8 +291,12 @@ impl LuaStrategy {
         let market_table = lua_api::market_data_to_lua(&self.lua, market_data)?;
         let context_table = lua_api::context_to_lua(&self.lua, context)?;
         let indicator_table = lua_api::indicators_to_lua(&self.lua, indicator_api)?;
+        let position_value = match position {
+            Some(position) => Value::Table(lua_api::position_to_lua(&self.lua, position)?),
+            None => Value::Nil,
+        };
 
-        let result: Value = func.call((market_table, context_table, indicator_table))?;
+        let result: Value = func.call((market_table, context_table, indicator_table, position_value))?;
 
         match result {
             Value::Nil => Ok(None),
@@ -239,6 +311,7 @@ impl LuaStrategy {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::test_support::TempScript;
 
     #[test]
     fn test_strategy_validation_missing_function() {
@@ -273,4 +346,38 @@ mod tests {
         let result = LuaStrategy::validate_strategy(&lua);
         assert!(result.is_ok());
     }
+
+    const SCHEMA_STRATEGY: &str = r#"
+        param_schema = {
+            fast_period = { type = "integer", min = 1, max = 200 },
+        }
+        function detect_opportunity() return { period = params.fast_period } end
+        function filter_commitment() end
+        function manage_position() end
+    "#;
+
+    #[test]
+    fn test_missing_required_param_fails_to_load() {
+        let script = TempScript::new("param_schema_missing", SCHEMA_STRATEGY);
+
+        let err = LuaStrategy::new(script.path()).err().unwrap().to_string();
+        assert!(err.contains("missing required param 'fast_period'"), "{}", err);
+    }
+
+    #[test]
+    fn test_params_are_injected() {
+        let script = TempScript::new("param_schema_injected", SCHEMA_STRATEGY);
+        let mut params = StrategyParams::new();
+        params.insert("fast_period".to_string(), ParamValue::Integer(12));
+
+        let strategy = LuaStrategy::with_params(script.path(), params).unwrap();
+        let indicators = IndicatorApi::new(crate::market_data::MarketDataWindow::new(10));
+        let market_data = MarketData::from_trade("BTCUSDT", 50000.0, 1.0, 0);
+        let signal = strategy
+            .detect_opportunity(&market_data, &Context::new(), &indicators)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(signal.get::<_, i64>("period").unwrap(), 12);
+    }
 }
@@ -0,0 +1,127 @@
+//! Windows for several symbols at once, for pairs and basket strategies.
+//!
+//! A runner configured with `context_symbols` also receives those symbols'
+//! bars and keeps a [`MultiSymbolContext`] of recent windows, its own symbol
+//! included. Before each strategy call the context is published as the global
+//! `symbols` table, keyed by symbol:
+//!
+//! ```lua
+//! function detect_opportunity(market_data, context, indicators)
+//!     local btc, eth = symbols.BTCUSDT, symbols.ETHUSDT
+//!     if btc and eth and btc.close / eth.close > 17 then
+//!         return { signal = "ratio_high" }
+//!     end
+//!     return nil
+//! end
+//! ```
+//!
+//! Each entry has the fields of the symbol's latest bar (like `market_data`),
+//! plus `closes` (the window's closes, oldest first) and `bar_count`. Symbols
+//! that haven't had a bar yet are `nil`.
+
+use super::lua_api::market_data_to_lua;
+use crate::market_data::{MarketData, MarketDataWindow};
+use mlua::{Lua, Table};
+use std::collections::BTreeMap;
+
+/// Latest windows for a configured set of symbols
+///
+/// # Example
+///
+/// ```
+/// use trading_engine::strategy::MultiSymbolContext;
+/// use trading_engine::MarketData;
+///
+/// let mut context = MultiSymbolContext::new(["BTCUSDT", "ETHUSDT"], 50);
+///
+/// assert!(context.push(MarketData::from_trade("ETHUSDT", 3000.0, 1.0, 0)));
+/// assert!(!context.push(MarketData::from_trade("SOLUSDT", 150.0, 1.0, 0)));
+///
+/// assert_eq!(context.latest("ETHUSDT").unwrap().close, 3000.0);
+/// assert!(context.latest("BTCUSDT").is_none());
+/// ```
+#[derive(Clone)]
+pub struct MultiSymbolContext {
+    windows: BTreeMap<String, MarketDataWindow>,
+}
+
+impl MultiSymbolContext {
+    /// Track `symbols`, keeping up to `window_size` bars of each
+    pub fn new(symbols: impl IntoIterator<Item = impl Into<String>>, window_size: usize) -> Self {
+        let windows = symbols
+            .into_iter()
+            .map(|symbol| (symbol.into(), MarketDataWindow::new(window_size)))
+            .collect();
+        Self { windows }
+    }
+
+    /// Tracked symbols, sorted
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.windows.keys().map(String::as_str)
+    }
+
+    /// Check if `symbol` is tracked
+    pub fn contains(&self, symbol: &str) -> bool {
+        self.windows.contains_key(symbol)
+    }
+
+    /// Add a bar to its symbol's window
+    ///
+    /// Returns `false`, dropping the bar, if the symbol isn't tracked.
+    pub fn push(&mut self, data: MarketData) -> bool {
+        match self.windows.get_mut(&data.symbol) {
+            Some(window) => {
+                window.push(data);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Window of recent bars for `symbol`
+    pub fn window(&self, symbol: &str) -> Option<&MarketDataWindow> {
+        self.windows.get(symbol)
+    }
+
+    /// Latest bar for `symbol`, if it has had one
+    pub fn latest(&self, symbol: &str) -> Option<&MarketData> {
+        self.windows.get(symbol)?.latest()
+    }
+}
+
+/// Convert a context into the `symbols` table strategies read
+pub(crate) fn symbols_to_lua<'lua>(lua: &'lua Lua, context: &MultiSymbolContext) -> mlua::Result<Table<'lua>> {
+    let table = lua.create_table()?;
+    for (symbol, window) in &context.windows {
+        if let Some(latest) = window.latest() {
+            let entry = market_data_to_lua(lua, latest).map_err(mlua::Error::external)?;
+            entry.set("closes", window.closes(window.len()))?;
+            entry.set("bar_count", window.len())?;
+            table.set(symbol.as_str(), entry)?;
+        }
+    }
+    Ok(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbols_table_skips_symbols_without_bars() {
+        let mut context = MultiSymbolContext::new(["BTCUSDT", "ETHUSDT"], 3);
+        for i in 0..5 {
+            context.push(MarketData::from_trade("BTCUSDT", 50000.0 + i as f64, 1.0, i));
+        }
+
+        let lua = Lua::new();
+        lua.globals().set("symbols", symbols_to_lua(&lua, &context).unwrap()).unwrap();
+
+        let close: f64 = lua.load("return symbols.BTCUSDT.close").eval().unwrap();
+        assert_eq!(close, 50004.0);
+        let closes: Vec<f64> = lua.load("return symbols.BTCUSDT.closes").eval().unwrap();
+        assert_eq!(closes, vec![50002.0, 50003.0, 50004.0]);
+        let eth_missing: bool = lua.load("return symbols.ETHUSDT == nil").eval().unwrap();
+        assert!(eth_missing);
+    }
+}
@@ -0,0 +1,240 @@
+//! Strategy parameters and the optional `param_schema` a script can declare.
+//!
+//! Parameters are injected into a strategy as the global `params` table. A
+//! script lists the parameters it expects in a global `param_schema` table:
+//!
+//! ```lua
+//! param_schema = {
+//!     fast_period = { type = "integer", min = 1, max = 200 },
+//!     threshold = { type = "number", required = false },
+//! }
+//! ```
+//!
+//! Entries are required unless `required = false`. `type` is one of
+//! `"number"`, `"integer"`, `"string"` or `"boolean"`; `min` and `max` bound
+//! numeric params.
+
+use mlua::{Lua, Table, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Parameters injected into a strategy, by name
+pub type StrategyParams = HashMap<String, ParamValue>;
+
+/// A single strategy parameter value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ParamValue {
+    Integer(i64),
+    Number(f64),
+    Boolean(bool),
+    String(String),
+}
+
+impl ParamValue {
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            ParamValue::Integer(i) => Some(*i as f64),
+            ParamValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn into_lua(self, lua: &Lua) -> mlua::Result<Value<'_>> {
+        Ok(match self {
+            ParamValue::Integer(i) => Value::Integer(i),
+            ParamValue::Number(n) => Value::Number(n),
+            ParamValue::Boolean(b) => Value::Boolean(b),
+            ParamValue::String(s) => Value::String(lua.create_string(&s)?),
+        })
+    }
+}
+
+/// Expected type of a strategy parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamType {
+    /// Any number; integers are accepted too
+    Number,
+    Integer,
+    String,
+    Boolean,
+}
+
+impl ParamType {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "number" => Some(ParamType::Number),
+            "integer" => Some(ParamType::Integer),
+            "string" => Some(ParamType::String),
+            "boolean" => Some(ParamType::Boolean),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ParamType::Number => "number",
+            ParamType::Integer => "integer",
+            ParamType::String => "string",
+            ParamType::Boolean => "boolean",
+        }
+    }
+
+    fn accepts(self, value: &ParamValue) -> bool {
+        matches!(
+            (self, value),
+            (ParamType::Number, ParamValue::Number(_) | ParamValue::Integer(_))
+                | (ParamType::Integer, ParamValue::Integer(_))
+                | (ParamType::String, ParamValue::String(_))
+                | (ParamType::Boolean, ParamValue::Boolean(_))
+        )
+    }
+}
+
+/// One entry of a strategy's `param_schema`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamSpec {
+    pub name: String,
+    pub param_type: ParamType,
+    pub required: bool,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+/// The parameters a strategy declares in its `param_schema` global
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParamSchema {
+    /// Declared params, sorted by name
+    pub params: Vec<ParamSpec>,
+}
+
+impl ParamSchema {
+    /// Read a schema from a `param_schema` Lua table
+    pub fn from_lua(table: &Table) -> Result<Self, String> {
+        let mut params = Vec::new();
+
+        for pair in table.clone().pairs::<String, Table>() {
+            let (name, spec) = pair.map_err(|e| format!("invalid param_schema entry: {}", e))?;
+            let field_error = |e: mlua::Error| format!("invalid param_schema entry '{}': {}", name, e);
+
+            let type_name: String = spec.get("type").map_err(field_error)?;
+            let param_type = ParamType::parse(&type_name)
+                .ok_or_else(|| format!("unknown type '{}' for param '{}'", type_name, name))?;
+            let required: Option<bool> = spec.get("required").map_err(field_error)?;
+
+            params.push(ParamSpec {
+                param_type,
+                required: required.unwrap_or(true),
+                min: spec.get("min").map_err(field_error)?,
+                max: spec.get("max").map_err(field_error)?,
+                name,
+            });
+        }
+
+        params.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Self { params })
+    }
+
+    /// Check `params` against the schema, listing every problem found
+    pub fn validate(&self, params: &StrategyParams) -> Result<(), String> {
+        let mut problems = Vec::new();
+
+        for spec in &self.params {
+            let Some(value) = params.get(&spec.name) else {
+                if spec.required {
+                    problems.push(format!("missing required param '{}'", spec.name));
+                }
+                continue;
+            };
+
+            if !spec.param_type.accepts(value) {
+                problems.push(format!(
+                    "param '{}' must be {}, got {:?}",
+                    spec.name,
+                    spec.param_type.name(),
+                    value
+                ));
+                continue;
+            }
+
+            if let Some(n) = value.as_f64() {
+                if spec.min.is_some_and(|min| n < min) || spec.max.is_some_and(|max| n > max) {
+                    problems.push(format!(
+                        "param '{}' = {} is outside [{}, {}]",
+                        spec.name,
+                        n,
+                        spec.min.map_or("-inf".to_string(), |m| m.to_string()),
+                        spec.max.map_or("inf".to_string(), |m| m.to_string()),
+                    ));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("; "))
+        }
+    }
+}
+
+/// Build the `params` Lua table from injected params
+pub(crate) fn params_to_lua<'lua>(lua: &'lua Lua, params: &StrategyParams) -> mlua::Result<Table<'lua>> {
+    let table = lua.create_table()?;
+    for (name, value) in params {
+        table.set(name.as_str(), value.clone().into_lua(lua)?)?;
+    }
+    Ok(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(lua_source: &str) -> ParamSchema {
+        let lua = Lua::new();
+        let table: Table = lua.load(lua_source).eval().unwrap();
+        ParamSchema::from_lua(&table).unwrap()
+    }
+
+    #[test]
+    fn test_schema_parsing() {
+        let schema = schema(
+            r#"{
+                fast_period = { type = "integer", min = 1, max = 200 },
+                threshold = { type = "number", required = false },
+            }"#,
+        );
+
+        assert_eq!(schema.params.len(), 2);
+        assert_eq!(schema.params[0].name, "fast_period");
+        assert_eq!(schema.params[0].param_type, ParamType::Integer);
+        assert!(schema.params[0].required);
+        assert_eq!(schema.params[0].max, Some(200.0));
+        assert!(!schema.params[1].required);
+    }
+
+    #[test]
+    fn test_validate_types_and_ranges() {
+        let schema = schema(
+            r#"{
+                fast_period = { type = "integer", min = 1, max = 200 },
+                threshold = { type = "number", required = false },
+            }"#,
+        );
+
+        let mut params = StrategyParams::new();
+        params.insert("fast_period".to_string(), ParamValue::Integer(10));
+        assert!(schema.validate(&params).is_ok());
+
+        // Integers are accepted for number params
+        params.insert("threshold".to_string(), ParamValue::Integer(1));
+        assert!(schema.validate(&params).is_ok());
+
+        params.insert("fast_period".to_string(), ParamValue::Integer(500));
+        assert!(schema.validate(&params).unwrap_err().contains("outside [1, 200]"));
+
+        params.insert("fast_period".to_string(), ParamValue::Number(10.5));
+        assert!(schema.validate(&params).unwrap_err().contains("must be integer"));
+    }
+}
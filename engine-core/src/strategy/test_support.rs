@@ -0,0 +1,43 @@
+//! Helpers for tests that load a strategy from inline Lua source
+
+use super::LuaStrategy;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Lua source written to a temp file that is removed on drop
+///
+/// File names include the process ID and a counter, so tests running in
+/// parallel never share a script.
+pub(crate) struct TempScript {
+    path: PathBuf,
+}
+
+impl TempScript {
+    pub(crate) fn new(name: &str, source: &str) -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "{}_{}_{}.lua",
+            name,
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&path, source).expect("Failed to write test script");
+        Self { path }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempScript {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Load a strategy from inline Lua source
+pub(crate) fn strategy_from_source(name: &str, source: &str) -> LuaStrategy {
+    let script = TempScript::new(name, source);
+    LuaStrategy::new(script.path()).unwrap_or_else(|e| panic!("Failed to load {} strategy: {}", name, e))
+}
@@ -2,7 +2,8 @@
 //!
 //! Tests the flow from data source → storage → queries
 
-use trading_engine::{MarketDataSource, SimulatedFeed, MarketDataStorage};
+use std::time::Duration;
+use trading_engine::{MarketData, MarketDataSource, ReplayFeed, SimulatedFeed, MarketDataStorage};
 
 #[tokio::test]
 async fn test_simulated_feed_to_storage() {
@@ -98,6 +99,66 @@ async fn test_feed_error_handling() {
     assert!(feed.disconnect().await.is_ok());
 }
 
+#[tokio::test]
+async fn test_next_tick_timeout_returns_none_for_slow_feed() {
+    let mut feed = SimulatedFeed::new("BTCUSDT".to_string(), 50000.0)
+        .with_interval(Duration::from_secs(5));
+    feed.connect().await.expect("Should connect");
+
+    // Deadline passes long before the next tick is generated
+    let result = feed
+        .next_tick_timeout(Duration::from_millis(20))
+        .await
+        .expect("Timeout should not be an error");
+    assert!(result.is_none());
+
+    // A generous deadline still yields data from a fast feed
+    let mut fast_feed = SimulatedFeed::new("BTCUSDT".to_string(), 50000.0)
+        .with_interval(Duration::from_millis(1));
+    let data = fast_feed
+        .next_tick_timeout(Duration::from_secs(1))
+        .await
+        .expect("Should get tick");
+    assert_eq!(data.map(|d| d.symbol), Some("BTCUSDT".to_string()));
+}
+
+#[tokio::test]
+async fn test_replay_feed_reset_restarts_from_first_bar() {
+    let bars: Vec<MarketData> = (0..3)
+        .map(|i| {
+            let price = 100.0 + i as f64;
+            MarketData {
+                symbol: "BTCUSDT".to_string(),
+                timestamp: i * 60_000,
+                open: price,
+                high: price + 1.0,
+                low: price - 1.0,
+                close: price,
+                volume: 1000,
+                bid: price - 0.1,
+                ask: price + 0.1,
+                bid_qty: None,
+                ask_qty: None,
+                is_final: true,
+            }
+        })
+        .collect();
+    let mut feed = ReplayFeed::new(bars);
+
+    // Consume the whole replay
+    for _ in 0..3 {
+        feed.next_tick().await.expect("Should get tick");
+    }
+    assert_eq!(feed.remaining(), 0);
+    assert!(feed.next_tick().await.is_err());
+
+    // Rewinding replays the first bar again
+    feed.reset().await.expect("Should reset");
+    let first = feed.next_tick().await.expect("Should get first bar again");
+    assert_eq!(first.timestamp, 0);
+    assert_eq!(first.close, 100.0);
+}
+
 #[tokio::test]
 async fn test_storage_under_concurrent_access() {
     let storage = MarketDataStorage::new(1000);
@@ -179,6 +240,9 @@ fn test_window_with_realistic_queries() {
             volume: 1000,
             bid: 50049.0 + (i as f64 * 10.0),
             ask: 50051.0 + (i as f64 * 10.0),
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         };
         storage.push(data);
     }
@@ -37,6 +37,9 @@ fn test_detect_opportunity() {
             volume: 1000,
             bid: 49950.0,
             ask: 50050.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         });
     }
 
@@ -65,6 +68,9 @@ fn test_filter_commitment() {
             volume: 1000,
             bid: 49950.0,
             ask: 50050.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         });
     }
 
@@ -98,6 +104,9 @@ fn test_manage_position() {
             volume: 1000,
             bid: 43950.0,
             ask: 44050.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         });
     }
 
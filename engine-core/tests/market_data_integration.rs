@@ -22,6 +22,9 @@ fn test_end_to_end_data_flow() {
             volume: 1000 + i as u64,
             bid: 50499.0 + i as f64,
             ask: 50501.0 + i as f64,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         };
 
         data.validate().expect("Data should be valid");
@@ -58,6 +61,9 @@ fn test_multi_symbol_storage() {
                 volume: 1000,
                 bid: 104.0,
                 ask: 106.0,
+                bid_qty: None,
+                ask_qty: None,
+                is_final: true,
             };
             storage.push(data);
         }
@@ -73,6 +79,23 @@ fn test_multi_symbol_storage() {
     }
 }
 
+#[test]
+fn test_latest_prices_across_symbols() {
+    let storage = MarketDataStorage::new(100);
+
+    for (symbol, base) in [("BTCUSDT", 50000.0), ("ETHUSDT", 3000.0), ("SOLUSDT", 100.0)] {
+        for i in 0..10 {
+            storage.push(MarketData::from_trade(symbol, base + i as f64, 1.0, i));
+        }
+    }
+
+    let prices = storage.latest_prices();
+    assert_eq!(prices.len(), 3);
+    assert_eq!(prices["BTCUSDT"], 50009.0);
+    assert_eq!(prices["ETHUSDT"], 3009.0);
+    assert_eq!(prices["SOLUSDT"], 109.0);
+}
+
 #[test]
 fn test_storage_isolation() {
     let storage = MarketDataStorage::new(100);
@@ -89,6 +112,9 @@ fn test_storage_isolation() {
             volume: 1000,
             bid: 104.0,
             ask: 106.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         };
         storage.push(data);
     }
@@ -116,6 +142,9 @@ fn test_window_query_consistency() {
             volume: 1000,
             bid: 100.0,
             ask: 100.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         };
         window.push(data);
     }
@@ -144,6 +173,9 @@ fn test_storage_clone_independence() {
         volume: 1000,
         bid: 104.0,
         ask: 106.0,
+        bid_qty: None,
+        ask_qty: None,
+        is_final: true,
     };
     storage1.push(data.clone());
 
@@ -178,6 +210,9 @@ fn test_large_dataset() {
             volume: 1000,
             bid: 50499.0,
             ask: 50501.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         };
         window.push(data);
     }
@@ -212,6 +247,9 @@ fn test_realistic_trading_scenario() {
             volume: 1000 + (i % 100) as u64,
             bid: price + 4.0,
             ask: price + 6.0,
+            bid_qty: None,
+            ask_qty: None,
+            is_final: true,
         };
 
         storage.push(data);
@@ -22,6 +22,9 @@ fn create_market_data(symbol: &str, close: f64, timestamp: i64) -> MarketData {
         volume: 1000,
         bid: close - 1.0,
         ask: close + 1.0,
+        bid_qty: None,
+        ask_qty: None,
+        is_final: true,
     }
 }
 
@@ -33,6 +36,7 @@ async fn test_single_runner_with_strategy() {
         .expect("Failed to load strategy");
 
     let mut runner = SymbolRunner::new(
+        "btc_runner".to_string(),
         "BTCUSDT".to_string(),
         strategy,
         rx,
@@ -348,8 +352,9 @@ async fn test_concurrent_multi_symbol_processing() {
     // Shutdown
     let results = engine.shutdown_with_results().await;
     assert_eq!(results.len(), 6);
-    for (runner_id, result) in results {
-        assert!(result.is_ok(), "Runner {} failed: {:?}", runner_id, result);
+    for (runner_id, shutdown) in results {
+        assert!(shutdown.result.is_ok(), "Runner {} failed: {:?}", runner_id, shutdown.result);
+        assert!(shutdown.final_snapshot.is_some());
     }
 }
 
@@ -395,3 +400,41 @@ async fn test_remove_nonexistent_runner_error() {
     // Should error
     assert!(result.is_err());
 }
+
+#[tokio::test]
+async fn test_deterministic_engine_processes_inline() {
+    let mut engine = TradingEngine::new_deterministic();
+
+    let strategy1 = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+        .expect("Failed to load strategy 1");
+    let strategy2 = LuaStrategy::new("../lua-strategies/test_strategy.lua")
+        .expect("Failed to load strategy 2");
+
+    engine.add_runner("btc_runner_1", "BTCUSDT", strategy1).unwrap();
+    engine.add_runner("btc_runner_2", "BTCUSDT", strategy2).unwrap();
+
+    // No sleeping: every tick is fully processed before feed_data returns
+    for i in 0..5 {
+        let data = create_market_data("BTCUSDT", 50000.0 + (i as f64 * 100.0), 1000 + i);
+        engine.feed_data(data).await.unwrap();
+
+        for runner_id in ["btc_runner_1", "btc_runner_2"] {
+            let snapshot = engine.get_runner_snapshot(runner_id).await.unwrap();
+            assert_eq!(snapshot.stats.ticks_processed, i as u64 + 1);
+        }
+    }
+
+    let history = engine.get_price_history("btc_runner_1", None).await.unwrap();
+    assert_eq!(history.len(), 5);
+
+    // Commands take effect immediately too
+    assert!(engine.pause_runner("btc_runner_1").await.unwrap());
+    engine.feed_data(create_market_data("BTCUSDT", 50600.0, 1005)).await.unwrap();
+    let paused = engine.get_runner_snapshot("btc_runner_1").await.unwrap();
+    let running = engine.get_runner_snapshot("btc_runner_2").await.unwrap();
+    assert_eq!(paused.stats.ticks_processed, 5);
+    assert_eq!(running.stats.ticks_processed, 6);
+
+    assert_eq!(engine.runner_is_healthy("btc_runner_1"), Some(true));
+    engine.shutdown().await.unwrap();
+}
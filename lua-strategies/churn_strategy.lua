@@ -0,0 +1,20 @@
+-- Churn strategy for tests: enters long on every bar and exits on the next
+
+function detect_opportunity(market_data, context, indicators)
+    return { signal = "bullish" }
+end
+
+function filter_commitment(market_data, context, indicators)
+    return {
+        action = "enter_long",
+        price = market_data.close,
+        quantity = 1.0
+    }
+end
+
+function manage_position(market_data, context, indicators)
+    return {
+        action = "exit",
+        price = market_data.close
+    }
+end
@@ -4,6 +4,7 @@ use axum::{
     Json,
 };
 use serde::{Deserialize, Serialize};
+use trading_engine::TradingEngineError;
 
 /// API error types
 #[derive(Debug, thiserror::Error)]
@@ -22,6 +23,10 @@ pub enum ApiError {
 
     #[error("Internal server error")]
     InternalError,
+
+    /// An error returned by the trading engine, mapped to a status by variant
+    #[error(transparent)]
+    Engine(#[from] TradingEngineError),
 }
 
 /// Error response format
@@ -36,6 +41,9 @@ pub struct ErrorResponse {
 pub struct ErrorDetail {
     pub code: String,
     pub message: String,
+    /// Engine error variant name, present when the error came from the engine
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
 }
 
 impl ApiError {
@@ -47,6 +55,20 @@ impl ApiError {
             ApiError::EngineError(_) => "ENGINE_ERROR",
             ApiError::StrategyError(_) => "STRATEGY_ERROR",
             ApiError::InternalError => "INTERNAL_ERROR",
+            ApiError::Engine(err) => match err {
+                TradingEngineError::RunnerNotFound(_) => "RUNNER_NOT_FOUND",
+                TradingEngineError::RunnerAlreadyExists(_) => "RUNNER_ALREADY_EXISTS",
+                TradingEngineError::NoRunnersForSymbol(_) => "NO_RUNNERS_FOR_SYMBOL",
+                TradingEngineError::InvalidData(_) => "INVALID_DATA",
+                TradingEngineError::StrategyError(_) => "STRATEGY_ERROR",
+                TradingEngineError::ChannelClosed(_) | TradingEngineError::TaskPanic(_) => {
+                    "RUNNER_UNAVAILABLE"
+                }
+                TradingEngineError::InvalidTransition { .. } => "INVALID_TRANSITION",
+                TradingEngineError::InvalidConfig(_) => "INVALID_CONFIG",
+                TradingEngineError::Timeout(_) => "TIMEOUT",
+                _ => "ENGINE_ERROR",
+            },
         }
     }
 
@@ -58,6 +80,27 @@ impl ApiError {
             ApiError::EngineError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::StrategyError(_) => StatusCode::BAD_REQUEST,
             ApiError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::Engine(err) => match err {
+                TradingEngineError::RunnerNotFound(_) => StatusCode::NOT_FOUND,
+                TradingEngineError::RunnerAlreadyExists(_) => StatusCode::CONFLICT,
+                TradingEngineError::NoRunnersForSymbol(_) => StatusCode::NOT_FOUND,
+                TradingEngineError::InvalidData(_) => StatusCode::BAD_REQUEST,
+                TradingEngineError::StrategyError(_) => StatusCode::BAD_REQUEST,
+                TradingEngineError::ChannelClosed(_) => StatusCode::SERVICE_UNAVAILABLE,
+                TradingEngineError::TaskPanic(_) => StatusCode::SERVICE_UNAVAILABLE,
+                TradingEngineError::InvalidTransition { .. } => StatusCode::CONFLICT,
+                TradingEngineError::InvalidConfig(_) => StatusCode::BAD_REQUEST,
+                TradingEngineError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            },
+        }
+    }
+
+    /// Engine error variant name, if this error came from the engine
+    fn variant(&self) -> Option<&'static str> {
+        match self {
+            ApiError::Engine(err) => Some(err.variant_name()),
+            _ => None,
         }
     }
 }
@@ -70,6 +113,7 @@ impl IntoResponse for ApiError {
             error: ErrorDetail {
                 code: self.error_code().to_string(),
                 message: self.to_string(),
+                variant: self.variant().map(str::to_string),
             },
             timestamp: chrono::Utc::now().timestamp(),
         };
@@ -120,4 +164,43 @@ mod tests {
             StatusCode::INTERNAL_SERVER_ERROR
         );
     }
+
+    #[test]
+    fn test_engine_error_status_codes() {
+        let cases = [
+            (TradingEngineError::RunnerNotFound("r".into()), StatusCode::NOT_FOUND),
+            (TradingEngineError::RunnerAlreadyExists("r".into()), StatusCode::CONFLICT),
+            (TradingEngineError::NoRunnersForSymbol("BTCUSDT".into()), StatusCode::NOT_FOUND),
+            (TradingEngineError::InvalidData("bad".into()), StatusCode::BAD_REQUEST),
+            (TradingEngineError::StrategyError("bad".into()), StatusCode::BAD_REQUEST),
+            (TradingEngineError::ChannelClosed("r".into()), StatusCode::SERVICE_UNAVAILABLE),
+            (TradingEngineError::TaskPanic("r".into()), StatusCode::SERVICE_UNAVAILABLE),
+            (
+                TradingEngineError::InvalidTransition {
+                    from: trading_engine::state_machine::State::Idle,
+                    action: "ExitPosition".into(),
+                },
+                StatusCode::CONFLICT,
+            ),
+            (TradingEngineError::InvalidConfig("bad".into()), StatusCode::BAD_REQUEST),
+            (TradingEngineError::Timeout("r".into()), StatusCode::GATEWAY_TIMEOUT),
+            (TradingEngineError::NotConnected, StatusCode::INTERNAL_SERVER_ERROR),
+        ];
+
+        for (err, status) in cases {
+            assert_eq!(ApiError::from(err).status_code(), status);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_engine_error_body_names_variant() {
+        let response =
+            ApiError::from(TradingEngineError::RunnerAlreadyExists("r".into())).into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.error.code, "RUNNER_ALREADY_EXISTS");
+        assert_eq!(body.error.variant.as_deref(), Some("RunnerAlreadyExists"));
+    }
 }
@@ -0,0 +1,51 @@
+// Market data feed helpers
+
+use std::collections::HashSet;
+
+/// Symbols to subscribe and unsubscribe when the engine's symbol set changes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolDiff {
+    /// Symbols in the new set only, sorted
+    pub added: Vec<String>,
+    /// Symbols in the old set only, sorted
+    pub removed: Vec<String>,
+}
+
+impl SymbolDiff {
+    /// Compare the subscribed symbols with the engine's current ones
+    pub fn between(subscribed: &HashSet<String>, current: &HashSet<String>) -> Self {
+        let mut added: Vec<String> = current.difference(subscribed).cloned().collect();
+        let mut removed: Vec<String> = subscribed.difference(current).cloned().collect();
+        added.sort();
+        removed.sort();
+        Self { added, removed }
+    }
+
+    /// Check if the symbol sets are the same
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(symbols: &[&str]) -> HashSet<String> {
+        symbols.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_symbol_diff() {
+        let diff = SymbolDiff::between(
+            &set(&["BTCUSDT", "ETHUSDT"]),
+            &set(&["SOLUSDT", "BTCUSDT", "ADAUSDT"]),
+        );
+        assert_eq!(diff.added, vec!["ADAUSDT", "SOLUSDT"]);
+        assert_eq!(diff.removed, vec!["ETHUSDT"]);
+        assert!(!diff.is_empty());
+
+        let unchanged = SymbolDiff::between(&set(&["BTCUSDT"]), &set(&["BTCUSDT"]));
+        assert!(unchanged.is_empty());
+    }
+}
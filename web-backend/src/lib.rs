@@ -1,4 +1,5 @@
 pub mod error;
+pub mod feed;
 pub mod routes;
 pub mod state;
 pub mod websocket;
@@ -42,6 +43,8 @@ pub fn build_router(state: AppState) -> Router {
         .route("/health", get(routes::health::health_check))
         .route("/api/engine/health", get(routes::engine::engine_health))
         .route("/api/engine/summary", get(routes::engine::engine_summary))
+        .route("/api/engine/state", get(routes::engine::engine_state))
+        .route("/api/engine/flatten", post(routes::engine::flatten_all))
         // Runner endpoints
         .route(
             "/api/runners/:id/snapshot",
@@ -51,15 +54,31 @@ pub fn build_router(state: AppState) -> Router {
             "/api/runners/:id/history",
             get(routes::runners::get_price_history),
         )
-        .route("/api/runners", post(routes::runners::add_runner))
+        .route(
+            "/api/runners/:id/indicators",
+            get(routes::runners::get_indicator_series),
+        )
+        .route(
+            "/api/runners",
+            get(routes::runners::list_runners).post(routes::runners::add_runner),
+        )
+        .route("/api/runners/batch", post(routes::runners::add_runners_batch))
+        .route("/api/runners/snapshots", get(routes::runners::get_all_snapshots))
         .route("/api/runners/:id", delete(routes::runners::remove_runner))
         // Runner control endpoints
         .route("/api/runners/:id/pause", post(routes::runners::pause_runner))
         .route("/api/runners/:id/resume", post(routes::runners::resume_runner))
         .route("/api/runners/:id/stop", post(routes::runners::stop_runner))
+        // Backtesting
+        .route("/api/backtest", post(routes::backtest::run_backtest))
+        // Market data
+        .route("/api/prices", get(routes::prices::latest_prices))
         // Strategy endpoints
         .route("/api/strategies", get(routes::strategies::list_strategies))
         .route("/api/symbols", get(routes::strategies::list_symbols))
+        .route("/api/symbols/:symbol/compare", get(routes::runners::compare_strategies))
+        // Event stream
+        .route("/ws", get(websocket::ws_handler))
         .with_state(state);
 
     // Add CORS middleware
@@ -2,6 +2,7 @@ use anyhow::Result;
 use std::collections::HashSet;
 use trading_engine::runner::TradingEngine;
 use trading_engine::sources::{BinanceFeed, BinanceRegion, MarketDataSource};
+use trading_web_backend::feed::SymbolDiff;
 use trading_web_backend::{start_server, AppState, ServerConfig};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -31,7 +32,11 @@ async fn main() -> Result<()> {
     let state = AppState::new(engine);
     tracing::info!("Trading engine initialized");
 
-    // Spawn background task to feed market data
+    // Feed the engine from the market data bus
+    let forward_state = state.clone();
+    tokio::spawn(async move { forward_state.forward_market_data().await });
+
+    // Spawn background task to publish market data
     let feed_state = state.clone();
     tokio::spawn(async move {
         if let Err(e) = run_market_data_feed(feed_state).await {
@@ -45,7 +50,7 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-/// Background task that feeds market data from Binance to the engine
+/// Background task that publishes market data from Binance to the bus
 async fn run_market_data_feed(state: AppState) -> Result<()> {
     loop {
         // Get current symbols from engine
@@ -75,7 +80,7 @@ async fn run_market_data_feed(state: AppState) -> Result<()> {
             continue;
         }
 
-        let subscribed_symbols: HashSet<String> = symbols.iter().cloned().collect();
+        let mut subscribed_symbols: HashSet<String> = symbols.iter().cloned().collect();
 
         // Feed data loop
         loop {
@@ -85,29 +90,26 @@ async fn run_market_data_feed(state: AppState) -> Result<()> {
                 engine.active_symbols().iter().cloned().collect::<HashSet<_>>()
             };
 
-            // If symbols changed, reconnect
-            if current_symbols != subscribed_symbols {
-                tracing::info!("Symbols changed, reconnecting feed...");
-                if let Err(e) = feed.disconnect().await {
-                    tracing::warn!("Error disconnecting feed: {}", e);
+            // Update the live connection in place, so unchanged symbols keep
+            // their data; fall back to a full reconnect if that fails
+            let diff = SymbolDiff::between(&subscribed_symbols, &current_symbols);
+            if !diff.is_empty() {
+                if current_symbols.is_empty() {
+                    tracing::info!("No active symbols left, closing feed...");
+                    break;
+                }
+                tracing::info!("Symbols changed (added {:?}, removed {:?})", diff.added, diff.removed);
+                if let Err(e) = feed.update_subscriptions(&diff.added, &diff.removed).await {
+                    tracing::warn!("Failed to update subscriptions, reconnecting feed: {}", e);
+                    break; // Break inner loop to reconnect
                 }
-                break; // Break inner loop to reconnect
+                subscribed_symbols = current_symbols;
             }
 
             // Get next tick
             match feed.next_tick().await {
-                Ok(data) => {
-                    let symbol = data.symbol.clone();
-                    let price = data.close;
-
-                    // Feed to engine
-                    let engine = state.engine.lock().await;
-                    if let Err(e) = engine.feed_data(data).await {
-                        tracing::warn!("Failed to feed data for {}: {}", symbol, e);
-                    } else {
-                        tracing::debug!("Fed data for {} at price {}", symbol, price);
-                    }
-                }
+                // Reaches both the storage and the engine
+                Ok(data) => state.bus.publish(data),
                 Err(e) => {
                     tracing::error!("Error receiving tick: {}", e);
                     tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
@@ -0,0 +1,73 @@
+use axum::Json;
+use serde::Deserialize;
+use trading_engine::{
+    market_data::MarketData,
+    runner::{BacktestReport, RunnerConfig},
+};
+
+use crate::ApiError;
+
+/// Request body for a backtest
+#[derive(Debug, Deserialize)]
+pub struct BacktestRequest {
+    pub strategy_path: String,
+    /// Bars to replay, oldest first
+    pub data: Vec<MarketData>,
+    #[serde(default = "default_window_size")]
+    pub window_size: usize,
+    /// Runner configuration (defaults to `RunnerConfig::quiet()`)
+    #[serde(default)]
+    pub config: Option<RunnerConfig>,
+}
+
+fn default_window_size() -> usize {
+    200
+}
+
+/// Most bars a single backtest request may replay
+pub const MAX_BACKTEST_BARS: usize = 10_000;
+
+/// Run a strategy over the provided history
+///
+/// Uses a fresh runner outside the engine, so running runners are unaffected.
+/// The replay runs on the blocking pool so it doesn't stall other requests.
+/// Returns the closed trades, final stats and the equity curve.
+pub async fn run_backtest(
+    Json(request): Json<BacktestRequest>,
+) -> Result<Json<BacktestReport>, ApiError> {
+    use trading_engine::strategy::LuaStrategy;
+
+    if request.data.is_empty() {
+        return Err(ApiError::InvalidRequest("data cannot be empty".to_string()));
+    }
+    if request.data.len() > MAX_BACKTEST_BARS {
+        return Err(ApiError::InvalidRequest(format!(
+            "data has {} bars, the limit is {}",
+            request.data.len(),
+            MAX_BACKTEST_BARS
+        )));
+    }
+
+    let strategy = LuaStrategy::new(&request.strategy_path)
+        .map_err(|e| ApiError::StrategyError(format!("Failed to load strategy: {}", e)))?;
+    let config = request.config.unwrap_or_else(RunnerConfig::quiet);
+
+    // Every bar runs the Lua strategy, so keep the replay off the async workers.
+    // The error is boxed across the task boundary to keep the result small.
+    let runtime = tokio::runtime::Handle::current();
+    let report = tokio::task::spawn_blocking(move || {
+        runtime
+            .block_on(trading_engine::runner::run_backtest(
+                strategy,
+                request.data,
+                request.window_size,
+                config,
+            ))
+            .map_err(Box::new)
+    })
+    .await
+    .map_err(|e| ApiError::EngineError(format!("Backtest task failed: {}", e)))?
+    .map_err(|e| *e)?;
+
+    Ok(Json(report))
+}
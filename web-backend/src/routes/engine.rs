@@ -1,5 +1,7 @@
 use axum::{extract::State, http::StatusCode, Json};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use trading_engine::runner::{EngineSummary, RunnerSnapshot};
 
 use crate::{ApiError, AppState};
 
@@ -46,8 +48,8 @@ pub struct RunnerSummary {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EngineSummaryResponse {
     pub status: String,
-    pub total_runners: usize,
-    pub healthy_runners: usize,
+    #[serde(flatten)]
+    pub summary: EngineSummary,
     pub active_symbols: Vec<String>,
     pub runners: Vec<RunnerSummary>,
     pub timestamp: i64,
@@ -61,9 +63,7 @@ pub async fn engine_summary(
 ) -> Result<Json<EngineSummaryResponse>, ApiError> {
     let engine = state.engine.lock().await;
 
-    let total_runners = engine.runner_count();
-    let unhealthy = engine.unhealthy_runners().len();
-    let healthy_runners = total_runners - unhealthy;
+    let summary = engine.summary_struct();
     let active_symbols = engine.active_symbols();
 
     // Get all runners
@@ -79,8 +79,7 @@ pub async fn engine_summary(
 
     let response = EngineSummaryResponse {
         status: "ok".to_string(),
-        total_runners,
-        healthy_runners,
+        summary,
         active_symbols,
         runners,
         timestamp: chrono::Utc::now().timestamp(),
@@ -89,6 +88,88 @@ pub async fn engine_summary(
     Ok(Json(response))
 }
 
+/// Full engine state response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EngineStateResponse {
+    pub status: String,
+    pub summary: EngineSummary,
+    /// Snapshot of every runner, by runner ID
+    pub snapshots: HashMap<String, RunnerSnapshot>,
+    pub active_symbols: Vec<String>,
+    /// Health of every runner, by runner ID
+    pub health: HashMap<String, bool>,
+    pub timestamp: i64,
+}
+
+/// Get the full engine state
+///
+/// Returns the summary, every runner snapshot, the active symbols and runner
+/// health in one document, read under a single engine lock so the sections
+/// agree with each other.
+pub async fn engine_state(
+    State(state): State<AppState>,
+) -> Result<Json<EngineStateResponse>, ApiError> {
+    let engine = state.engine.lock().await;
+
+    let response = EngineStateResponse {
+        status: "ok".to_string(),
+        summary: engine.summary_struct(),
+        snapshots: engine.all_snapshots().await,
+        active_symbols: engine.active_symbols(),
+        health: engine.health_check(),
+        timestamp: chrono::Utc::now().timestamp(),
+    };
+
+    Ok(Json(response))
+}
+
+/// A runner whose position could not be closed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FlattenFailure {
+    pub runner_id: String,
+    pub error: String,
+}
+
+/// Result of flattening all positions
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FlattenResponse {
+    /// Runners whose positions were closed
+    pub flattened: Vec<String>,
+    /// Runners whose positions are still open
+    pub failed: Vec<FlattenFailure>,
+    pub timestamp: i64,
+}
+
+/// Close every open position at the latest price
+///
+/// Emergency stop for all runners. Runners keep running afterwards and may
+/// enter new positions.
+pub async fn flatten_all(
+    State(state): State<AppState>,
+) -> Result<Json<FlattenResponse>, ApiError> {
+    let engine = state.engine.lock().await;
+
+    let results = engine.flatten_all().await?;
+
+    let mut flattened = Vec::new();
+    let mut failed = Vec::new();
+    for (runner_id, result) in results {
+        match result {
+            Ok(()) => flattened.push(runner_id),
+            Err(e) => failed.push(FlattenFailure {
+                runner_id,
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    Ok(Json(FlattenResponse {
+        flattened,
+        failed,
+        timestamp: chrono::Utc::now().timestamp(),
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,4 +187,57 @@ mod tests {
         assert_eq!(response.runners_count, 0);
         assert_eq!(response.healthy_runners, 0);
     }
+
+    #[tokio::test]
+    async fn test_engine_summary_json() {
+        use trading_engine::runner::TradingEngine;
+
+        let state = AppState::new(TradingEngine::new());
+        let Json(response) = engine_summary(State(state)).await.unwrap();
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["total_runners"], 0);
+        assert_eq!(json["healthy_runners"], 0);
+        assert_eq!(json["unhealthy_runners"], 0);
+        assert_eq!(json["symbol_count"], 0);
+        assert_eq!(json["runners_per_symbol"], 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_engine_state_has_all_sections() {
+        use trading_engine::runner::TradingEngine;
+        use trading_engine::strategy::LuaStrategy;
+        use trading_engine::MarketData;
+
+        let mut engine = TradingEngine::new_deterministic();
+        let strategy = || LuaStrategy::new("../lua-strategies/test_strategy.lua").unwrap();
+        engine.add_runner("btc", "BTCUSDT", strategy()).unwrap();
+        engine.add_runner("eth", "ETHUSDT", strategy()).unwrap();
+        engine
+            .feed_data(MarketData::from_trade("BTCUSDT", 50000.0, 1.0, 0))
+            .await
+            .unwrap();
+        let state = AppState::new(engine);
+
+        let Json(response) = engine_state(State(state)).await.unwrap();
+        let json = serde_json::to_value(&response).unwrap();
+
+        assert_eq!(json["summary"]["total_runners"], 2);
+        assert_eq!(json["summary"]["symbol_count"], 2);
+        assert_eq!(json["snapshots"]["btc"]["stats"]["ticks_processed"], 1);
+        assert_eq!(json["snapshots"]["eth"]["symbol"], "ETHUSDT");
+        assert_eq!(json["active_symbols"], serde_json::json!(["BTCUSDT", "ETHUSDT"]));
+        assert_eq!(json["health"]["btc"], true);
+        assert_eq!(json["health"]["eth"], true);
+    }
+
+    #[tokio::test]
+    async fn test_flatten_without_positions() {
+        use trading_engine::runner::TradingEngine;
+
+        let state = AppState::new(TradingEngine::new());
+        let Json(response) = flatten_all(State(state)).await.unwrap();
+        assert!(response.flattened.is_empty());
+        assert!(response.failed.is_empty());
+    }
 }
@@ -1,4 +1,6 @@
+pub mod backtest;
 pub mod engine;
 pub mod health;
+pub mod prices;
 pub mod runners;
 pub mod strategies;
@@ -0,0 +1,47 @@
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{ApiError, AppState};
+
+/// Latest prices response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LatestPricesResponse {
+    /// Latest close per symbol
+    pub prices: HashMap<String, f64>,
+    pub timestamp: i64,
+}
+
+/// Get the latest close for every stored symbol
+///
+/// Read from the shared market data storage in one pass, for ticker tapes
+/// and screeners.
+pub async fn latest_prices(
+    State(state): State<AppState>,
+) -> Result<Json<LatestPricesResponse>, ApiError> {
+    Ok(Json(LatestPricesResponse {
+        prices: state.storage.latest_prices(),
+        timestamp: chrono::Utc::now().timestamp(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_engine::runner::TradingEngine;
+    use trading_engine::MarketData;
+
+    #[tokio::test]
+    async fn test_latest_prices() {
+        let state = AppState::new(TradingEngine::new());
+        state.storage.push(MarketData::from_trade("BTCUSDT", 50000.0, 1.0, 1));
+        state.storage.push(MarketData::from_trade("BTCUSDT", 50100.0, 1.0, 2));
+        state.storage.push(MarketData::from_trade("ETHUSDT", 3000.0, 1.0, 1));
+
+        let Json(response) = latest_prices(State(state)).await.unwrap();
+
+        assert_eq!(response.prices.len(), 2);
+        assert_eq!(response.prices["BTCUSDT"], 50100.0);
+        assert_eq!(response.prices["ETHUSDT"], 3000.0);
+    }
+}
@@ -4,17 +4,67 @@ use axum::{
     Json,
 };
 use serde::{Deserialize, Serialize};
-use trading_engine::{market_data::MarketData, runner::RunnerSnapshot};
+use std::collections::HashMap;
+use trading_engine::{
+    indicators::{compute, Indicator, InsufficientDataPolicy},
+    market_data::MarketData,
+    runner::{ComparisonMetric, RunnerSnapshot, StrategyComparisonRow},
+    TradingEngineError,
+};
 
 use crate::{ApiError, AppState};
 
 /// Query parameters for price history
 #[derive(Debug, Deserialize)]
 pub struct HistoryQuery {
-    /// Number of recent data points to return (None = all)
+    /// Number of recent data points to return (None = all, up to the server's limit)
     pub count: Option<usize>,
 }
 
+/// Response for price history
+#[derive(Debug, Serialize)]
+pub struct PriceHistoryResponse {
+    pub data: Vec<MarketData>,
+    /// Whether older bars were left out because of the server's history limit
+    pub truncated: bool,
+}
+
+/// Query parameters for indicator series; each given period adds a series
+#[derive(Debug, Default, Deserialize)]
+pub struct IndicatorQuery {
+    pub sma: Option<usize>,
+    pub ema: Option<usize>,
+    pub rsi: Option<usize>,
+}
+
+/// Response for indicator series
+///
+/// Every series has one entry per bar in `timestamps`, null during warmup.
+#[derive(Debug, Serialize)]
+pub struct IndicatorSeriesResponse {
+    pub timestamps: Vec<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sma: Option<Vec<Option<f64>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ema: Option<Vec<Option<f64>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rsi: Option<Vec<Option<f64>>>,
+}
+
+/// Query parameters for listing runners
+#[derive(Debug, Deserialize)]
+pub struct ListRunnersQuery {
+    /// Only list runners with this tag, as `key:value` (e.g. `env:prod`)
+    pub tag: Option<String>,
+}
+
+/// Query parameters for comparing strategies on a symbol
+#[derive(Debug, Deserialize)]
+pub struct CompareQuery {
+    /// Metric to sort by: `win_rate`, `profit_factor`, `total_pnl` (default) or `trade_count`
+    pub sort: Option<ComparisonMetric>,
+}
+
 /// Request body for adding a runner
 #[derive(Debug, Deserialize, Serialize)]
 pub struct AddRunnerRequest {
@@ -37,6 +87,42 @@ pub struct AddRunnerResponse {
     pub message: String,
 }
 
+/// List runner IDs
+///
+/// Returns all runner IDs, or only those carrying `?tag=key:value`.
+pub async fn list_runners(
+    Query(params): Query<ListRunnersQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<String>>, ApiError> {
+    let engine = state.engine.lock().await;
+
+    let runner_ids = match params.tag {
+        Some(tag) => {
+            let (key, value) = tag.split_once(':').ok_or_else(|| {
+                ApiError::InvalidRequest(format!("tag must be key:value, got '{}'", tag))
+            })?;
+            engine.runners_with_tag(key, value)
+        }
+        None => engine.runner_ids(),
+    };
+
+    Ok(Json(runner_ids))
+}
+
+/// Outcome for one runner in a batch add
+#[derive(Debug, Serialize)]
+pub struct BatchRunnerResult {
+    pub runner_id: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Response for adding runners in bulk
+#[derive(Debug, Serialize)]
+pub struct BatchAddResponse {
+    pub results: Vec<BatchRunnerResult>,
+}
+
 /// Get runner snapshot
 ///
 /// Returns complete snapshot of runner state including position, context, and stats.
@@ -54,22 +140,97 @@ pub async fn get_runner_snapshot(
     Ok(Json(snapshot))
 }
 
+/// Get snapshots of all runners
+///
+/// Queries every runner concurrently, keyed by runner ID. Runners that
+/// don't answer in time are left out.
+pub async fn get_all_snapshots(
+    State(state): State<AppState>,
+) -> Result<Json<HashMap<String, RunnerSnapshot>>, ApiError> {
+    let engine = state.engine.lock().await;
+
+    Ok(Json(engine.all_snapshots().await))
+}
+
+/// Compare strategies on a symbol
+///
+/// Returns win rate, profit factor, total P&L and trade count for every
+/// runner on the symbol, best first by `?sort=`.
+pub async fn compare_strategies(
+    Path(symbol): Path<String>,
+    Query(params): Query<CompareQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<StrategyComparisonRow>>, ApiError> {
+    let engine = state.engine.lock().await;
+
+    let rows = engine
+        .strategy_comparison(&symbol, params.sort.unwrap_or_default())
+        .await?;
+
+    Ok(Json(rows))
+}
+
 /// Get runner price history
 ///
-/// Returns recent price data from the runner's market data window.
+/// Returns recent price data from the runner's market data window, at most
+/// the server's history limit bars even when more are requested.
 pub async fn get_price_history(
     Path(runner_id): Path<String>,
     Query(params): Query<HistoryQuery>,
     State(state): State<AppState>,
-) -> Result<Json<Vec<MarketData>>, ApiError> {
+) -> Result<Json<PriceHistoryResponse>, ApiError> {
+    let limit = state.history_limit;
     let engine = state.engine.lock().await;
 
-    let history = engine
-        .get_price_history(&runner_id, params.count)
+    // Ask for one bar past the limit to tell whether anything was cut
+    let requested = params.count.map_or(limit + 1, |count| count.min(limit + 1));
+    let mut data = engine
+        .get_price_history(&runner_id, Some(requested))
         .await
         .ok_or_else(|| ApiError::RunnerNotFound(runner_id.clone()))?;
 
-    Ok(Json(history))
+    let truncated = data.len() > limit;
+    if truncated {
+        data.drain(..data.len() - limit);
+    }
+
+    Ok(Json(PriceHistoryResponse { data, truncated }))
+}
+
+/// Get indicator series over a runner's price history
+///
+/// Computes the requested indicators on the closes of the runner's window
+/// (up to the server's history limit), aligned bar for bar with it.
+pub async fn get_indicator_series(
+    Path(runner_id): Path<String>,
+    Query(params): Query<IndicatorQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<IndicatorSeriesResponse>, ApiError> {
+    let requested = [("sma", params.sma), ("ema", params.ema), ("rsi", params.rsi)];
+    if let Some((name, _)) = requested.iter().find(|(_, period)| *period == Some(0)) {
+        return Err(ApiError::InvalidRequest(format!("{} period must be positive", name)));
+    }
+
+    let engine = state.engine.lock().await;
+    let data = engine
+        .get_price_history(&runner_id, Some(state.history_limit))
+        .await
+        .ok_or_else(|| ApiError::RunnerNotFound(runner_id.clone()))?;
+
+    let closes: Vec<f64> = data.iter().map(|bar| bar.close).collect();
+    let series = |indicator: Indicator| {
+        compute(indicator, &closes, InsufficientDataPolicy::FillNan)
+            .into_iter()
+            .map(|value| (!value.is_nan()).then_some(value))
+            .collect()
+    };
+
+    Ok(Json(IndicatorSeriesResponse {
+        timestamps: data.iter().map(|bar| bar.timestamp).collect(),
+        sma: params.sma.map(|period| series(Indicator::Sma(period))),
+        ema: params.ema.map(|period| series(Indicator::Ema(period))),
+        rsi: params.rsi.map(|period| series(Indicator::Rsi(period))),
+    }))
 }
 
 /// Add a new runner
@@ -93,10 +254,7 @@ pub async fn add_runner(
 
     // Check if runner already exists
     if engine.has_runner(&request.runner_id) {
-        return Err(ApiError::InvalidRequest(format!(
-            "Runner '{}' already exists",
-            request.runner_id
-        )));
+        return Err(TradingEngineError::RunnerAlreadyExists(request.runner_id).into());
     }
 
     // Load the Lua strategy
@@ -104,13 +262,7 @@ pub async fn add_runner(
         .map_err(|e| ApiError::StrategyError(format!("Failed to load strategy: {}", e)))?;
 
     // Add the runner to the engine
-    engine
-        .add_runner(
-            request.runner_id.clone(),
-            request.symbol.clone(),
-            strategy,
-        )
-        .map_err(|e| ApiError::EngineError(e.to_string()))?;
+    engine.add_runner(request.runner_id.clone(), request.symbol.clone(), strategy)?;
 
     let response = AddRunnerResponse {
         runner_id: request.runner_id,
@@ -121,6 +273,53 @@ pub async fn add_runner(
     Ok((StatusCode::CREATED, Json(response)))
 }
 
+/// Add several runners
+///
+/// Attempts every runner and reports each outcome, so one bad strategy
+/// doesn't prevent the rest from starting. Results are in request order.
+pub async fn add_runners_batch(
+    State(state): State<AppState>,
+    Json(requests): Json<Vec<AddRunnerRequest>>,
+) -> Result<Json<BatchAddResponse>, ApiError> {
+    use trading_engine::runner::RunnerSpec;
+
+    // Invalid requests are answered in place; the rest fill their slots below
+    let mut results: Vec<Option<BatchRunnerResult>> = Vec::with_capacity(requests.len());
+    let mut specs = Vec::with_capacity(requests.len());
+    for request in requests {
+        if request.runner_id.is_empty() || request.symbol.is_empty() {
+            results.push(Some(BatchRunnerResult {
+                runner_id: request.runner_id,
+                success: false,
+                error: Some("runner_id and symbol cannot be empty".to_string()),
+            }));
+        } else {
+            results.push(None);
+            specs.push(
+                RunnerSpec::new(request.runner_id, request.symbol, request.strategy_path)
+                    .with_window_size(request.window_size),
+            );
+        }
+    }
+
+    let mut engine = state.engine.lock().await;
+    let mut added = engine.add_runners_batch(specs).into_iter();
+    let results = results
+        .into_iter()
+        .filter_map(|result| {
+            result.or_else(|| {
+                added.next().map(|(runner_id, result)| BatchRunnerResult {
+                    runner_id,
+                    success: result.is_ok(),
+                    error: result.err().map(|e| e.to_string()),
+                })
+            })
+        })
+        .collect();
+
+    Ok(Json(BatchAddResponse { results }))
+}
+
 /// Remove runner request
 pub async fn remove_runner(
     Path(runner_id): Path<String>,
@@ -128,10 +327,7 @@ pub async fn remove_runner(
 ) -> Result<StatusCode, ApiError> {
     let mut engine = state.engine.lock().await;
 
-    engine
-        .remove_runner(&runner_id)
-        .await
-        .map_err(|e| ApiError::EngineError(e.to_string()))?;
+    engine.remove_runner(&runner_id).await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -152,10 +348,7 @@ pub async fn pause_runner(
 ) -> Result<Json<ControlResponse>, ApiError> {
     let engine = state.engine.lock().await;
 
-    let success = engine
-        .pause_runner(&runner_id)
-        .await
-        .map_err(|e| ApiError::EngineError(e.to_string()))?;
+    let success = engine.pause_runner(&runner_id).await?;
 
     let message = if success {
         format!("Runner '{}' paused successfully", runner_id)
@@ -175,10 +368,7 @@ pub async fn resume_runner(
 ) -> Result<Json<ControlResponse>, ApiError> {
     let engine = state.engine.lock().await;
 
-    let success = engine
-        .resume_runner(&runner_id)
-        .await
-        .map_err(|e| ApiError::EngineError(e.to_string()))?;
+    let success = engine.resume_runner(&runner_id).await?;
 
     let message = if success {
         format!("Runner '{}' resumed successfully", runner_id)
@@ -198,10 +388,7 @@ pub async fn stop_runner(
 ) -> Result<Json<ControlResponse>, ApiError> {
     let engine = state.engine.lock().await;
 
-    let success = engine
-        .stop_runner(&runner_id)
-        .await
-        .map_err(|e| ApiError::EngineError(e.to_string()))?;
+    let success = engine.stop_runner(&runner_id).await?;
 
     let message = format!("Runner '{}' stopped successfully", runner_id);
 
@@ -223,6 +410,24 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_get_all_snapshots() {
+        use trading_engine::strategy::LuaStrategy;
+
+        let mut engine = TradingEngine::new();
+        let strategy = || LuaStrategy::new("../lua-strategies/test_strategy.lua").unwrap();
+        engine.add_runner("btc", "BTCUSDT", strategy()).unwrap();
+        engine.add_runner("eth", "ETHUSDT", strategy()).unwrap();
+        engine.add_runner("sol", "SOLUSDT", strategy()).unwrap();
+        let state = AppState::new(engine);
+
+        let Json(snapshots) = get_all_snapshots(State(state)).await.unwrap();
+
+        let mut runner_ids: Vec<_> = snapshots.keys().cloned().collect();
+        runner_ids.sort();
+        assert_eq!(runner_ids, vec!["btc", "eth", "sol"]);
+    }
+
     #[tokio::test]
     async fn test_get_price_history_not_found() {
         let engine = TradingEngine::new();
@@ -239,6 +444,156 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_get_price_history_caps_at_limit() {
+        use trading_engine::runner::RunnerConfig;
+        use trading_engine::strategy::LuaStrategy;
+
+        let mut engine = TradingEngine::new_deterministic();
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua").unwrap();
+        engine
+            .add_runner_with_config("btc", "BTCUSDT", strategy, 1000, RunnerConfig::default())
+            .unwrap();
+        for i in 0..1000 {
+            let bar = MarketData::from_trade("BTCUSDT", 50000.0 + i as f64, 1.0, i);
+            engine.feed_data(bar).await.unwrap();
+        }
+        let state = AppState::new(engine);
+
+        let query = HistoryQuery { count: None };
+        let Json(history) = get_price_history(Path("btc".to_string()), Query(query), State(state.clone()))
+            .await
+            .unwrap();
+        assert_eq!(history.data.len(), crate::state::DEFAULT_HISTORY_LIMIT);
+        assert!(history.truncated);
+        assert_eq!(history.data.last().unwrap().close, 50999.0);
+
+        let query = HistoryQuery { count: Some(10) };
+        let Json(history) = get_price_history(Path("btc".to_string()), Query(query), State(state))
+            .await
+            .unwrap();
+        assert_eq!(history.data.len(), 10);
+        assert!(!history.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_get_indicator_series_matches_batch_functions() {
+        use trading_engine::indicators::{exponential_moving_average, relative_strength_index};
+        use trading_engine::runner::RunnerConfig;
+        use trading_engine::strategy::LuaStrategy;
+
+        let mut engine = TradingEngine::new_deterministic();
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua").unwrap();
+        engine
+            .add_runner_with_config("btc", "BTCUSDT", strategy, 100, RunnerConfig::default())
+            .unwrap();
+        let closes: Vec<f64> = (0..60).map(|i| 50000.0 + ((i * 7) % 13) as f64 * 10.0).collect();
+        for (i, &close) in closes.iter().enumerate() {
+            let bar = MarketData::from_trade("BTCUSDT", close, 1.0, i as i64);
+            engine.feed_data(bar).await.unwrap();
+        }
+        let state = AppState::new(engine);
+
+        let query = IndicatorQuery { ema: Some(20), rsi: Some(14), ..Default::default() };
+        let Json(response) = get_indicator_series(Path("btc".to_string()), Query(query), State(state.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.timestamps.len(), 60);
+        assert!(response.sma.is_none());
+
+        let ema = response.ema.unwrap();
+        let expected = exponential_moving_average(&closes, 20);
+        assert_eq!(ema[..19], [None; 19]);
+        assert_eq!(ema[19..], expected[19..].iter().copied().map(Some).collect::<Vec<_>>()[..]);
+
+        let rsi = response.rsi.unwrap();
+        let expected = relative_strength_index(&closes, 14);
+        assert_eq!(rsi[..14], [None; 14]);
+        assert_eq!(rsi[14..], expected[14..].iter().copied().map(Some).collect::<Vec<_>>()[..]);
+
+        let query = IndicatorQuery { sma: Some(0), ..Default::default() };
+        let result = get_indicator_series(Path("btc".to_string()), Query(query), State(state)).await;
+        assert!(matches!(result, Err(ApiError::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_compare_strategies() {
+        use trading_engine::strategy::LuaStrategy;
+
+        let mut engine = TradingEngine::new();
+        let strategy = || LuaStrategy::new("../lua-strategies/test_strategy.lua").unwrap();
+        engine.add_runner("btc_b", "BTCUSDT", strategy()).unwrap();
+        engine.add_runner("btc_a", "BTCUSDT", strategy()).unwrap();
+        let state = AppState::new(engine);
+
+        let query = CompareQuery { sort: Some(ComparisonMetric::WinRate) };
+        let Json(rows) = compare_strategies(Path("BTCUSDT".to_string()), Query(query), State(state.clone()))
+            .await
+            .unwrap();
+        let runner_ids: Vec<_> = rows.iter().map(|row| row.runner_id.as_str()).collect();
+        assert_eq!(runner_ids, vec!["btc_a", "btc_b"]);
+
+        let query = CompareQuery { sort: None };
+        let result = compare_strategies(Path("ETHUSDT".to_string()), Query(query), State(state)).await;
+        assert!(matches!(result, Err(ApiError::Engine(TradingEngineError::NoRunnersForSymbol(_)))));
+    }
+
+    #[tokio::test]
+    async fn test_list_runners_by_tag() {
+        use trading_engine::runner::RunnerConfig;
+        use trading_engine::strategy::LuaStrategy;
+
+        let mut engine = TradingEngine::new();
+        let strategy = || LuaStrategy::new("../lua-strategies/test_strategy.lua").unwrap();
+        let prod = RunnerConfig::default().with_tag("env", "prod");
+        engine.add_runner_with_config("btc_prod", "BTCUSDT", strategy(), 50, prod).unwrap();
+        engine.add_runner("btc_dev", "BTCUSDT", strategy()).unwrap();
+        let state = AppState::new(engine);
+
+        let query = ListRunnersQuery { tag: Some("env:prod".to_string()) };
+        let Json(runners) = list_runners(Query(query), State(state.clone())).await.unwrap();
+        assert_eq!(runners, vec!["btc_prod"]);
+
+        let query = ListRunnersQuery { tag: None };
+        let Json(runners) = list_runners(Query(query), State(state.clone())).await.unwrap();
+        assert_eq!(runners, vec!["btc_dev", "btc_prod"]);
+
+        let query = ListRunnersQuery { tag: Some("env".to_string()) };
+        assert!(list_runners(Query(query), State(state)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_add_runners_batch_reports_failures() {
+        let state = AppState::new(TradingEngine::new());
+        let request = |runner_id: &str, strategy_path: &str| AddRunnerRequest {
+            runner_id: runner_id.to_string(),
+            symbol: "BTCUSDT".to_string(),
+            strategy_path: strategy_path.to_string(),
+            window_size: 200,
+        };
+
+        let Json(response) = add_runners_batch(
+            State(state.clone()),
+            Json(vec![
+                request("btc_bad", "../lua-strategies/does_not_exist.lua"),
+                request("", "../lua-strategies/test_strategy.lua"),
+                request("btc_ok", "../lua-strategies/test_strategy.lua"),
+            ]),
+        )
+        .await
+        .unwrap();
+
+        // Results come back in request order, invalid requests included
+        let runner_ids: Vec<&str> = response.results.iter().map(|r| r.runner_id.as_str()).collect();
+        assert_eq!(runner_ids, vec!["btc_bad", "", "btc_ok"]);
+        assert!(!response.results[0].success);
+        assert!(response.results[0].error.is_some());
+        assert!(!response.results[1].success);
+        assert!(response.results[2].success);
+        assert!(state.engine.lock().await.has_runner("btc_ok"));
+    }
+
     #[tokio::test]
     async fn test_remove_runner_not_found() {
         let engine = TradingEngine::new();
@@ -248,4 +603,40 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_add_duplicate_runner_conflicts() {
+        use axum::response::IntoResponse;
+
+        let state = AppState::new(TradingEngine::new());
+        let request = || AddRunnerRequest {
+            runner_id: "btc".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            strategy_path: "../lua-strategies/test_strategy.lua".to_string(),
+            window_size: 200,
+        };
+
+        let (status, _) = add_runner(State(state.clone()), Json(request())).await.unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        let err = add_runner(State(state), Json(request())).await.unwrap_err();
+
+        assert_eq!(err.into_response().status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_control_missing_runner_not_found() {
+        use axum::response::IntoResponse;
+
+        let state = AppState::new(TradingEngine::new());
+        let missing = || Path("nonexistent".to_string());
+
+        let err = remove_runner(missing(), State(state.clone())).await.unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::NOT_FOUND);
+
+        let err = pause_runner(missing(), State(state.clone())).await.unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::NOT_FOUND);
+
+        let err = stop_runner(missing(), State(state)).await.unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::NOT_FOUND);
+    }
 }
@@ -1,19 +1,75 @@
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use trading_engine::runner::TradingEngine;
+use trading_engine::{MarketDataBus, MarketDataStorage};
+
+/// Bars kept per symbol in the shared market data storage
+pub const STORAGE_WINDOW_SIZE: usize = 1000;
+
+/// Most bars a price history request returns, whatever count is asked for
+pub const DEFAULT_HISTORY_LIMIT: usize = 500;
 
 /// Application state shared across all handlers
 #[derive(Clone)]
 pub struct AppState {
     /// The trading engine instance
     pub engine: Arc<Mutex<TradingEngine>>,
+
+    /// Latest bars per symbol, filled from the market data bus
+    pub storage: MarketDataStorage,
+
+    /// Single entry point for live market data
+    pub bus: MarketDataBus,
+
+    /// Cap on bars returned by the price history endpoint
+    pub history_limit: usize,
 }
 
 impl AppState {
     /// Create a new AppState with a TradingEngine
+    ///
+    /// The storage is attached to the bus; the engine is fed from a bus
+    /// subscription by [`forward_market_data`](Self::forward_market_data).
     pub fn new(engine: TradingEngine) -> Self {
+        let storage = MarketDataStorage::new(STORAGE_WINDOW_SIZE);
+        let bus = MarketDataBus::new();
+        bus.add_storage(storage.clone());
+
         Self {
             engine: Arc::new(Mutex::new(engine)),
+            storage,
+            bus,
+            history_limit: DEFAULT_HISTORY_LIMIT,
+        }
+    }
+
+    /// Cap price history responses at `history_limit` bars (default 500)
+    pub fn with_history_limit(mut self, history_limit: usize) -> Self {
+        self.history_limit = history_limit;
+        self
+    }
+
+    /// Feed every bar published on the bus to the engine
+    ///
+    /// Runs until the bus and all its clones are dropped. Bars for symbols
+    /// without runners are skipped.
+    pub async fn forward_market_data(&self) {
+        let mut data = self.bus.subscribe();
+        while let Some(bar) = data.recv().await {
+            let symbol = bar.symbol.clone();
+            let price = bar.close;
+
+            let engine = self.engine.lock().await;
+            match engine.feed_data_counted(bar).await {
+                Ok(delivered) => tracing::debug!(
+                    "Fed data for {} at price {} to {}/{} runners",
+                    symbol,
+                    price,
+                    delivered,
+                    engine.runner_count_for_symbol(&symbol)
+                ),
+                Err(e) => tracing::warn!("Failed to feed data for {}: {}", symbol, e),
+            }
         }
     }
 
@@ -36,6 +92,28 @@ mod tests {
         let _cloned = state.clone();
     }
 
+    #[tokio::test]
+    async fn test_bus_reaches_storage_and_engine() {
+        use trading_engine::strategy::LuaStrategy;
+        use trading_engine::MarketData;
+
+        let mut engine = TradingEngine::new_deterministic();
+        let strategy = LuaStrategy::new("../lua-strategies/test_strategy.lua").unwrap();
+        engine.add_runner("btc_ema", "BTCUSDT", strategy).unwrap();
+        let state = AppState::new(engine);
+
+        let forwarder = state.clone();
+        tokio::spawn(async move { forwarder.forward_market_data().await });
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        state.bus.publish(MarketData::from_trade("BTCUSDT", 50000.0, 1.0, 1234567890));
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+        assert_eq!(state.storage.get_window("BTCUSDT").unwrap().len(), 1);
+        let snapshot = state.engine.lock().await.get_runner_snapshot("btc_ema").await.unwrap();
+        assert_eq!(snapshot.stats.ticks_processed, 1);
+    }
+
     #[tokio::test]
     async fn test_app_state_engine_access() {
         let engine = TradingEngine::new();
@@ -1,6 +1,141 @@
 // WebSocket handlers for real-time event streaming
-// Will be implemented in Phase 6, Step 8
 
-// TODO: Implement WebSocket connection handler
 // TODO: Implement event subscription/filtering
 // TODO: Implement event throttling
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use trading_engine::events::SequencedEvent;
+
+use crate::AppState;
+
+/// Wire format for events sent over the WebSocket
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventFormat {
+    /// One JSON text message per event
+    #[default]
+    Json,
+    /// One MessagePack binary message per event, with the same field names as JSON
+    Msgpack,
+}
+
+impl EventFormat {
+    /// Encode an event as a WebSocket message
+    pub fn encode<T: Serialize>(self, event: &T) -> anyhow::Result<Message> {
+        Ok(match self {
+            EventFormat::Json => Message::Text(serde_json::to_string(event)?),
+            EventFormat::Msgpack => Message::Binary(rmp_serde::to_vec_named(event)?),
+        })
+    }
+}
+
+/// Query parameters for the event stream
+#[derive(Debug, Deserialize)]
+pub struct WsQuery {
+    /// `json` (default) or `msgpack`
+    #[serde(default)]
+    pub format: EventFormat,
+}
+
+/// Stream all runner events over a WebSocket
+///
+/// `GET /ws?format=msgpack` switches to binary MessagePack frames, which are
+/// much smaller than JSON for tick-heavy streams. Every event carries the
+/// engine's `seq` number, so a reconnecting client can spot duplicates and gaps.
+pub async fn ws_handler(
+    ws: WebSocketUpgrade,
+    Query(query): Query<WsQuery>,
+    State(state): State<AppState>,
+) -> Response {
+    let events = state.engine.lock().await.subscribe_sequenced_events();
+    ws.on_upgrade(move |socket| stream_events(socket, events, query.format))
+}
+
+/// Forward events to the client until either side goes away
+async fn stream_events(
+    mut socket: WebSocket,
+    mut events: mpsc::UnboundedReceiver<SequencedEvent>,
+    format: EventFormat,
+) {
+    while let Some(event) = events.recv().await {
+        let message = match format.encode(&event) {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::warn!("Failed to encode event as {:?}: {}", format, e);
+                continue;
+            }
+        };
+
+        if socket.send(message).await.is_err() {
+            tracing::debug!("WebSocket client disconnected");
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_engine::events::{ErrorSeverity, RunnerEvent};
+
+    #[test]
+    fn test_msgpack_round_trip() {
+        let event = RunnerEvent::Error {
+            runner_id: "btc_ema".to_string(),
+            error: "Strategy error".to_string(),
+            severity: ErrorSeverity::Warning,
+            timestamp: 1234567890,
+        };
+
+        let Message::Binary(bytes) = EventFormat::Msgpack.encode(&event).unwrap() else {
+            panic!("MessagePack events should be binary messages");
+        };
+        let decoded: RunnerEvent = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&event).unwrap()
+        );
+
+        // Smaller than the same event as JSON
+        let Message::Text(json) = EventFormat::Json.encode(&event).unwrap() else {
+            panic!("JSON events should be text messages");
+        };
+        assert!(bytes.len() < json.len());
+    }
+
+    #[test]
+    fn test_json_carries_seq() {
+        let event = SequencedEvent {
+            seq: 7,
+            event: RunnerEvent::RunnerStarted {
+                runner_id: "btc_ema".to_string(),
+                symbol: "BTCUSDT".to_string(),
+                timestamp: 1234567890,
+            },
+        };
+
+        let Message::Text(json) = EventFormat::Json.encode(&event).unwrap() else {
+            panic!("JSON events should be text messages");
+        };
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["seq"], 7);
+        assert_eq!(value["type"], "RunnerStarted");
+    }
+
+    #[test]
+    fn test_format_defaults_to_json() {
+        let query: WsQuery = serde_json::from_str("{}").unwrap();
+        assert_eq!(query.format, EventFormat::Json);
+
+        let query: WsQuery = serde_json::from_str(r#"{"format":"msgpack"}"#).unwrap();
+        assert_eq!(query.format, EventFormat::Msgpack);
+    }
+}
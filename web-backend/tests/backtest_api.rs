@@ -0,0 +1,88 @@
+//! Integration tests for the backtest endpoint
+
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use trading_engine::runner::TradingEngine;
+use trading_web_backend::{build_router, AppState};
+
+/// Enters on every opportunity and exits on the next bar
+const CHURN_STRATEGY: &str = "../lua-strategies/churn_strategy.lua";
+
+fn bar(i: i64, close: f64) -> Value {
+    json!({
+        "symbol": "BTCUSDT",
+        "timestamp": i * 60_000,
+        "open": close,
+        "high": close,
+        "low": close,
+        "close": close,
+        "volume": 100,
+        "bid": close,
+        "ask": close
+    })
+}
+
+/// Serve the API on an ephemeral port and POST `body` to `/api/backtest`
+async fn post_backtest(body: Value) -> (StatusCode, Value) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = build_router(AppState::new(TradingEngine::new()));
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/api/backtest", addr))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+
+    let status = response.status();
+    (status, response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn test_backtest_returns_executed_trades() {
+    let data: Vec<Value> = (0..6).map(|i| bar(i, 100.0 + i as f64)).collect();
+
+    let (status, report) = post_backtest(json!({
+        "strategy_path": CHURN_STRATEGY,
+        "data": data
+    }))
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let trades = report["trades"].as_array().expect("trades");
+    assert_eq!(trades.len(), 2);
+    assert_eq!(trades[0]["entry_price"], 101.0);
+    assert_eq!(trades[0]["exit_price"], 102.0);
+    assert_eq!(report["realized_pnl"], 2.0);
+    assert_eq!(report["stats"]["ticks_processed"], 6);
+    assert_eq!(report["equity_curve"].as_array().unwrap().len(), 6);
+}
+
+#[tokio::test]
+async fn test_backtest_rejects_empty_data() {
+    let (status, body) = post_backtest(json!({
+        "strategy_path": "../lua-strategies/test_strategy.lua",
+        "data": []
+    }))
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "INVALID_REQUEST");
+}
+
+#[tokio::test]
+async fn test_backtest_rejects_too_many_bars() {
+    use trading_web_backend::routes::backtest::MAX_BACKTEST_BARS;
+
+    let data: Vec<Value> = (0..=MAX_BACKTEST_BARS as i64).map(|i| bar(i, 100.0)).collect();
+    let (status, body) = post_backtest(json!({
+        "strategy_path": "../lua-strategies/test_strategy.lua",
+        "data": data
+    }))
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "INVALID_REQUEST");
+}
@@ -46,12 +46,20 @@ export function RunnerListTable({ runners, onRunnerUpdated }: RunnerListTablePro
 
   const getStatusColor = (status: RunnerStatus): string => {
     switch (status) {
+      case 'starting':
+        return 'bg-blue-100 text-blue-800';
       case 'running':
         return 'bg-green-100 text-green-800';
+      case 'stale':
+        return 'bg-orange-100 text-orange-800';
       case 'paused':
         return 'bg-yellow-100 text-yellow-800';
       case 'stopped':
         return 'bg-red-100 text-red-800';
+      case 'error':
+        return 'bg-red-200 text-red-900';
+      case 'finished':
+        return 'bg-gray-100 text-gray-800';
     }
   };
 
@@ -180,7 +188,7 @@ export function RunnerListTable({ runners, onRunnerUpdated }: RunnerListTablePro
               </td>
               <td className="px-6 py-4 whitespace-nowrap text-right text-sm font-medium">
                 <div className="flex justify-end gap-2">
-                  {runner.status === 'running' && (
+                  {(runner.status === 'running' || runner.status === 'starting') && (
                     <button
                       onClick={() => handleControl(runner.runner_id, 'pause')}
                       disabled={loadingActions[runner.runner_id]}
@@ -200,7 +208,7 @@ export function RunnerListTable({ runners, onRunnerUpdated }: RunnerListTablePro
                       Resume
                     </button>
                   )}
-                  {runner.status !== 'stopped' && (
+                  {!['stopped', 'error', 'finished'].includes(runner.status) && (
                     <button
                       onClick={() => handleControl(runner.runner_id, 'stop')}
                       disabled={loadingActions[runner.runner_id]}
@@ -50,7 +50,7 @@ export function RunnerDetail() {
     return `${seconds}s`;
   };
 
-  const chartData = history?.map((data, index) => {
+  const chartData = history?.data.map((data, index) => {
     const isGreen = data.close >= data.open;
     return {
       time: new Date(data.timestamp).toLocaleTimeString(),
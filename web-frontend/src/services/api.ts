@@ -2,13 +2,19 @@ import type {
   HealthResponse,
   EngineHealthResponse,
   EngineSummaryResponse,
+  EngineStateResponse,
   RunnerSnapshot,
-  MarketData,
+  PriceHistoryResponse,
+  IndicatorPeriods,
+  IndicatorSeriesResponse,
   AddRunnerRequest,
   AddRunnerResponse,
   ControlResponse,
   StrategyListResponse,
   SymbolListResponse,
+  LatestPricesResponse,
+  ComparisonMetric,
+  StrategyComparisonRow,
 } from '../types/api';
 
 const API_BASE_URL = import.meta.env.VITE_API_URL || 'http://localhost:3000';
@@ -53,6 +59,10 @@ class ApiClient {
     return this.fetch<EngineSummaryResponse>('/api/engine/summary');
   }
 
+  async getEngineState(): Promise<EngineStateResponse> {
+    return this.fetch<EngineStateResponse>('/api/engine/state');
+  }
+
   // Runner endpoints
   async getRunnerSnapshot(runnerId: string): Promise<RunnerSnapshot> {
     return this.fetch<RunnerSnapshot>(`/api/runners/${runnerId}/snapshot`);
@@ -61,13 +71,27 @@ class ApiClient {
   async getPriceHistory(
     runnerId: string,
     count?: number
-  ): Promise<MarketData[]> {
+  ): Promise<PriceHistoryResponse> {
     const params = count ? `?count=${count}` : '';
-    return this.fetch<MarketData[]>(
+    return this.fetch<PriceHistoryResponse>(
       `/api/runners/${runnerId}/history${params}`
     );
   }
 
+  async getIndicatorSeries(
+    runnerId: string,
+    periods: IndicatorPeriods
+  ): Promise<IndicatorSeriesResponse> {
+    const params = new URLSearchParams(
+      Object.entries(periods)
+        .filter(([, period]) => period !== undefined)
+        .map(([name, period]) => [name, String(period)])
+    );
+    return this.fetch<IndicatorSeriesResponse>(
+      `/api/runners/${runnerId}/indicators?${params}`
+    );
+  }
+
   async removeRunner(runnerId: string): Promise<void> {
     await this.fetch<void>(`/api/runners/${runnerId}`, {
       method: 'DELETE',
@@ -109,6 +133,19 @@ class ApiClient {
   async listSymbols(): Promise<SymbolListResponse> {
     return this.fetch<SymbolListResponse>('/api/symbols');
   }
+
+  // Market data endpoints
+  async getLatestPrices(): Promise<LatestPricesResponse> {
+    return this.fetch<LatestPricesResponse>('/api/prices');
+  }
+
+  async compareStrategies(
+    symbol: string,
+    sort?: ComparisonMetric
+  ): Promise<StrategyComparisonRow[]> {
+    const params = sort ? `?sort=${sort}` : '';
+    return this.fetch<StrategyComparisonRow[]>(`/api/symbols/${symbol}/compare${params}`);
+  }
 }
 
 export const apiClient = new ApiClient();
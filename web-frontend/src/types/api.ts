@@ -22,11 +22,22 @@ export interface EngineSummaryResponse {
   status: string;
   total_runners: number;
   healthy_runners: number;
+  unhealthy_runners: number;
+  symbol_count: number;
+  runners_per_symbol: number;
   active_symbols: string[];
   runners: RunnerSummary[];
   timestamp: number;
 }
 
+export interface EngineSummary {
+  total_runners: number;
+  healthy_runners: number;
+  unhealthy_runners: number;
+  symbol_count: number;
+  runners_per_symbol: number;
+}
+
 export interface Position {
   entry_price: number;
   quantity: number;
@@ -48,6 +59,12 @@ export interface RunnerStats {
   ticks_processed: number;
   actions_executed: number;
   errors: number;
+  no_action_ticks?: number;
+  last_no_action_reason?: string | null;
+  trades?: number;
+  winning_trades?: number;
+  gross_profit?: number;
+  gross_loss?: number;
   avg_tick_duration: Duration;
   min_tick_duration: Duration;
   max_tick_duration: Duration;
@@ -58,7 +75,7 @@ export interface Duration {
   nanos: number;
 }
 
-export type RunnerStatus = 'running' | 'paused' | 'stopped';
+export type RunnerStatus = 'starting' | 'running' | 'stale' | 'paused' | 'stopped' | 'error' | 'finished';
 
 export interface RunnerSnapshot {
   runner_id: string;
@@ -70,6 +87,16 @@ export interface RunnerSnapshot {
   stats: RunnerStats;
   uptime_secs: number;
   snapshot_timestamp: number;
+  unrealized_pnl_pct?: number | null;
+}
+
+export interface EngineStateResponse {
+  status: string;
+  summary: EngineSummary;
+  snapshots: Record<string, RunnerSnapshot>;
+  active_symbols: string[];
+  health: Record<string, boolean>;
+  timestamp: number;
 }
 
 export interface MarketData {
@@ -84,11 +111,30 @@ export interface MarketData {
   ask: number;
 }
 
+export interface PriceHistoryResponse {
+  data: MarketData[];
+  truncated: boolean;
+}
+
+export interface IndicatorPeriods {
+  sma?: number;
+  ema?: number;
+  rsi?: number;
+}
+
+export interface IndicatorSeriesResponse {
+  timestamps: number[];
+  sma?: (number | null)[];
+  ema?: (number | null)[];
+  rsi?: (number | null)[];
+}
+
 export interface ApiError {
   status: string;
   error: {
     code: string;
     message: string;
+    variant?: string;
   };
   timestamp: number;
 }
@@ -130,3 +176,18 @@ export interface SymbolInfo {
 export interface SymbolListResponse {
   symbols: SymbolInfo[];
 }
+
+export interface LatestPricesResponse {
+  prices: Record<string, number>;
+  timestamp: number;
+}
+
+export type ComparisonMetric = 'win_rate' | 'profit_factor' | 'total_pnl' | 'trade_count';
+
+export interface StrategyComparisonRow {
+  runner_id: string;
+  trades: number;
+  win_rate: number;
+  profit_factor: number | null;
+  total_pnl: number;
+}